@@ -0,0 +1,68 @@
+//! Time `parser::from_slice` against validating the bytes as UTF-8 first
+//! and calling `parser::from_str`, on machine-generated ASCII EDN.
+//!
+//! ```sh
+//! cargo run --release --example ascii_bench -- 2000
+//! ```
+//!
+//! The argument is the number of records in the document, 1000 by default.
+
+extern crate edn;
+
+use std::env;
+use std::str;
+use std::time::{Duration, Instant};
+
+use edn::parser;
+
+fn document(records: usize) -> String {
+    let mut out = String::from("[");
+    for i in 0..records {
+        out.push_str(&format!(
+            "{{:id {} :name \"user-{}\" :score {}.5 :tags #{{:a :b}} :active {} :message \"{}\"}}\n",
+            i,
+            i,
+            i % 100,
+            i % 2 == 0,
+            "request completed without errors after retrying the upstream call"
+        ));
+    }
+    out.push(']');
+    out
+}
+
+fn time<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let records = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(1000);
+    let document = document(records);
+    let bytes = document.as_bytes();
+    let iterations = 50;
+
+    let validated = time(iterations, || {
+        parser::from_str(str::from_utf8(bytes).unwrap()).unwrap();
+    });
+    let ascii = time(iterations, || {
+        parser::from_slice(bytes).unwrap();
+    });
+    let utf8 = time(iterations, || {
+        str::from_utf8(bytes).unwrap();
+    });
+    let is_ascii = time(iterations, || {
+        assert!(bytes.is_ascii());
+    });
+    println!("{} bytes, {} records", bytes.len(), records);
+    println!("checking UTF-8:       {:?}", utf8);
+    println!("checking ASCII:       {:?}", is_ascii);
+    println!("from_utf8 + from_str: {:?}", validated);
+    println!("from_slice:           {:?}", ascii);
+}
@@ -0,0 +1,111 @@
+//! Write a synthetic file of datom-like records, then time reading it form by
+//! form through `mmap::MmapFile` and through `read::IoRead`, reporting the
+//! most heap memory in use at once while reading.
+//!
+//! ```sh
+//! cargo run --release --features mmap --example mmap_bench -- 1024
+//! ```
+//!
+//! The argument is the size of the file in MB, 1024 by default. The file is
+//! written to the system's temporary directory and removed afterwards.
+
+extern crate edn;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use edn::mmap::MmapFile;
+use edn::read::IoRead;
+
+/// Tracks the bytes allocated and the most allocated at once.
+struct Tracking;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn allocated(size: usize) {
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for Tracking {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        allocated(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        allocated(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Tracking = Tracking;
+
+/// Run `f`, returning the number of forms it read and the peak heap use
+/// above what was in use before.
+fn measure<F: FnOnce() -> usize>(name: &str, bytes: u64, f: F) {
+    let before = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let start = Instant::now();
+    let forms = f();
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<6} {:>10} forms {:>10.2?} {:>8.1} MB/s {:>10} bytes peak heap",
+        name,
+        forms,
+        elapsed,
+        bytes as f64 / elapsed.as_secs_f64() / 1e6,
+        peak
+    );
+}
+
+fn main() {
+    let megabytes: u64 = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(1024);
+    let path = env::temp_dir().join("edn-mmap-bench.edn");
+
+    let mut out = BufWriter::new(File::create(&path).unwrap());
+    let (mut written, mut i) = (0, 0u64);
+    while written < megabytes * 1_000_000 {
+        let record = format!(
+            "#datom [{} :user/email \"user{}@example.com\" 13194139534312 true]\n",
+            17592186045418 + i,
+            i
+        );
+        out.write_all(record.as_bytes()).unwrap();
+        written += record.len() as u64;
+        i += 1;
+    }
+    out.flush().unwrap();
+    drop(out);
+
+    measure("mmap", written, || {
+        let file = MmapFile::open(&path).unwrap();
+        file.forms()
+            .unwrap()
+            .inspect(|form| assert!(form.is_ok()))
+            .count()
+    });
+    measure("io", written, || {
+        IoRead::new(File::open(&path).unwrap())
+            .inspect(|form| assert!(form.is_ok()))
+            .count()
+    });
+
+    fs::remove_file(&path).unwrap();
+}
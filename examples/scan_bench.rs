@@ -0,0 +1,132 @@
+//! Time `parser::from_str` and count its allocations on documents dominated
+//! by long strings, by indentation and comments, by short keywords and
+//! symbols, in the shape of a Datomic backup, and by vectors of numbers.
+//!
+//! ```sh
+//! cargo run --release --example scan_bench -- 20000
+//! ```
+//!
+//! The argument is the number of records in each document, 10000 by
+//! default.
+
+extern crate edn;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use edn::parser;
+
+/// Counts allocations and reallocations.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn strings(records: usize) -> String {
+    let mut out = String::from("[");
+    for i in 0..records {
+        out.push_str(&format!(
+            "\"{} {}\\n\" ",
+            i, "request completed without errors after retrying the upstream call, résumé attached"
+        ));
+    }
+    out.push(']');
+    out
+}
+
+fn whitespace(records: usize) -> String {
+    let mut out = String::from("[\n");
+    for i in 0..records {
+        out.push_str(&format!(
+            "  ;; record {} of the backup, written by the nightly job\n  {{:id   {},\n   :ok   true}}\n\n",
+            i, i
+        ));
+    }
+    out.push(']');
+    out
+}
+
+fn datoms(records: usize) -> String {
+    let mut out = String::from("[");
+    for i in 0..records {
+        out.push_str(&format!(
+            "#datom [{} :user/email \"user{}@example.com\" 13194139534312 true] ",
+            17592186045418 + i,
+            i
+        ));
+    }
+    out.push(']');
+    out
+}
+
+fn vectors(records: usize) -> String {
+    let mut out = String::from("[");
+    for i in 0..records {
+        out.push('[');
+        for j in 0..24 {
+            out.push_str(&format!("{} ", i * j));
+        }
+        out.push_str("] ");
+    }
+    out.push(']');
+    out
+}
+
+fn time<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let records = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(10000);
+    let iterations = 20;
+    let documents = [
+        ("strings", strings(records)),
+        ("whitespace", whitespace(records)),
+        ("datoms", datoms(records)),
+        ("vectors", vectors(records)),
+    ];
+    for &(name, ref document) in &documents {
+        let elapsed = time(iterations, || {
+            parser::from_str(document).unwrap();
+        });
+        let throughput = document.len() as f64 / elapsed.as_secs_f64() / 1e6;
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        parser::from_str(document).unwrap();
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        println!(
+            "{:<10} {:>9} bytes {:>10.2?} {:>8.1} MB/s {:>9} allocations",
+            name,
+            document.len(),
+            elapsed,
+            throughput,
+            allocations
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Time writing a large nested value as compact, pretty and canonical EDN.
+//!
+//! ```sh
+//! cargo run --release --example ser_bench -- 20000
+//! ```
+//!
+//! The argument is the number of records in the value, 10000 by default.
+
+extern crate edn;
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use edn::{parser, ser, Value};
+
+fn value(records: usize) -> Value {
+    let mut out = String::from("[");
+    for i in 0..records {
+        out.push_str(&format!(
+            "{{:id {} :user/name \"user-{}\" :score {}.25 :ratio {} :active {} :tags #{{:a :b}} \
+             :events [{{:at #inst \"2020-01-01T00:00:00Z\" :kind :login :count {}}}] \
+             :note \"line one\\nline \\\"two\\\" résumé\"}}\n",
+            i,
+            i,
+            i % 1000,
+            1.0 / (i + 1) as f64,
+            i % 2 == 0,
+            i * 31
+        ));
+    }
+    out.push(']');
+    parser::from_str(&out).unwrap()
+}
+
+type Write = fn(&Value) -> Vec<u8>;
+
+fn time<F: FnMut() -> usize>(iterations: u32, mut f: F) -> (Duration, usize) {
+    let start = Instant::now();
+    let mut len = 0;
+    for _ in 0..iterations {
+        len = f();
+    }
+    (start.elapsed() / iterations, len)
+}
+
+fn main() {
+    let records = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(10000);
+    let value = value(records);
+    let iterations = 20;
+    let writers: [(&str, Write); 3] = [
+        ("compact", ser::to_vec),
+        ("pretty", ser::to_vec_pretty),
+        ("canonical", ser::to_vec_canonical),
+    ];
+    for &(name, write) in &writers {
+        let (elapsed, len) = time(iterations, || write(&value).len());
+        let throughput = len as f64 / elapsed.as_secs_f64() / 1e6;
+        println!(
+            "{:<10} {:>9} bytes {:>10.2?} {:>8.1} MB/s",
+            name, len, elapsed, throughput
+        );
+    }
+}
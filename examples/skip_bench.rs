@@ -0,0 +1,77 @@
+//! Time skipping forms with `Parser::skip_form` against reading them with
+//! `Parser::read_raw` and `Parser::read`, on messages in which only a small
+//! `:id` is wanted from beside a large `:payload`.
+//!
+//! ```sh
+//! cargo run --release --example skip_bench -- 20000
+//! ```
+//!
+//! The argument is the number of messages, 10000 by default.
+
+extern crate edn;
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use edn::parser::Parser;
+
+/// A way of getting past the next form, false at the end of the input.
+type Run = fn(&mut Parser) -> bool;
+
+fn messages(count: usize) -> String {
+    let mut out = String::new();
+    for i in 0..count {
+        out.push_str(&format!("{{:id {} :payload [", i));
+        for j in 0..20 {
+            out.push_str(&format!(
+                "{{:user/email \"user{}@example.com\" :tags #{{:a :b}} :score {}.5 :note \"a \\\"quoted\\\" note\"}} ",
+                j, j
+            ));
+        }
+        out.push_str("]}\n");
+    }
+    out
+}
+
+fn time<F: FnMut()>(iterations: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let count = env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(10000);
+    let iterations = 10;
+    let input = messages(count);
+    // The payloads alone, as a consumer picking out `:id` would skip them.
+    let payloads: String = input
+        .lines()
+        .map(|line| &line[line.find(":payload").unwrap() + 9..line.len() - 1])
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let runs: [(&str, Run); 3] = [
+        ("skip", |parser| parser.skip_form().map(Result::unwrap).is_some()),
+        ("read_raw", |parser| parser.read_raw().map(Result::unwrap).is_some()),
+        ("read", |parser| parser.read().map(Result::unwrap).is_some()),
+    ];
+    for &(name, run) in &runs {
+        let elapsed = time(iterations, || {
+            let mut parser = Parser::new(&payloads);
+            while run(&mut parser) {}
+        });
+        let throughput = payloads.len() as f64 / elapsed.as_secs_f64() / 1e6;
+        println!(
+            "{:<9} {:>10} bytes {:>10.2?} {:>8.1} MB/s",
+            name,
+            payloads.len(),
+            elapsed,
+            throughput
+        );
+    }
+}
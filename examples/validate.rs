@@ -0,0 +1,32 @@
+//! Check every `.edn` file under a directory and print the report as EDN.
+//!
+//! ```sh
+//! cargo run --example validate -- path/to/dir
+//! ```
+//!
+//! Exits with status 1 if any file has an error.
+
+extern crate edn;
+
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process;
+
+use edn::validate;
+
+fn main() {
+    let dir = env::args().nth(1).unwrap_or_else(|| ".".into());
+    let report = match validate::check_dir(Path::new(&dir)) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("{}: {}", dir, err);
+            process::exit(2);
+        }
+    };
+    edn::ser::to_writer_pretty(io::stdout(), &report.to_value()).unwrap();
+    println!();
+    if !report.is_ok() {
+        process::exit(1);
+    }
+}
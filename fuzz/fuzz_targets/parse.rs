@@ -0,0 +1,35 @@
+//! Feeds arbitrary text to each reader and only checks that none of them
+//! panic. Run with e.g. `cargo fuzz run parse --features bigint,ratio`.
+
+#![no_main]
+
+extern crate edn;
+#[macro_use]
+extern crate libfuzzer_sys;
+
+use edn::borrowed::parse_borrowed;
+use edn::lexer::Lexer;
+use edn::parser::{Parser, ParserBuilder};
+
+fuzz_target!(|bytes: &[u8]| {
+    let str = match ::std::str::from_utf8(bytes) {
+        Ok(str) => str,
+        Err(_) => return,
+    };
+
+    for result in Parser::new(str) {
+        if result.is_err() {
+            break;
+        }
+    }
+    let mut clojure = ParserBuilder::new().clojure_syntax(true).build(str);
+    while let Some(Ok(_)) = clojure.read() {}
+    let _ = Parser::new(str).parse_with_recovery();
+
+    for token in Lexer::new(str) {
+        if token.is_err() {
+            break;
+        }
+    }
+    let _ = parse_borrowed(str);
+});
@@ -0,0 +1,30 @@
+//! Writes an arbitrary value with each formatter and checks it reads back
+//! the same. Run with e.g.
+//! `cargo fuzz run roundtrip --features bigint,bigdecimal,ratio`, and add
+//! any value it finds to `tests/corpus`.
+
+#![no_main]
+
+extern crate edn;
+#[macro_use]
+extern crate libfuzzer_sys;
+
+use edn::arbitrary::Generator;
+use edn::parser::Parser;
+use edn::ser;
+
+fuzz_target!(|bytes: &[u8]| {
+    let value = Generator::new(bytes).value();
+    let written = vec![
+        ser::to_string(&value),
+        ser::to_string_pretty(&value),
+        ser::to_string_canonical(&value),
+        ser::pretty::to_string(&value),
+        ser::pretty::PrettyPrinter::new().width(1).to_string(&value),
+    ];
+    for written in written {
+        let mut parser = Parser::new(&written);
+        assert_eq!(parser.read(), Some(Ok(value.clone())), "{:?}", written);
+        assert_eq!(parser.read(), None, "{:?}", written);
+    }
+});
@@ -0,0 +1,228 @@
+#[cfg(feature = "bigint")]
+use std::convert::TryFrom;
+
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Set, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Set, Vec};
+
+impl Value {
+    pub fn is_nil(&self) -> bool {
+        matches!(*self, Value::Nil)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Boolean(boolean) => Some(boolean),
+            _ => None,
+        }
+    }
+
+    pub fn is_bool(&self) -> bool {
+        self.as_bool().is_some()
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::String(ref string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_str_mut(&mut self) -> Option<&mut String> {
+        match *self {
+            Value::String(ref mut string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn is_string(&self) -> bool {
+        self.as_str().is_some()
+    }
+
+    pub fn as_char(&self) -> Option<char> {
+        match *self {
+            Value::Char(ch) => Some(ch),
+            _ => None,
+        }
+    }
+
+    pub fn is_char(&self) -> bool {
+        self.as_char().is_some()
+    }
+
+    pub fn as_symbol(&self) -> Option<&Symbol> {
+        match *self {
+            Value::Symbol(ref symbol) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    pub fn is_symbol(&self) -> bool {
+        self.as_symbol().is_some()
+    }
+
+    pub fn as_keyword(&self) -> Option<&Keyword> {
+        match *self {
+            Value::Keyword(ref keyword) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    pub fn is_keyword(&self) -> bool {
+        self.as_keyword().is_some()
+    }
+
+    /// Whether this is an integer or float of any precision.
+    pub fn is_number(&self) -> bool {
+        match *self {
+            Value::Integer(_) | Value::Float(_) => true,
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => true,
+            #[cfg(feature = "ratio")]
+            Value::Ratio(..) => true,
+            #[cfg(feature = "bigdecimal")]
+            Value::Decimal(_) => true,
+            _ => false,
+        }
+    }
+
+    /// The value of an `Integer`, or of a `BigInt` which fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Value::Integer(integer) => Some(integer),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(ref integer) => i64::try_from(integer).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn is_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    /// The value of a non-negative `Integer`, or of a `BigInt` which fits in
+    /// a `u64`, such as one above `i64::MAX` read by the parser.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Value::Integer(integer) if integer >= 0 => Some(integer as u64),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(ref integer) => u64::try_from(integer).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    /// The value of a `Float`, or of an `Integer` or `Ratio` converted to a
+    /// float.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Float(float) => Some(float.into_inner()),
+            Value::Integer(integer) => Some(integer as f64),
+            #[cfg(feature = "ratio")]
+            Value::Ratio(numerator, denominator) => Some(numerator as f64 / denominator as f64),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a `Float`. Unlike `as_f64`, integers don't count.
+    pub fn is_f64(&self) -> bool {
+        matches!(*self, Value::Float(_))
+    }
+
+    pub fn as_list(&self) -> Option<&Vec<Value>> {
+        match *self {
+            Value::List(ref items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match *self {
+            Value::List(ref mut items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn is_list(&self) -> bool {
+        self.as_list().is_some()
+    }
+
+    pub fn as_vector(&self) -> Option<&Vec<Value>> {
+        match *self {
+            Value::Vector(ref items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_vector_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match *self {
+            Value::Vector(ref mut items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn is_vector(&self) -> bool {
+        self.as_vector().is_some()
+    }
+
+    pub fn as_map(&self) -> Option<&Map<Value, Value>> {
+        match *self {
+            Value::Map(ref map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_map_mut(&mut self) -> Option<&mut Map<Value, Value>> {
+        match *self {
+            Value::Map(ref mut map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn is_map(&self) -> bool {
+        self.as_map().is_some()
+    }
+
+    pub fn as_set(&self) -> Option<&Set<Value>> {
+        match *self {
+            Value::Set(ref set) => Some(set),
+            _ => None,
+        }
+    }
+
+    pub fn as_set_mut(&mut self) -> Option<&mut Set<Value>> {
+        match *self {
+            Value::Set(ref mut set) => Some(set),
+            _ => None,
+        }
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.as_set().is_some()
+    }
+
+    /// The tag and value of a tagged element.
+    pub fn as_tagged(&self) -> Option<(&str, &Value)> {
+        match *self {
+            Value::Tagged(ref tag, ref value) => Some((tag, value)),
+            _ => None,
+        }
+    }
+
+    pub fn as_tagged_mut(&mut self) -> Option<(&str, &mut Value)> {
+        match *self {
+            Value::Tagged(ref tag, ref mut value) => Some((tag, value)),
+            _ => None,
+        }
+    }
+
+    pub fn is_tagged(&self) -> bool {
+        self.as_tagged().is_some()
+    }
+}
@@ -0,0 +1,336 @@
+//! Random values for property tests and fuzzing, built from bytes the way
+//! the `arbitrary` crate builds them, so any source of bytes (a seeded RNG,
+//! a fuzzer's input) drives it.
+//!
+//! Only values the parser reads back as themselves are built: symbols and
+//! keywords with readable names, and tags with no reader.
+//!
+//! ```
+//! use edn::arbitrary::{Arbitrary, Generator};
+//! use edn::Value;
+//!
+//! let mut generator = Generator::new(&[7, 1, 2, 3, 200, 9]).max_depth(2);
+//! let value = Value::arbitrary(&mut generator);
+//! assert_eq!(edn::parser::from_str(&edn::to_string(&value)), Ok(value.clone()));
+//! assert!(value.shrink().iter().all(|smaller| smaller != &value));
+//! ```
+
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::Vec;
+#[cfg(not(feature = "immutable"))]
+use standard::Vec;
+
+const CHARS: &[char] = &[
+    'a', 'z', 'A', '0', ' ', '\n', '\r', '\t', '"', '\\', '(', ']', '#', ',', ';', 'λ', 'é', '💡',
+    '\u{0}', '\u{8}', '\u{c}', '\u{7f}', '\u{a0}', '\u{2028}',
+];
+
+const NAME_CHARS: &[u8] = b"abcxyz-*+!?_<>=.0123";
+
+const TAGS: &[&str] = &["my/tag", "tag", "a.b/c-d"];
+
+/// A type which can be built from a `Generator`.
+pub trait Arbitrary: Sized {
+    fn arbitrary(generator: &mut Generator) -> Self;
+}
+
+impl Arbitrary for Value {
+    fn arbitrary(generator: &mut Generator) -> Value {
+        generator.value()
+    }
+}
+
+impl Arbitrary for Keyword {
+    fn arbitrary(generator: &mut Generator) -> Keyword {
+        generator.keyword()
+    }
+}
+
+impl Arbitrary for Symbol {
+    fn arbitrary(generator: &mut Generator) -> Symbol {
+        generator.symbol()
+    }
+}
+
+/// A source of choices. Once the bytes run out every choice is 0, which
+/// always leads to a small value.
+pub struct Generator<'a> {
+    bytes: &'a [u8],
+    max_depth: usize,
+    max_len: usize,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(bytes: &'a [u8]) -> Generator<'a> {
+        Generator {
+            bytes,
+            max_depth: 4,
+            max_len: 4,
+        }
+    }
+
+    /// The deepest a generated value nests, 4 by default.
+    pub fn max_depth(mut self, depth: usize) -> Generator<'a> {
+        self.max_depth = depth;
+        self
+    }
+
+    /// The most items in a generated collection, 4 by default.
+    pub fn max_len(mut self, len: usize) -> Generator<'a> {
+        self.max_len = len;
+        self
+    }
+
+    fn byte(&mut self) -> u8 {
+        match self.bytes.split_first() {
+            Some((&byte, rest)) => {
+                self.bytes = rest;
+                byte
+            }
+            None => 0,
+        }
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.byte() as usize % n
+    }
+
+    fn u64(&mut self) -> u64 {
+        (0..8).fold(0, |n, _| n << 8 | u64::from(self.byte()))
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::from(["a", "b", "x", "-a", "*"][self.below(5)]);
+        for _ in 0..self.below(6) {
+            name.push(NAME_CHARS[self.below(NAME_CHARS.len())] as char);
+        }
+        name
+    }
+
+    fn namespace(&mut self) -> Option<String> {
+        if self.below(3) == 0 {
+            Some(self.name())
+        } else {
+            None
+        }
+    }
+
+    fn string(&mut self) -> String {
+        (0..self.below(8))
+            .map(|_| CHARS[self.below(CHARS.len())])
+            .collect()
+    }
+
+    /// Half the time a short decimal like `-12.375`, and otherwise any
+    /// `f64` at all, including huge, tiny and non-finite ones.
+    fn float(&mut self) -> f64 {
+        if self.below(2) == 0 {
+            let mantissa = (self.u64() % 2_000_001) as f64 - 1_000_000.0;
+            mantissa / f64::from(1 << self.below(9))
+        } else {
+            f64::from_bits(self.u64())
+        }
+    }
+
+    fn items(&mut self, depth: usize) -> ::std::vec::Vec<Value> {
+        (0..self.below(self.max_len + 1))
+            .map(|_| self.value_at(depth + 1))
+            .collect()
+    }
+
+    pub fn keyword(&mut self) -> Keyword {
+        match self.namespace() {
+            Some(namespace) => Keyword::namespaced(&namespace, &self.name()),
+            None => Keyword::new(&self.name()),
+        }
+    }
+
+    pub fn symbol(&mut self) -> Symbol {
+        match self.namespace() {
+            Some(namespace) => Symbol::namespaced(&namespace, &self.name()),
+            None => Symbol::new(&self.name()),
+        }
+    }
+
+    /// An integer, a float, or a number of one of the types behind a
+    /// feature.
+    pub fn number(&mut self) -> Value {
+        match self.below(4) {
+            0 => Value::Integer(self.u64() as i64),
+            1 => Value::Integer(self.below(256) as i64 - 128),
+            2 => Value::from(self.float()),
+            _ => self.big_number(),
+        }
+    }
+
+    fn big_number(&mut self) -> Value {
+        let digits = format!("{}{}", self.u64() as i64, self.u64());
+        match self.below(3) {
+            #[cfg(feature = "bigint")]
+            0 => Value::BigInt(digits.parse().unwrap()),
+            #[cfg(feature = "ratio")]
+            1 => Value::ratio(self.u64() as i64, self.u64() % 1000).unwrap_or(Value::Nil),
+            #[cfg(feature = "bigdecimal")]
+            2 => Value::Decimal(format!("{}.{}", digits, self.below(1000)).parse().unwrap()),
+            _ => Value::Integer(digits.len() as i64),
+        }
+    }
+
+    /// A whole document: a collection or tagged element.
+    pub fn value(&mut self) -> Value {
+        self.value_at(0)
+    }
+
+    fn value_at(&mut self, depth: usize) -> Value {
+        // Collections and tags at the top, scalars at the bottom.
+        let kind = match depth {
+            0 if self.max_depth > 0 => 11 + self.below(6),
+            _ if depth >= self.max_depth => self.below(11),
+            _ => self.below(17),
+        };
+        match kind {
+            0 => Value::Nil,
+            1 => Value::Boolean(self.below(2) == 0),
+            2 => Value::String(self.string()),
+            3 => Value::Char(CHARS[self.below(CHARS.len())]),
+            4 => Value::Symbol(self.symbol()),
+            5 => Value::Keyword(self.keyword()),
+            6 => Value::Integer(self.u64() as i64),
+            7 => Value::Integer(self.below(256) as i64 - 128),
+            8 => Value::from(self.float()),
+            9 => self.big_number(),
+            10 => Value::Vector(Vec::new()),
+            11 => Value::List(self.items(depth).into_iter().collect()),
+            12 => Value::Vector(self.items(depth).into_iter().collect()),
+            13 => Value::Set(self.items(depth).into_iter().collect()),
+            14 | 15 => Value::Map(
+                self.items(depth)
+                    .into_iter()
+                    .map(|key| (key, self.value_at(depth + 1)))
+                    .collect(),
+            ),
+            _ => Value::Tagged(
+                TAGS[self.below(TAGS.len())].into(),
+                Box::new(self.value_at(depth + 1)),
+            ),
+        }
+    }
+}
+
+/// The items of a collection, then the collection with one removed, then
+/// with one shrunk, each rebuilt by `rebuild`.
+fn shrink_items<'a, I, F>(items: I, rebuild: F) -> ::std::vec::Vec<Value>
+where
+    I: Iterator<Item = &'a Value>,
+    F: Fn(::std::vec::Vec<Value>) -> Value,
+{
+    let items: ::std::vec::Vec<Value> = items.cloned().collect();
+    let mut smaller = items.clone();
+    for i in 0..items.len() {
+        let mut rest = items.clone();
+        rest.remove(i);
+        smaller.push(rebuild(rest));
+    }
+    for (i, item) in items.iter().enumerate() {
+        for shrunk in item.shrink() {
+            let mut items = items.clone();
+            items[i] = shrunk;
+            smaller.push(rebuild(items));
+        }
+    }
+    smaller
+}
+
+impl Value {
+    /// Values a little simpler than this one, simplest first, for a property
+    /// test to try in place of a failing value. Collections shrink to their
+    /// items, then to themselves with an item removed or shrunk; `nil` and
+    /// empty collections don't shrink.
+    pub fn shrink(&self) -> ::std::vec::Vec<Value> {
+        match *self {
+            Value::Nil => vec![],
+            Value::Boolean(boolean) if boolean => vec![Value::Boolean(false)],
+            Value::Boolean(_) => vec![],
+            Value::String(ref string) if string.is_empty() => vec![],
+            Value::String(ref string) => {
+                let half: String = string.chars().take(string.chars().count() / 2).collect();
+                let mut smaller = vec![Value::String(String::new())];
+                if !half.is_empty() {
+                    smaller.push(Value::String(half));
+                }
+                smaller
+            }
+            Value::Char('a') => vec![],
+            Value::Char(_) => vec![Value::Char('a')],
+            Value::Symbol(ref symbol) => match symbol.namespace() {
+                Some(_) => vec![Value::Symbol(Symbol::new(symbol.name()))],
+                None => vec![],
+            },
+            Value::Keyword(ref keyword) => match keyword.namespace() {
+                Some(_) => vec![Value::Keyword(Keyword::new(keyword.name()))],
+                None => vec![],
+            },
+            Value::Integer(0) => vec![],
+            Value::Integer(integer) if integer / 2 == 0 => vec![Value::Integer(0)],
+            Value::Integer(integer) => vec![Value::Integer(0), Value::Integer(integer / 2)],
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => vec![Value::Integer(0)],
+            #[cfg(feature = "ratio")]
+            Value::Ratio(numerator, denominator) => {
+                vec![
+                    Value::Integer(0),
+                    Value::Integer(numerator / denominator as i64),
+                ]
+            }
+            Value::Float(float) if float.into_inner() == 0.0 => vec![],
+            Value::Float(float) if float.into_inner().fract() == 0.0 => vec![Value::from(0.0)],
+            Value::Float(float) if !float.into_inner().is_finite() => vec![Value::from(0.0)],
+            Value::Float(float) => vec![Value::from(0.0), Value::from(float.into_inner().trunc())],
+            #[cfg(feature = "bigdecimal")]
+            Value::Decimal(_) => vec![Value::Integer(0)],
+            Value::List(ref items) => shrink_items(items.iter(), |items| {
+                Value::List(items.into_iter().collect())
+            }),
+            Value::Vector(ref items) => shrink_items(items.iter(), |items| {
+                Value::Vector(items.into_iter().collect())
+            }),
+            Value::Set(ref items) => shrink_items(items.iter(), |items| {
+                Value::Set(items.into_iter().collect())
+            }),
+            Value::Map(ref map) => {
+                let entries: ::std::vec::Vec<_> = map
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                let mut smaller: ::std::vec::Vec<_> =
+                    entries.iter().map(|(_, value)| value.clone()).collect();
+                for i in 0..entries.len() {
+                    let mut rest = entries.clone();
+                    rest.remove(i);
+                    smaller.push(Value::Map(rest.into_iter().collect()));
+                }
+                for (i, (_, value)) in entries.iter().enumerate() {
+                    for shrunk in value.shrink() {
+                        let mut entries = entries.clone();
+                        entries[i].1 = shrunk;
+                        smaller.push(Value::Map(entries.into_iter().collect()));
+                    }
+                }
+                smaller
+            }
+            Value::Tagged(ref tag, ref value) => {
+                let mut smaller = vec![(**value).clone()];
+                smaller.extend(
+                    value
+                        .shrink()
+                        .into_iter()
+                        .map(|value| Value::Tagged(tag.clone(), Box::new(value))),
+                );
+                smaller
+            }
+            Value::WithMeta(ref value, _) => vec![(**value).clone()],
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Exporting a collection of similarly shaped maps as an Avro object
+//! container file, e.g. to load EDN logs into analytics tools.
+//!
+//! Each map is a record with one field per key. Scalars keep their type and
+//! any other value is written as its EDN text in a string field. Blocks are
+//! written uncompressed.
+
+use std::fmt;
+use std::io;
+
+use ids::Fnv;
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::Map;
+#[cfg(not(feature = "immutable"))]
+use standard::Map;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error {
+            message: err.to_string(),
+        }
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Boolean,
+    Long,
+    Double,
+    /// Strings, and the EDN text of every other value.
+    String,
+}
+
+impl Kind {
+    pub(crate) fn of(value: &Value) -> Option<Kind> {
+        match *value {
+            Value::Nil => None,
+            Value::Boolean(_) => Some(Kind::Boolean),
+            Value::Integer(_) => Some(Kind::Long),
+            Value::Float(_) => Some(Kind::Double),
+            _ => Some(Kind::String),
+        }
+    }
+
+    pub(crate) fn merge(self, other: Kind) -> Kind {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Kind::Long, Kind::Double) | (Kind::Double, Kind::Long) => Kind::Double,
+            _ => Kind::String,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Boolean => "boolean",
+            Kind::Long => "long",
+            Kind::Double => "double",
+            Kind::String => "string",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    /// The Avro field name.
+    pub name: String,
+    /// The map key the field is read from.
+    pub key: Value,
+    pub kind: Kind,
+    /// Whether the field is a union with `null`, for missing and `nil` values.
+    pub nullable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+    /// The Avro record name.
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+/// `name` with every character Avro doesn't allow in names replaced by `_`.
+fn avro_name(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+    if out.is_empty() || out.starts_with(|ch: char| ch.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+pub(crate) fn rows(rows: &Value) -> Result<Vec<&Map<Value, Value>>, Error> {
+    match *rows {
+        Value::List(ref items) | Value::Vector(ref items) => items
+            .iter()
+            .map(|item| match *item {
+                Value::Map(ref map) => Ok(map),
+                _ => error(format!(
+                    "expected every row to be a map, found {}",
+                    item.kind()
+                )),
+            })
+            .collect(),
+        _ => error(format!(
+            "expected a list or vector of rows, found {}",
+            rows.kind()
+        )),
+    }
+}
+
+impl Schema {
+    /// A schema with a field for every keyword or string key in `rows`, in
+    /// order of first appearance. Under `immutable`, whose maps are
+    /// unordered, each row's keys are taken in sorted order. Fields missing from some rows or `nil` in
+    /// some are nullable, and fields with both integers and floats are
+    /// doubles.
+    pub fn infer(name: &str, rows: &Value) -> Result<Schema, Error> {
+        let rows = self::rows(rows)?;
+        let mut fields: Vec<Field> = vec![];
+        for row in &rows {
+            let mut entries: Vec<(&Value, &Value)> = row.iter().collect();
+            if cfg!(feature = "immutable") {
+                entries.sort_unstable_by_key(|entry| entry.0);
+            }
+            for (key, value) in entries {
+                let kind = Kind::of(value);
+                if let Some(field) = fields.iter_mut().find(|field| field.key == *key) {
+                    field.nullable |= kind.is_none();
+                    if let Some(kind) = kind {
+                        field.kind = field.kind.merge(kind);
+                    }
+                    continue;
+                }
+                let name = match *key {
+                    Value::Keyword(ref keyword) => avro_name(keyword.name()),
+                    Value::String(ref string) => avro_name(string),
+                    _ => {
+                        return error(format!(
+                            "expected keyword or string keys, found {}",
+                            key.kind()
+                        ))
+                    }
+                };
+                if fields.iter().any(|field| field.name == name) {
+                    return error(format!("more than one key maps to the field `{}`", name));
+                }
+                fields.push(Field {
+                    name,
+                    key: key.clone(),
+                    kind: kind.unwrap_or(Kind::String),
+                    nullable: kind.is_none(),
+                });
+            }
+        }
+        for field in &mut fields {
+            field.nullable |= rows.iter().any(|row| row.get(&field.key).is_none());
+        }
+        Ok(Schema {
+            name: avro_name(name),
+            fields,
+        })
+    }
+
+    /// The schema as Avro's JSON schema declaration.
+    pub fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| {
+                if field.nullable {
+                    format!(
+                        r#"{{"name":"{}","type":["null","{}"],"default":null}}"#,
+                        field.name,
+                        field.kind.name()
+                    )
+                } else {
+                    format!(
+                        r#"{{"name":"{}","type":"{}"}}"#,
+                        field.name,
+                        field.kind.name()
+                    )
+                }
+            })
+            .collect();
+        format!(
+            r#"{{"type":"record","name":"{}","fields":[{}]}}"#,
+            self.name,
+            fields.join(",")
+        )
+    }
+}
+
+fn write_long(out: &mut Vec<u8>, long: i64) {
+    let mut zigzag = ((long << 1) ^ (long >> 63)) as u64;
+    while zigzag >= 0x80 {
+        out.push((zigzag as u8) | 0x80);
+        zigzag >>= 7;
+    }
+    out.push(zigzag as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(out, bytes.len() as i64);
+    out.extend_from_slice(bytes);
+}
+
+fn write_field(out: &mut Vec<u8>, field: &Field, value: Option<&Value>) -> Result<(), Error> {
+    let value = match value {
+        None | Some(&Value::Nil) if field.nullable => {
+            write_long(out, 0);
+            return Ok(());
+        }
+        None | Some(&Value::Nil) => {
+            return error(format!("expected a value for the field `{}`", field.name))
+        }
+        Some(value) => value,
+    };
+    if field.nullable {
+        write_long(out, 1);
+    }
+    match (field.kind, value) {
+        (Kind::Boolean, &Value::Boolean(boolean)) => out.push(boolean as u8),
+        (Kind::Long, &Value::Integer(integer)) => write_long(out, integer),
+        (Kind::Double, &Value::Integer(integer)) => {
+            out.extend_from_slice(&(integer as f64).to_le_bytes())
+        }
+        (Kind::Double, &Value::Float(float)) => {
+            out.extend_from_slice(&float.into_inner().to_le_bytes())
+        }
+        (Kind::String, Value::String(string)) => write_bytes(out, string.as_bytes()),
+        (Kind::String, value) => {
+            let mut text = vec![];
+            ::ser::to_writer(&mut text, value)?;
+            write_bytes(out, &text);
+        }
+        (kind, value) => {
+            return error(format!(
+                "expected a {} for the field `{}`, found {}",
+                kind.name(),
+                field.name,
+                value.kind()
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Write `rows`, a list or vector of maps, as an Avro object container file
+/// with `schema`.
+///
+/// The sync marker is derived from the schema, so the same rows always
+/// produce the same bytes.
+pub fn to_writer<W: io::Write>(mut writer: W, schema: &Schema, rows: &Value) -> Result<(), Error> {
+    let rows = self::rows(rows)?;
+    let json = schema.to_json();
+
+    let mut sync = [0; 16];
+    for (i, chunk) in sync.chunks_mut(8).enumerate() {
+        let hash = Fnv::new().write_u64(i as u64).write(json.as_bytes()).0;
+        chunk.copy_from_slice(&hash.to_le_bytes());
+    }
+
+    let mut header = b"Obj\x01".to_vec();
+    write_long(&mut header, 2);
+    write_bytes(&mut header, b"avro.schema");
+    write_bytes(&mut header, json.as_bytes());
+    write_bytes(&mut header, b"avro.codec");
+    write_bytes(&mut header, b"null");
+    write_long(&mut header, 0);
+    header.extend_from_slice(&sync);
+    writer.write_all(&header)?;
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let mut records = vec![];
+    for row in &rows {
+        for field in &schema.fields {
+            write_field(&mut records, field, row.get(&field.key))?;
+        }
+    }
+    let mut block = vec![];
+    write_long(&mut block, rows.len() as i64);
+    write_long(&mut block, records.len() as i64);
+    writer.write_all(&block)?;
+    writer.write_all(&records)?;
+    writer.write_all(&sync)?;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::convert::TryFrom;
+
+use num_bigint::BigInt;
+
+use Value;
+
+impl From<BigInt> for Value {
+    fn from(s: BigInt) -> Self {
+        Value::BigInt(s)
+    }
+}
+
+/// An `Integer` if `integer` fits in an `i64`, or else a `BigInt`.
+impl From<i128> for Value {
+    fn from(integer: i128) -> Self {
+        match i64::try_from(integer) {
+            Ok(integer) => Value::Integer(integer),
+            Err(_) => Value::BigInt(BigInt::from(integer)),
+        }
+    }
+}
+
+/// An `Integer` if `integer` fits in an `i64`, or else a `BigInt`.
+impl From<u128> for Value {
+    fn from(integer: u128) -> Self {
+        match i64::try_from(integer) {
+            Ok(integer) => Value::Integer(integer),
+            Err(_) => Value::BigInt(BigInt::from(integer)),
+        }
+    }
+}
+
+impl Value {
+    /// The value of an `Integer` or `BigInt`, as a `BigInt`.
+    pub fn as_bigint(&self) -> Option<BigInt> {
+        match *self {
+            Value::Integer(integer) => Some(BigInt::from(integer)),
+            Value::BigInt(ref integer) => Some(integer.clone()),
+            _ => None,
+        }
+    }
+}
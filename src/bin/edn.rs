@@ -0,0 +1,119 @@
+//! The `edn` command, for working with EDN files from the shell.
+//!
+//! ```sh
+//! cargo install edn --features cli
+//! edn fmt config.edn
+//! ```
+//!
+//! Each command reads the file given, or stdin if there is none or it is
+//! `-`, and writes to stdout.
+
+extern crate edn;
+extern crate serde_json;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process;
+
+use edn::json;
+use edn::parser::Parser;
+use edn::Value;
+
+const USAGE: &str = "usage: edn <command> [file]
+
+commands:
+  fmt        pretty print every form
+  check      report every syntax error with its line and column
+  to-json    write every form as JSON
+  from-json  write every JSON value as EDN";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, path) = match args.len() {
+        1 => (args[0].as_str(), "-"),
+        2 => (args[0].as_str(), args[1].as_str()),
+        _ => usage(),
+    };
+    let input = match read(path) {
+        Ok(input) => input,
+        Err(err) => fail(&format!("{}: {}", path, err)),
+    };
+    let result = match command {
+        "fmt" => fmt(&input),
+        "check" => check(path, &input),
+        "to-json" => to_json(&input),
+        "from-json" => from_json(&input),
+        _ => usage(),
+    };
+    if let Err(message) = result {
+        fail(&format!("{}: {}", path, message));
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn read(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Every form in `input`, stopping at the first error.
+fn forms(input: &str) -> Result<Vec<Value>, String> {
+    Parser::new(input)
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())
+}
+
+fn fmt(input: &str) -> Result<(), String> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for form in forms(input)? {
+        edn::to_writer_pretty(&mut out, &form)
+            .and_then(|_| writeln!(out))
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+fn check(path: &str, input: &str) -> Result<(), String> {
+    let (_, diagnostics) = Parser::new(input).parse_with_recovery();
+    for diagnostic in &diagnostics {
+        eprintln!("{}:{}", path, diagnostic);
+    }
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        process::exit(1);
+    }
+}
+
+fn to_json(input: &str) -> Result<(), String> {
+    let options = json::Options::new();
+    for form in forms(input)? {
+        let json = options.to_json(&form).map_err(|err| err.to_string())?;
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+    }
+    Ok(())
+}
+
+fn from_json(input: &str) -> Result<(), String> {
+    for json in serde_json::Deserializer::from_str(input).into_iter::<serde_json::Value>() {
+        let json = json.map_err(|err| err.to_string())?;
+        println!("{}", edn::to_string_pretty(&Value::from(json)));
+    }
+    Ok(())
+}
@@ -0,0 +1,371 @@
+//! A `Value` borrowing its strings, keywords and symbols from the input, for
+//! workloads which parse a message, look at a few keys and throw it away.
+//!
+//! ```
+//! use edn::borrowed::{parse_borrowed, Value};
+//!
+//! let message = parse_borrowed("{:route :orders/new, :body [1 2 3]}").unwrap();
+//! assert_eq!(
+//!     message.get(&Value::Keyword("route")),
+//!     Some(&Value::Keyword("orders/new"))
+//! );
+//! let owned = message.into_owned().unwrap();
+//! ```
+//!
+//! Only collections allocate: maps are a `Vec` of entries in the order
+//! written, so nothing is hashed, and strings are only copied when they
+//! contain escapes. Numbers which don't fit an `i64` or `f64`, such as `1N`,
+//! `1.5M` and `22/7`, are kept as written until `into_owned`. Tagged
+//! elements are left to `into_owned` too, which applies the installed
+//! readers as `parser::from_str` does. Metadata is dropped.
+
+use std::borrow::Cow;
+
+use lexer::{Lexer, Span, Token};
+use parser::{self, char_literal, unicode_escape, Category, Error, DEFAULT_RECURSION_LIMIT};
+use readers;
+use symbol;
+use {Keyword, Symbol};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'a> {
+    Nil,
+    Boolean(bool),
+    /// A string, borrowed unless it contains escapes.
+    String(Cow<'a, str>),
+    Char(char),
+    Symbol(&'a str),
+    /// A keyword without the leading `:`, e.g. `person/name`.
+    Keyword(&'a str),
+    Integer(i64),
+    Float(f64),
+    /// A number which doesn't fit an `i64` or `f64`, as written.
+    Number(&'a str),
+    List(Vec<Value<'a>>),
+    Vector(Vec<Value<'a>>),
+    /// A map's entries in the order written.
+    Map(Vec<(Value<'a>, Value<'a>)>),
+    /// A set's items in the order written.
+    Set(Vec<Value<'a>>),
+    Tagged(&'a str, Box<Value<'a>>),
+}
+
+impl<'a> Value<'a> {
+    /// The value for `key` in a map, by a linear search.
+    pub fn get(&self, key: &Value) -> Option<&Value<'a>> {
+        match *self {
+            Value::Map(ref entries) => entries.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Value::Integer(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// The owned `Value`, as `parser::from_str` would have read it. Fails if
+    /// a `Number` can't be represented with the enabled features, or an
+    /// installed reader rejects a tagged element.
+    pub fn into_owned(self) -> Result<::Value, Error> {
+        Ok(match self {
+            Value::Nil => ::Value::Nil,
+            Value::Boolean(b) => ::Value::Boolean(b),
+            Value::String(s) => ::Value::String(s.into_owned()),
+            Value::Char(c) => ::Value::Char(c),
+            Value::Symbol(s) => ::Value::Symbol(Symbol::from(s)),
+            Value::Keyword(k) => ::Value::Keyword(Keyword::from(k)),
+            Value::Integer(i) => ::Value::Integer(i),
+            Value::Float(f) => ::Value::from(f),
+            Value::Number(text) => parser::from_str(text)?,
+            Value::List(items) => ::Value::List(owned(items)?),
+            Value::Vector(items) => ::Value::Vector(owned(items)?),
+            Value::Map(entries) => ::Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| Ok((k.into_owned()?, v.into_owned()?)))
+                    .collect::<Result<_, Error>>()?,
+            ),
+            Value::Set(items) => ::Value::Set(owned(items)?),
+            Value::Tagged(tag, value) => readers::read_installed(tag, value.into_owned()?)?,
+        })
+    }
+}
+
+fn owned<'a, C>(items: ::std::vec::Vec<Value<'a>>) -> Result<C, Error>
+where
+    C: ::std::iter::FromIterator<::Value>,
+{
+    items.into_iter().map(Value::into_owned).collect()
+}
+
+/// Parse exactly one form from `str`, borrowing from it.
+pub fn parse_borrowed(str: &str) -> Result<Value<'_>, Error> {
+    let mut reader = Reader {
+        str,
+        tokens: Lexer::new(str),
+        depth: 0,
+    };
+    let value = match reader.form()? {
+        Form::Value(value) => value,
+        Form::Close(span) => return reader.unexpected(span),
+        Form::End => {
+            return reader.error(
+                Span {
+                    lo: str.len(),
+                    hi: str.len(),
+                },
+                Category::Eof,
+                "expected a form, found EOF",
+            )
+        }
+    };
+    match reader.form()? {
+        Form::End => Ok(value),
+        Form::Value(_) | Form::Close(_) => {
+            let lo = str.len() - str.trim_start().len();
+            reader.error(
+                Span { lo, hi: str.len() },
+                Category::Syntax,
+                "expected exactly one form",
+            )
+        }
+    }
+}
+
+enum Form<'a> {
+    Value(Value<'a>),
+    /// A closing delimiter.
+    Close(Span),
+    End,
+}
+
+struct Reader<'a> {
+    str: &'a str,
+    tokens: Lexer<'a>,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error<T, M: Into<String>>(
+        &self,
+        span: Span,
+        category: Category,
+        message: M,
+    ) -> Result<T, Error> {
+        Err(Error::at(
+            self.str,
+            span.lo,
+            span.hi,
+            category,
+            message.into(),
+        ))
+    }
+
+    fn unexpected<T>(&self, span: Span) -> Result<T, Error> {
+        let text = &self.str[span.lo..span.hi];
+        self.error(span, Category::Syntax, format!("unexpected `{}`", text))
+    }
+
+    /// Fail on a symbol, keyword or tag which wouldn't be written back as
+    /// read, like the parser does.
+    fn invalid<T>(&self, span: Span, kind: &str) -> Result<T, Error> {
+        let text = &self.str[span.lo..span.hi];
+        self.error(
+            span,
+            Category::Syntax,
+            format!("invalid {} `{}`", kind, text),
+        )
+    }
+
+    /// The next form, skipping whitespace, comments and discarded forms.
+    fn form(&mut self) -> Result<Form<'a>, Error> {
+        loop {
+            let (token, span) = match self.tokens.next().transpose()? {
+                Some(next) => next,
+                None => return Ok(Form::End),
+            };
+            let value = match token {
+                Token::Whitespace(_) | Token::Comment(_) => continue,
+                Token::Discard => {
+                    self.prefixed(span)?;
+                    continue;
+                }
+                Token::Meta => {
+                    self.prefixed(span)?;
+                    self.prefixed(span)?
+                }
+                Token::CloseList | Token::CloseVector | Token::CloseMap => {
+                    return Ok(Form::Close(span))
+                }
+                Token::OpenList => Value::List(self.collection(span, ")")?),
+                Token::OpenVector => Value::Vector(self.collection(span, "]")?),
+                Token::OpenSet => Value::Set(self.collection(span, "}")?),
+                Token::OpenMap => {
+                    let items = self.collection(span, "}")?;
+                    if items.len() % 2 == 1 {
+                        return self.error(
+                            span,
+                            Category::Syntax,
+                            "a map needs a value for every key",
+                        );
+                    }
+                    let mut entries = Vec::with_capacity(items.len() / 2);
+                    let mut items = items.into_iter();
+                    while let (Some(k), Some(v)) = (items.next(), items.next()) {
+                        entries.push((k, v));
+                    }
+                    Value::Map(entries)
+                }
+                Token::Tag(tag) if !symbol::is_valid_tag(tag) => return self.invalid(span, "tag"),
+                Token::Tag(tag) => Value::Tagged(tag, Box::new(self.prefixed(span)?)),
+                Token::Nil => Value::Nil,
+                Token::Boolean(b) => Value::Boolean(b),
+                Token::Symbol(s) if !readable(s, false) => return self.invalid(span, "symbol"),
+                Token::Symbol(s) => Value::Symbol(s),
+                Token::Keyword(k) if !readable(k, true) => return self.invalid(span, "keyword"),
+                Token::Keyword(k) => Value::Keyword(k),
+                Token::Integer(text) => match text.parse() {
+                    Ok(i) => Value::Integer(i),
+                    Err(_) => Value::Number(text),
+                },
+                Token::Ratio(text) => Value::Number(text),
+                Token::Float(text) => float(text),
+                Token::String(text) => Value::String(self.unescape(text, span)?),
+                Token::Char(text) => match char_literal(text) {
+                    Some(c) => Value::Char(c),
+                    None => return self.unexpected(span),
+                },
+            };
+            return Ok(Form::Value(value));
+        }
+    }
+
+    fn enter(&mut self, span: Span) -> Result<(), Error> {
+        if self.depth >= DEFAULT_RECURSION_LIMIT {
+            return self.error(span, Category::Syntax, "recursion limit exceeded");
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// The items of a collection opened at `open`, up to `close`.
+    fn collection(&mut self, open: Span, close: &str) -> Result<Vec<Value<'a>>, Error> {
+        self.enter(open)?;
+        let mut items = vec![];
+        loop {
+            match self.form()? {
+                Form::Value(value) => items.push(value),
+                Form::Close(span) => {
+                    let text = &self.str[span.lo..span.hi];
+                    if text != close {
+                        return self.error(
+                            span,
+                            Category::Syntax,
+                            format!("unexpected `{}`, expected `{}`", text, close),
+                        );
+                    }
+                    self.depth -= 1;
+                    return Ok(items);
+                }
+                Form::End => {
+                    let open_text = &self.str[open.lo..open.hi];
+                    return self.error(
+                        Span {
+                            lo: open.lo,
+                            hi: self.str.len(),
+                        },
+                        Category::Eof,
+                        format!("unclosed `{}`", open_text),
+                    );
+                }
+            }
+        }
+    }
+
+    /// The form after a prefix at `prefix`, such as `#_` or a tag.
+    fn prefixed(&mut self, prefix: Span) -> Result<Value<'a>, Error> {
+        self.enter(prefix)?;
+        let value = match self.form()? {
+            Form::Value(value) => value,
+            Form::Close(span) => return self.unexpected(span),
+            Form::End => {
+                return self.error(
+                    Span {
+                        lo: prefix.lo,
+                        hi: self.str.len(),
+                    },
+                    Category::Eof,
+                    format!(
+                        "expected a form after `{}`, found EOF",
+                        &self.str[prefix.lo..prefix.hi]
+                    ),
+                )
+            }
+        };
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    /// The text of a string token with its escapes, which the lexer has
+    /// already checked, decoded.
+    fn unescape(&self, text: &'a str, span: Span) -> Result<Cow<'a, str>, Error> {
+        if !text.contains('\\') {
+            return Ok(Cow::Borrowed(text));
+        }
+        let mut s = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(i) = rest.find('\\') {
+            s.push_str(&rest[..i]);
+            rest = &rest[i + 1..];
+            let escape = rest.chars().next();
+            rest = &rest[escape.map_or(0, char::len_utf8)..];
+            s.push(match escape {
+                Some('t') => '\t',
+                Some('r') => '\r',
+                Some('n') => '\n',
+                Some('b') => '\u{8}',
+                Some('f') => '\u{c}',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                Some('u') => match unicode_escape(rest) {
+                    Some((c, len)) => {
+                        rest = &rest[len..];
+                        c
+                    }
+                    None => return self.unexpected(span),
+                },
+                _ => return self.unexpected(span),
+            });
+        }
+        s.push_str(rest);
+        Ok(Cow::Owned(s))
+    }
+}
+
+fn readable(text: &str, keyword: bool) -> bool {
+    let (namespace, name) = symbol::split_ref(text);
+    symbol::is_readable(namespace, name, keyword)
+}
+
+fn float(text: &str) -> Value<'_> {
+    match text {
+        "##Inf" => Value::Float(f64::INFINITY),
+        "##-Inf" => Value::Float(f64::NEG_INFINITY),
+        "##NaN" => Value::Float(f64::NAN),
+        _ => match text.parse() {
+            Ok(f) => Value::Float(f),
+            // e.g. a decimal with an `M` suffix.
+            Err(_) => Value::Number(text),
+        },
+    }
+}
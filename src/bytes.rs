@@ -0,0 +1,93 @@
+//! Byte arrays as base64 strings tagged `#edn.rs/base64`.
+//!
+//! EDN has no bytes type, and a `Vec<u8>` converted with `Value::from` is a
+//! vector of integers, about four times the size of its base64. `encode`
+//! writes the compact form instead and `Value::as_bytes` reads it back.
+//!
+//! ```
+//! use edn::bytes;
+//!
+//! let value = bytes::encode(b"edn");
+//! assert_eq!(edn::to_string(&value), "#edn.rs/base64 \"ZWRu\"");
+//! assert_eq!(value.as_bytes(), Some(b"edn".to_vec()));
+//! ```
+//!
+//! `encode_tagged` and `decode_tagged` use another tag, e.g. to match what a
+//! Clojure service registers in its `data_readers.clj`.
+
+use Value;
+
+pub const TAG: &str = "edn.rs/base64";
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// `bytes` as a padded base64 string tagged with `TAG`.
+pub fn encode(bytes: &[u8]) -> Value {
+    encode_tagged(bytes, TAG)
+}
+
+/// `bytes` as a padded base64 string tagged with `tag`.
+pub fn encode_tagged(bytes: &[u8], tag: &str) -> Value {
+    Value::Tagged(tag.into(), Box::new(Value::String(to_base64(bytes))))
+}
+
+/// The bytes of a base64 string tagged with `tag`.
+pub fn decode_tagged(value: &Value, tag: &str) -> Option<::std::vec::Vec<u8>> {
+    match *value {
+        Value::Tagged(ref t, ref value) if t == tag => match **value {
+            Value::String(ref string) => from_base64(string),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `bytes` in standard base64, padded with `=`.
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(group >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// The bytes of standard base64, with or without padding, or `None` if
+/// `string` isn't base64.
+pub fn from_base64(string: &str) -> Option<::std::vec::Vec<u8>> {
+    let digits = string.trim_end_matches('=');
+    if string.len() - digits.len() > 2
+        || (string.len() != digits.len() && !string.len().is_multiple_of(4))
+        || digits.len() % 4 == 1
+    {
+        return None;
+    }
+    let mut out = ::std::vec::Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.as_bytes().chunks(4) {
+        let mut group = 0u32;
+        for (i, &digit) in chunk.iter().enumerate() {
+            let index = ALPHABET.iter().position(|&ch| ch == digit)?;
+            group |= (index as u32) << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+impl Value {
+    /// The bytes of a base64 string tagged with `TAG`, as `encode` writes
+    /// them.
+    pub fn as_bytes(&self) -> Option<::std::vec::Vec<u8>> {
+        decode_tagged(self, TAG)
+    }
+}
@@ -0,0 +1,244 @@
+//! Finding and fixing keywords written in a different case from the rest of
+//! a document, e.g. `:fooBar` among `:foo-bar`s.
+//!
+//! ```
+//! use edn::casing::{self, Case};
+//!
+//! let str = "{:first-name \"Ada\" :lastName \"Lovelace\" :born-in 1815}";
+//! let audit = casing::audit(str).unwrap();
+//! assert_eq!(audit.convention, Some(Case::Kebab));
+//! assert_eq!(audit.findings[0].keyword, ":lastName");
+//! assert_eq!(audit.findings[0].suggestion, ":last-name");
+//! assert_eq!(
+//!     casing::rewrite(str, Case::Kebab).unwrap(),
+//!     "{:first-name \"Ada\" :last-name \"Lovelace\" :born-in 1815}"
+//! );
+//! ```
+//!
+//! Only the name of a keyword is checked, not its namespace, so `:my.app/fooBar`
+//! becomes `:my.app/foo-bar`.
+
+use lexer::{Lexer, Span, Token};
+use parser::Error;
+use {Keyword, Value};
+
+/// A way of joining the words of a name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Case {
+    /// `foo-bar`, usual in Clojure.
+    Kebab,
+    /// `foo_bar`.
+    Snake,
+    /// `fooBar`.
+    Camel,
+    /// `FooBar`.
+    Pascal,
+}
+
+impl Case {
+    /// The case `name` is written in, or `None` if it is a single lowercase
+    /// word, which fits several, or mixes them.
+    pub fn of(name: &str) -> Option<Case> {
+        let upper = name.chars().any(char::is_uppercase);
+        match (name.contains('-'), name.contains('_'), upper) {
+            (true, false, false) => Some(Case::Kebab),
+            (false, true, false) => Some(Case::Snake),
+            (false, false, true) if name.starts_with(char::is_uppercase) => Some(Case::Pascal),
+            (false, false, true) => Some(Case::Camel),
+            _ => None,
+        }
+    }
+
+    /// `name` in this case, e.g. `"fooBar"` for `Case::Kebab` is
+    /// `"foo-bar"`.
+    pub fn convert(self, name: &str) -> String {
+        let words = words(name);
+        let mut out = String::with_capacity(name.len());
+        for (i, word) in words.iter().enumerate() {
+            match self {
+                Case::Kebab | Case::Snake => {
+                    if i > 0 {
+                        out.push(if self == Case::Kebab { '-' } else { '_' });
+                    }
+                    out.push_str(&word.to_lowercase());
+                }
+                Case::Camel if i == 0 => out.push_str(&word.to_lowercase()),
+                Case::Camel | Case::Pascal => {
+                    let mut chars = word.chars();
+                    if let Some(first) = chars.next() {
+                        out.extend(first.to_uppercase());
+                        out.push_str(&chars.as_str().to_lowercase());
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// The words of `name`, split at `-`, `_` and where the case changes from
+/// lower to upper, or from upper to upper followed by lower as in `HTTPServer`.
+fn words(name: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = 0;
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    for (i, &(index, ch)) in chars.iter().enumerate() {
+        if ch == '-' || ch == '_' {
+            if index > start {
+                words.push(&name[start..index]);
+            }
+            start = index + ch.len_utf8();
+            continue;
+        }
+        let previous = if i > 0 { Some(chars[i - 1].1) } else { None };
+        let next = chars.get(i + 1).map(|&(_, ch)| ch);
+        let boundary = ch.is_uppercase()
+            && index > start
+            && previous.is_some_and(|previous| {
+                !previous.is_uppercase() || next.is_some_and(char::is_lowercase)
+            });
+        if boundary {
+            words.push(&name[start..index]);
+            start = index;
+        }
+    }
+    if start < name.len() {
+        words.push(&name[start..]);
+    }
+    words
+}
+
+/// A keyword whose name isn't in the document's convention.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// The keyword as written, e.g. `:lastName`.
+    pub keyword: String,
+    pub span: Span,
+    /// The case the keyword is written in, if it has a clear one.
+    pub case: Option<Case>,
+    /// The keyword in the document's convention.
+    pub suggestion: String,
+}
+
+/// The result of `audit`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Audit {
+    /// The case most keywords with a clear case are written in, preferring
+    /// kebab case on a tie, or `None` if no keyword has a clear case.
+    pub convention: Option<Case>,
+    /// The keywords not in `convention`, in the order they appear.
+    pub findings: Vec<Finding>,
+}
+
+/// The keyword tokens in `str` with their spans and the span of the name.
+fn keywords(str: &str) -> Result<Vec<(Span, Span)>, Error> {
+    let mut keywords = vec![];
+    for token in Lexer::new(str) {
+        if let (Token::Keyword(text), span) = token? {
+            let name = span.hi - text.len() + text.find('/').map_or(0, |slash| slash + 1);
+            keywords.push((
+                span,
+                Span {
+                    lo: name,
+                    hi: span.hi,
+                },
+            ));
+        }
+    }
+    Ok(keywords)
+}
+
+/// Find the case most keywords in `str` are written in and the keywords
+/// which aren't.
+pub fn audit(str: &str) -> Result<Audit, Error> {
+    let keywords = keywords(str)?;
+    let mut counts = [0; 4];
+    for &(_, name) in &keywords {
+        if let Some(case) = Case::of(&str[name.lo..name.hi]) {
+            counts[case as usize] += 1;
+        }
+    }
+    let convention = [Case::Kebab, Case::Snake, Case::Camel, Case::Pascal]
+        .iter()
+        .cloned()
+        .filter(|&case| counts[case as usize] > 0)
+        .max_by(|&a, &b| counts[a as usize].cmp(&counts[b as usize]).then(b.cmp(&a)));
+    let findings = match convention {
+        Some(convention) => check(str, &keywords, convention),
+        None => vec![],
+    };
+    Ok(Audit {
+        convention,
+        findings,
+    })
+}
+
+/// The keywords in `str` which aren't in `case`.
+pub fn check_case(str: &str, case: Case) -> Result<Vec<Finding>, Error> {
+    Ok(check(str, &keywords(str)?, case))
+}
+
+fn check(str: &str, keywords: &[(Span, Span)], convention: Case) -> Vec<Finding> {
+    keywords
+        .iter()
+        .filter_map(|&(span, name)| {
+            let converted = convention.convert(&str[name.lo..name.hi]);
+            if converted == str[name.lo..name.hi] {
+                return None;
+            }
+            Some(Finding {
+                keyword: str[span.lo..span.hi].into(),
+                span,
+                case: Case::of(&str[name.lo..name.hi]),
+                suggestion: format!("{}{}", &str[span.lo..name.lo], converted),
+            })
+        })
+        .collect()
+}
+
+/// `str` with every keyword converted to `case`, leaving everything else,
+/// including whitespace and comments, as it is.
+pub fn rewrite(str: &str, case: Case) -> Result<String, Error> {
+    let mut out = String::with_capacity(str.len());
+    let mut end = 0;
+    for finding in check_case(str, case)? {
+        out.push_str(&str[end..finding.span.lo]);
+        out.push_str(&finding.suggestion);
+        end = finding.span.hi;
+    }
+    out.push_str(&str[end..]);
+    Ok(out)
+}
+
+/// `value` with every keyword in it, including map keys, converted to
+/// `case`.
+pub fn convert(value: &Value, case: Case) -> Value {
+    match *value {
+        Value::Keyword(ref keyword) => {
+            let name = case.convert(keyword.name());
+            Value::Keyword(match keyword.namespace() {
+                Some(namespace) => Keyword::namespaced(namespace, &name),
+                None => Keyword::new(&name),
+            })
+        }
+        Value::List(ref items) => {
+            Value::List(items.iter().map(|item| convert(item, case)).collect())
+        }
+        Value::Vector(ref items) => {
+            Value::Vector(items.iter().map(|item| convert(item, case)).collect())
+        }
+        Value::Set(ref items) => Value::Set(items.iter().map(|item| convert(item, case)).collect()),
+        Value::Map(ref map) => Value::Map(
+            map.iter()
+                .map(|(key, value)| (convert(key, case), convert(value, case)))
+                .collect(),
+        ),
+        Value::Tagged(ref tag, ref value) => {
+            Value::Tagged(tag.clone(), Box::new(convert(value, case)))
+        }
+        Value::WithMeta(ref value, ref meta) => {
+            Value::WithMeta(Box::new(convert(value, case)), meta.clone())
+        }
+        ref other => other.clone(),
+    }
+}
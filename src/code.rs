@@ -0,0 +1,180 @@
+//! Building Clojure code as values, for programs which generate scripts or
+//! REPL input, instead of formatting strings.
+//!
+//! ```
+//! use edn::code::{call, fill, quote};
+//! use edn::parser::Parser;
+//! use edn::Value;
+//!
+//! let form = call("println", vec![quote(Value::Symbol("x".into()))]);
+//! let template = Parser::new("(let [x# (unquote v)] (f x# (unquote-splicing args)))")
+//!     .read()
+//!     .unwrap()
+//!     .unwrap();
+//! let code = fill(&template, &[("v", form), ("args", Value::vector(1..3))]).unwrap();
+//! // e.g. (let [x__1__auto__ (println (quote x))] (f x__1__auto__ 1 2))
+//! # let _ = code;
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use {Symbol, Value};
+
+/// The error returned by `fill`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+static GENSYM_COUNTER: AtomicUsize = AtomicUsize::new(1);
+
+/// A symbol which no other call returns, like Clojure's auto-gensyms:
+/// `gensym("x")` is `x__<n>__auto__`.
+pub fn gensym(prefix: &str) -> Symbol {
+    let n = GENSYM_COUNTER.fetch_add(1, Ordering::Relaxed);
+    Symbol::new(&format!("{}__{}__auto__", prefix, n))
+}
+
+/// The call `(function args...)`.
+pub fn call<I>(function: &str, args: I) -> Value
+where
+    I: IntoIterator,
+    Value: From<I::Item>,
+{
+    Value::List(
+        Some(Value::Symbol(function.into()))
+            .into_iter()
+            .chain(args.into_iter().map(Value::from))
+            .collect(),
+    )
+}
+
+/// `(quote form)`, which Clojure reads back as `form` itself.
+pub fn quote(form: Value) -> Value {
+    call("quote", vec![form])
+}
+
+/// `template` with its placeholders filled in, like the body of a Clojure
+/// syntax-quote:
+///
+/// - `(unquote name)` is replaced by the value bound to `name`.
+/// - `(unquote-splicing name)` in a list, vector or set is replaced by the
+///   items of the list or vector bound to `name`.
+/// - Every symbol ending with `#`, e.g. `x#`, is replaced by the same
+///   `gensym` throughout the template.
+pub fn fill(template: &Value, bindings: &[(&str, Value)]) -> Result<Value, Error> {
+    Filler {
+        bindings,
+        gensyms: BTreeMap::new(),
+    }
+    .fill(template)
+}
+
+struct Filler<'a> {
+    bindings: &'a [(&'a str, Value)],
+    gensyms: BTreeMap<String, Symbol>,
+}
+
+impl<'a> Filler<'a> {
+    fn fill(&mut self, value: &Value) -> Result<Value, Error> {
+        if let Some(name) = placeholder(value, "unquote") {
+            return self.binding(name).cloned();
+        }
+        if placeholder(value, "unquote-splicing").is_some() {
+            return error("unquote-splicing outside of a list, vector or set".into());
+        }
+        Ok(match *value {
+            Value::Symbol(ref symbol) if symbol.namespace().is_none() => {
+                match symbol.name().strip_suffix('#') {
+                    Some(prefix) if !prefix.is_empty() => Value::Symbol(
+                        self.gensyms
+                            .entry(prefix.to_string())
+                            .or_insert_with(|| gensym(prefix))
+                            .clone(),
+                    ),
+                    _ => value.clone(),
+                }
+            }
+            Value::List(ref items) => Value::List(self.items(items.iter())?.into_iter().collect()),
+            Value::Vector(ref items) => {
+                Value::Vector(self.items(items.iter())?.into_iter().collect())
+            }
+            Value::Set(ref items) => Value::Set(self.items(items.iter())?.into_iter().collect()),
+            Value::Map(ref map) => Value::Map(
+                map.iter()
+                    .map(|(key, value)| Ok((self.fill(key)?, self.fill(value)?)))
+                    .collect::<Result<_, Error>>()?,
+            ),
+            Value::Tagged(ref tag, ref value) => {
+                Value::Tagged(tag.clone(), Box::new(self.fill(value)?))
+            }
+            Value::WithMeta(ref value, ref meta) => {
+                Value::WithMeta(Box::new(self.fill(value)?), meta.clone())
+            }
+            _ => value.clone(),
+        })
+    }
+
+    /// `items` filled in, with `unquote-splicing` placeholders spliced.
+    fn items<'v, I>(&mut self, items: I) -> Result<::std::vec::Vec<Value>, Error>
+    where
+        I: Iterator<Item = &'v Value>,
+    {
+        let mut filled = vec![];
+        for item in items {
+            match placeholder(item, "unquote-splicing") {
+                Some(name) => match *self.binding(name)? {
+                    Value::List(ref items) | Value::Vector(ref items) => {
+                        filled.extend(items.iter().cloned())
+                    }
+                    ref other => {
+                        return error(format!(
+                            "expected a list or vector to splice for `{}`, found {}",
+                            name,
+                            other.kind()
+                        ))
+                    }
+                },
+                None => filled.push(self.fill(item)?),
+            }
+        }
+        Ok(filled)
+    }
+
+    fn binding(&self, name: &str) -> Result<&'a Value, Error> {
+        match self.bindings.iter().find(|&&(key, _)| key == name) {
+            Some((_, value)) => Ok(value),
+            None => error(format!("no value bound to `{}`", name)),
+        }
+    }
+}
+
+/// The name in `(operator name)`.
+fn placeholder<'v>(value: &'v Value, operator: &str) -> Option<&'v str> {
+    let mut items = match *value {
+        Value::List(ref items) if items.len() == 2 => items.iter(),
+        _ => return None,
+    };
+    match (items.next(), items.next()) {
+        (Some(Value::Symbol(head)), Some(Value::Symbol(name)))
+            if head.namespace().is_none()
+                && head.name() == operator
+                && name.namespace().is_none() =>
+        {
+            Some(name.name())
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,90 @@
+//! Where this crate reads EDN differently from `clojure.edn`, the reference
+//! reader, to help code moving between the two.
+//!
+//! Each `Divergence` gives an input, what `clojure.edn/read-string` makes of
+//! it and what `parser::from_str` makes of it with the features this crate
+//! was built with. The tests check the second against the parser, so the
+//! table stays accurate as it changes.
+//!
+//! ```
+//! let divergence = edn::compat::divergence("{:a 1 :a 2}").unwrap();
+//! assert_eq!(divergence.clojure, "ERR Duplicate key: :a");
+//! assert_eq!(divergence.ours, &["{:a 2}"]);
+//! ```
+
+/// Input this crate reads differently from `clojure.edn`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub input: &'static str,
+    /// What `clojure.edn` reads, written back as EDN, or `ERR` and the
+    /// message of the exception it throws.
+    pub clojure: &'static str,
+    /// Each form this crate reads, written back as compact EDN, or `ERR`
+    /// and the message of the error it stops at.
+    pub ours: &'static [&'static str],
+    /// Why they differ, and how to get `clojure.edn`'s behavior if possible.
+    pub reason: &'static str,
+}
+
+/// Every known divergence for this build.
+pub const DIVERGENCES: &[Divergence] = &[
+    Divergence {
+        input: "{:a 1 :a 2}",
+        clojure: "ERR Duplicate key: :a",
+        ours: &["{:a 2}"],
+        reason: "the last entry wins; use `DuplicateKeyPolicy::Error` to fail",
+    },
+    Divergence {
+        input: "#{1 1}",
+        clojure: "ERR Duplicate key: 1",
+        ours: &["#{1}"],
+        reason: "duplicates are dropped; use `DuplicateKeyPolicy::Error` to fail",
+    },
+    Divergence {
+        input: "#myapp/Person {:first \"Fred\"}",
+        clojure: "ERR No reader function for tag myapp/Person",
+        ours: &["#myapp/Person {:first \"Fred\"}"],
+        reason: "tags without a reader are kept as `Value::Tagged`",
+    },
+    Divergence {
+        input: "\\o101",
+        clojure: "\\A",
+        ours: &["ERR invalid char literal `\\o101`"],
+        reason: "octal char literals are Clojure syntax, not EDN",
+    },
+    #[cfg(not(feature = "bigint"))]
+    Divergence {
+        input: "1N",
+        clojure: "1N",
+        ours: &["1"],
+        reason: "`N` is ignored without the `bigint` feature",
+    },
+    #[cfg(not(feature = "bigint"))]
+    Divergence {
+        input: "9223372036854775808",
+        clojure: "9223372036854775808N",
+        ours: &["ERR integer `9223372036854775808` out of range"],
+        reason: "integers wider than `i64` need the `bigint` feature",
+    },
+    #[cfg(not(feature = "bigdecimal"))]
+    Divergence {
+        input: "1.5M",
+        clojure: "1.5M",
+        ours: &["1.5"],
+        reason: "`M` is ignored without the `bigdecimal` feature",
+    },
+    #[cfg(not(feature = "ratio"))]
+    Divergence {
+        input: "1/2",
+        clojure: "1/2",
+        ours: &["1", "/", "2"],
+        reason: "ratios need the `ratio` feature",
+    },
+];
+
+/// The divergence for `input`, if it's a known one.
+pub fn divergence(input: &str) -> Option<&'static Divergence> {
+    DIVERGENCES
+        .iter()
+        .find(|divergence| divergence.input == input)
+}
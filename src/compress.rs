@@ -0,0 +1,211 @@
+//! Shrinking keyword-heavy documents for the wire by writing each frequent
+//! keyword once, in a dictionary, and referring to it by its index.
+//!
+//! ```
+//! use edn::{compress, parser};
+//!
+//! let value = parser::from_str(
+//!     "[{:user/name \"a\" :user/email \"a@x\"} {:user/name \"b\" :user/email \"b@x\"}]",
+//! )
+//! .unwrap();
+//! let text = compress::to_string(&value);
+//! assert!(text.starts_with("#edn/keywords [[:user/email :user/name] [{"));
+//! assert_eq!(compress::from_str(&text).unwrap(), value);
+//! ```
+//!
+//! An encoded document is `#edn/keywords [dictionary value]`, where
+//! `dictionary` is a vector of keywords and each `:0`, `:1`, ... in `value`
+//! stands for the keyword at that index. Keywords which look like references
+//! are always put in the dictionary, so they come back as themselves.
+//! Register the reader with `register` to decode such documents anywhere a
+//! `ReaderRegistry` is used.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use parser::{Error, ParserBuilder};
+use readers::ReaderRegistry;
+use {Keyword, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::Vec;
+#[cfg(not(feature = "immutable"))]
+use standard::Vec;
+
+/// The tag of an encoded document.
+pub const TAG: &str = "edn/keywords";
+
+/// `value` with its frequent keywords replaced by references to a
+/// dictionary, or `value` as it is if no keyword is worth replacing.
+pub fn encode(value: &Value) -> Value {
+    let mut counts = BTreeMap::new();
+    count(value, &mut counts);
+    let mut candidates: ::std::vec::Vec<(&Keyword, usize)> = counts.into_iter().collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    let mut dictionary = ::std::vec::Vec::new();
+    let mut references = BTreeMap::new();
+    for (keyword, count) in candidates {
+        let len = keyword.to_string().len();
+        let reference = Keyword::new(&dictionary.len().to_string());
+        let saved = count * len.saturating_sub(reference.to_string().len());
+        if saved > len + 1 || is_reference(keyword) {
+            references.insert(keyword.clone(), reference);
+            dictionary.push(Value::Keyword(keyword.clone()));
+        }
+    }
+    if dictionary.is_empty() {
+        return value.clone();
+    }
+    let value = replace(
+        value.clone(),
+        &|keyword: Keyword| -> Result<_, Infallible> {
+            Ok(references.get(&keyword).cloned().unwrap_or(keyword))
+        },
+    )
+    .unwrap_or_else(|never| match never {});
+    Value::Tagged(
+        TAG.into(),
+        Box::new(Value::Vector(Vec::from(vec![
+            Value::Vector(dictionary.into_iter().collect()),
+            value,
+        ]))),
+    )
+}
+
+/// `value` with the references in an encoded document replaced by their
+/// keywords, or `value` as it is if it isn't encoded.
+pub fn decode(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::Tagged(ref tag, ref payload) if tag == TAG => expand((**payload).clone()),
+        value => Ok(value),
+    }
+}
+
+/// `value` encoded, as compact EDN.
+pub fn to_string(value: &Value) -> String {
+    ::ser::to_string(&encode(value))
+}
+
+/// Parse exactly one form from `str`, decoding it if it is encoded.
+pub fn from_str(str: &str) -> Result<Value, Error> {
+    let mut readers = ReaderRegistry::new();
+    register(&mut readers);
+    ParserBuilder::new().readers(&readers).from_str(str)
+}
+
+/// Register the reader for `#edn/keywords` in `readers`.
+pub fn register(readers: &mut ReaderRegistry) {
+    readers.register(TAG, expand);
+}
+
+/// Decode the `[dictionary value]` of an encoded document.
+fn expand(payload: Value) -> Result<Value, Error> {
+    let (dictionary, value) = match payload {
+        Value::Vector(items) if items.len() == 2 => {
+            let mut items = items.into_iter();
+            let dictionary = items.next().expect("two items");
+            (dictionary, items.next().expect("two items"))
+        }
+        _ => {
+            return Err(Error::data(format!(
+                "expected [dictionary value] after #{}",
+                TAG
+            )))
+        }
+    };
+    let dictionary = match dictionary {
+        Value::Vector(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::Keyword(keyword) => Ok(keyword),
+                _ => Err(Error::data(
+                    "expected a vector of keywords as the dictionary",
+                )),
+            })
+            .collect::<Result<::std::vec::Vec<Keyword>, Error>>()?,
+        _ => {
+            return Err(Error::data(
+                "expected a vector of keywords as the dictionary",
+            ))
+        }
+    };
+    replace(value, &|keyword: Keyword| {
+        if !is_reference(&keyword) {
+            return Ok(keyword);
+        }
+        keyword
+            .name()
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| dictionary.get(index))
+            .cloned()
+            .ok_or_else(|| Error::data(format!("no keyword in the dictionary for {}", keyword)))
+    })
+}
+
+/// Whether `keyword` looks like a reference, e.g. `:12`.
+fn is_reference(keyword: &Keyword) -> bool {
+    keyword.namespace().is_none()
+        && !keyword.name().is_empty()
+        && keyword.name().bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn count<'a>(value: &'a Value, counts: &mut BTreeMap<&'a Keyword, usize>) {
+    match *value {
+        Value::Keyword(ref keyword) => *counts.entry(keyword).or_insert(0) += 1,
+        Value::List(ref items) | Value::Vector(ref items) => {
+            items.iter().for_each(|item| count(item, counts))
+        }
+        Value::Set(ref items) => items.iter().for_each(|item| count(item, counts)),
+        Value::Map(ref map) => map.iter().for_each(|(key, value)| {
+            count(key, counts);
+            count(value, counts);
+        }),
+        Value::Tagged(_, ref value) => count(value, counts),
+        Value::WithMeta(ref value, ref meta) => {
+            count(value, counts);
+            meta.iter().for_each(|(key, value)| {
+                count(key, counts);
+                count(value, counts);
+            });
+        }
+        _ => {}
+    }
+}
+
+/// `value` with every keyword in it passed through `f`.
+fn replace<F, E>(value: Value, f: &F) -> Result<Value, E>
+where
+    F: Fn(Keyword) -> Result<Keyword, E>,
+{
+    let entries = |map: ::Map<Value, Value>| {
+        map.into_iter()
+            .map(|(key, value)| Ok((replace(key, f)?, replace(value, f)?)))
+            .collect::<Result<_, E>>()
+    };
+    Ok(match value {
+        Value::Keyword(keyword) => Value::Keyword(f(keyword)?),
+        Value::List(items) => Value::List(replace_all(items, f)?),
+        Value::Vector(items) => Value::Vector(replace_all(items, f)?),
+        Value::Set(items) => Value::Set(
+            items
+                .into_iter()
+                .map(|item| replace(item, f))
+                .collect::<Result<_, E>>()?,
+        ),
+        Value::Map(map) => Value::Map(entries(map)?),
+        Value::Tagged(tag, value) => Value::Tagged(tag, Box::new(replace(*value, f)?)),
+        Value::WithMeta(value, meta) => {
+            Value::WithMeta(Box::new(replace(*value, f)?), entries(meta)?)
+        }
+        other => other,
+    })
+}
+
+fn replace_all<F, E>(items: Vec<Value>, f: &F) -> Result<Vec<Value>, E>
+where
+    F: Fn(Keyword) -> Result<Keyword, E>,
+{
+    items.into_iter().map(|item| replace(item, f)).collect()
+}
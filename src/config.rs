@@ -0,0 +1,260 @@
+//! Loading configuration split across several EDN files.
+//!
+//! `load_dir("config/**/*.edn")` reads every matching file, in sorted order,
+//! and merges their maps, with nested maps merged and later files winning
+//! otherwise. Two tags are resolved while loading:
+//!
+//! - `#include "db.edn"` is replaced by the contents of that file, relative
+//!   to the file it's in.
+//! - `#ref [:db :port]` is replaced by the value at that path in the merged
+//!   configuration.
+//!
+//! Patterns support `*` and `?` within a path segment and `**` for any
+//! number of directories.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use merge::{merge, Conflict};
+use parser;
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Set, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Set, Vec};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+/// Whether `name` matches `pattern`, a single path segment with `*` and `?`.
+fn matches_segment(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((&'*', rest)) => (0..=name.len()).any(|i| matches_segment(rest, &name[i..])),
+        Some((&'?', rest)) => !name.is_empty() && matches_segment(rest, &name[1..]),
+        Some((ch, rest)) => name.first() == Some(ch) && matches_segment(rest, &name[1..]),
+    }
+}
+
+fn matches(pattern: &[::std::vec::Vec<char>], path: &[::std::vec::Vec<char>]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((segment, rest)) if *segment == ['*', '*'] => {
+            (0..=path.len()).any(|i| matches(rest, &path[i..]))
+        }
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path)) => matches_segment(segment, name) && matches(rest, path),
+            None => false,
+        },
+    }
+}
+
+/// The files matching `pattern`, sorted.
+pub fn glob(pattern: &str) -> Result<::std::vec::Vec<PathBuf>, Error> {
+    let segments: ::std::vec::Vec<&str> = pattern.split('/').collect();
+    let literal = segments
+        .iter()
+        .take_while(|segment| !segment.contains(['*', '?']))
+        .count();
+    if literal == segments.len() {
+        return Ok(vec![PathBuf::from(pattern)]);
+    }
+    let base = match segments[..literal].join("/") {
+        ref base if base.is_empty() && pattern.starts_with('/') => PathBuf::from("/"),
+        ref base if base.is_empty() => PathBuf::from("."),
+        base => PathBuf::from(base),
+    };
+    let pattern: ::std::vec::Vec<::std::vec::Vec<char>> = segments[literal..]
+        .iter()
+        .map(|segment| segment.chars().collect())
+        .collect();
+
+    let mut files = vec![];
+    let mut dirs = vec![base.clone()];
+    while let Some(dir) = dirs.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|err| Error {
+                message: format!("{}: {}", dir.display(), err),
+            })?
+            .filter_map(Result::ok);
+        for entry in entries {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let relative: ::std::vec::Vec<::std::vec::Vec<char>> = path
+                .strip_prefix(&base)
+                .expect("walked paths are under the base")
+                .iter()
+                .map(|segment| segment.to_string_lossy().chars().collect())
+                .collect();
+            if matches(&pattern, &relative) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read `path`, replacing `#include`s. `stack` is the files being included,
+/// to detect cycles.
+fn read_file(path: &Path, stack: &mut ::std::vec::Vec<PathBuf>) -> Result<Value, Error> {
+    let canonical = fs::canonicalize(path).map_err(|err| Error {
+        message: format!("{}: {}", path.display(), err),
+    })?;
+    if stack.contains(&canonical) {
+        return error(format!("{} includes itself", path.display()));
+    }
+    let str = fs::read_to_string(path).map_err(|err| Error {
+        message: format!("{}: {}", path.display(), err),
+    })?;
+    let value = parser::from_str(&str).map_err(|err| Error {
+        message: format!("{}: {}", path.display(), err),
+    })?;
+    stack.push(canonical);
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let value = map_tagged(&value, &mut |tag, value| match (tag, value) {
+        ("include", Value::String(file)) => Some(read_file(&dir.join(file), stack)),
+        ("include", _) => Some(error(format!(
+            "{}: expected a string after #include, found {}",
+            path.display(),
+            value.kind()
+        ))),
+        _ => None,
+    });
+    stack.pop();
+    value
+}
+
+/// A copy of `value` with each tagged element for which `f` returns a result
+/// replaced by that result.
+fn map_tagged<F>(value: &Value, f: &mut F) -> Result<Value, Error>
+where
+    F: FnMut(&str, &Value) -> Option<Result<Value, Error>>,
+{
+    Ok(match *value {
+        Value::List(ref items) => Value::List(
+            items
+                .iter()
+                .map(|item| map_tagged(item, f))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Value::Vector(ref items) => Value::Vector(
+            items
+                .iter()
+                .map(|item| map_tagged(item, f))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Value::Set(ref items) => Value::Set(
+            items
+                .iter()
+                .map(|item| map_tagged(item, f))
+                .collect::<Result<Set<_>, _>>()?,
+        ),
+        Value::Map(ref map) => Value::Map(
+            map.iter()
+                .map(|(key, value)| Ok((key.clone(), map_tagged(value, f)?)))
+                .collect::<Result<Map<_, _>, _>>()?,
+        ),
+        Value::Tagged(ref tag, ref inner) => match f(tag, inner) {
+            Some(result) => result?,
+            None => Value::Tagged(tag.clone(), Box::new(map_tagged(inner, f)?)),
+        },
+        Value::WithMeta(ref inner, ref meta) => {
+            Value::WithMeta(Box::new(map_tagged(inner, f)?), meta.clone())
+        }
+        ref value => value.clone(),
+    })
+}
+
+fn edn(value: &Value) -> String {
+    ::ser::to_string(value)
+}
+
+fn lookup<'a>(root: &'a Value, path: &Value) -> Option<&'a Value> {
+    let keys = match *path {
+        Value::Vector(ref keys) | Value::List(ref keys) => keys,
+        _ => return None,
+    };
+    keys.iter().try_fold(root, |value, key| match (value, key) {
+        (Value::Map(map), key) => map.get(key),
+        (Value::Vector(items), Value::Integer(i)) => items.get(*i as usize),
+        _ => None,
+    })
+}
+
+/// Replace the `#ref`s in `value` with what they point to in `root`. `stack`
+/// is the refs being resolved, to detect cycles.
+fn resolve_refs(
+    root: &Value,
+    value: &Value,
+    stack: &mut ::std::vec::Vec<Value>,
+) -> Result<Value, Error> {
+    map_tagged(value, &mut |tag, path| {
+        if tag != "ref" {
+            return None;
+        }
+        if stack.contains(path) {
+            let cycle: ::std::vec::Vec<String> = stack.iter().chain(Some(path)).map(edn).collect();
+            return Some(error(format!("#ref cycle {}", cycle.join(" -> "))));
+        }
+        let target = match lookup(root, path) {
+            Some(target) => target,
+            None => return Some(error(format!("#ref {} not found", edn(path)))),
+        };
+        stack.push(path.clone());
+        let result = resolve_refs(root, target, stack);
+        stack.pop();
+        Some(result)
+    })
+}
+
+/// Load and merge every file matching `pattern`.
+pub fn load_dir(pattern: &str) -> Result<Value, Error> {
+    let files = glob(pattern)?;
+    if files.is_empty() {
+        return error(format!("no files match `{}`", pattern));
+    }
+    let mut merged = Value::Map(Map::new());
+    for file in &files {
+        match read_file(file, &mut vec![])? {
+            value @ Value::Map(_) => merged = merge(merged, value, Conflict::Replace),
+            value => {
+                return error(format!(
+                    "{}: expected a map, found {}",
+                    file.display(),
+                    value.kind()
+                ))
+            }
+        }
+    }
+    resolve_refs(&merged, &merged, &mut vec![])
+}
+
+/// Load and merge every file matching `pattern` and convert the result.
+pub fn load_dir_as<T>(pattern: &str) -> Result<T, Error>
+where
+    T: TryFrom<Value>,
+    T::Error: fmt::Display,
+{
+    T::try_from(load_dir(pattern)?).map_err(|err| Error {
+        message: err.to_string(),
+    })
+}
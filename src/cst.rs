@@ -0,0 +1,289 @@
+//! A concrete syntax tree which keeps whitespace, commas, comments and
+//! discarded forms, so text can be edited without losing any of them.
+//!
+//! ```
+//! use edn::cst::Cst;
+//!
+//! let str = "{:a 1, ; the answer\n :b #_2 42}";
+//! assert_eq!(Cst::parse(str).unwrap().to_string(), str);
+//! ```
+//!
+//! Only the syntax is checked, so e.g. a map with an odd number of forms is
+//! fine here but an error for the parser.
+
+use std::fmt;
+
+use lexer::{Lexer, Span, Token};
+use parser::{Category, Error, DEFAULT_RECURSION_LIMIT};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delimiter {
+    List,
+    Vector,
+    Map,
+    Set,
+}
+
+impl Delimiter {
+    pub fn open(self) -> &'static str {
+        match self {
+            Delimiter::List => "(",
+            Delimiter::Vector => "[",
+            Delimiter::Map => "{",
+            Delimiter::Set => "#{",
+        }
+    }
+
+    pub fn close(self) -> &'static str {
+        match self {
+            Delimiter::List => ")",
+            Delimiter::Vector => "]",
+            Delimiter::Map | Delimiter::Set => "}",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    /// Whitespace, including commas.
+    Whitespace(String),
+    /// A comment including the leading `;`.
+    Comment(String),
+    /// Any other form which isn't a collection, as written.
+    Atom(String),
+    Collection {
+        delimiter: Delimiter,
+        children: Vec<Node>,
+    },
+    /// `#tag` followed by one form. `children` includes the whitespace and
+    /// comments before it.
+    Tagged { tag: String, children: Vec<Node> },
+    /// `^` followed by the metadata and the form it's attached to.
+    Meta { children: Vec<Node> },
+    /// `#_` followed by the form it discards.
+    Discard { children: Vec<Node> },
+}
+
+impl Node {
+    /// Whether this node is read as a value by the parser, rather than being
+    /// whitespace, a comment or a discarded form.
+    pub fn is_form(&self) -> bool {
+        !matches!(
+            *self,
+            Node::Whitespace(_) | Node::Comment(_) | Node::Discard { .. }
+        )
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let children = match *self {
+            Node::Whitespace(ref text) | Node::Comment(ref text) | Node::Atom(ref text) => {
+                return f.write_str(text)
+            }
+            Node::Collection {
+                delimiter,
+                ref children,
+            } => {
+                f.write_str(delimiter.open())?;
+                write_all(f, children)?;
+                return f.write_str(delimiter.close());
+            }
+            Node::Tagged {
+                ref tag,
+                ref children,
+            } => {
+                write!(f, "#{}", tag)?;
+                children
+            }
+            Node::Meta { ref children } => {
+                f.write_str("^")?;
+                children
+            }
+            Node::Discard { ref children } => {
+                f.write_str("#_")?;
+                children
+            }
+        };
+        write_all(f, children)
+    }
+}
+
+fn write_all(f: &mut fmt::Formatter, nodes: &[Node]) -> fmt::Result {
+    for node in nodes {
+        write!(f, "{}", node)?;
+    }
+    Ok(())
+}
+
+/// A whole input, which `to_string` reproduces byte for byte.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cst {
+    pub nodes: Vec<Node>,
+}
+
+impl Cst {
+    pub fn parse(str: &str) -> Result<Cst, Error> {
+        let mut builder = Builder {
+            str,
+            tokens: Lexer::new(str),
+            depth: 0,
+        };
+        let mut nodes = vec![];
+        while let Some((token, span)) = builder.next()? {
+            nodes.push(builder.node(token, span)?);
+        }
+        Ok(Cst { nodes })
+    }
+
+    /// The top-level nodes read as values by the parser.
+    pub fn forms(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter(|node| node.is_form())
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_all(f, &self.nodes)
+    }
+}
+
+struct Builder<'a> {
+    str: &'a str,
+    tokens: Lexer<'a>,
+    depth: usize,
+}
+
+impl<'a> Builder<'a> {
+    fn next(&mut self) -> Result<Option<(Token<'a>, Span)>, Error> {
+        self.tokens.next().transpose()
+    }
+
+    fn error<T, M: Into<String>>(
+        &self,
+        span: Span,
+        category: Category,
+        message: M,
+    ) -> Result<T, Error> {
+        Err(Error::at(
+            self.str,
+            span.lo,
+            span.hi,
+            category,
+            message.into(),
+        ))
+    }
+
+    fn node(&mut self, token: Token<'a>, span: Span) -> Result<Node, Error> {
+        let text = &self.str[span.lo..span.hi];
+        Ok(match token {
+            Token::Whitespace(_) => Node::Whitespace(text.into()),
+            Token::Comment(_) => Node::Comment(text.into()),
+            Token::OpenList => self.collection(Delimiter::List, span)?,
+            Token::OpenVector => self.collection(Delimiter::Vector, span)?,
+            Token::OpenMap => self.collection(Delimiter::Map, span)?,
+            Token::OpenSet => self.collection(Delimiter::Set, span)?,
+            Token::CloseList | Token::CloseVector | Token::CloseMap => {
+                return self.error(span, Category::Syntax, format!("unexpected `{}`", text))
+            }
+            Token::Tag(tag) => Node::Tagged {
+                tag: tag.into(),
+                children: self.prefixed(span, 1)?,
+            },
+            Token::Meta => Node::Meta {
+                children: self.prefixed(span, 2)?,
+            },
+            Token::Discard => Node::Discard {
+                children: self.prefixed(span, 1)?,
+            },
+            _ => Node::Atom(text.into()),
+        })
+    }
+
+    fn enter(&mut self, span: Span) -> Result<(), Error> {
+        if self.depth >= DEFAULT_RECURSION_LIMIT {
+            return self.error(span, Category::Syntax, "recursion limit exceeded");
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn collection(&mut self, delimiter: Delimiter, open: Span) -> Result<Node, Error> {
+        self.enter(open)?;
+        let mut children = vec![];
+        loop {
+            match self.next()? {
+                Some((token, span)) => match token {
+                    Token::CloseList | Token::CloseVector | Token::CloseMap => {
+                        let text = &self.str[span.lo..span.hi];
+                        if text != delimiter.close() {
+                            return self.error(
+                                span,
+                                Category::Syntax,
+                                format!("unexpected `{}`, expected `{}`", text, delimiter.close()),
+                            );
+                        }
+                        self.depth -= 1;
+                        return Ok(Node::Collection {
+                            delimiter,
+                            children,
+                        });
+                    }
+                    _ => children.push(self.node(token, span)?),
+                },
+                None => {
+                    return self.error(
+                        Span {
+                            lo: open.lo,
+                            hi: self.str.len(),
+                        },
+                        Category::Eof,
+                        format!("unclosed `{}`", delimiter.open()),
+                    )
+                }
+            }
+        }
+    }
+
+    /// The nodes after a prefix at `prefix`, up to and including `forms`
+    /// forms.
+    fn prefixed(&mut self, prefix: Span, mut forms: usize) -> Result<Vec<Node>, Error> {
+        self.enter(prefix)?;
+        let mut children = vec![];
+        while forms > 0 {
+            match self.next()? {
+                Some((Token::CloseList, span))
+                | Some((Token::CloseVector, span))
+                | Some((Token::CloseMap, span)) => {
+                    return self.error(
+                        span,
+                        Category::Syntax,
+                        format!("unexpected `{}`", &self.str[span.lo..span.hi]),
+                    )
+                }
+                Some((token, span)) => {
+                    let node = self.node(token, span)?;
+                    if node.is_form() {
+                        forms -= 1;
+                    }
+                    children.push(node);
+                }
+                None => {
+                    return self.error(
+                        Span {
+                            lo: prefix.lo,
+                            hi: self.str.len(),
+                        },
+                        Category::Eof,
+                        format!(
+                            "expected a form after `{}`, found EOF",
+                            &self.str[prefix.lo..prefix.hi]
+                        ),
+                    )
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(children)
+    }
+}
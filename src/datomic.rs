@@ -0,0 +1,119 @@
+//! Typed views of Datomic transaction reports exported as EDN.
+//!
+//! A datom is printed either as a tagged `#datom [e a v tx added]` element or
+//! as a plain `[e a v tx added]` vector inside `:tx-data`.
+
+use parser::Error;
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Datom {
+    pub e: i64,
+    /// The attribute, either as an ident keyword or an entity id.
+    pub a: Value,
+    pub v: Value,
+    pub tx: i64,
+    pub added: bool,
+}
+
+impl Datom {
+    /// Read a datom from a `[e a v tx added]` vector, optionally tagged with
+    /// `#datom`. `added` defaults to `true` when omitted.
+    pub fn from_value(value: &Value) -> Option<Datom> {
+        let items = match *value {
+            Value::Tagged(ref tag, ref value) if tag == "datom" => return Datom::from_value(value),
+            Value::Vector(ref items) | Value::List(ref items) => items,
+            _ => return None,
+        };
+        let items: ::std::vec::Vec<&Value> = items.iter().collect();
+        let added = match items.get(4) {
+            None => true,
+            Some(&&Value::Boolean(added)) => added,
+            Some(_) => return None,
+        };
+        match (items.len(), items.first(), items.get(3)) {
+            (4..=5, Some(&&Value::Integer(e)), Some(&&Value::Integer(tx))) => Some(Datom {
+                e,
+                a: items[1].clone(),
+                v: items[2].clone(),
+                tx,
+                added,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<Datom> for Value {
+    fn from(s: Datom) -> Self {
+        Value::Tagged(
+            "datom".into(),
+            Box::new(Value::Vector(Vec::from(vec![
+                Value::Integer(s.e),
+                s.a,
+                s.v,
+                Value::Integer(s.tx),
+                Value::Boolean(s.added),
+            ]))),
+        )
+    }
+}
+
+/// The result of a transaction, as delivered by `transact` or the
+/// tx-report-queue.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxReport {
+    pub db_before: Value,
+    pub db_after: Value,
+    pub tx_data: ::std::vec::Vec<Datom>,
+    pub tempids: Map<Value, Value>,
+}
+
+impl TxReport {
+    /// Read a report from a map with `:db-before`, `:db-after`, `:tx-data`
+    /// and `:tempids` keys. Missing databases and tempids are `nil` and
+    /// empty respectively, but `:tx-data` must be present and well formed.
+    pub fn from_value(value: &Value) -> Option<TxReport> {
+        let map = match *value {
+            Value::Map(ref map) => map,
+            _ => return None,
+        };
+        let get = |key: &str| map.get(&Value::Keyword(key.into()));
+
+        let tx_data = match get("tx-data") {
+            Some(Value::Vector(items)) | Some(Value::List(items)) => {
+                let mut tx_data = vec![];
+                for item in items.iter() {
+                    tx_data.push(Datom::from_value(item)?);
+                }
+                tx_data
+            }
+            _ => return None,
+        };
+        let tempids = match get("tempids") {
+            Some(Value::Map(tempids)) => tempids.clone(),
+            None | Some(&Value::Nil) => Map::new(),
+            Some(_) => return None,
+        };
+
+        Some(TxReport {
+            db_before: get("db-before").cloned().unwrap_or(Value::Nil),
+            db_after: get("db-after").cloned().unwrap_or(Value::Nil),
+            tx_data,
+            tempids,
+        })
+    }
+}
+
+/// A reader for `#datom [...]` which checks the shape of the datom.
+pub fn read_datom(value: Value) -> Result<Value, Error> {
+    match Datom::from_value(&value) {
+        Some(datom) => Ok(Value::from(datom)),
+        None => Err(Error::data("expected `[e a v tx added]` for `#datom`")),
+    }
+}
@@ -0,0 +1,27 @@
+use bigdecimal::BigDecimal;
+
+use Value;
+
+impl From<BigDecimal> for Value {
+    fn from(s: BigDecimal) -> Self {
+        Value::Decimal(s)
+    }
+}
+
+impl Value {
+    /// Parse an exact decimal such as `"3.14159"`, with or without the `M`
+    /// suffix.
+    pub fn from_decimal_str(str: &str) -> Option<Value> {
+        let str = str.strip_suffix('M').unwrap_or(str);
+        str.parse().ok().map(Value::Decimal)
+    }
+
+    /// The value of an `Integer` or `Decimal`, as a `BigDecimal`.
+    pub fn as_decimal(&self) -> Option<BigDecimal> {
+        match *self {
+            Value::Integer(integer) => Some(BigDecimal::from(integer)),
+            Value::Decimal(ref decimal) => Some(decimal.clone()),
+            _ => None,
+        }
+    }
+}
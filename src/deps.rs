@@ -0,0 +1,236 @@
+//! Resolving aliases in a tools.deps `deps.edn`.
+//!
+//! `resolve` combines the named aliases the way the Clojure CLI does for
+//! `clojure -A:dev:test` and applies them to the top-level `:deps` and
+//! `:paths`, giving the effective configuration:
+//!
+//! ```
+//! use edn::deps;
+//! use edn::parser;
+//!
+//! let deps_edn = parser::from_str(r#"
+//!     {:paths ["src"]
+//!      :deps {org.clojure/clojure {:mvn/version "1.11.1"}}
+//!      :aliases {:test {:extra-paths ["test"]
+//!                       :extra-deps {lambdaisland/kaocha {:mvn/version "1.87.1366"}}}}}
+//! "#).unwrap();
+//! let config = deps::resolve(&deps_edn, &["test"]).unwrap();
+//! assert_eq!(config, parser::from_str(r#"
+//!     {:paths ["test" "src"]
+//!      :deps {org.clojure/clojure {:mvn/version "1.11.1"}
+//!             lambdaisland/kaocha {:mvn/version "1.87.1366"}}}
+//! "#).unwrap());
+//! ```
+//!
+//! Nothing is fetched, so `:override-deps` and `:default-deps` only affect
+//! libraries listed in `:deps` or `:extra-deps`, not their transitive
+//! dependencies.
+
+use std::fmt;
+
+use {Keyword, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// The error returned by `resolve` for a malformed `deps.edn`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+fn key(name: &str) -> Value {
+    Value::Keyword(Keyword::new(name))
+}
+
+/// The aliases named in `aliases`, e.g. `["dev", "test"]`, combined in
+/// order like tools.deps' `combine-aliases`: dependency maps are merged with
+/// later aliases winning, path and `:jvm-opts` vectors are concatenated, and
+/// the last `:main-opts` wins.
+pub fn combine_aliases(deps_edn: &Value, aliases: &[&str]) -> Result<Value, Error> {
+    let defined = match get(deps_edn, "aliases", "deps.edn")? {
+        Some(Value::Map(map)) => Some(map),
+        Some(other) => return error(format!("`:aliases` must be a map, found {}", other.kind())),
+        None => None,
+    };
+    let mut combined = Map::new();
+    for &name in aliases {
+        let alias = Value::Keyword(name.into());
+        let entries = match defined.and_then(|defined| defined.get(&alias)) {
+            Some(Value::Map(entries)) => entries,
+            Some(other) => {
+                return error(format!(
+                    "alias `{}` must be a map, found {}",
+                    name,
+                    other.kind()
+                ))
+            }
+            None => return error(format!("unknown alias `{}`", name)),
+        };
+        for (key, value) in entries.iter() {
+            let merged = match (combined.get(key), key) {
+                (Some(previous), Value::Keyword(keyword)) if keyword.namespace().is_none() => {
+                    combine(keyword.name(), name, previous, value)?
+                }
+                _ => value.clone(),
+            };
+            combined.insert(key.clone(), merged);
+        }
+    }
+    Ok(Value::Map(combined))
+}
+
+fn combine(key: &str, alias: &str, previous: &Value, value: &Value) -> Result<Value, Error> {
+    let kind = match key {
+        "extra-deps" | "override-deps" | "default-deps" | "replace-deps" | "deps" => "a map",
+        "extra-paths" | "replace-paths" | "paths" | "jvm-opts" => "a vector",
+        _ => return Ok(value.clone()),
+    };
+    Ok(match (previous, value) {
+        (Value::Map(previous), Value::Map(value)) => Value::Map(
+            previous
+                .iter()
+                .chain(value.iter())
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        (Value::Vector(previous), Value::Vector(value)) if key == "jvm-opts" => {
+            Value::Vector(previous.iter().chain(value.iter()).cloned().collect())
+        }
+        (Value::Vector(previous), Value::Vector(value)) => {
+            Value::Vector(distinct(previous.iter().chain(value.iter())))
+        }
+        _ => {
+            let found = if previous.kind() == kind {
+                value
+            } else {
+                previous
+            };
+            return error(format!(
+                "`:{}` of alias `{}` must be {}, found {}",
+                key,
+                alias,
+                kind,
+                found.kind()
+            ));
+        }
+    })
+}
+
+fn distinct<'a, I: Iterator<Item = &'a Value>>(items: I) -> Vec<Value> {
+    let mut seen = ::std::collections::BTreeSet::new();
+    items.filter(|item| seen.insert(*item)).cloned().collect()
+}
+
+/// The entry for `name` in the map `value`, which is described as `what` in
+/// errors.
+fn get<'a>(value: &'a Value, name: &str, what: &str) -> Result<Option<&'a Value>, Error> {
+    match *value {
+        Value::Map(ref map) => Ok(map.get(&key(name))),
+        ref other => error(format!("{} must be a map, found {}", what, other.kind())),
+    }
+}
+
+fn map<'a>(value: Option<&'a Value>, name: &str) -> Result<Option<&'a Map<Value, Value>>, Error> {
+    match value {
+        None | Some(Value::Nil) => Ok(None),
+        Some(Value::Map(map)) => Ok(Some(map)),
+        Some(other) => error(format!("`:{}` must be a map, found {}", name, other.kind())),
+    }
+}
+
+fn vector<'a>(value: Option<&'a Value>, name: &str) -> Result<Option<&'a Vec<Value>>, Error> {
+    match value {
+        None | Some(Value::Nil) => Ok(None),
+        Some(Value::Vector(items)) => Ok(Some(items)),
+        Some(other) => error(format!(
+            "`:{}` must be a vector, found {}",
+            name,
+            other.kind()
+        )),
+    }
+}
+
+/// `deps_edn` with `aliases` applied to its `:deps` and `:paths`, as the
+/// Clojure CLI would for `-A` with those aliases:
+///
+/// - `:replace-deps` (or `:deps`) and `:replace-paths` (or `:paths`) replace
+///   the top-level ones.
+/// - `:extra-deps` are added, replacing top-level coordinates for the same
+///   library.
+/// - `:override-deps` replace the coordinates of libraries in `:deps`.
+/// - `:default-deps` give the coordinates of libraries in `:deps` whose
+///   coordinate is `nil`.
+/// - `:extra-paths` go before `:paths`.
+/// - `:jvm-opts` and `:main-opts` are copied to the result.
+///
+/// `:aliases` itself is left out of the result.
+pub fn resolve(deps_edn: &Value, aliases: &[&str]) -> Result<Value, Error> {
+    let combined = combine_aliases(deps_edn, aliases)?;
+    let alias = |name: &str| get(&combined, name, "alias");
+
+    let deps = match map(alias("replace-deps")?.or(alias("deps")?), "replace-deps")? {
+        Some(deps) => Some(deps),
+        None => map(get(deps_edn, "deps", "deps.edn")?, "deps")?,
+    };
+    let mut deps = deps.cloned().unwrap_or_default();
+    if let Some(extra) = map(alias("extra-deps")?, "extra-deps")? {
+        for (lib, coordinate) in extra.iter() {
+            deps.insert(lib.clone(), coordinate.clone());
+        }
+    }
+    let overrides = map(alias("override-deps")?, "override-deps")?;
+    let defaults = map(alias("default-deps")?, "default-deps")?;
+    let deps: Map<Value, Value> = deps
+        .into_iter()
+        .map(|(lib, coordinate)| {
+            let coordinate = match (overrides.and_then(|o| o.get(&lib)), &coordinate) {
+                (Some(coordinate), _) => coordinate.clone(),
+                (None, Value::Nil) => defaults
+                    .and_then(|d| d.get(&lib))
+                    .cloned()
+                    .unwrap_or(Value::Nil),
+                (None, _) => coordinate,
+            };
+            (lib, coordinate)
+        })
+        .collect();
+
+    let paths = match vector(alias("replace-paths")?.or(alias("paths")?), "replace-paths")? {
+        Some(paths) => Some(paths),
+        None => vector(get(deps_edn, "paths", "deps.edn")?, "paths")?,
+    };
+    let extra_paths = vector(alias("extra-paths")?, "extra-paths")?;
+    let paths = distinct(
+        extra_paths
+            .into_iter()
+            .flat_map(|paths| paths.iter())
+            .chain(paths.into_iter().flat_map(|paths| paths.iter())),
+    );
+
+    let mut config = match *deps_edn {
+        Value::Map(ref map) => map.clone(),
+        _ => unreachable!("checked by combine_aliases"),
+    };
+    config.remove(&key("aliases"));
+    config.insert(key("deps"), Value::Map(deps));
+    config.insert(key("paths"), Value::Vector(paths));
+    for name in &["jvm-opts", "main-opts"] {
+        if let Some(value) = vector(alias(name)?, name)? {
+            config.insert(key(name), Value::Vector(value.clone()));
+        }
+    }
+    Ok(Value::Map(config))
+}
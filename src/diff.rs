@@ -0,0 +1,396 @@
+//! Structural diffs between values, for sending changes to a document
+//! instead of the whole document.
+//!
+//! ```
+//! use edn::parser;
+//!
+//! let old = parser::from_str("{:name \"a\" :tags [:x :y] :age 1}").unwrap();
+//! let new = parser::from_str("{:name \"b\" :tags [:w :x :y]}").unwrap();
+//! let diff = edn::diff(&old, &new);
+//! assert_eq!(
+//!     edn::to_string(&diff.to_value()),
+//!     "[[:- [:age]] [:r [:name] \"b\"] [:+ [:tags 0] :w]]"
+//! );
+//!
+//! let mut patched = old.clone();
+//! edn::patch(&mut patched, &diff).unwrap();
+//! assert_eq!(patched, new);
+//! ```
+//!
+//! A path is a vector of steps from the root: a key in a map, an index in a
+//! list or vector, an element of a set, or the tag of a tagged element, as a
+//! symbol, to step into its value. Sets and metadata aren't stepped into;
+//! their elements are added and removed, and values with metadata replaced
+//! whole.
+
+use std::fmt;
+
+use {Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::Vec;
+#[cfg(not(feature = "immutable"))]
+use standard::Vec;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+/// One change, at the path it is at.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    /// Add an entry to a map, insert an item into a list or vector before the
+    /// item at the index, or add an element to a set.
+    Add(::std::vec::Vec<Value>, Value),
+    /// Remove an entry from a map, an item from a list or vector, or an
+    /// element from a set.
+    Remove(::std::vec::Vec<Value>),
+    /// Replace the value at the path, which may be empty for the root.
+    Replace(::std::vec::Vec<Value>, Value),
+}
+
+/// The edits which turn one value into another, applied in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diff {
+    pub edits: ::std::vec::Vec<Edit>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// The diff as EDN, a vector of `[:+ path value]`, `[:- path]` and
+    /// `[:r path value]` edits, as in editscript.
+    pub fn to_value(&self) -> Value {
+        let path = |path: &[Value]| Value::Vector(Vec::from(path.to_vec()));
+        let edit = |op: &str, rest: ::std::vec::Vec<Value>| {
+            let mut items = vec![Value::Keyword(op.into())];
+            items.extend(rest);
+            Value::Vector(Vec::from(items))
+        };
+        Value::Vector(
+            self.edits
+                .iter()
+                .map(|e| match *e {
+                    Edit::Add(ref p, ref value) => edit("+", vec![path(p), value.clone()]),
+                    Edit::Remove(ref p) => edit("-", vec![path(p)]),
+                    Edit::Replace(ref p, ref value) => edit("r", vec![path(p), value.clone()]),
+                })
+                .collect(),
+        )
+    }
+
+    /// Read a diff written by `to_value`.
+    pub fn from_value(value: &Value) -> Result<Diff, Error> {
+        let edits = match *value {
+            Value::Vector(ref edits) => edits,
+            _ => {
+                return error(format!(
+                    "expected a vector of edits, found {}",
+                    value.kind()
+                ))
+            }
+        };
+        let edits = edits
+            .iter()
+            .map(|edit| {
+                let items: ::std::vec::Vec<&Value> = match *edit {
+                    Value::Vector(ref items) => items.iter().collect(),
+                    _ => return error(format!("expected an edit, found {}", edit.kind())),
+                };
+                let path = match items.get(1) {
+                    Some(&Value::Vector(path)) => path.clone().into_iter().collect(),
+                    _ => return error("expected a path vector after the operation".into()),
+                };
+                let op = match items[0] {
+                    Value::Keyword(ref op) if op.namespace().is_none() => op.name(),
+                    _ => "",
+                };
+                match (op, items.len()) {
+                    ("+", 3) => Ok(Edit::Add(path, items[2].clone())),
+                    ("-", 2) => Ok(Edit::Remove(path)),
+                    ("r", 3) => Ok(Edit::Replace(path, items[2].clone())),
+                    _ => error(format!(
+                        "expected [:+ path value], [:- path] or [:r path value], found {}",
+                        ::ser::to_string(edit)
+                    )),
+                }
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Diff { edits })
+    }
+}
+
+/// The edits which turn `old` into `new`.
+///
+/// Maps are compared key by key and sets element by element. Lists and
+/// vectors are compared by their longest common subsequence, so inserting or
+/// removing an item is a single edit.
+pub fn diff(old: &Value, new: &Value) -> Diff {
+    let mut edits = vec![];
+    diff_at(&mut vec![], old, new, &mut edits);
+    Diff { edits }
+}
+
+fn diff_at(
+    path: &mut ::std::vec::Vec<Value>,
+    old: &Value,
+    new: &Value,
+    edits: &mut ::std::vec::Vec<Edit>,
+) {
+    if old == new {
+        return;
+    }
+    let at = |path: &[Value], step: Value| {
+        let mut path = path.to_vec();
+        path.push(step);
+        path
+    };
+    match (old, new) {
+        (Value::Map(old), Value::Map(new)) => {
+            for key in old.keys().filter(|&key| !new.contains_key(key)) {
+                edits.push(Edit::Remove(at(path, key.clone())));
+            }
+            for (key, value) in new.iter() {
+                match old.get(key) {
+                    Some(old) => {
+                        path.push(key.clone());
+                        diff_at(path, old, value, edits);
+                        path.pop();
+                    }
+                    None => edits.push(Edit::Add(at(path, key.clone()), value.clone())),
+                }
+            }
+        }
+        (Value::List(old), Value::List(new)) | (Value::Vector(old), Value::Vector(new)) => {
+            let old: ::std::vec::Vec<&Value> = old.iter().collect();
+            let new: ::std::vec::Vec<&Value> = new.iter().collect();
+            diff_items(path, &old, &new, edits);
+        }
+        (Value::Set(old), Value::Set(new)) => {
+            for item in old.iter().filter(|&item| !new.contains(item)) {
+                edits.push(Edit::Remove(at(path, item.clone())));
+            }
+            for item in new.iter().filter(|&item| !old.contains(item)) {
+                edits.push(Edit::Add(at(path, item.clone()), item.clone()));
+            }
+        }
+        (Value::Tagged(tag, old), Value::Tagged(new_tag, new)) if tag == new_tag => {
+            path.push(Value::Symbol(Symbol::from(tag.as_str())));
+            diff_at(path, old, new, edits);
+            path.pop();
+        }
+        _ => edits.push(Edit::Replace(path.clone(), new.clone())),
+    }
+}
+
+/// Diff the items of two lists or vectors by pairing up the items of their
+/// longest common subsequence and diffing the runs between them item by
+/// item, removing or adding what's left over.
+fn diff_items(
+    path: &mut ::std::vec::Vec<Value>,
+    old: &[&Value],
+    new: &[&Value],
+    edits: &mut ::std::vec::Vec<Edit>,
+) {
+    let prefix = old.iter().zip(new).take_while(|&(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&(a, b)| a == b)
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+    let mut matches = common_subsequence(old, new);
+    matches.push((old.len(), new.len()));
+    let (mut i, mut j) = (0, 0);
+    for (next_i, next_j) in matches {
+        let common = (next_i - i).min(next_j - j);
+        for k in 0..common {
+            path.push(index(prefix + j + k));
+            diff_at(path, old[i + k], new[j + k], edits);
+            path.pop();
+        }
+        for _ in common..next_i - i {
+            let mut path = path.clone();
+            path.push(index(prefix + j + common));
+            edits.push(Edit::Remove(path));
+        }
+        for k in common..next_j - j {
+            let mut path = path.clone();
+            path.push(index(prefix + j + k));
+            edits.push(Edit::Add(path, new[j + k].clone()));
+        }
+        i = next_i + 1;
+        j = next_j + 1;
+    }
+}
+
+/// Past this many pairs of items, lists and vectors are diffed item by item
+/// without looking for a common subsequence.
+const MAX_SUBSEQUENCE_PAIRS: usize = 1 << 20;
+
+/// The positions in `old` and `new` of the items of their longest common
+/// subsequence.
+fn common_subsequence(old: &[&Value], new: &[&Value]) -> ::std::vec::Vec<(usize, usize)> {
+    if old.len().saturating_mul(new.len()) > MAX_SUBSEQUENCE_PAIRS {
+        return vec![];
+    }
+    let width = new.len() + 1;
+    // lengths[i * width + j] is the length for old[i..] and new[j..].
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+    let mut matches = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+fn index(i: usize) -> Value {
+    Value::Integer(i as i64)
+}
+
+/// Apply the edits in `diff` to `value` in order, stopping at the first
+/// which doesn't fit it, e.g. one removing a key which isn't there.
+pub fn patch(value: &mut Value, diff: &Diff) -> Result<(), Error> {
+    for edit in &diff.edits {
+        match *edit {
+            Edit::Replace(ref path, ref new) => *get_mut(value, path)? = new.clone(),
+            Edit::Add(ref path, ref new) => {
+                let (parent, step) = parent(value, path)?;
+                match *parent {
+                    Value::Map(ref mut map) if !map.contains_key(step) => {
+                        map.insert(step.clone(), new.clone());
+                    }
+                    Value::List(ref mut items) | Value::Vector(ref mut items) => {
+                        match position(step, items.len() + 1) {
+                            Some(i) => items.insert(i, new.clone()),
+                            None => return step_error(step, "insert at"),
+                        }
+                    }
+                    Value::Set(ref mut items) if !items.contains(new) => {
+                        items.insert(new.clone());
+                    }
+                    _ => return step_error(step, "add"),
+                }
+            }
+            Edit::Remove(ref path) => {
+                let (parent, step) = parent(value, path)?;
+                let removed = match *parent {
+                    Value::Map(ref mut map) => map.remove(step).is_some(),
+                    Value::List(ref mut items) | Value::Vector(ref mut items) => {
+                        match position(step, items.len()) {
+                            Some(i) => {
+                                items.remove(i);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    Value::Set(ref mut items) if items.contains(step) => {
+                        items.remove(step);
+                        true
+                    }
+                    _ => false,
+                };
+                if !removed {
+                    return step_error(step, "remove");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The value `path` leads to in `value`.
+fn get_mut<'a>(value: &'a mut Value, path: &[Value]) -> Result<&'a mut Value, Error> {
+    let mut value = value;
+    for step in path {
+        value = step_into(value, step)?;
+    }
+    Ok(value)
+}
+
+fn step_into<'a>(value: &'a mut Value, step: &Value) -> Result<&'a mut Value, Error> {
+    let found = match *value {
+        Value::Map(ref mut map) => map.get_mut(step),
+        Value::List(ref mut items) | Value::Vector(ref mut items) => {
+            match position(step, items.len()) {
+                Some(i) => items.get_mut(i),
+                None => None,
+            }
+        }
+        Value::Tagged(ref tag, ref mut value) => match *step {
+            Value::Symbol(ref symbol) if symbol.to_string() == *tag => Some(&mut **value),
+            _ => None,
+        },
+        Value::WithMeta(ref mut value, _) => return step_into(value, step),
+        _ => None,
+    };
+    match found {
+        Some(value) => Ok(value),
+        None => step_error(step, "find"),
+    }
+}
+
+/// The value the last step of `path` is in, and that step.
+fn parent<'a, 'p>(
+    value: &'a mut Value,
+    path: &'p [Value],
+) -> Result<(&'a mut Value, &'p Value), Error> {
+    let (step, path) = match path.split_last() {
+        Some(split) => split,
+        None => return error("expected a path to add or remove at, found []".into()),
+    };
+    Ok((without_meta(get_mut(value, path)?), step))
+}
+
+fn without_meta(value: &mut Value) -> &mut Value {
+    match *value {
+        Value::WithMeta(ref mut value, _) => without_meta(value),
+        ref mut value => value,
+    }
+}
+
+/// `step` as an index up to `len`.
+fn position(step: &Value, len: usize) -> Option<usize> {
+    match *step {
+        Value::Integer(i) if i >= 0 && (i as u64) < len as u64 => Some(i as usize),
+        _ => None,
+    }
+}
+
+fn step_error<T>(step: &Value, action: &str) -> Result<T, Error> {
+    error(format!("can't {} {}", action, ::ser::to_string(step)))
+}
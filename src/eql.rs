@@ -0,0 +1,245 @@
+//! EQL (EDN Query Language), as used by Pathom and Fulcro.
+//!
+//! `parse` turns a query such as `[:user/name {:user/friends [:user/name]}]`
+//! into a `Query` and `Value::from` turns it back into EDN.
+
+use std::fmt;
+
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub children: ::std::vec::Vec<Node>,
+}
+
+/// What a property or join reads: a keyword, or an ident like `[:user/id 1]`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Key {
+    Prop(Keyword),
+    Ident(Keyword, Value),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JoinQuery {
+    Query(Query),
+    /// `{:kind-a [...] :kind-b [...]}`.
+    Union(::std::vec::Vec<(Keyword, Query)>),
+    /// `...` for unbounded recursion, or a depth limit.
+    Recursion(Option<u64>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Prop {
+        key: Key,
+        params: Option<Map<Value, Value>>,
+    },
+    Join {
+        key: Key,
+        query: JoinQuery,
+        params: Option<Map<Value, Value>>,
+    },
+    /// A mutation, `(my.app/save {...})`, optionally joined with a query on
+    /// its result.
+    Call {
+        name: Symbol,
+        params: Map<Value, Value>,
+        query: Option<Query>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: &str) -> Result<T, Error> {
+    Err(Error {
+        message: message.into(),
+    })
+}
+
+pub fn parse(value: &Value) -> Result<Query, Error> {
+    match *value {
+        Value::Vector(ref items) => Ok(Query {
+            children: items.iter().map(parse_node).collect::<Result<_, _>>()?,
+        }),
+        _ => error("expected a query vector"),
+    }
+}
+
+fn parse_node(value: &Value) -> Result<Node, Error> {
+    match *value {
+        Value::List(ref items) => {
+            let items: ::std::vec::Vec<&Value> = items.iter().collect();
+            let params = match items.get(1) {
+                Some(Value::Map(params)) if items.len() == 2 => params.clone(),
+                _ => return error("expected `(expr {params})`"),
+            };
+            match *items[0] {
+                Value::Symbol(ref name) => Ok(Node::Call {
+                    name: name.clone(),
+                    params,
+                    query: None,
+                }),
+                ref expr => match parse_node(expr)? {
+                    Node::Prop { key, params: None } => Ok(Node::Prop {
+                        key,
+                        params: Some(params),
+                    }),
+                    Node::Join {
+                        key,
+                        query,
+                        params: None,
+                    } => Ok(Node::Join {
+                        key,
+                        query,
+                        params: Some(params),
+                    }),
+                    _ => error("parameters can only be given once"),
+                },
+            }
+        }
+        Value::Map(ref map) => {
+            if map.len() != 1 {
+                return error("a join must have exactly one entry");
+            }
+            let (key, query) = map.iter().next().unwrap();
+            if let Value::List(_) = *key {
+                if let Node::Call { name, params, .. } = parse_node(key)? {
+                    return Ok(Node::Call {
+                        name,
+                        params,
+                        query: Some(parse(query)?),
+                    });
+                }
+            }
+            let (key, params) = match parse_node(key)? {
+                Node::Prop { key, params } => (key, params),
+                _ => return error("expected a property or ident as join key"),
+            };
+            Ok(Node::Join {
+                key,
+                query: parse_join_query(query)?,
+                params,
+            })
+        }
+        _ => Ok(Node::Prop {
+            key: parse_key(value)?,
+            params: None,
+        }),
+    }
+}
+
+fn parse_key(value: &Value) -> Result<Key, Error> {
+    match *value {
+        Value::Keyword(ref name) => Ok(Key::Prop(name.clone())),
+        Value::Vector(ref items) if items.len() == 2 => {
+            let mut items = items.iter();
+            match (items.next(), items.next()) {
+                (Some(Value::Keyword(name)), Some(value)) => {
+                    Ok(Key::Ident(name.clone(), value.clone()))
+                }
+                _ => error("expected `[:key value]` ident"),
+            }
+        }
+        _ => error("expected a keyword or ident"),
+    }
+}
+
+fn parse_join_query(value: &Value) -> Result<JoinQuery, Error> {
+    match *value {
+        Value::Symbol(ref symbol) if symbol.namespace().is_none() && symbol.name() == "..." => {
+            Ok(JoinQuery::Recursion(None))
+        }
+        Value::Integer(depth) if depth >= 0 => Ok(JoinQuery::Recursion(Some(depth as u64))),
+        Value::Map(ref map) => {
+            let mut union = vec![];
+            for (key, query) in map.iter() {
+                match *key {
+                    Value::Keyword(ref name) => union.push((name.clone(), parse(query)?)),
+                    _ => return error("union keys must be keywords"),
+                }
+            }
+            Ok(JoinQuery::Union(union))
+        }
+        _ => parse(value).map(JoinQuery::Query),
+    }
+}
+
+impl From<Query> for Value {
+    fn from(s: Query) -> Self {
+        Value::Vector(s.children.into_iter().map(Value::from).collect())
+    }
+}
+
+impl From<Key> for Value {
+    fn from(s: Key) -> Self {
+        match s {
+            Key::Prop(name) => Value::Keyword(name),
+            Key::Ident(name, value) => Value::Vector(Vec::from(vec![Value::Keyword(name), value])),
+        }
+    }
+}
+
+impl From<JoinQuery> for Value {
+    fn from(s: JoinQuery) -> Self {
+        match s {
+            JoinQuery::Query(query) => Value::from(query),
+            JoinQuery::Union(union) => Value::Map(
+                union
+                    .into_iter()
+                    .map(|(name, query)| (Value::Keyword(name), Value::from(query)))
+                    .collect(),
+            ),
+            JoinQuery::Recursion(None) => Value::Symbol(Symbol::new("...")),
+            JoinQuery::Recursion(Some(depth)) => Value::Integer(depth as i64),
+        }
+    }
+}
+
+impl From<Node> for Value {
+    fn from(s: Node) -> Self {
+        fn with_params(expr: Value, params: Option<Map<Value, Value>>) -> Value {
+            match params {
+                Some(params) => Value::List(Vec::from(vec![expr, Value::Map(params)])),
+                None => expr,
+            }
+        }
+
+        fn join(key: Value, query: Value) -> Value {
+            let mut map = Map::new();
+            map.insert(key, query);
+            Value::Map(map)
+        }
+
+        match s {
+            Node::Prop { key, params } => with_params(Value::from(key), params),
+            Node::Join { key, query, params } => {
+                with_params(join(Value::from(key), Value::from(query)), params)
+            }
+            Node::Call {
+                name,
+                params,
+                query,
+            } => {
+                let call = with_params(Value::Symbol(name), Some(params));
+                match query {
+                    Some(query) => join(call, Value::from(query)),
+                    None => call,
+                }
+            }
+        }
+    }
+}
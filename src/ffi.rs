@@ -0,0 +1,345 @@
+//! A C API for embedding the parser in other languages.
+//!
+//! Build a shared library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` and generate
+//! `edn.h` with `cbindgen --config cbindgen.toml --output edn.h`.
+//!
+//! Parsed values are opaque `EdnValue` handles. Handles returned by
+//! `edn_parse` are owned by the caller and freed with `edn_free`; handles
+//! returned while navigating borrow from their parent and must not be freed
+//! or used after it is. Strings returned by this API are owned by the caller
+//! and freed with `edn_string_free`.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use parser;
+use ser;
+use Value;
+
+/// A parsed value.
+#[repr(transparent)]
+pub struct EdnValue(Value);
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdnType {
+    Nil,
+    Boolean,
+    String,
+    Char,
+    Symbol,
+    Keyword,
+    Integer,
+    BigInt,
+    Ratio,
+    Float,
+    Decimal,
+    List,
+    Vector,
+    Map,
+    Set,
+    Tagged,
+}
+
+/// `value` without any metadata.
+unsafe fn inner<'a>(value: *const EdnValue) -> Option<&'a Value> {
+    let mut value = &value.as_ref()?.0;
+    while let Value::WithMeta(ref inner, _) = *value {
+        value = inner;
+    }
+    Some(value)
+}
+
+fn handle(value: Option<&Value>) -> *const EdnValue {
+    value.map_or(ptr::null(), |value| {
+        value as *const Value as *const EdnValue
+    })
+}
+
+/// A C string copy of `str`, with any interior NUL truncating it.
+fn c_string(str: &str) -> *mut c_char {
+    let bytes = str.split('\0').next().unwrap_or("");
+    CString::new(bytes).unwrap().into_raw()
+}
+
+/// Parse exactly one form from the NUL-terminated UTF-8 string `input`.
+/// Returns NULL on failure and, if `error` isn't NULL, stores a message in it
+/// to be freed with `edn_string_free`.
+///
+/// # Safety
+///
+/// `input` must be a valid NUL-terminated string and `error` NULL or valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn edn_parse(input: *const c_char, error: *mut *mut c_char) -> *mut EdnValue {
+    let result = if input.is_null() {
+        Err("input is NULL".to_string())
+    } else {
+        match CStr::from_ptr(input).to_str() {
+            Ok(input) => parser::from_str(input).map_err(|err| err.to_string()),
+            Err(_) => Err("input is not valid UTF-8".to_string()),
+        }
+    };
+    match result {
+        Ok(value) => Box::into_raw(Box::new(EdnValue(value))),
+        Err(message) => {
+            if !error.is_null() {
+                *error = c_string(&message);
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Free a value returned by `edn_parse`. Does nothing with NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or returned by `edn_parse` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn edn_free(value: *mut EdnValue) {
+    if !value.is_null() {
+        drop(Box::from_raw(value));
+    }
+}
+
+/// Free a string returned by this API. Does nothing with NULL.
+///
+/// # Safety
+///
+/// `str` must be NULL or returned by this API and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn edn_string_free(str: *mut c_char) {
+    if !str.is_null() {
+        drop(CString::from_raw(str));
+    }
+}
+
+/// `value` as compact EDN text, or NULL if `value` is NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_write(value: *const EdnValue) -> *mut c_char {
+    match value.as_ref() {
+        Some(value) => c_string(&ser::to_string(&value.0)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The type of `value`, ignoring metadata. NULL is `Nil`.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_type(value: *const EdnValue) -> EdnType {
+    match inner(value) {
+        None | Some(Value::Nil) => EdnType::Nil,
+        Some(Value::Boolean(_)) => EdnType::Boolean,
+        Some(Value::String(_)) => EdnType::String,
+        Some(Value::Char(_)) => EdnType::Char,
+        Some(Value::Symbol(_)) => EdnType::Symbol,
+        Some(Value::Keyword(_)) => EdnType::Keyword,
+        Some(Value::Integer(_)) => EdnType::Integer,
+        #[cfg(feature = "bigint")]
+        Some(Value::BigInt(_)) => EdnType::BigInt,
+        #[cfg(feature = "ratio")]
+        Some(Value::Ratio(..)) => EdnType::Ratio,
+        Some(Value::Float(_)) => EdnType::Float,
+        #[cfg(feature = "bigdecimal")]
+        Some(Value::Decimal(_)) => EdnType::Decimal,
+        Some(Value::List(_)) => EdnType::List,
+        Some(Value::Vector(_)) => EdnType::Vector,
+        Some(Value::Map(_)) => EdnType::Map,
+        Some(Value::Set(_)) => EdnType::Set,
+        Some(Value::Tagged(..)) => EdnType::Tagged,
+        Some(Value::WithMeta(..)) => unreachable!(),
+    }
+}
+
+/// The number of items in a list, vector or set, or entries in a map, and 0
+/// for anything else.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_count(value: *const EdnValue) -> usize {
+    match inner(value) {
+        Some(Value::List(items)) | Some(Value::Vector(items)) => items.len(),
+        Some(Value::Set(items)) => items.len(),
+        Some(Value::Map(map)) => map.len(),
+        _ => 0,
+    }
+}
+
+/// The item at `index` of a list, vector or set, in iteration order, or NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_nth(value: *const EdnValue, index: usize) -> *const EdnValue {
+    handle(match inner(value) {
+        Some(Value::List(items)) | Some(Value::Vector(items)) => items.get(index),
+        Some(Value::Set(items)) => items.iter().nth(index),
+        _ => None,
+    })
+}
+
+/// The key of the entry at `index` of a map, in iteration order, or NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_map_key(value: *const EdnValue, index: usize) -> *const EdnValue {
+    handle(match inner(value) {
+        Some(Value::Map(map)) => map.iter().nth(index).map(|(key, _)| key),
+        _ => None,
+    })
+}
+
+/// The value of the entry at `index` of a map, in iteration order, or NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_map_value(value: *const EdnValue, index: usize) -> *const EdnValue {
+    handle(match inner(value) {
+        Some(Value::Map(map)) => map.iter().nth(index).map(|(_, value)| value),
+        _ => None,
+    })
+}
+
+/// The value for `key` in a map, or NULL.
+///
+/// # Safety
+///
+/// `value` and `key` must be NULL or live handles.
+#[no_mangle]
+pub unsafe extern "C" fn edn_get(value: *const EdnValue, key: *const EdnValue) -> *const EdnValue {
+    handle(match (inner(value), key.as_ref()) {
+        (Some(Value::Map(map)), Some(key)) => map.get(&key.0),
+        _ => None,
+    })
+}
+
+/// The value for the keyword `name`, e.g. `"user/id"`, in a map, or NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle and `name` NULL or a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn edn_get_kw(
+    value: *const EdnValue,
+    name: *const c_char,
+) -> *const EdnValue {
+    if name.is_null() {
+        return ptr::null();
+    }
+    match (inner(value), CStr::from_ptr(name).to_str()) {
+        (Some(value), Ok(name)) => handle(value.get_kw(name)),
+        _ => ptr::null(),
+    }
+}
+
+/// Store a boolean in `out` and return `true`, or return `false` if `value`
+/// isn't a boolean.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn edn_as_bool(value: *const EdnValue, out: *mut bool) -> bool {
+    match inner(value).and_then(Value::as_bool) {
+        Some(boolean) => {
+            *out = boolean;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Store an integer in `out` and return `true`, or return `false` if `value`
+/// isn't an integer which fits in 64 bits.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn edn_as_i64(value: *const EdnValue, out: *mut i64) -> bool {
+    match inner(value).and_then(Value::as_i64) {
+        Some(integer) => {
+            *out = integer;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Store a float, or an integer or ratio converted to one, in `out` and return
+/// `true`, or return `false` if `value` isn't a number.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn edn_as_f64(value: *const EdnValue, out: *mut f64) -> bool {
+    match inner(value).and_then(Value::as_f64) {
+        Some(float) => {
+            *out = float;
+            true
+        }
+        None => false,
+    }
+}
+
+/// A copy of a string's contents, the text of a keyword or symbol without
+/// the leading `:`, or a char as a string. NULL for anything else.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_as_string(value: *const EdnValue) -> *mut c_char {
+    match inner(value) {
+        Some(Value::String(string)) => c_string(string),
+        Some(Value::Keyword(keyword)) => c_string(&keyword.to_string()[1..]),
+        Some(Value::Symbol(symbol)) => c_string(&symbol.to_string()),
+        Some(Value::Char(ch)) => c_string(&ch.to_string()),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// A copy of the tag of a tagged element, or NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_tag(value: *const EdnValue) -> *mut c_char {
+    match inner(value) {
+        Some(Value::Tagged(tag, _)) => c_string(tag),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// The value of a tagged element, or NULL.
+///
+/// # Safety
+///
+/// `value` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn edn_tagged_value(value: *const EdnValue) -> *const EdnValue {
+    handle(match inner(value) {
+        Some(Value::Tagged(_, value)) => Some(&**value),
+        _ => None,
+    })
+}
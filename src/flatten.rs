@@ -0,0 +1,135 @@
+//! Converting nested values to and from flat `key.path = value` properties,
+//! for key-value stores such as environment variables, Consul or Java
+//! properties files.
+//!
+//! `{:db {:host "localhost" :ports [5432 5433]}}` flattens to `db.host`,
+//! `db.ports.0` and `db.ports.1`.
+
+use std::collections::BTreeMap;
+
+use {Keyword, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// Every leaf of the maps, lists and vectors in `value` with its dotted path.
+///
+/// Keywords become their name with any namespace, `:db/host` is `db/host`,
+/// strings are used as is and other keys are printed as EDN. Sets, tagged
+/// values and empty collections are leaves. A `value` which isn't a
+/// collection is a single property with an empty key.
+pub fn to_properties(value: &Value) -> ::std::vec::Vec<(String, Value)> {
+    let mut properties = vec![];
+    flatten(value, String::new(), &mut properties);
+    properties
+}
+
+fn flatten(value: &Value, prefix: String, properties: &mut ::std::vec::Vec<(String, Value)>) {
+    let join = |segment: &str| {
+        if prefix.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{}", prefix, segment)
+        }
+    };
+    match *value {
+        Value::Map(ref map) if !map.is_empty() => {
+            for (key, value) in map.iter() {
+                flatten(value, join(&segment(key)), properties);
+            }
+        }
+        Value::List(ref items) | Value::Vector(ref items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(item, join(&i.to_string()), properties);
+            }
+        }
+        _ => properties.push((prefix, value.clone())),
+    }
+}
+
+fn segment(key: &Value) -> String {
+    match *key {
+        Value::Keyword(ref keyword) => keyword.to_string()[1..].to_string(),
+        Value::String(ref string) => string.clone(),
+        _ => ::ser::to_string(key),
+    }
+}
+
+enum Node {
+    Leaf(Value),
+    Branch(BTreeMap<String, Node>),
+}
+
+/// Rebuild a nested value from dotted properties, the inverse of
+/// `to_properties`.
+///
+/// Path segments become keyword keys, except that a level whose keys are
+/// exactly `0` to `n - 1` becomes a vector. When properties overlap, such as
+/// `a` and `a.b`, later ones replace earlier ones.
+pub fn from_properties<I, K>(properties: I) -> Value
+where
+    I: IntoIterator<Item = (K, Value)>,
+    K: AsRef<str>,
+{
+    let mut root = Node::Branch(BTreeMap::new());
+    for (key, value) in properties {
+        let key = key.as_ref();
+        if key.is_empty() {
+            root = Node::Leaf(value);
+            continue;
+        }
+        let mut node = &mut root;
+        for segment in key.split('.') {
+            if let Node::Leaf(_) = *node {
+                *node = Node::Branch(BTreeMap::new());
+            }
+            node = match *node {
+                Node::Branch(ref mut children) => children
+                    .entry(segment.to_string())
+                    .or_insert_with(|| Node::Branch(BTreeMap::new())),
+                Node::Leaf(_) => unreachable!(),
+            };
+        }
+        *node = Node::Leaf(value);
+    }
+    build(root)
+}
+
+fn build(node: Node) -> Value {
+    let children = match node {
+        Node::Leaf(value) => return value,
+        Node::Branch(children) => children,
+    };
+    let mut indices = children
+        .keys()
+        .map(|key| {
+            key.parse::<usize>()
+                .ok()
+                .filter(|index| index.to_string() == *key)
+        })
+        .collect::<Option<::std::vec::Vec<_>>>()
+        .unwrap_or_default();
+    indices.sort_unstable();
+    if !indices.is_empty() && indices.iter().enumerate().all(|(i, index)| i == *index) {
+        let mut items: ::std::vec::Vec<_> = children
+            .into_iter()
+            .map(|(key, node)| (key.parse::<usize>().unwrap(), build(node)))
+            .collect();
+        items.sort_by_key(|&(index, _)| index);
+        Value::Vector(Vec::from(
+            items
+                .into_iter()
+                .map(|(_, item)| item)
+                .collect::<::std::vec::Vec<_>>(),
+        ))
+    } else {
+        Value::Map(
+            children
+                .into_iter()
+                .map(|(key, node)| (Value::Keyword(Keyword::from(key)), build(node)))
+                .collect::<Map<_, _>>(),
+        )
+    }
+}
@@ -0,0 +1,69 @@
+//! Headers: metadata on the top form of a document recording what the
+//! document is, e.g. the version of its format.
+//!
+//! ```
+//! use edn::header;
+//! use edn::Value;
+//!
+//! let (meta, body) = header::read("^{:edn/version 2} {:a 1}").unwrap();
+//! assert_eq!(header::version(&meta), Some(2));
+//! assert_eq!(body["a"], Value::Integer(1));
+//!
+//! let meta = header::with_version(meta, 3);
+//! assert_eq!(header::write(&meta, &body), "^{:edn/version 3} {:a 1}");
+//! ```
+
+use parser::{Error, ParserBuilder};
+use {Map, Value};
+
+/// The key of the version of a document's format in its header.
+pub const VERSION: &str = "edn/version";
+
+/// Parse the one form in `str`, returning its header, which is empty if it
+/// has none, and the form without it. Metadata inside the form is kept as
+/// `Value::WithMeta`, as with `ParserBuilder::keep_metadata`.
+pub fn read(str: &str) -> Result<(Map<Value, Value>, Value), Error> {
+    Ok(strip(
+        ParserBuilder::new().keep_metadata(true).from_str(str)?,
+    ))
+}
+
+/// `value` with its header written before it, or just `value` if the header
+/// is empty.
+pub fn write(header: &Map<Value, Value>, value: &Value) -> String {
+    ::ser::to_string(&inject(value.clone(), header.clone()))
+}
+
+/// Split the header off `value`.
+pub fn strip(value: Value) -> (Map<Value, Value>, Value) {
+    match value {
+        Value::WithMeta(value, meta) => (meta, *value),
+        value => (Map::new(), value),
+    }
+}
+
+/// `value` with `header` added to its metadata, replacing entries with the
+/// same keys.
+pub fn inject(value: Value, header: Map<Value, Value>) -> Value {
+    let (mut meta, value) = strip(value);
+    meta.extend(header);
+    if meta.is_empty() {
+        value
+    } else {
+        Value::WithMeta(Box::new(value), meta)
+    }
+}
+
+/// The `:edn/version` in `header`, if it is an integer.
+pub fn version(header: &Map<Value, Value>) -> Option<i64> {
+    match header.get_kw(VERSION) {
+        Some(&Value::Integer(version)) => Some(version),
+        _ => None,
+    }
+}
+
+/// `header` with its `:edn/version` set to `version`.
+pub fn with_version(mut header: Map<Value, Value>, version: i64) -> Map<Value, Value> {
+    header.insert(Value::Keyword(VERSION.into()), Value::Integer(version));
+    header
+}
@@ -0,0 +1,168 @@
+//! Stable identifiers for the forms in a document, so tools like review
+//! comments or caches can refer to parts of it across edits.
+//!
+//! A form's id has two halves: a hash of its path from the root, which stays
+//! the same while the form is edited in place, and a hash of its content,
+//! which stays the same while it's moved. Both are FNV-1a hashes of a fixed
+//! encoding, so they don't change between runs, platforms or versions of
+//! this crate.
+
+use std::fmt;
+
+use iter::PathSegment;
+use Value;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Also gives `avro` its sync markers.
+pub(crate) struct Fnv(pub(crate) u64);
+
+impl Fnv {
+    pub(crate) fn new() -> Fnv {
+        Fnv(FNV_OFFSET)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> &mut Fnv {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+        self
+    }
+
+    pub(crate) fn write_u64(&mut self, n: u64) -> &mut Fnv {
+        self.write(&n.to_le_bytes())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FormId {
+    pub path: u64,
+    pub content: u64,
+}
+
+/// Both halves in hex, e.g. `8e1f6c0a2b3d4e5f-0123456789abcdef`.
+impl fmt::Display for FormId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:016x}-{:016x}", self.path, self.content)
+    }
+}
+
+fn edn(value: &Value) -> ::std::vec::Vec<u8> {
+    ::ser::to_vec(value)
+}
+
+/// The hash of `path`.
+pub fn path_hash(path: &[PathSegment]) -> u64 {
+    let mut hash = Fnv::new();
+    for segment in path {
+        match *segment {
+            PathSegment::Key(key) => hash.write(b"k").write(&edn(key)),
+            PathSegment::Index(i) => hash.write(b"i").write_u64(i as u64),
+            PathSegment::Element(item) => hash.write(b"e").write(&edn(item)),
+            PathSegment::Tag(tag) => hash.write(b"t").write(tag.as_bytes()),
+        };
+        hash.write(&[0]);
+    }
+    hash.0
+}
+
+/// The hash of `value`. Entries of maps and sets are hashed in no particular
+/// order and metadata is ignored.
+pub fn content_hash(value: &Value) -> u64 {
+    visit(value, &mut vec![], &mut None)
+}
+
+/// The content hash of `value`, also pushing its id and those of the forms
+/// inside it to `out` if given.
+fn visit<'a>(
+    value: &'a Value,
+    path: &mut ::std::vec::Vec<PathSegment<'a>>,
+    out: &mut Option<&mut ::std::vec::Vec<(::std::vec::Vec<PathSegment<'a>>, FormId)>>,
+) -> u64 {
+    if let Value::WithMeta(ref value, _) = *value {
+        return visit(value, path, out);
+    }
+    let slot = out.as_mut().map(|out| {
+        out.push((
+            path.clone(),
+            FormId {
+                path: path_hash(path),
+                content: 0,
+            },
+        ));
+        out.len() - 1
+    });
+    let mut child = |segment, value| {
+        path.push(segment);
+        let hash = visit(value, path, out);
+        path.pop();
+        hash
+    };
+    let mut hash = Fnv::new();
+    match *value {
+        Value::List(ref items) | Value::Vector(ref items) => {
+            hash.write(if let Value::List(_) = *value {
+                b"("
+            } else {
+                b"["
+            });
+            for (i, item) in items.iter().enumerate() {
+                hash.write_u64(child(PathSegment::Index(i), item));
+            }
+        }
+        Value::Map(ref map) => {
+            // Visited in key order so `form_ids` is the same in every build.
+            let mut entries: ::std::vec::Vec<(&Value, &Value)> = map.iter().collect();
+            entries.sort_unstable_by_key(|entry| entry.0);
+            let mut entries: ::std::vec::Vec<u64> = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = child(PathSegment::Key(key), value);
+                    Fnv::new().write_u64(content_hash(key)).write_u64(value).0
+                })
+                .collect();
+            entries.sort_unstable();
+            hash.write(b"{");
+            for entry in entries {
+                hash.write_u64(entry);
+            }
+        }
+        Value::Set(ref set) => {
+            let mut items: ::std::vec::Vec<&Value> = set.iter().collect();
+            items.sort_unstable();
+            let mut items: ::std::vec::Vec<u64> = items
+                .into_iter()
+                .map(|item| child(PathSegment::Element(item), item))
+                .collect();
+            items.sort_unstable();
+            hash.write(b"#{");
+            for item in items {
+                hash.write_u64(item);
+            }
+        }
+        Value::Tagged(ref tag, ref value) => {
+            let value = child(PathSegment::Tag(tag), value);
+            hash.write(b"#")
+                .write(tag.as_bytes())
+                .write(&[0])
+                .write_u64(value);
+        }
+        _ => {
+            hash.write(b"s").write(&edn(value));
+        }
+    }
+    if let (Some(out), Some(slot)) = (out.as_mut(), slot) {
+        out[slot].1.content = hash.0;
+    }
+    hash.0
+}
+
+/// The id of `value` and of every form inside it, with their paths, parents
+/// before children and map entries and set items in sorted order. Metadata
+/// isn't a form of its own.
+pub fn form_ids(value: &Value) -> ::std::vec::Vec<(::std::vec::Vec<PathSegment<'_>>, FormId)> {
+    let mut out = vec![];
+    visit(value, &mut vec![], &mut Some(&mut out));
+    out
+}
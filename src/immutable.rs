@@ -1,8 +1,8 @@
 extern crate im;
 
 
-use im::{HashMap, HashSet, Vector};
+use im::{HashSet, Vector};
 
-pub type Map<K, V> = HashMap<K, V>;
+pub use map::Map;
 pub type Vec<T> = Vector<T>;
 pub type Set<T> = HashSet<T>;
@@ -0,0 +1,173 @@
+use std::ops;
+
+use map::KeyRef;
+use sealed::Sealed;
+use Value;
+
+/// A key which `Value::get` and `value[...]` can look up: a `usize` for
+/// lists and vectors, or a `Value` or `str` for maps.
+///
+/// A `str` matches a keyword key with that name, with or without a leading
+/// `:`, and otherwise a string key, so `value["name"]` and `value[":name"]`
+/// both find `{:name ...}`.
+///
+/// Only this crate implements `Index`, unless the `unstable` feature is
+/// enabled.
+pub trait Index: Sealed {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value>;
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value>;
+}
+
+#[cfg(not(feature = "unstable"))]
+impl Sealed for usize {}
+
+impl Index for usize {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match *value {
+            Value::List(ref items) | Value::Vector(ref items) => items.get(*self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        match *value {
+            Value::List(ref mut items) | Value::Vector(ref mut items) => items.get_mut(*self),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(feature = "unstable"))]
+impl Sealed for Value {}
+
+impl Index for Value {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match *value {
+            Value::Map(ref map) => map.get(self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        match *value {
+            Value::Map(ref mut map) => map.get_mut(self),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(feature = "unstable"))]
+impl Sealed for str {}
+
+impl Index for str {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match *value {
+            Value::Map(ref map) => map.get_kw(self).or_else(|| map.get_str(self)),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        match *value {
+            Value::Map(ref mut map) => {
+                if map.get_kw(self).is_some() {
+                    map.get_kw_mut(self)
+                } else {
+                    map.get_str_mut(self)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(feature = "unstable"))]
+impl Sealed for String {}
+
+impl Index for String {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self[..].index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        self[..].index_into_mut(value)
+    }
+}
+
+#[cfg(not(feature = "unstable"))]
+impl<T: ?Sized + Sealed> Sealed for &T {}
+
+impl<T: ?Sized + Index> Index for &T {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(value)
+    }
+}
+
+impl Value {
+    /// The item at `index` of a list or vector, or the value for `index` in a
+    /// map. See `Index` for how strings are matched against map keys.
+    pub fn get<I: Index>(&self, index: I) -> Option<&Value> {
+        index.index_into(self)
+    }
+
+    pub fn get_mut<I: Index>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+
+    /// Follow `path` through nested maps, lists and vectors, like Clojure's
+    /// `get-in`. Maps are looked up by key, and sequences by integer index.
+    pub fn get_in(&self, path: &[&Value]) -> Option<&Value> {
+        path.iter()
+            .try_fold(self, |value, &key| match (value, key) {
+                (Value::Map(map), key) => map.get(key),
+                (Value::List(items), &Value::Integer(i))
+                | (Value::Vector(items), &Value::Integer(i))
+                    if i >= 0 =>
+                {
+                    items.get(i as usize)
+                }
+                _ => None,
+            })
+    }
+
+    /// Like `get_in`, but with a path of anything `get` accepts:
+    ///
+    /// ```
+    /// # use edn::parser::from_str;
+    /// let value = from_str("{:users [{:name \"Ada\"}]}").unwrap();
+    /// assert_eq!(value.get_path(&[&"users", &0, &":name"]).unwrap().as_str(), Some("Ada"));
+    /// ```
+    pub fn get_path(&self, path: &[&dyn Index]) -> Option<&Value> {
+        path.iter()
+            .try_fold(self, |value, index| index.index_into(value))
+    }
+
+    /// The value for the keyword `name`, e.g. `"user/id"`, in a map. Unlike
+    /// `get`, string keys never match.
+    pub fn get_kw(&self, name: &str) -> Option<&Value> {
+        ::keyword::lookup(KeyRef::keyword(name), self)
+    }
+}
+
+static NIL: Value = Value::Nil;
+
+/// Like `Value::get`, but `nil` when there is nothing at `index`.
+impl<I: Index> ops::Index<I> for Value {
+    type Output = Value;
+
+    fn index(&self, index: I) -> &Value {
+        self.get(index).unwrap_or(&NIL)
+    }
+}
+
+/// Like `Value::get_mut`, but panics when there is nothing at `index`.
+impl<I: Index> ops::IndexMut<I> for Value {
+    fn index_mut(&mut self, index: I) -> &mut Value {
+        self.get_mut(index).expect("no entry found for index")
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use Value;
+
+impl From<DateTime<Utc>> for Value {
+    fn from(s: DateTime<Utc>) -> Self {
+        Value::Tagged(
+            "inst".into(),
+            Box::new(Value::String(s.to_rfc3339_opts(SecondsFormat::AutoSi, true))),
+        )
+    }
+}
+
+impl Value {
+    /// The timestamp of an `#inst "..."` tagged element, converted to UTC.
+    pub fn as_inst(&self) -> Option<DateTime<Utc>> {
+        match *self {
+            Value::Tagged(ref tag, ref value) if tag == "inst" => match **value {
+                Value::String(ref string) => DateTime::parse_from_rfc3339(string)
+                    .ok()
+                    .map(|datetime| datetime.with_timezone(&Utc)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
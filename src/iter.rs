@@ -0,0 +1,207 @@
+use map;
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Set, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Set, Vec};
+
+/// A consuming iterator over the items of a `Value`.
+///
+/// Lists, vectors and sets yield their elements. Maps yield each entry as a
+/// two element `[key value]` vector, like Clojure's `seq` on a map. Every
+/// other value yields nothing.
+pub struct IntoIter {
+    inner: Inner,
+}
+
+enum Inner {
+    Seq(<Vec<Value> as IntoIterator>::IntoIter),
+    Map(<Map<Value, Value> as IntoIterator>::IntoIter),
+    Set(<Set<Value> as IntoIterator>::IntoIter),
+    Empty,
+}
+
+impl Iterator for IntoIter {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        match self.inner {
+            Inner::Seq(ref mut iter) => iter.next(),
+            Inner::Map(ref mut iter) => iter
+                .next()
+                .map(|(key, value)| Value::Vector(Vec::from(vec![key, value]))),
+            Inner::Set(ref mut iter) => iter.next(),
+            Inner::Empty => None,
+        }
+    }
+}
+
+impl IntoIterator for Value {
+    type Item = Value;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        let inner = match self {
+            Value::List(items) | Value::Vector(items) => Inner::Seq(items.into_iter()),
+            Value::Map(map) => Inner::Map(map.into_iter()),
+            Value::Set(set) => Inner::Set(set.into_iter()),
+            _ => Inner::Empty,
+        };
+        IntoIter { inner }
+    }
+}
+
+/// A borrowing iterator over the items of a `Value`, from `Value::iter`.
+pub enum ValueIter<'a> {
+    /// The elements of a list, vector or set. Other values have none.
+    Items(Items<'a>),
+    /// The entries of a map.
+    Entries(map::Iter<'a, Value, Value>),
+}
+
+/// The elements of a list, vector or set.
+pub struct Items<'a> {
+    inner: ItemsInner<'a>,
+}
+
+enum ItemsInner<'a> {
+    Seq(<&'a Vec<Value> as IntoIterator>::IntoIter),
+    Set(<&'a Set<Value> as IntoIterator>::IntoIter),
+    Empty,
+}
+
+impl<'a> Iterator for Items<'a> {
+    type Item = &'a Value;
+
+    fn next(&mut self) -> Option<&'a Value> {
+        match self.inner {
+            ItemsInner::Seq(ref mut iter) => iter.next(),
+            ItemsInner::Set(ref mut iter) => iter.next(),
+            ItemsInner::Empty => None,
+        }
+    }
+}
+
+/// A mutably borrowing iterator over the items of a `Value`, from
+/// `Value::iter_mut`.
+pub enum ValueIterMut<'a> {
+    /// The elements of a list or vector. Set elements can't be changed in
+    /// place, so sets, like other values, have none.
+    Items(ItemsMut<'a>),
+    /// The entries of a map, with mutable values.
+    Entries(map::IterMut<'a, Value, Value>),
+}
+
+#[cfg(feature = "immutable")]
+type SeqIterMut<'a> = ::im::vector::IterMut<'a, Value>;
+#[cfg(not(feature = "immutable"))]
+type SeqIterMut<'a> = ::std::slice::IterMut<'a, Value>;
+
+/// The elements of a list or vector.
+pub struct ItemsMut<'a> {
+    inner: Option<SeqIterMut<'a>>,
+}
+
+impl<'a> Iterator for ItemsMut<'a> {
+    type Item = &'a mut Value;
+
+    fn next(&mut self) -> Option<&'a mut Value> {
+        self.inner.as_mut()?.next()
+    }
+}
+
+impl Value {
+    /// The items of a list, vector, set or map, without consuming it. Use
+    /// `into_iter` to take them.
+    pub fn iter(&self) -> ValueIter<'_> {
+        let inner = match *self {
+            Value::List(ref items) | Value::Vector(ref items) => ItemsInner::Seq(items.iter()),
+            Value::Map(ref map) => return ValueIter::Entries(map.iter()),
+            Value::Set(ref set) => ItemsInner::Set(IntoIterator::into_iter(set)),
+            _ => ItemsInner::Empty,
+        };
+        ValueIter::Items(Items { inner })
+    }
+
+    /// The items of a list, vector or map, to change in place.
+    pub fn iter_mut(&mut self) -> ValueIterMut<'_> {
+        let inner = match *self {
+            Value::List(ref mut items) | Value::Vector(ref mut items) => Some(items.iter_mut()),
+            Value::Map(ref mut map) => return ValueIterMut::Entries(map.iter_mut()),
+            _ => None,
+        };
+        ValueIterMut::Items(ItemsMut { inner })
+    }
+}
+
+/// One step on the way from a value to one of its leaves.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment<'a> {
+    /// The value for this key in a map.
+    Key(&'a Value),
+    /// The item at this position in a list or vector.
+    Index(usize),
+    /// This element of a set.
+    Element(&'a Value),
+    /// The value of an element with this tag.
+    Tag(&'a str),
+}
+
+/// An iterator over the leaves of a `Value` and the path to each, created by
+/// `Value::paths`.
+///
+/// Leaves are the values which aren't collections or tagged elements, and
+/// empty collections. They are visited depth first, in iteration order.
+pub struct Paths<'a> {
+    stack: ::std::vec::Vec<(::std::vec::Vec<PathSegment<'a>>, &'a Value)>,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = (::std::vec::Vec<PathSegment<'a>>, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (path, value) = self.stack.pop()?;
+            let mut children: ::std::vec::Vec<(PathSegment<'a>, &'a Value)> = match *value {
+                Value::List(ref items) | Value::Vector(ref items) => items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| (PathSegment::Index(i), item))
+                    .collect(),
+                Value::Map(ref map) => map
+                    .iter()
+                    .map(|(key, value)| (PathSegment::Key(key), value))
+                    .collect(),
+                Value::Set(ref set) => set
+                    .iter()
+                    .map(|item| (PathSegment::Element(item), item))
+                    .collect(),
+                Value::Tagged(ref tag, ref value) => vec![(PathSegment::Tag(tag), &**value)],
+                Value::WithMeta(ref value, _) => {
+                    self.stack.push((path, value));
+                    continue;
+                }
+                _ => vec![],
+            };
+            if children.is_empty() {
+                return Some((path, value));
+            }
+            while let Some((segment, child)) = children.pop() {
+                let mut path = path.clone();
+                path.push(segment);
+                self.stack.push((path, child));
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Every leaf of this value along with its path, e.g. for flattening a
+    /// document to dotted keys.
+    pub fn paths(&self) -> Paths<'_> {
+        Paths {
+            stack: vec![(vec![], self)],
+        }
+    }
+}
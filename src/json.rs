@@ -0,0 +1,396 @@
+//! Conversions between `Value` and `serde_json::Value`.
+//!
+//! Every JSON value has an EDN equivalent, so `Value::from` never fails:
+//! objects become maps with string keys. Going the other way, EDN values with
+//! no JSON equivalent are converted as set on `Options`, or are an error.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde_json::{self, Map as JsonMap, Number};
+
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+impl From<serde_json::Value> for Value {
+    fn from(s: serde_json::Value) -> Self {
+        match s {
+            serde_json::Value::Null => Value::Nil,
+            serde_json::Value::Bool(boolean) => Value::Boolean(boolean),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(integer) => Value::Integer(integer),
+                None => Value::from(number.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(string) => Value::String(string),
+            serde_json::Value::Array(items) => {
+                Value::Vector(items.into_iter().map(Value::from).collect::<Vec<_>>())
+            }
+            serde_json::Value::Object(object) => Value::Map(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (Value::String(key), Value::from(value)))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+/// How keywords are written, both as values and as map keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeywordStyle {
+    /// `:user/name` is `"user/name"`.
+    Name,
+    /// `:user/name` is `":user/name"`.
+    Colon,
+    Error,
+}
+
+/// How tagged elements are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaggedStyle {
+    /// `#inst "..."` is just its value, `"..."`.
+    Value,
+    /// `#inst "..."` is `{"#inst": "..."}`.
+    Object,
+    Error,
+}
+
+/// Options for converting a `Value` to JSON.
+///
+/// The default is as lenient as possible: keywords, symbols and chars are
+/// strings, sets are arrays, tags are dropped and other map keys are written
+/// as their EDN text.
+#[derive(Clone, Debug)]
+pub struct Options {
+    keywords: KeywordStyle,
+    symbols: bool,
+    chars: bool,
+    sets: bool,
+    keys: bool,
+    tagged: TaggedStyle,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            keywords: KeywordStyle::Name,
+            symbols: true,
+            chars: true,
+            sets: true,
+            keys: true,
+            tagged: TaggedStyle::Value,
+        }
+    }
+}
+
+impl Options {
+    pub fn new() -> Options {
+        Options::default()
+    }
+
+    pub fn keywords(mut self, style: KeywordStyle) -> Options {
+        self.keywords = style;
+        self
+    }
+
+    /// Write symbols as strings, or fail if `false`.
+    pub fn symbols(mut self, as_strings: bool) -> Options {
+        self.symbols = as_strings;
+        self
+    }
+
+    /// Write chars as one character strings, or fail if `false`.
+    pub fn chars(mut self, as_strings: bool) -> Options {
+        self.chars = as_strings;
+        self
+    }
+
+    /// Write sets as arrays, or fail if `false`.
+    pub fn sets(mut self, as_arrays: bool) -> Options {
+        self.sets = as_arrays;
+        self
+    }
+
+    /// Write map keys other than strings, keywords and symbols as their EDN
+    /// text, or fail if `false`.
+    pub fn non_string_keys(mut self, as_edn: bool) -> Options {
+        self.keys = as_edn;
+        self
+    }
+
+    pub fn tagged(mut self, style: TaggedStyle) -> Options {
+        self.tagged = style;
+        self
+    }
+
+    pub fn to_json(&self, value: &Value) -> Result<serde_json::Value, Error> {
+        Ok(match *value {
+            Value::Nil => serde_json::Value::Null,
+            Value::Boolean(boolean) => serde_json::Value::Bool(boolean),
+            Value::String(ref string) => serde_json::Value::String(string.clone()),
+            Value::Integer(integer) => serde_json::Value::from(integer),
+            Value::Float(float) => match Number::from_f64(float.into_inner()) {
+                Some(number) => serde_json::Value::Number(number),
+                None => return error(format!("{} has no JSON equivalent", float)),
+            },
+            #[cfg(feature = "bigint")]
+            Value::BigInt(ref integer) => match integer.to_string().parse::<Number>() {
+                Ok(number) => serde_json::Value::Number(number),
+                Err(_) => return error(format!("{} is out of range for JSON", integer)),
+            },
+            #[cfg(feature = "bigdecimal")]
+            Value::Decimal(ref decimal) => match decimal
+                .to_string()
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+            {
+                Some(number) => serde_json::Value::Number(number),
+                None => return error(format!("{} is out of range for JSON", decimal)),
+            },
+            Value::Char(ch) if self.chars => serde_json::Value::String(ch.to_string()),
+            Value::Keyword(_) | Value::Symbol(_) => serde_json::Value::String(self.key(value)?),
+            Value::List(ref items) | Value::Vector(ref items) => serde_json::Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.to_json(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Set(ref items) if self.sets => serde_json::Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.to_json(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Map(ref map) => {
+                let mut object = JsonMap::new();
+                for (key, value) in map.iter() {
+                    object.insert(self.key(key)?, self.to_json(value)?);
+                }
+                serde_json::Value::Object(object)
+            }
+            Value::Tagged(ref tag, ref value) => match self.tagged {
+                TaggedStyle::Value => self.to_json(value)?,
+                TaggedStyle::Object => {
+                    let mut object = JsonMap::new();
+                    object.insert(format!("#{}", tag), self.to_json(value)?);
+                    serde_json::Value::Object(object)
+                }
+                TaggedStyle::Error => return error(format!("#{} has no JSON equivalent", tag)),
+            },
+            Value::WithMeta(ref value, _) => self.to_json(value)?,
+            _ => return error(format!("{} has no JSON equivalent", value.kind())),
+        })
+    }
+
+    /// `value` as an object key. Keywords and symbols are also written as
+    /// strings with this.
+    fn key(&self, value: &Value) -> Result<String, Error> {
+        match *value {
+            Value::String(ref string) => Ok(string.clone()),
+            Value::Keyword(ref keyword) => match self.keywords {
+                KeywordStyle::Name => Ok(keyword.to_string()[1..].into()),
+                KeywordStyle::Colon => Ok(keyword.to_string()),
+                KeywordStyle::Error => error(format!("{} has no JSON equivalent", keyword)),
+            },
+            Value::Symbol(ref symbol) if self.symbols => Ok(symbol.to_string()),
+            Value::Symbol(ref symbol) => error(format!("{} has no JSON equivalent", symbol)),
+            _ if self.keys => Ok(::ser::to_string(value)),
+            _ => error(format!("{} can't be a JSON object key", value.kind())),
+        }
+    }
+}
+
+/// Converts with the default `Options`.
+impl TryFrom<Value> for serde_json::Value {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<serde_json::Value, Error> {
+        Options::new().to_json(&value)
+    }
+}
+
+/// `value` as JSON which `from_lossless_json` reads back exactly, tags and
+/// metadata included.
+///
+/// `nil`, booleans, strings, `Integer`s, finite floats and vectors are their
+/// JSON equivalents. Every other value is an object with a single key naming
+/// its kind:
+///
+/// - `{"#kw": "user/id"}`, `{"#sym": "inc"}`, `{"#char": "a"}`
+/// - `{"#float": "##NaN"}` for non-finite floats, and `{"#bigint": "1"}`,
+///   `{"#decimal": "1.5"}` and `{"#ratio": [22, 7]}` with those features
+/// - `{"#list": [...]}`, `{"#set": [...]}`, and `{"#map": [k1, v1, ...]}`
+/// - `{"#tag": ["inst", value]}` and `{"#meta": [{"#map": [...]}, value]}`
+pub fn to_lossless_json(value: &Value) -> serde_json::Value {
+    let wrap = |key: &str, json: serde_json::Value| {
+        let mut object = JsonMap::new();
+        object.insert(key.into(), json);
+        serde_json::Value::Object(object)
+    };
+    let array = |items: &mut dyn Iterator<Item = &Value>| {
+        serde_json::Value::Array(items.map(to_lossless_json).collect())
+    };
+    match *value {
+        Value::Nil => serde_json::Value::Null,
+        Value::Boolean(boolean) => serde_json::Value::Bool(boolean),
+        Value::String(ref string) => serde_json::Value::String(string.clone()),
+        Value::Char(ch) => wrap("#char", ch.to_string().into()),
+        Value::Symbol(ref symbol) => wrap("#sym", symbol.to_string().into()),
+        Value::Keyword(ref keyword) => wrap("#kw", keyword.to_string()[1..].into()),
+        Value::Integer(integer) => integer.into(),
+        #[cfg(feature = "bigint")]
+        Value::BigInt(ref integer) => wrap("#bigint", integer.to_string().into()),
+        #[cfg(feature = "ratio")]
+        Value::Ratio(numerator, denominator) => wrap(
+            "#ratio",
+            serde_json::Value::Array(vec![numerator.into(), denominator.into()]),
+        ),
+        Value::Float(float) => match Number::from_f64(float.into_inner()) {
+            Some(number) => serde_json::Value::Number(number),
+            None => wrap("#float", ::ser::to_string(value).into()),
+        },
+        #[cfg(feature = "bigdecimal")]
+        Value::Decimal(ref decimal) => wrap("#decimal", decimal.to_string().into()),
+        Value::List(ref items) => wrap("#list", array(&mut items.iter())),
+        Value::Vector(ref items) => array(&mut items.iter()),
+        Value::Set(ref items) => wrap("#set", array(&mut items.iter())),
+        Value::Map(ref map) => wrap(
+            "#map",
+            array(&mut map.iter().flat_map(|(key, value)| vec![key, value])),
+        ),
+        Value::Tagged(ref tag, ref value) => wrap(
+            "#tag",
+            vec![tag.clone().into(), to_lossless_json(value)].into(),
+        ),
+        Value::WithMeta(ref value, ref meta) => wrap(
+            "#meta",
+            vec![
+                to_lossless_json(&Value::Map(meta.clone())),
+                to_lossless_json(value),
+            ]
+            .into(),
+        ),
+    }
+}
+
+/// Read JSON written by `to_lossless_json`.
+pub fn from_lossless_json(json: &serde_json::Value) -> Result<Value, Error> {
+    let object = match *json {
+        serde_json::Value::Null => return Ok(Value::Nil),
+        serde_json::Value::Bool(boolean) => return Ok(Value::Boolean(boolean)),
+        serde_json::Value::String(ref string) => return Ok(Value::String(string.clone())),
+        serde_json::Value::Number(ref number) => {
+            return match (number.as_i64(), number.as_f64()) {
+                (Some(integer), _) if !number.is_f64() => Ok(Value::Integer(integer)),
+                (_, Some(float)) => Ok(Value::from(float)),
+                _ => error(format!("{} is out of range", number)),
+            }
+        }
+        serde_json::Value::Array(ref items) => {
+            return Ok(Value::Vector(
+                items
+                    .iter()
+                    .map(from_lossless_json)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ))
+        }
+        serde_json::Value::Object(ref object) => object,
+    };
+    let (key, body) = match object.iter().next() {
+        Some(entry) if object.len() == 1 => entry,
+        _ => return error("expected an object with a single key".into()),
+    };
+    let string = || match *body {
+        serde_json::Value::String(ref string) => Ok(&string[..]),
+        _ => error(format!("expected a string for `{}`", key)),
+    };
+    let items = || match *body {
+        serde_json::Value::Array(ref items) => items
+            .iter()
+            .map(from_lossless_json)
+            .collect::<Result<::std::vec::Vec<_>, _>>(),
+        _ => error(format!("expected an array for `{}`", key)),
+    };
+    let pair = || match items()? {
+        ref items if items.len() == 2 => Ok((items[0].clone(), items[1].clone())),
+        _ => error(format!("expected two items for `{}`", key)),
+    };
+    Ok(match &key[..] {
+        "#kw" => Value::Keyword(string()?.into()),
+        "#sym" => Value::Symbol(string()?.into()),
+        "#char" => {
+            let mut chars = string()?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Value::Char(ch),
+                _ => return error("expected one character for `#char`".into()),
+            }
+        }
+        "#float" => match ::parser::from_str(string()?) {
+            Ok(float @ Value::Float(_)) => float,
+            _ => return error(format!("invalid float `{}`", string()?)),
+        },
+        #[cfg(feature = "bigint")]
+        "#bigint" => match string()?.parse() {
+            Ok(integer) => Value::BigInt(integer),
+            Err(_) => return error(format!("invalid integer `{}`", string()?)),
+        },
+        #[cfg(feature = "bigdecimal")]
+        "#decimal" => match string()?.parse() {
+            Ok(decimal) => Value::Decimal(decimal),
+            Err(_) => return error(format!("invalid decimal `{}`", string()?)),
+        },
+        #[cfg(feature = "ratio")]
+        "#ratio" => match pair()? {
+            (Value::Integer(numerator), Value::Integer(denominator)) if denominator > 0 => {
+                match Value::ratio(numerator, denominator as u64) {
+                    Some(ratio) => ratio,
+                    None => unreachable!(),
+                }
+            }
+            _ => return error("expected a numerator and denominator for `#ratio`".into()),
+        },
+        "#list" => Value::List(Vec::from(items()?)),
+        "#set" => Value::Set(items()?.into_iter().collect()),
+        "#map" => {
+            let items = items()?;
+            if items.len() % 2 != 0 {
+                return error("odd number of items in `#map`".into());
+            }
+            let mut map = Map::new();
+            let mut items = items.into_iter();
+            while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                map.insert(key, value);
+            }
+            Value::Map(map)
+        }
+        "#tag" => match pair()? {
+            (Value::String(tag), value) => Value::Tagged(tag, Box::new(value)),
+            _ => return error("expected a tag and a value for `#tag`".into()),
+        },
+        "#meta" => match pair()? {
+            (Value::Map(meta), value) => Value::WithMeta(Box::new(value), meta),
+            _ => return error("expected a map and a value for `#meta`".into()),
+        },
+        _ => return error(format!("unknown key `{}`", key)),
+    })
+}
@@ -0,0 +1,68 @@
+//! Framing EDN messages for Kafka in the Confluent wire format: a zero magic
+//! byte, a big-endian 32 bit schema id, then the compact EDN text.
+//!
+//! The schema id lets consumers tell message versions apart before reading
+//! them. Error offsets from `decode` are into the whole message, while lines
+//! and columns are within the EDN text.
+
+use parser::{self, Category, Error};
+use ser;
+use Value;
+
+pub const MAGIC: u8 = 0;
+
+const HEADER: usize = 5;
+
+pub fn encode(schema_id: u32, value: &Value) -> Vec<u8> {
+    let mut out = vec![MAGIC];
+    out.extend_from_slice(&schema_id.to_be_bytes());
+    ser::to_writer(&mut out, value).expect("symbols, keywords and tags must be printable");
+    out
+}
+
+fn error<T>(lo: usize, hi: usize, message: &str) -> Result<T, Error> {
+    Err(Error {
+        lo,
+        hi,
+        line: 1,
+        column: 1,
+        category: Category::Syntax,
+        message: message.into(),
+    })
+}
+
+/// The schema id of a framed message, without reading its value.
+pub fn schema_id(message: &[u8]) -> Result<u32, Error> {
+    if message.len() < HEADER {
+        return error(
+            0,
+            message.len(),
+            "message is shorter than its 5 byte header",
+        );
+    }
+    if message[0] != MAGIC {
+        return error(0, 1, "expected magic byte 0");
+    }
+    let mut id = [0; 4];
+    id.copy_from_slice(&message[1..HEADER]);
+    Ok(u32::from_be_bytes(id))
+}
+
+pub fn decode(message: &[u8]) -> Result<(u32, Value), Error> {
+    let id = schema_id(message)?;
+    let payload = match ::std::str::from_utf8(&message[HEADER..]) {
+        Ok(payload) => payload,
+        Err(err) => {
+            let at = HEADER + err.valid_up_to();
+            return error(at, at, "invalid UTF-8 in message");
+        }
+    };
+    match parser::from_str(payload) {
+        Ok(value) => Ok((id, value)),
+        Err(err) => Err(Error {
+            lo: err.lo + HEADER,
+            hi: err.hi + HEADER,
+            ..err
+        }),
+    }
+}
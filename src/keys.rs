@@ -0,0 +1,164 @@
+//! Typed extraction of keyword entries from a map, like Clojure's
+//! `{:keys [...]}` destructuring. See `edn_keys!`.
+//!
+//! ```
+//! # #[macro_use] extern crate edn;
+//! # fn main() {
+//! let value = edn::parser::from_str("{:name \"Ada\" :age 36}").unwrap();
+//! let (name, age, email): (String, i64, Option<String>) =
+//!     edn_keys!(value, :name, :age, :email).unwrap();
+//! assert_eq!((name.as_str(), age, email), ("Ada", 36, None));
+//! # }
+//! ```
+
+use std::fmt;
+
+use {Keyword, Symbol, TypeError, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// A type which can be read from the value of a map entry.
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, TypeError>;
+
+    /// The value to use when the key is missing, or `None` if that's an
+    /// error.
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+fn mismatch<T>(expected: &'static str, found: &Value) -> Result<T, TypeError> {
+    Err(TypeError {
+        expected,
+        found: found.kind(),
+    })
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Value, TypeError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<bool, TypeError> {
+        value
+            .as_bool()
+            .map_or_else(|| mismatch("a boolean", value), Ok)
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<String, TypeError> {
+        value
+            .as_str()
+            .map_or_else(|| mismatch("a string", value), |str| Ok(str.into()))
+    }
+}
+
+impl FromValue for char {
+    fn from_value(value: &Value) -> Result<char, TypeError> {
+        value
+            .as_char()
+            .map_or_else(|| mismatch("a char", value), Ok)
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Result<i64, TypeError> {
+        value
+            .as_i64()
+            .map_or_else(|| mismatch("an integer", value), Ok)
+    }
+}
+
+impl FromValue for u64 {
+    fn from_value(value: &Value) -> Result<u64, TypeError> {
+        value
+            .as_u64()
+            .map_or_else(|| mismatch("a non-negative integer", value), Ok)
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<f64, TypeError> {
+        value
+            .as_f64()
+            .map_or_else(|| mismatch("a number", value), Ok)
+    }
+}
+
+impl FromValue for Keyword {
+    fn from_value(value: &Value) -> Result<Keyword, TypeError> {
+        value.as_keyword().map_or_else(
+            || mismatch("a keyword", value),
+            |keyword| Ok(keyword.clone()),
+        )
+    }
+}
+
+impl FromValue for Symbol {
+    fn from_value(value: &Value) -> Result<Symbol, TypeError> {
+        value
+            .as_symbol()
+            .map_or_else(|| mismatch("a symbol", value), |symbol| Ok(symbol.clone()))
+    }
+}
+
+/// `None` for a missing key or `nil`.
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Option<T>, TypeError> {
+        match *value {
+            Value::Nil => Ok(None),
+            ref value => T::from_value(value).map(Some),
+        }
+    }
+
+    fn missing() -> Option<Option<T>> {
+        Some(None)
+    }
+}
+
+/// The items of a list or vector.
+/// Either a list or a vector; see `List` to require a list.
+impl<T: FromValue> FromValue for ::std::vec::Vec<T> {
+    fn from_value(value: &Value) -> Result<::std::vec::Vec<T>, TypeError> {
+        match *value {
+            Value::List(ref items) | Value::Vector(ref items) => {
+                items.iter().map(T::from_value).collect()
+            }
+            ref value => mismatch("a list or vector", value),
+        }
+    }
+}
+
+/// The value for the keyword `name`, e.g. `"user/name"`, in the map
+/// `value`, converted to `T`.
+pub fn get<T: FromValue>(value: &Value, name: &str) -> Result<T, Error> {
+    let map = match *value {
+        Value::Map(ref map) => map,
+        ref value => {
+            return Err(Error {
+                message: format!("expected a map, found {}", value.kind()),
+            })
+        }
+    };
+    match map.get_kw(name) {
+        Some(entry) => T::from_value(entry).map_err(|err| Error {
+            message: format!(":{}: {}", name, err),
+        }),
+        None => T::missing().ok_or_else(|| Error {
+            message: format!("missing key :{}", name),
+        }),
+    }
+}
@@ -0,0 +1,108 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::str::FromStr;
+
+use map::{Key, KeyRef};
+use symbol::{is_valid, split};
+use Value;
+
+/// An EDN keyword, e.g. `:foo` or `:person/name`.
+///
+/// Keywords are converted from strings without the leading `:`, so
+/// `Keyword::from("person/name")` is `:person/name`. Parsing with `FromStr`
+/// also accepts the printed form, `":person/name"`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Keyword {
+    namespace: Option<String>,
+    name: String,
+}
+
+impl Keyword {
+    pub fn new(name: &str) -> Keyword {
+        Keyword {
+            namespace: None,
+            name: name.into(),
+        }
+    }
+
+    pub fn namespaced(namespace: &str, name: &str) -> Keyword {
+        Keyword {
+            namespace: Some(namespace.into()),
+            name: name.into(),
+        }
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_ref().map(|namespace| &namespace[..])
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the keyword follows the EDN spec's grammar, so that it can be
+    /// written as EDN and read back as the same keyword.
+    pub fn is_valid(&self) -> bool {
+        is_valid(self.namespace(), &self.name, true)
+    }
+
+    /// Look this keyword up in `value`, like `(:keyword value)` in Clojure:
+    /// the value for it in a map, or the keyword itself from a set.
+    pub fn lookup<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        lookup(KeyRef::Keyword(self.namespace(), self.name()), value)
+    }
+
+    /// Like `lookup`, but `default` if the keyword isn't found, like
+    /// `(:keyword value default)`.
+    pub fn lookup_or<'v>(&self, value: &'v Value, default: &'v Value) -> &'v Value {
+        self.lookup(value).unwrap_or(default)
+    }
+}
+
+/// `Keyword::lookup` for the keyword `key`, which doesn't need a `Keyword`.
+pub(crate) fn lookup<'v>(key: KeyRef, value: &'v Value) -> Option<&'v Value> {
+    let key = &key as &dyn Key;
+    match *value {
+        Value::Map(ref map) => map.get(key),
+        Value::Set(ref set) if set.contains(key) => set
+            .iter()
+            .find(|&item| Borrow::<dyn Key>::borrow(item) == key),
+        _ => None,
+    }
+}
+
+impl<'a> From<&'a str> for Keyword {
+    fn from(s: &'a str) -> Self {
+        let (namespace, name) = split(s);
+        Keyword { namespace, name }
+    }
+}
+
+impl From<String> for Keyword {
+    fn from(s: String) -> Self {
+        Keyword::from(&s[..])
+    }
+}
+
+impl FromStr for Keyword {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Keyword, String> {
+        let s = s.strip_prefix(':').unwrap_or(s);
+        if s.is_empty() {
+            Err("empty keyword".into())
+        } else {
+            Ok(Keyword::from(s))
+        }
+    }
+}
+
+/// Prints the keyword with its leading `:`.
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.namespace {
+            Some(ref namespace) => write!(f, ":{}/{}", namespace, self.name),
+            None => write!(f, ":{}", self.name),
+        }
+    }
+}
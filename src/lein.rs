@@ -0,0 +1,222 @@
+//! Reading Leiningen `project.clj` files.
+//!
+//! A `project.clj` is Clojure code, but its `defproject` form is mostly data.
+//! `parse` reads it with `ParserBuilder::clojure_syntax`, so quoting and
+//! regexes don't stop it, and collects the forms Leiningen would evaluate
+//! instead of evaluating them.
+//!
+//! ```
+//! use edn::lein;
+//!
+//! let project = lein::parse(r#"
+//!     (defproject org.example/app "0.1.0-SNAPSHOT"
+//!       :description "An app"
+//!       :dependencies [[org.clojure/clojure "1.11.1"]
+//!                      [cheshire "5.12.0" :exclusions [com.fasterxml.jackson.core/jackson-core]]]
+//!       :jar-exclusions [#"\.java$"])
+//! "#).unwrap();
+//! assert_eq!(project.group(), "org.example");
+//! assert_eq!(project.artifact(), "app");
+//! assert_eq!(project.dependencies().unwrap()[1].name.name(), "cheshire");
+//! ```
+
+use std::fmt;
+
+use parser::ParserBuilder;
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::Map;
+#[cfg(not(feature = "immutable"))]
+use standard::Map;
+
+/// The error returned by `parse` and `Project::dependencies`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+/// A `defproject` form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Project {
+    /// `group/artifact`, or just `artifact` when they are the same.
+    pub name: Symbol,
+    /// Usually a string, but may be a form to evaluate.
+    pub version: Value,
+    /// The keyword options after the version, e.g. `:dependencies`.
+    pub options: Map<Value, Value>,
+    /// The forms in the project which Leiningen would evaluate: `~form`,
+    /// `~@form` and `#=form`, in the order they appear.
+    pub evaluated: Vec<Value>,
+}
+
+/// A `[name version & options]` dependency vector.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dependency {
+    pub name: Symbol,
+    pub version: Value,
+    /// The keyword options after the version, e.g. `:exclusions` or
+    /// `:scope`.
+    pub options: Map<Value, Value>,
+}
+
+impl Project {
+    pub fn group(&self) -> &str {
+        self.name.namespace().unwrap_or_else(|| self.name.name())
+    }
+
+    pub fn artifact(&self) -> &str {
+        self.name.name()
+    }
+
+    /// The value of the option `:name`.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.options.get(&Value::Keyword(Keyword::new(name)))
+    }
+
+    /// The entries of `:dependencies`.
+    pub fn dependencies(&self) -> Result<Vec<Dependency>, Error> {
+        dependencies(self.get("dependencies"), "dependencies")
+    }
+
+    /// The entries of `:plugins`.
+    pub fn plugins(&self) -> Result<Vec<Dependency>, Error> {
+        dependencies(self.get("plugins"), "plugins")
+    }
+}
+
+fn dependencies(value: Option<&Value>, key: &str) -> Result<Vec<Dependency>, Error> {
+    let items = match value {
+        None | Some(Value::Nil) => return Ok(vec![]),
+        Some(Value::Vector(items)) | Some(Value::List(items)) => items,
+        Some(other) => {
+            return error(format!(
+                "`:{}` must be a vector, found {}",
+                key,
+                other.kind()
+            ))
+        }
+    };
+    items
+        .iter()
+        .map(|item| {
+            let mut parts = match *item {
+                Value::Vector(ref parts) => parts.iter(),
+                ref other => {
+                    return error(format!(
+                        "expected a dependency vector in `:{}`, found {}",
+                        key,
+                        other.kind()
+                    ))
+                }
+            };
+            let name = match parts.next() {
+                Some(Value::Symbol(name)) => name.clone(),
+                _ => return error(format!("expected a dependency name in `:{}`", key)),
+            };
+            let version = parts.next().cloned().unwrap_or(Value::Nil);
+            Ok(Dependency {
+                options: options(parts, &format!("dependency `{}`", name))?,
+                name,
+                version,
+            })
+        })
+        .collect()
+}
+
+/// The keyword options in `items`, which belong to `what`.
+fn options<'a, I>(mut items: I, what: &str) -> Result<Map<Value, Value>, Error>
+where
+    I: Iterator<Item = &'a Value>,
+{
+    let mut options = Map::new();
+    while let Some(key) = items.next() {
+        match items.next() {
+            Some(value) => {
+                options.insert(key.clone(), value.clone());
+            }
+            None => return error(format!("odd number of options in {}", what)),
+        }
+    }
+    Ok(options)
+}
+
+/// Read the `defproject` form in the text of a `project.clj`. Other
+/// top-level forms, such as `def`s it refers to, are skipped.
+pub fn parse(str: &str) -> Result<Project, Error> {
+    let builder = ParserBuilder::new().clojure_syntax(true);
+    let mut parser = builder.build(str);
+    while let Some(form) = parser.read() {
+        let form = form.map_err(|err| Error {
+            message: err.to_string(),
+        })?;
+        let mut items = match form {
+            Value::List(ref items) if is_defproject(items.iter().next()) => items.iter().skip(1),
+            _ => continue,
+        };
+        let name = match items.next() {
+            Some(Value::Symbol(name)) => name.clone(),
+            _ => return error("expected a project name after `defproject`".into()),
+        };
+        let version = match items.next() {
+            Some(version) => version.clone(),
+            None => return error("expected a version after the project name".into()),
+        };
+        let mut evaluated = vec![];
+        collect_evaluated(&form, &mut evaluated);
+        return Ok(Project {
+            name,
+            version,
+            options: options(items, "`defproject`")?,
+            evaluated,
+        });
+    }
+    error("no `defproject` form found".into())
+}
+
+fn is_defproject(head: Option<&Value>) -> bool {
+    matches!(head, Some(Value::Symbol(symbol)) if symbol.name() == "defproject")
+}
+
+fn collect_evaluated(value: &Value, evaluated: &mut Vec<Value>) {
+    match *value {
+        Value::List(ref items) => {
+            let head = items.iter().next().and_then(Value::as_symbol);
+            if head.is_some_and(|head| {
+                head.namespace().is_none() && matches!(head.name(), "unquote" | "unquote-splicing" | "read-eval")
+            }) {
+                evaluated.push(value.clone());
+                return;
+            }
+            items
+                .iter()
+                .for_each(|item| collect_evaluated(item, evaluated));
+        }
+        Value::Vector(ref items) => items
+            .iter()
+            .for_each(|item| collect_evaluated(item, evaluated)),
+        Value::Set(ref items) => items
+            .iter()
+            .for_each(|item| collect_evaluated(item, evaluated)),
+        Value::Map(ref map) => {
+            for (key, value) in map.iter() {
+                collect_evaluated(key, evaluated);
+                collect_evaluated(value, evaluated);
+            }
+        }
+        Value::Tagged(_, ref value) | Value::WithMeta(ref value, _) => {
+            collect_evaluated(value, evaluated)
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,336 @@
+//! Splitting EDN text into tokens, for tools like syntax highlighters,
+//! formatters and linters which need the source rather than values.
+//!
+//! ```
+//! use edn::lexer::{Lexer, Span, Token};
+//!
+//! let tokens: Vec<_> = Lexer::new("[:a 1]").map(Result::unwrap).collect();
+//! assert_eq!(tokens[1], (Token::Keyword("a"), Span { lo: 1, hi: 3 }));
+//! ```
+//!
+//! Every byte of the input is part of exactly one token, so joining the text
+//! of the spans gives back the input. Tokens aren't checked against each
+//! other, e.g. for balanced delimiters; that's up to the parser.
+
+use parser::{
+    char_literal, exponent_len, is_symbol_head, is_symbol_tail, unicode_escape, Category, Error,
+};
+
+/// Byte offsets `lo..hi` of a token in the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub lo: usize,
+    pub hi: usize,
+}
+
+/// A token, borrowing its text from the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// Whitespace, including commas.
+    Whitespace(&'a str),
+    /// A comment without the leading `;` or the trailing newline.
+    Comment(&'a str),
+    OpenList,
+    CloseList,
+    OpenVector,
+    CloseVector,
+    OpenMap,
+    CloseMap,
+    /// `#{`, closed by `CloseMap`.
+    OpenSet,
+    /// `#_`, which discards the next form.
+    Discard,
+    /// `^`, which attaches the next form as metadata to the one after it.
+    Meta,
+    /// A tag without the leading `#`.
+    Tag(&'a str),
+    Nil,
+    Boolean(bool),
+    Symbol(&'a str),
+    /// A keyword without the leading `:`.
+    Keyword(&'a str),
+    /// An integer as written, including any sign and `N` suffix.
+    Integer(&'a str),
+    /// A ratio as written, e.g. `-22/7`.
+    Ratio(&'a str),
+    /// A float as written, including any sign, exponent and `M` suffix, or
+    /// `##Inf`, `##-Inf` or `##NaN`.
+    Float(&'a str),
+    /// The contents of a string between the quotes, with escapes as written.
+    String(&'a str),
+    /// A char literal without the leading `\`, e.g. `newline`.
+    Char(&'a str),
+}
+
+/// An iterator over the tokens of a string.
+///
+/// Iteration stops after the first error.
+pub struct Lexer<'a> {
+    str: &'a str,
+    pos: usize,
+    failed: bool,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(str: &'a str) -> Lexer<'a> {
+        Lexer {
+            str,
+            pos: 0,
+            failed: false,
+        }
+    }
+
+    /// A lexer over `str` starting at byte offset `pos`.
+    pub(crate) fn at(str: &'a str, pos: usize) -> Lexer<'a> {
+        Lexer {
+            str,
+            pos,
+            failed: false,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.str[self.pos..].chars().next()
+    }
+
+    /// Advance past the characters matching `f`, returning the new position.
+    fn advance_while<F: FnMut(char) -> bool>(&mut self, mut f: F) -> usize {
+        self.pos = self.str[self.pos..]
+            .char_indices()
+            .find(|&(_, ch)| !f(ch))
+            .map_or(self.str.len(), |(i, _)| self.pos + i);
+        self.pos
+    }
+
+    fn error<T, M: Into<String>>(
+        &self,
+        lo: usize,
+        hi: usize,
+        category: Category,
+        message: M,
+    ) -> Result<T, Error> {
+        Err(Error::at(self.str, lo, hi, category, message.into()))
+    }
+
+    fn number(&mut self, start: usize) -> Token<'a> {
+        self.advance_while(|ch| ch.is_ascii_digit());
+        let mut float = self.str[start..].starts_with('.');
+        if !float && self.peek() == Some('.') {
+            float = true;
+            self.pos += 1;
+            self.advance_while(|ch| ch.is_ascii_digit());
+        }
+        let exponent = exponent_len(&self.str[self.pos..]);
+        if exponent > 0 {
+            float = true;
+            self.pos += exponent;
+        }
+        if !float
+            && self.peek() == Some('/')
+            && self.str[self.pos + 1..].starts_with(|ch: char| ch.is_ascii_digit())
+        {
+            self.pos += 1;
+            let end = self.advance_while(|ch| ch.is_ascii_digit());
+            return Token::Ratio(&self.str[start..end]);
+        }
+        match self.peek() {
+            Some('M') => {
+                float = true;
+                self.pos += 1;
+            }
+            Some('N') if !float => self.pos += 1,
+            _ => {}
+        }
+        let text = &self.str[start..self.pos];
+        if float {
+            Token::Float(text)
+        } else {
+            Token::Integer(text)
+        }
+    }
+
+    fn token(&mut self, start: usize, ch: char) -> Result<Token<'a>, Error> {
+        self.pos += ch.len_utf8();
+        Ok(match ch {
+            ch if ch.is_whitespace() || ch == ',' => {
+                let end = self.advance_while(|ch| ch.is_whitespace() || ch == ',');
+                Token::Whitespace(&self.str[start..end])
+            }
+            ';' => {
+                let end = self.advance_while(|ch| ch != '\n');
+                Token::Comment(&self.str[start + 1..end])
+            }
+            '(' => Token::OpenList,
+            ')' => Token::CloseList,
+            '[' => Token::OpenVector,
+            ']' => Token::CloseVector,
+            '{' => Token::OpenMap,
+            '}' => Token::CloseMap,
+            '^' => Token::Meta,
+            '0'..='9' => self.number(start),
+            '+' | '-' | '.' if self.peek().is_some_and(|ch| ch.is_ascii_digit()) => {
+                self.number(start)
+            }
+            ':' => {
+                let end = self.advance_while(is_symbol_tail);
+                Token::Keyword(&self.str[start + 1..end])
+            }
+            '/' => Token::Symbol("/"),
+            ch if is_symbol_head(ch) => {
+                let end = self.advance_while(is_symbol_tail);
+                match &self.str[start..end] {
+                    "nil" => Token::Nil,
+                    "true" => Token::Boolean(true),
+                    "false" => Token::Boolean(false),
+                    symbol => Token::Symbol(symbol),
+                }
+            }
+            '#' => match self.peek() {
+                Some('#') => {
+                    self.pos += 1;
+                    let end = self.advance_while(is_symbol_tail);
+                    match &self.str[start + 2..end] {
+                        "Inf" | "-Inf" | "NaN" => Token::Float(&self.str[start..end]),
+                        name => {
+                            return self.error(
+                                start,
+                                end,
+                                Category::Syntax,
+                                format!("unknown symbolic value `##{}`", name),
+                            )
+                        }
+                    }
+                }
+                Some('{') => {
+                    self.pos += 1;
+                    Token::OpenSet
+                }
+                Some('_') => {
+                    self.pos += 1;
+                    Token::Discard
+                }
+                Some(ch) if is_symbol_head(ch) => {
+                    let end = self.advance_while(is_symbol_tail);
+                    Token::Tag(&self.str[start + 1..end])
+                }
+                Some(ch) => {
+                    return self.error(
+                        start,
+                        self.pos + ch.len_utf8(),
+                        Category::Syntax,
+                        format!("unexpected `#{}`", ch),
+                    )
+                }
+                None => {
+                    return self.error(
+                        start,
+                        self.pos,
+                        Category::Eof,
+                        "expected a tag, `{` or `_` after `#`, found EOF",
+                    )
+                }
+            },
+            '\\' => {
+                let first = match self.peek() {
+                    Some(ch) => ch,
+                    None => {
+                        return self.error(
+                            start,
+                            self.pos,
+                            Category::Eof,
+                            "expected a char after `\\`, found EOF",
+                        )
+                    }
+                };
+                self.pos += first.len_utf8();
+                let end =
+                    self.advance_while(|ch| !ch.is_whitespace() && !"()[]{}\",;\\".contains(ch));
+                match &self.str[start + 1..end] {
+                    name if char_literal(name).is_some() => Token::Char(name),
+                    name => {
+                        return self.error(
+                            start,
+                            end,
+                            Category::Syntax,
+                            format!("invalid char literal `\\{}`", name),
+                        )
+                    }
+                }
+            }
+            '"' => return self.string(start),
+            ch => {
+                return self.error(
+                    start,
+                    self.pos,
+                    Category::Syntax,
+                    format!("unexpected `{}`", ch),
+                )
+            }
+        })
+    }
+
+    fn string(&mut self, start: usize) -> Result<Token<'a>, Error> {
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(Token::String(&self.str[start + 1..self.pos - 1]));
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('t') | Some('r') | Some('n') | Some('b') | Some('f') | Some('\\')
+                        | Some('"') => self.pos += 1,
+                        Some('u') => match unicode_escape(&self.str[self.pos + 1..]) {
+                            Some((_, len)) => self.pos += len + 1,
+                            None => {
+                                return self.error(
+                                    self.pos - 1,
+                                    self.pos + 1,
+                                    Category::Syntax,
+                                    "expected four hex digits naming a char after `\\u`",
+                                )
+                            }
+                        },
+                        Some(ch) => {
+                            return self.error(
+                                self.pos - 1,
+                                self.pos + ch.len_utf8(),
+                                Category::Syntax,
+                                format!("invalid string escape `\\{}`", ch),
+                            )
+                        }
+                        None => break,
+                    }
+                }
+                Some(ch) => self.pos += ch.len_utf8(),
+                None => break,
+            }
+        }
+        self.error(
+            start,
+            self.str.len(),
+            Category::Eof,
+            "expected closing `\"`, found EOF",
+        )
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<(Token<'a>, Span), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let lo = self.pos;
+        let ch = self.peek()?;
+        match self.token(lo, ch) {
+            Ok(token) => Some(Ok((token, Span { lo, hi: self.pos }))),
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
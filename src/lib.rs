@@ -1,3 +1,5 @@
+extern crate itoa;
+extern crate memchr;
 extern crate ordered_float;
 
 use ordered_float::OrderedFloat;
@@ -5,10 +7,61 @@ use ordered_float::OrderedFloat;
 #[cfg(feature = "immutable")]
 extern crate im;
 
+#[cfg(feature = "ordered")]
+extern crate indexmap;
+
+#[cfg(feature = "chrono")]
+extern crate chrono;
+
+#[cfg(feature = "uuid")]
+extern crate uuid;
+
+#[cfg(feature = "bigint")]
+extern crate num_bigint;
+
+#[cfg(feature = "bigdecimal")]
+extern crate bigdecimal;
+
+#[cfg(feature = "json")]
+extern crate serde_json;
+#[cfg(feature = "wasm")]
+extern crate js_sys;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+#[cfg(feature = "futures")]
+extern crate futures_core;
+
+#[cfg(feature = "mmap")]
+extern crate memmap2;
+
+#[cfg(feature = "prost")]
+extern crate prost_types;
+
+#[cfg(feature = "arrow")]
+extern crate arrow;
+
+#[cfg(feature = "redis")]
+extern crate redis;
+
+#[cfg(feature = "rhai")]
+extern crate rhai;
+
+#[cfg(feature = "python")]
+extern crate pyo3;
+// pyo3's macros refer to `::core`.
+#[cfg(feature = "python")]
+extern crate core;
+
+#[cfg(feature = "node")]
+extern crate napi;
+#[cfg(feature = "node")]
+extern crate napi_derive;
+
 #[cfg(feature = "immutable")]
-use immutable::{Map, Set, Vec};
+use immutable::{Set, Vec};
 #[cfg(not(feature = "immutable"))]
-use standard::{Map, Set, Vec};
+use standard::{Set, Vec};
 
 #[cfg(feature = "immutable")]
 use im::{HashMap, HashSet, Vector};
@@ -20,28 +73,285 @@ use std::hash::Hash;
 
 #[cfg(not(feature = "immutable"))]
 mod standard;
+#[cfg(all(feature = "ordered", not(feature = "immutable")))]
+mod ordered;
 #[cfg(feature = "immutable")]
 mod immutable;
 
+#[cfg(feature = "bigint")]
+mod bigint;
+
+#[cfg(feature = "bigdecimal")]
+mod decimal;
+
+#[cfg(feature = "ratio")]
+mod ratio;
+
+mod seqs;
+mod sets;
+
+#[cfg(feature = "chrono")]
+mod inst;
+
+#[cfg(feature = "uuid")]
+mod uuid_tag;
+
+#[cfg(feature = "prost")]
+pub mod protobuf;
+
 use std::fmt;
 
+mod accessors;
+mod index;
+mod keyword;
+mod merge;
+mod normalize;
+mod numeric;
+mod partial_eq;
+mod raw;
+mod shared;
+mod symbol;
+mod walk;
+
+/// The supertrait of `Formatter` and `Index`, so that only this crate can
+/// implement them, and add methods to them, unless the `unstable` feature
+/// is enabled.
+mod sealed {
+    pub trait Sealed {}
+
+    #[cfg(feature = "unstable")]
+    impl<T: ?Sized> Sealed for T {}
+}
+
+pub use diff::{diff, patch, Diff};
+pub use index::Index;
+pub use keyword::Keyword;
+pub use map::Map;
+pub use merge::Conflict;
+pub use normalize::Normalize;
+pub use numeric::{NumberError, NumericEq};
+pub use raw::RawEdn;
+pub use seqs::{CollectValue, List};
+pub use ser::{
+    to_string, to_string_pretty, to_vec, to_vec_pretty, to_writer, to_writer_pretty,
+};
+pub use shared::ArcValue;
+pub use symbol::Symbol;
+
+#[doc(hidden)]
+#[macro_use]
+pub mod macros;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+pub mod avro;
+pub mod borrowed;
+pub mod bytes;
+pub mod casing;
+pub mod code;
+pub mod compat;
+#[cfg(feature = "unstable")]
+pub mod compress;
+pub mod config;
+#[cfg(feature = "unstable")]
+pub mod cst;
+pub mod datomic;
+pub mod deps;
+pub mod diff;
+pub mod eql;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod flatten;
+pub mod header;
+pub mod ids;
+pub mod iter;
+pub mod keys;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod kafka;
+pub mod lein;
+pub mod lexer;
+pub mod map;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+#[cfg(feature = "node")]
+pub mod node;
 pub mod parser;
+#[cfg(feature = "pod")]
+pub mod pod;
+pub mod prepl;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod read;
+pub mod readers;
+#[cfg(feature = "arrow")]
+pub mod record_batch;
+#[cfg(feature = "redis")]
+pub mod redis_store;
+pub mod ring;
+pub mod schema;
+#[cfg(feature = "rhai")]
+pub mod scripting;
+pub mod ser;
+pub mod stats;
+pub mod std_types;
+pub mod tagged;
+#[cfg(feature = "json")]
+pub mod transit;
+pub mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+/// An EDN value.
+///
+/// `==`, `Ord` and `Hash` compare variants structurally, as Clojure's `=`
+/// does: `Integer(1)` and `Float(1.0)` are different values, and every
+/// `Integer` sorts before every `Float`, so `2 < 1.5`. Compare numbers by
+/// value with `numeric_eq`, `numeric_cmp` and `NumericEq`.
 #[derive(Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Value {
     Nil,
     Boolean(bool),
     String(String),
     Char(char),
-    Symbol(String),
-    Keyword(String),
+    Symbol(Symbol),
+    Keyword(Keyword),
     Integer(i64),
+    /// An integer written with an `N` suffix, e.g. `123N`.
+    #[cfg(feature = "bigint")]
+    BigInt(num_bigint::BigInt),
+    /// A ratio in lowest terms with a denominator above 1, e.g. `22/7`, as
+    /// created by `Value::ratio`.
+    #[cfg(feature = "ratio")]
+    Ratio(i64, u64),
     Float(OrderedFloat<f64>),
+    /// A decimal written with an `M` suffix, e.g. `3.14M`.
+    #[cfg(feature = "bigdecimal")]
+    Decimal(bigdecimal::BigDecimal),
     List(Vec<Value>),
     Vector(Vec<Value>),
     Map(Map<Value, Value>),
     Set(Set<Value>),
     Tagged(String, Box<Value>),
+    /// A value with `^` metadata, kept when parsing with
+    /// `ParserBuilder::keep_metadata`.
+    WithMeta(Box<Value>, Map<Value, Value>),
+}
+
+impl Value {
+    /// A list of `items`. The `From` impls for sequences build vectors, so
+    /// use this (or `CollectValue::collect_list`) for code forms like
+    /// `(inc x)`.
+    pub fn list<I>(items: I) -> Value
+    where
+        I: IntoIterator,
+        Value: From<I::Item>,
+    {
+        Value::List(items.into_iter().map(Value::from).collect())
+    }
+
+    /// A vector of `items`.
+    pub fn vector<I>(items: I) -> Value
+    where
+        I: IntoIterator,
+        Value: From<I::Item>,
+    {
+        Value::Vector(items.into_iter().map(Value::from).collect())
+    }
+
+    /// A set of `items`.
+    pub fn set<I>(items: I) -> Value
+    where
+        I: IntoIterator,
+        Value: From<I::Item>,
+    {
+        Value::Set(items.into_iter().map(Value::from).collect())
+    }
+
+    /// A map of `entries`. Later entries replace earlier ones with the same
+    /// key.
+    pub fn map<I, K, V>(entries: I) -> Value
+    where
+        I: IntoIterator<Item = (K, V)>,
+        Value: From<K> + From<V>,
+    {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (Value::from(key), Value::from(value)))
+                .collect(),
+        )
+    }
+
+    /// Replace this value with `nil`, returning it.
+    pub fn take(&mut self) -> Value {
+        ::std::mem::replace(self, Value::Nil)
+    }
+
+    /// Move the items out of a `List` or `Vector`, or give back `self` for
+    /// any other variant.
+    pub fn into_vec(self) -> Result<Vec<Value>, Value> {
+        match self {
+            Value::List(items) | Value::Vector(items) => Ok(items),
+            otherwise => Err(otherwise),
+        }
+    }
+
+    /// Move the entries out of a `Map`, or give back `self` for any other
+    /// variant.
+    pub fn into_map(self) -> Result<Map<Value, Value>, Value> {
+        match self {
+            Value::Map(map) => Ok(map),
+            otherwise => Err(otherwise),
+        }
+    }
+
+    /// Move the elements out of a `Set`, or give back `self` for any other
+    /// variant.
+    pub fn into_set(self) -> Result<Set<Value>, Value> {
+        match self {
+            Value::Set(set) => Ok(set),
+            otherwise => Err(otherwise),
+        }
+    }
+
+    /// The name of this variant, as used in error messages.
+    pub(crate) fn kind(&self) -> &'static str {
+        match *self {
+            Value::Nil => "nil",
+            Value::Boolean(_) => "a boolean",
+            Value::String(_) => "a string",
+            Value::Char(_) => "a char",
+            Value::Symbol(_) => "a symbol",
+            Value::Keyword(_) => "a keyword",
+            Value::Integer(_) => "an integer",
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => "an integer",
+            #[cfg(feature = "ratio")]
+            Value::Ratio(..) => "a ratio",
+            Value::Float(_) => "a float",
+            #[cfg(feature = "bigdecimal")]
+            Value::Decimal(_) => "a decimal",
+            Value::List(_) => "a list",
+            Value::Vector(_) => "a vector",
+            Value::Map(_) => "a map",
+            Value::Set(_) => "a set",
+            Value::Tagged(..) => "a tagged element",
+            Value::WithMeta(ref value, _) => value.kind(),
+        }
+    }
+}
+
+/// The error returned when a `Value` method is given the wrong variant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeError {
+    pub expected: &'static str,
+    pub found: &'static str,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)
+    }
 }
 
 // TODO.
@@ -75,23 +385,56 @@ impl From<char> for Value {
     }
 }
 
+impl From<Symbol> for Value {
+    fn from(s: Symbol) -> Self {
+        Value::Symbol(s)
+    }
+}
+
+impl From<Keyword> for Value {
+    fn from(s: Keyword) -> Self {
+        Value::Keyword(s)
+    }
+}
+
 impl From<i64> for Value {
     fn from(s: i64) -> Self {
         Value::Integer(s)
     }
 }
+
+macro_rules! from_integer {
+    ($($ty:ident)*) => ($(
+        impl From<$ty> for Value {
+            fn from(s: $ty) -> Self {
+                Value::Integer(i64::from(s))
+            }
+        }
+    )*)
+}
+
+from_integer!(i8 i16 i32 u8 u16 u32);
+
 impl From<f64> for Value {
     fn from(s: f64) -> Self {
         Value::Float(OrderedFloat(s))
     }
 }
 
+/// Widens exactly, so `0.1f32` is `0.10000000149011612`.
+impl From<f32> for Value {
+    fn from(s: f32) -> Self {
+        Value::Float(OrderedFloat(f64::from(s)))
+    }
+}
+
 impl From<OrderedFloat<f64>> for Value {
     fn from(s: OrderedFloat<f64>) -> Self {
         Value::Float(s)
     }
 }
 
+/// Builds a `Vector`; see `Value::list` for lists.
 #[cfg(not(feature = "immutable"))]
 impl<A> From<Vec<A>> for Value
     where
@@ -102,6 +445,7 @@ impl<A> From<Vec<A>> for Value
     }
 }
 
+/// Builds a `Vector`; see `Value::list` for lists.
 #[cfg(feature = "immutable")]
 impl<A> From<Vector<A>> for Value
     where
@@ -120,7 +464,7 @@ impl<K, V> From<BTreeMap<K, V>> for Value
         Value: From<K>,
         Value: From<V>,
 {
-    fn from(s: Map<K, V>) -> Self {
+    fn from(s: BTreeMap<K, V>) -> Self {
         let mut map = Map::new();
         for (k, v) in s {
             map.insert(Value::from(k), Value::from(v));
@@ -152,7 +496,7 @@ impl<A> From<BTreeSet<A>> for Value
         Value: From<A>,
 {
     fn from(s: BTreeSet<A>) -> Self {
-        let mut set = BTreeSet::new();
+        let mut set = Set::new();
         s.into_iter().for_each(|a| {
             set.insert(Value::from(a));
         });
@@ -171,3 +515,64 @@ impl<A> From<HashSet<A>> for Value
         Value::Set(s.iter().map(|v| Value::from(v.clone())).collect())
     }
 }
+
+/// Builds a `Vector`; see `Value::list` for lists.
+#[cfg(feature = "immutable")]
+impl<A> From<::std::vec::Vec<A>> for Value
+    where
+        Value: From<A>,
+{
+    fn from(s: ::std::vec::Vec<A>) -> Self {
+        Value::Vector(s.into_iter().map(Value::from).collect())
+    }
+}
+
+#[cfg(feature = "immutable")]
+impl<K, V> From<::std::collections::BTreeMap<K, V>> for Value
+    where
+        Value: From<K>,
+        Value: From<V>,
+{
+    fn from(s: ::std::collections::BTreeMap<K, V>) -> Self {
+        Value::Map(s.into_iter().map(|(k, v)| (Value::from(k), Value::from(v))).collect())
+    }
+}
+
+#[cfg(feature = "immutable")]
+impl<A> From<::std::collections::BTreeSet<A>> for Value
+    where
+        Value: From<A>,
+{
+    fn from(s: ::std::collections::BTreeSet<A>) -> Self {
+        Value::Set(s.into_iter().map(Value::from).collect())
+    }
+}
+
+impl<K, V, S> From<::std::collections::HashMap<K, V, S>> for Value
+    where
+        Value: From<K>,
+        Value: From<V>,
+{
+    fn from(s: ::std::collections::HashMap<K, V, S>) -> Self {
+        Value::Map(s.into_iter().map(|(k, v)| (Value::from(k), Value::from(v))).collect())
+    }
+}
+
+impl<A, S> From<::std::collections::HashSet<A, S>> for Value
+    where
+        Value: From<A>,
+{
+    fn from(s: ::std::collections::HashSet<A, S>) -> Self {
+        Value::Set(s.into_iter().map(Value::from).collect())
+    }
+}
+
+/// `None` is `nil`.
+impl<A> From<Option<A>> for Value
+    where
+        Value: From<A>,
+{
+    fn from(s: Option<A>) -> Self {
+        s.map_or(Value::Nil, Value::from)
+    }
+}
@@ -0,0 +1,213 @@
+/// Construct a `Value` from an EDN literal.
+///
+/// ```
+/// # #[macro_use] extern crate edn;
+/// # fn main() {
+/// let score: i64 = 3;
+/// let value = edn!({:name "John" :tags #{:a :b} :scores [1 2 ~score]});
+/// # let _ = value;
+/// # }
+/// ```
+///
+/// Supported forms are `nil`, booleans, Rust string, char, integer and float
+/// literals, keywords, symbols, lists, vectors, maps and sets. `~expr`
+/// interpolates any Rust expression implementing `Into<Value>`; wrap anything
+/// longer than a single token in parentheses, e.g. `~(a + b)`.
+///
+/// Keywords and symbols are built from Rust identifiers joined by `-`, `.` or
+/// `/`, so `:first-name` and `my.ns/foo` work as expected. Since the macro
+/// cannot see whitespace, `:a - b` is read the same as `:a-b`.
+///
+/// In the 2018 edition, import it by path instead of with `#[macro_use]`:
+///
+/// ```edition2018
+/// use edn::edn;
+///
+/// let value = edn!([1 {:a #{(f x)}}]);
+/// # let _ = value;
+/// ```
+#[macro_export]
+macro_rules! edn {
+    ($($tt:tt)+) => {
+        $crate::macros::single($crate::edn_internal!(@seq [] $($tt)+))
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! edn_internal {
+    (@seq [$($out:expr,)*]) => {
+        vec![$($out,)*]
+    };
+
+    (@seq [$($out:expr,)*] nil $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Nil,] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] true $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Boolean(true),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] false $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Boolean(false),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] ~ $value:tt $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::from($value),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] $value:literal $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::from($value),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] : $head:ident $($rest:tt)*) => {
+        $crate::edn_internal!(@name Keyword [$($out,)*] ($head) $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] $head:ident $($rest:tt)*) => {
+        $crate::edn_internal!(@name Symbol [$($out,)*] ($head) $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] + $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Symbol("+".into()),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] - $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Symbol("-".into()),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] * $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Symbol("*".into()),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] / $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Symbol("/".into()),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] = $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Symbol("=".into()),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] < $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Symbol("<".into()),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] > $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Symbol(">".into()),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::List(
+            $crate::edn_internal!(@seq [] $($inner)*).into_iter().collect()
+        ),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] [$($inner:tt)*] $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Vector(
+            $crate::edn_internal!(@seq [] $($inner)*).into_iter().collect()
+        ),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] {$($inner:tt)*} $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::macros::map(
+            $crate::edn_internal!(@seq [] $($inner)*)
+        ),] $($rest)*)
+    };
+
+    (@seq [$($out:expr,)*] # {$($inner:tt)*} $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::Set(
+            $crate::edn_internal!(@seq [] $($inner)*).into_iter().collect()
+        ),] $($rest)*)
+    };
+
+    // Glue `-`, `.` and `/` separated identifiers into a single name.
+    (@name $kind:ident [$($out:expr,)*] ($($name:tt)*) - $next:ident $($rest:tt)*) => {
+        $crate::edn_internal!(@name $kind [$($out,)*] ($($name)* - $next) $($rest)*)
+    };
+
+    (@name $kind:ident [$($out:expr,)*] ($($name:tt)*) . $next:ident $($rest:tt)*) => {
+        $crate::edn_internal!(@name $kind [$($out,)*] ($($name)* . $next) $($rest)*)
+    };
+
+    (@name $kind:ident [$($out:expr,)*] ($($name:tt)*) / $next:ident $($rest:tt)*) => {
+        $crate::edn_internal!(@name $kind [$($out,)*] ($($name)* / $next) $($rest)*)
+    };
+
+    (@name $kind:ident [$($out:expr,)*] ($($name:tt)*) $($rest:tt)*) => {
+        $crate::edn_internal!(@seq [$($out,)* $crate::Value::$kind(
+            stringify!($($name)*).replace(' ', "").into()
+        ),] $($rest)*)
+    };
+}
+
+/// Extract the entries for several keywords from a map at once, converting
+/// each with `keys::FromValue`, like Clojure's `{:keys [...]}`.
+///
+/// ```
+/// # #[macro_use] extern crate edn;
+/// # fn main() {
+/// # fn run() -> Result<(), edn::keys::Error> {
+/// let value = edn!({:user/name "Ada" :tags [:a :b]});
+/// let (name, tags): (String, Vec<edn::Keyword>) = edn_keys!(value, :user/name, :tags)?;
+/// # assert_eq!((name.as_str(), tags.len()), ("Ada", 2));
+/// # Ok(())
+/// # }
+/// # run().unwrap();
+/// # }
+/// ```
+///
+/// Evaluates to a `Result` of a tuple with one item per keyword, or the
+/// error for the first entry which is missing or of the wrong type. Keys
+/// whose type is an `Option` may be missing or `nil`.
+#[macro_export]
+macro_rules! edn_keys {
+    ($value:expr, $($keys:tt)+) => {{
+        let value: &$crate::Value = &$value;
+        (|| -> ::std::result::Result<_, $crate::keys::Error> {
+            Ok($crate::edn_keys_internal!(@key value [] () $($keys)+))
+        })()
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! edn_keys_internal {
+    (@key $value:ident [$($out:expr,)*] ()) => {
+        ($($out,)*)
+    };
+
+    (@key $value:ident [$($out:expr,)*] (: $($name:tt)+)) => {
+        $crate::edn_keys_internal!(@key $value [$($out,)* $crate::edn_keys_internal!(@get $value $($name)+),] ())
+    };
+
+    (@key $value:ident [$($out:expr,)*] (: $($name:tt)+) , $($rest:tt)*) => {
+        $crate::edn_keys_internal!(@key $value [$($out,)* $crate::edn_keys_internal!(@get $value $($name)+),] () $($rest)*)
+    };
+
+    (@key $value:ident [$($out:expr,)*] ($($name:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::edn_keys_internal!(@key $value [$($out,)*] ($($name)* $next) $($rest)*)
+    };
+
+    (@get $value:ident $($name:tt)+) => {
+        $crate::keys::get($value, &stringify!($($name)+).replace(' ', ""))?
+    };
+}
+
+use Value;
+
+#[doc(hidden)]
+pub fn single(mut values: ::std::vec::Vec<Value>) -> Value {
+    assert!(values.len() == 1, "edn! expects exactly one form");
+    values.pop().unwrap()
+}
+
+#[doc(hidden)]
+pub fn map(values: ::std::vec::Vec<Value>) -> Value {
+    assert_eq!(values.len() % 2, 0, "odd number of items in a Map");
+    let mut iter = values.into_iter();
+    let mut entries = vec![];
+    while let (Some(key), Some(value)) = (iter.next(), iter.next()) {
+        entries.push((key, value));
+    }
+    Value::Map(entries.into_iter().collect())
+}
@@ -0,0 +1,539 @@
+//! `Map`, the map in `Value::Map`.
+//!
+//! The map is a `BTreeMap` by default, an `IndexMap` with the `ordered`
+//! feature and an `im::HashMap` with the `immutable` feature. `Map` wraps
+//! whichever it is with the same methods, so code using it compiles with any
+//! of them. Keys must be `Ord`, `Hash` and `Clone`, and values `Clone`, which
+//! covers what every backend needs.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops;
+
+use Value;
+
+#[cfg(feature = "immutable")]
+use im::hashmap as backend;
+#[cfg(all(feature = "ordered", not(feature = "immutable")))]
+use indexmap::map as backend;
+#[cfg(not(any(feature = "ordered", feature = "immutable")))]
+use std::collections::btree_map as backend;
+
+#[cfg(feature = "immutable")]
+type Inner<K, V> = backend::HashMap<K, V>;
+#[cfg(all(feature = "ordered", not(feature = "immutable")))]
+type Inner<K, V> = ::ordered::OrderedMap<K, V>;
+#[cfg(not(any(feature = "ordered", feature = "immutable")))]
+type Inner<K, V> = backend::BTreeMap<K, V>;
+
+/// A map from `K` to `V`, iterated in key order by default, in insertion
+/// order with the `ordered` feature and in no particular order with the
+/// `immutable` feature.
+#[derive(Clone)]
+pub struct Map<K, V>(Inner<K, V>);
+
+impl<K, V> Map<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    pub fn new() -> Map<K, V> {
+        Map(Inner::default())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.0.get(key)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.0.get_mut(key)
+    }
+
+    /// The key and value for `key`, for when the key in the map differs
+    /// from `key`, e.g. in metadata.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.0.get_key_value(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.0.contains_key(key)
+    }
+
+    /// Insert `value` at `key`, returning the value it replaces. A new key
+    /// goes last with the `ordered` feature; a replaced one keeps its place.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.0.insert(key, value)
+    }
+
+    /// Remove `key`, keeping the order of the other entries.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.0.remove(key)
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, f: F) {
+        // `im::HashMap::retain` only lends the values immutably.
+        #[cfg(feature = "immutable")]
+        {
+            let mut f = f;
+            let removed: Vec<K> = self
+                .0
+                .iter_mut()
+                .filter_map(|(key, value)| if f(key, value) { None } else { Some(key.clone()) })
+                .collect();
+            for key in removed {
+                self.0.remove(&key);
+            }
+        }
+        #[cfg(not(feature = "immutable"))]
+        self.0.retain(f)
+    }
+
+    /// The entry for `key`, to insert or update in place.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        Entry { map: self, key }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.0.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut(self.0.iter_mut())
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.0.keys())
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values(self.0.values())
+    }
+}
+
+impl<V: Clone> Map<Value, V> {
+    /// The value for the keyword `name`, with or without its leading `:`,
+    /// e.g. `"person/name"` for `:person/name`. Unlike `get`, this doesn't
+    /// build a `Value` to look up, so doesn't allocate.
+    pub fn get_kw(&self, name: &str) -> Option<&V> {
+        self.get(&KeyRef::keyword(name) as &dyn Key)
+    }
+
+    /// A mutable reference to the value for the keyword `name`.
+    pub fn get_kw_mut(&mut self, name: &str) -> Option<&mut V> {
+        self.get_mut(&KeyRef::keyword(name) as &dyn Key)
+    }
+
+    /// The value for the string key `key`, without allocating.
+    pub fn get_str(&self, key: &str) -> Option<&V> {
+        self.get(&KeyRef::String(key) as &dyn Key)
+    }
+
+    /// A mutable reference to the value for the string key `key`.
+    pub fn get_str_mut(&mut self, key: &str) -> Option<&mut V> {
+        self.get_mut(&KeyRef::String(key) as &dyn Key)
+    }
+}
+
+pub(crate) use self::key::{Key, KeyRef};
+
+/// Looking up keyword and string keys by `&str`s.
+///
+/// `Value` borrows as `dyn Key`, which compares, and hashes, the same as the
+/// `Value` it is. `KeyRef`, a keyword or string made of `&str`s, is a `Key`
+/// too, so a map can be searched with one without making a `Value`.
+mod key {
+    use std::borrow::Borrow;
+    use std::cmp::Ordering;
+    use std::hash::{Hash, Hasher};
+
+    use symbol::split_ref;
+    use {Keyword, Value};
+
+    pub trait Key {
+        fn key(&self) -> KeyRef<'_>;
+    }
+
+    #[derive(Clone, Copy)]
+    pub enum KeyRef<'a> {
+        String(&'a str),
+        Keyword(Option<&'a str>, &'a str),
+        /// Any other value.
+        Value(&'a Value),
+    }
+
+    impl<'a> KeyRef<'a> {
+        /// The keyword `name`, with or without its leading `:`.
+        pub fn keyword(name: &'a str) -> KeyRef<'a> {
+            let (namespace, name) = split_ref(name.strip_prefix(':').unwrap_or(name));
+            KeyRef::Keyword(namespace, name)
+        }
+    }
+
+    impl<'a> Key for KeyRef<'a> {
+        fn key(&self) -> KeyRef<'_> {
+            *self
+        }
+    }
+
+    impl Key for Value {
+        fn key(&self) -> KeyRef<'_> {
+            match *self {
+                Value::String(ref string) => KeyRef::String(string),
+                Value::Keyword(ref keyword) => KeyRef::Keyword(keyword.namespace(), keyword.name()),
+                ref value => KeyRef::Value(value),
+            }
+        }
+    }
+
+    impl<'a> Borrow<dyn Key + 'a> for Value {
+        fn borrow(&self) -> &(dyn Key + 'a) {
+            self
+        }
+    }
+
+    /// The first variants of `Value`, with borrowed contents, so the derived
+    /// `Hash` is the same as `Value`'s.
+    #[derive(Hash)]
+    enum Shadow<'a> {
+        _Nil,
+        _Boolean,
+        String(&'a str),
+        _Char,
+        _Symbol,
+        Keyword(ShadowKeyword<'a>),
+    }
+
+    /// `Keyword`'s fields, borrowed.
+    #[derive(Hash)]
+    struct ShadowKeyword<'a> {
+        namespace: Option<&'a str>,
+        name: &'a str,
+    }
+
+    impl<'a> Hash for dyn Key + 'a {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            match self.key() {
+                KeyRef::String(string) => Shadow::String(string).hash(state),
+                KeyRef::Keyword(namespace, name) => {
+                    Shadow::Keyword(ShadowKeyword { namespace, name }).hash(state)
+                }
+                KeyRef::Value(value) => value.hash(state),
+            }
+        }
+    }
+
+    impl<'a> Ord for dyn Key + 'a {
+        fn cmp(&self, other: &Self) -> Ordering {
+            // Values of different variants compare by variant, so any string
+            // or keyword, e.g. an empty one, which doesn't allocate, stands
+            // in for the others.
+            let empty_string = || Value::String(String::new());
+            let empty_keyword = || Value::Keyword(Keyword::new(""));
+            match (self.key(), other.key()) {
+                (KeyRef::String(a), KeyRef::String(b)) => a.cmp(b),
+                (KeyRef::Keyword(a, b), KeyRef::Keyword(c, d)) => (a, b).cmp(&(c, d)),
+                (KeyRef::Value(a), KeyRef::Value(b)) => a.cmp(b),
+                (KeyRef::String(_), KeyRef::Keyword(..)) => Ordering::Less,
+                (KeyRef::Keyword(..), KeyRef::String(_)) => Ordering::Greater,
+                (KeyRef::String(_), KeyRef::Value(b)) => empty_string().cmp(b),
+                (KeyRef::Keyword(..), KeyRef::Value(b)) => empty_keyword().cmp(b),
+                (KeyRef::Value(a), KeyRef::String(_)) => a.cmp(&empty_string()),
+                (KeyRef::Value(a), KeyRef::Keyword(..)) => a.cmp(&empty_keyword()),
+            }
+        }
+    }
+
+    impl<'a> PartialOrd for dyn Key + 'a {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl<'a> PartialEq for dyn Key + 'a {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == Ordering::Equal
+        }
+    }
+
+    impl<'a> Eq for dyn Key + 'a {}
+}
+
+/// A key in a `Map` and the map, from `Map::entry`.
+pub struct Entry<'a, K: 'a, V: 'a> {
+    map: &'a mut Map<K, V>,
+    key: K,
+}
+
+impl<'a, K, V> Entry<'a, K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        if !self.map.contains_key(&self.key) {
+            self.map.insert(self.key.clone(), default());
+        }
+        self.map
+            .get_mut(&self.key)
+            .expect("the entry was just inserted")
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Call `f` with the value, if there is one.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Entry<'a, K, V> {
+        if let Some(value) = self.map.get_mut(&self.key) {
+            f(value);
+        }
+        self
+    }
+}
+
+macro_rules! iterator {
+    ($(#[$attr:meta])* $name:ident, $inner:ident, $item:ty $(, $bound:ident: $trait:ident)*) => {
+        $(#[$attr])*
+        pub struct $name<'a, K: 'a, V: 'a>(backend::$inner<'a, K, V>)
+        where
+            $($bound: $trait),*;
+
+        impl<'a, K, V> Iterator for $name<'a, K, V>
+        where
+            $($bound: $trait),*
+        {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<$item> {
+                self.0.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+    };
+}
+
+iterator!(
+    /// The entries of a `Map`, from `Map::iter`.
+    Iter, Iter, (&'a K, &'a V)
+);
+iterator!(
+    /// The entries of a `Map` with mutable values, from `Map::iter_mut`.
+    /// `im::HashMap` clones shared nodes as it goes, hence the bounds.
+    IterMut, IterMut, (&'a K, &'a mut V), K: Clone, V: Clone
+);
+iterator!(
+    /// The keys of a `Map`, from `Map::keys`.
+    Keys, Keys, &'a K
+);
+iterator!(
+    /// The values of a `Map`, from `Map::values`.
+    Values, Values, &'a V
+);
+
+#[cfg(feature = "immutable")]
+type InnerIntoIter<K, V> = backend::ConsumingIter<(K, V)>;
+#[cfg(not(feature = "immutable"))]
+type InnerIntoIter<K, V> = backend::IntoIter<K, V>;
+
+/// The entries of a `Map`, from `Map::into_iter`.
+pub struct IntoIter<K, V>(InnerIntoIter<K, V>)
+where
+    K: Ord + Hash + Clone,
+    V: Clone;
+
+impl<K, V> Iterator for IntoIter<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<K, V> Default for Map<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    fn default() -> Map<K, V> {
+        Map::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Map<K, V>
+where
+    Inner<K, V>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<K, V> PartialEq for Map<K, V>
+where
+    Inner<K, V>: PartialEq,
+{
+    fn eq(&self, other: &Map<K, V>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K, V> Eq for Map<K, V> where Inner<K, V>: Eq {}
+
+impl<K, V> PartialOrd for Map<K, V>
+where
+    Inner<K, V>: Ord,
+{
+    fn partial_cmp(&self, other: &Map<K, V>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, V> Ord for Map<K, V>
+where
+    Inner<K, V>: Ord,
+{
+    fn cmp(&self, other: &Map<K, V>) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<K, V> Hash for Map<K, V>
+where
+    Inner<K, V>: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<K, Q, V> ops::Index<&Q> for Map<K, V>
+where
+    K: Ord + Hash + Clone + Borrow<Q>,
+    Q: Ord + Hash + ?Sized,
+    V: Clone,
+{
+    type Output = V;
+
+    /// # Panics
+    ///
+    /// If `key` isn't in the map.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for Map<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Map<K, V> {
+        Map(iter.into_iter().collect())
+    }
+}
+
+impl<K, V> Extend<(K, V)> for Map<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V> IntoIterator for Map<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter(self.0.into_iter())
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a Map<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut Map<K, V>
+where
+    K: Ord + Hash + Clone,
+    V: Clone,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
@@ -0,0 +1,118 @@
+use std::mem;
+
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// How `Value::merge_with` settles a key both maps have, when the values
+/// aren't both maps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// Take the value from `other`, as `Value::merge` does.
+    Replace,
+    /// Keep the value from `self`.
+    Keep,
+    /// Concatenate two lists or two vectors and take the union of two sets,
+    /// and otherwise take the value from `other`.
+    Combine,
+}
+
+/// `a` with `b` merged into it; see `Value::merge_with`.
+pub(crate) fn merge(a: Value, b: Value, conflict: Conflict) -> Value {
+    match (a, b, conflict) {
+        (Value::Map(mut a), Value::Map(b), _) => {
+            for (key, value) in b {
+                match a.get_mut(&key) {
+                    Some(old) => *old = merge(mem::replace(old, Value::Nil), value, conflict),
+                    None => {
+                        a.insert(key, value);
+                    }
+                }
+            }
+            Value::Map(a)
+        }
+        (a, _, Conflict::Keep) => a,
+        (Value::List(mut a), Value::List(b), Conflict::Combine) => {
+            a.extend(b);
+            Value::List(a)
+        }
+        (Value::Vector(mut a), Value::Vector(b), Conflict::Combine) => {
+            a.extend(b);
+            Value::Vector(a)
+        }
+        (Value::Set(mut a), Value::Set(b), Conflict::Combine) => {
+            a.extend(b);
+            Value::Set(a)
+        }
+        (_, b, _) => b,
+    }
+}
+
+fn update<F: FnOnce(Value) -> Value>(value: Value, path: &[&Value], f: F) -> Option<Value> {
+    let (key, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return Some(f(value)),
+    };
+    match value {
+        Value::Nil => update(Value::Map(Map::new()), path, f),
+        Value::Map(mut map) => {
+            let old = map.get(*key).cloned().unwrap_or(Value::Nil);
+            map.insert((*key).clone(), update(old, rest, f)?);
+            Some(Value::Map(map))
+        }
+        Value::List(items) => update_item(items, key, rest, f).map(Value::List),
+        Value::Vector(items) => update_item(items, key, rest, f).map(Value::Vector),
+        Value::WithMeta(value, meta) => {
+            update(*value, path, f).map(|value| Value::WithMeta(Box::new(value), meta))
+        }
+        _ => None,
+    }
+}
+
+fn update_item<F: FnOnce(Value) -> Value>(
+    mut items: Vec<Value>,
+    index: &Value,
+    rest: &[&Value],
+    f: F,
+) -> Option<Vec<Value>> {
+    let index = match *index {
+        Value::Integer(index) if index >= 0 && index as u64 <= items.len() as u64 => index as usize,
+        _ => return None,
+    };
+    if index == items.len() {
+        items.insert(index, update(Value::Nil, rest, f)?);
+    } else {
+        let old = items[index].clone();
+        items[index] = update(old, rest, f)?;
+    }
+    Some(items)
+}
+
+impl Value {
+    /// `self` with the entries of `other` added, merging the maps they both
+    /// have at the same key, at any depth, and otherwise taking the value
+    /// from `other`, e.g. to layer environment settings over defaults. If
+    /// either isn't a map, the result is `other`.
+    pub fn merge(&self, other: &Value) -> Value {
+        self.merge_with(other, Conflict::Replace)
+    }
+
+    /// Like `merge`, settling the values at keys both maps have which aren't
+    /// both maps, or `self` and `other` if they aren't, with `conflict`.
+    pub fn merge_with(&self, other: &Value, conflict: Conflict) -> Value {
+        merge(self.clone(), other.clone(), conflict)
+    }
+
+    /// `self` with the value at `path` replaced by `f` of it, like Clojure's
+    /// `update-in`. Maps are looked up by key, and lists and vectors by
+    /// integer index. A missing key or `nil` on the way is given to `f` as
+    /// `nil`, or becomes a map if the path goes on, and an index one past the
+    /// end appends. `None` if the path leads through anything else or further
+    /// past the end.
+    pub fn update_in<F: FnOnce(Value) -> Value>(&self, path: &[&Value], f: F) -> Option<Value> {
+        update(self.clone(), path, f)
+    }
+}
@@ -0,0 +1,63 @@
+//! Reading EDN files too large to load into memory, such as database dumps,
+//! by mapping them instead.
+//!
+//! The operating system pages the file in as it is read and can drop pages
+//! already read, so only the form being read and the values kept by the
+//! caller take up memory.
+//!
+//! ```no_run
+//! use edn::mmap::MmapFile;
+//!
+//! let file = MmapFile::open("dump.edn").unwrap();
+//! let mut count = 0;
+//! for form in file.forms().unwrap() {
+//!     form.unwrap();
+//!     count += 1;
+//! }
+//! println!("{} forms", count);
+//! ```
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use parser::{self, Category, Error, Parser, ParserBuilder};
+use Value;
+
+/// A file mapped into memory.
+///
+/// The file must not be changed by this or any other process while it is
+/// mapped, or reading it may see torn data or crash.
+pub struct MmapFile {
+    mmap: Mmap,
+}
+
+impl MmapFile {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<MmapFile> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read, and `MmapFile`'s docs require
+        // the file not to change while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(MmapFile { mmap })
+    }
+
+    /// The file's contents, which are checked to be UTF-8 in one pass.
+    pub fn as_str(&self) -> Result<&str, Error> {
+        parser::utf8(&self.mmap)
+    }
+
+    /// A parser over the file's top-level forms, to read them one at a time.
+    pub fn forms(&self) -> Result<Parser<'_>, Error> {
+        self.as_str().map(Parser::new)
+    }
+}
+
+/// Read exactly one form from the file at `path` by mapping it. Anything but
+/// whitespace and comments after it is an error.
+pub fn from_mmap<P: AsRef<Path>>(path: P) -> Result<Value, Error> {
+    let file = MmapFile::open(path)
+        .map_err(|err| Error::at("", 0, 0, Category::Io, err.to_string()))?;
+    ParserBuilder::new().from_str(file.as_str()?)
+}
@@ -0,0 +1,362 @@
+//! `parse` and `stringify` for Node.js through N-API, so JavaScript tooling
+//! such as editor extensions can reuse this parser.
+//!
+//! Build the addon as a `cdylib` depending on this crate with the `node`
+//! feature, calling `napi_build::setup()` from its build script, e.g. with
+//! `napi build --release`.
+//!
+//! ```js
+//! const { parse, stringify, Keyword } = require("./edn.node");
+//!
+//! const value = parse('{:user/id 1 :roles #{:admin}}');
+//! // Map { Keyword(:user/id) => 1, Keyword(:roles) => Set { Keyword(:admin) } }
+//! stringify([new Keyword("a"), 1], { pretty: true });
+//! // '[\n  :a\n  1\n]'
+//! ```
+//!
+//! Values are converted as by `wasm::to_js`, except that keywords are
+//! instances of the `Keyword` class, with `name` and `namespace` properties.
+//! `Map` keys are compared by identity, so look up keyword keys by iterating
+//! over a map's entries, comparing them with `equals`.
+
+use std::convert::TryFrom;
+
+use napi;
+use napi::bindgen_prelude::{ClassInstance, FromNapiValue};
+use napi::{Env, Error, JsFunction, JsObject, JsUnknown, NapiRaw, Result, ValueType};
+use napi_derive::napi;
+
+use {Keyword, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// The largest integer a JavaScript number holds exactly.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+fn error<T>(message: String) -> Result<T> {
+    Err(Error::from_reason(message))
+}
+
+/// `Keyword` in JavaScript, e.g. `new Keyword("user/id")` for `:user/id`.
+#[napi(js_name = "Keyword")]
+pub struct JsKeyword {
+    keyword: Keyword,
+}
+
+#[napi]
+impl JsKeyword {
+    #[napi(constructor)]
+    pub fn new(name: String) -> JsKeyword {
+        JsKeyword {
+            keyword: Keyword::from(name.trim_start_matches(':')),
+        }
+    }
+
+    #[napi(getter)]
+    pub fn name(&self) -> String {
+        self.keyword.name().into()
+    }
+
+    #[napi(getter)]
+    pub fn namespace(&self) -> Option<String> {
+        self.keyword.namespace().map(String::from)
+    }
+
+    #[napi]
+    pub fn equals(&self, other: &JsKeyword) -> bool {
+        self.keyword == other.keyword
+    }
+
+    #[napi(js_name = "toString")]
+    pub fn to_js_string(&self) -> String {
+        self.keyword.to_string()
+    }
+}
+
+#[napi(object)]
+pub struct StringifyOptions {
+    pub pretty: Option<bool>,
+}
+
+/// Parse one EDN form. Throws an `Error` with the parser's message if `str`
+/// isn't valid.
+#[napi]
+pub fn parse(env: Env, str: String) -> Result<JsUnknown> {
+    match ::parser::from_str(&str) {
+        Ok(value) => to_js(&env, &value),
+        Err(err) => error(err.to_string()),
+    }
+}
+
+/// `value` as EDN, pretty printed if `options.pretty`. Throws an `Error` if
+/// it can't be converted.
+#[napi]
+pub fn stringify(env: Env, value: JsUnknown, options: Option<StringifyOptions>) -> Result<String> {
+    let value = from_js(&env, value)?;
+    let mut out = vec![];
+    let written = match options.and_then(|options| options.pretty) {
+        Some(true) => ::ser::to_writer_pretty(&mut out, &value),
+        _ => ::ser::to_writer(&mut out, &value),
+    };
+    written.map_err(|err| Error::from_reason(err.to_string()))?;
+    Ok(String::from_utf8(out).expect("EDN output is valid UTF-8"))
+}
+
+fn global(env: &Env, name: &str) -> Result<JsFunction> {
+    env.get_global()?.get_named_property(name)
+}
+
+fn wrap(env: &Env, key: &str, value: JsUnknown) -> Result<JsUnknown> {
+    let mut object = env.create_object()?;
+    object.set_named_property(key, value)?;
+    Ok(object.into_unknown())
+}
+
+fn array<'a, I: Iterator<Item = &'a Value>>(env: &Env, items: I) -> Result<JsUnknown> {
+    let mut array = env.create_array_with_length(0)?;
+    for (i, item) in items.enumerate() {
+        array.set_element(i as u32, to_js(env, item)?)?;
+    }
+    Ok(array.into_unknown())
+}
+
+/// `value` as a JavaScript value.
+pub fn to_js(env: &Env, value: &Value) -> Result<JsUnknown> {
+    Ok(match *value {
+        Value::Nil => env.get_null()?.into_unknown(),
+        Value::Boolean(boolean) => env.get_boolean(boolean)?.into_unknown(),
+        Value::String(ref string) => env.create_string(string)?.into_unknown(),
+        Value::Char(ch) => wrap(
+            env,
+            "#char",
+            env.create_string(&ch.to_string())?.into_unknown(),
+        )?,
+        Value::Symbol(ref symbol) => wrap(
+            env,
+            "#sym",
+            env.create_string(&symbol.to_string())?.into_unknown(),
+        )?,
+        Value::Keyword(ref keyword) => JsKeyword {
+            keyword: keyword.clone(),
+        }
+        .into_instance(*env)?
+        .as_object(*env)
+        .into_unknown(),
+        Value::Integer(integer) if integer.abs() <= MAX_SAFE_INTEGER => {
+            env.create_double(integer as f64)?.into_unknown()
+        }
+        Value::Integer(integer) => env.create_bigint_from_i64(integer)?.into_unknown()?,
+        #[cfg(feature = "bigint")]
+        Value::BigInt(ref integer) => global(env, "BigInt")?.call(
+            None,
+            &[env.create_string(&integer.to_string())?.into_unknown()],
+        )?,
+        #[cfg(feature = "ratio")]
+        Value::Ratio(numerator, denominator) => wrap(
+            env,
+            "#ratio",
+            array(
+                env,
+                [
+                    Value::Integer(numerator),
+                    Value::Integer(denominator as i64),
+                ]
+                .iter(),
+            )?,
+        )?,
+        Value::Float(float) => env.create_double(float.into_inner())?.into_unknown(),
+        #[cfg(feature = "bigdecimal")]
+        Value::Decimal(ref decimal) => wrap(
+            env,
+            "#decimal",
+            env.create_string(&decimal.to_string())?.into_unknown(),
+        )?,
+        Value::List(ref items) => wrap(env, "#list", array(env, items.iter())?)?,
+        Value::Vector(ref items) => array(env, items.iter())?,
+        Value::Set(ref items) => {
+            let set = global(env, "Set")?.new_instance::<JsUnknown>(&[])?;
+            let add: JsFunction = set.get_named_property("add")?;
+            for item in items.iter() {
+                add.call(Some(&set), &[to_js(env, item)?])?;
+            }
+            set.into_unknown()
+        }
+        Value::Map(ref map) => {
+            let object = global(env, "Map")?.new_instance::<JsUnknown>(&[])?;
+            let set: JsFunction = object.get_named_property("set")?;
+            for (key, value) in map.iter() {
+                set.call(Some(&object), &[to_js(env, key)?, to_js(env, value)?])?;
+            }
+            object.into_unknown()
+        }
+        Value::Tagged(ref tag, ref value) => wrap(
+            env,
+            "#tag",
+            array(env, [Value::String(tag.clone()), (**value).clone()].iter())?,
+        )?,
+        Value::WithMeta(ref value, ref meta) => wrap(
+            env,
+            "#meta",
+            array(env, [Value::Map(meta.clone()), (**value).clone()].iter())?,
+        )?,
+    })
+}
+
+/// A JavaScript value as a `Value`.
+pub fn from_js(env: &Env, js: JsUnknown) -> Result<Value> {
+    match js.get_type()? {
+        ValueType::Null | ValueType::Undefined => Ok(Value::Nil),
+        ValueType::Boolean => Ok(Value::Boolean(js.coerce_to_bool()?.get_value()?)),
+        ValueType::String => Ok(Value::String(
+            js.coerce_to_string()?.into_utf8()?.into_owned()?,
+        )),
+        ValueType::Number => {
+            let number = js.coerce_to_number()?.get_double()?;
+            Ok(
+                if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER as f64 {
+                    Value::Integer(number as i64)
+                } else {
+                    Value::from(number)
+                },
+            )
+        }
+        ValueType::BigInt => big_integer(env, js),
+        ValueType::Object => from_object(env, JsObject::try_from(js)?),
+        other => error(format!("{} has no EDN equivalent", other)),
+    }
+}
+
+fn big_integer(env: &Env, integer: JsUnknown) -> Result<Value> {
+    let string = global(env, "String")?
+        .call(None, &[integer])?
+        .coerce_to_string()?
+        .into_utf8()?
+        .into_owned()?;
+    match string.parse() {
+        Ok(integer) => Ok(Value::Integer(integer)),
+        #[cfg(feature = "bigint")]
+        Err(_) => match string.parse() {
+            Ok(integer) => Ok(Value::BigInt(integer)),
+            Err(_) => unreachable!(),
+        },
+        #[cfg(not(feature = "bigint"))]
+        Err(_) => error("BigInt is out of range".into()),
+    }
+}
+
+fn items(env: &Env, array: &JsObject) -> Result<::std::vec::Vec<Value>> {
+    (0..array.get_array_length()?)
+        .map(|i| from_js(env, array.get_element(i)?))
+        .collect()
+}
+
+/// The items of an iterable, such as a `Set` or a `Map`'s entries.
+fn iterated(env: &Env, iterable: JsObject) -> Result<JsObject> {
+    let from: JsFunction = env
+        .get_global()?
+        .get_named_property::<JsUnknown>("Array")?
+        .coerce_to_object()?
+        .get_named_property("from")?;
+    JsObject::try_from(from.call(None, &[iterable])?)
+}
+
+/// A keyword, array, `Set` or `Map`, a single key object as written by
+/// `to_js`, or any other object as a map with string keys.
+fn from_object(env: &Env, object: JsObject) -> Result<Value> {
+    if JsKeyword::instance_of(*env, &object)? {
+        // `object` was just checked to be a `Keyword`, which wraps a `JsKeyword`.
+        let keyword =
+            unsafe { ClassInstance::<JsKeyword>::from_napi_value(env.raw(), object.raw())? };
+        return Ok(Value::Keyword(keyword.keyword.clone()));
+    }
+    if object.is_array()? {
+        return Ok(Value::Vector(Vec::from(items(env, &object)?)));
+    }
+    if object.instanceof(global(env, "Set")?)? {
+        let items = items(env, &iterated(env, object)?)?;
+        return Ok(Value::Set(items.into_iter().collect()));
+    }
+    if object.instanceof(global(env, "Map")?)? {
+        let mut map = Map::new();
+        for entry in items(env, &iterated(env, object)?)? {
+            match entry {
+                Value::Vector(ref entry) if entry.len() == 2 => {
+                    map.insert(entry[0].clone(), entry[1].clone());
+                }
+                _ => unreachable!(),
+            }
+        }
+        return Ok(Value::Map(map));
+    }
+
+    let names = object.get_property_names()?;
+    let mut keys = vec![];
+    for i in 0..names.get_array_length()? {
+        let key: JsUnknown = names.get_element(i)?;
+        keys.push(key.coerce_to_string()?.into_utf8()?.into_owned()?);
+    }
+    let kind = match keys.first() {
+        Some(kind) if keys.len() == 1 && kind.starts_with('#') => kind.clone(),
+        _ => {
+            let mut map = Map::new();
+            for key in keys {
+                let value = from_js(env, object.get_named_property(&key)?)?;
+                map.insert(Value::String(key), value);
+            }
+            return Ok(Value::Map(map));
+        }
+    };
+    let body = from_js(env, object.get_named_property(&kind)?)?;
+    let string = || match body {
+        Value::String(ref string) => Ok(string.clone()),
+        _ => error(format!("expected a string for `{}`", kind)),
+    };
+    let items = || match body {
+        Value::Vector(ref items) => Ok(items.clone()),
+        _ => error(format!("expected an array for `{}`", kind)),
+    };
+    let pair = || match items()? {
+        ref items if items.len() == 2 => Ok((items[0].clone(), items[1].clone())),
+        _ => error(format!("expected two items for `{}`", kind)),
+    };
+    Ok(match &kind[..] {
+        "#kw" => Value::Keyword(string()?[..].into()),
+        "#sym" => Value::Symbol(string()?[..].into()),
+        "#char" => {
+            let string = string()?;
+            let mut chars = string.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Value::Char(ch),
+                _ => return error("expected one character for `#char`".into()),
+            }
+        }
+        #[cfg(feature = "bigdecimal")]
+        "#decimal" => match string()?.parse() {
+            Ok(decimal) => Value::Decimal(decimal),
+            Err(_) => return error(format!("invalid decimal `{}`", string()?)),
+        },
+        #[cfg(feature = "ratio")]
+        "#ratio" => match pair()? {
+            (Value::Integer(numerator), Value::Integer(denominator)) if denominator > 0 => {
+                match Value::ratio(numerator, denominator as u64) {
+                    Some(ratio) => ratio,
+                    None => unreachable!(),
+                }
+            }
+            _ => return error("expected a numerator and denominator for `#ratio`".into()),
+        },
+        "#list" => Value::List(items()?),
+        "#tag" => match pair()? {
+            (Value::String(tag), value) => Value::Tagged(tag, Box::new(value)),
+            _ => return error("expected a tag and a value for `#tag`".into()),
+        },
+        "#meta" => match pair()? {
+            (Value::Map(meta), value) => Value::WithMeta(Box::new(value), meta),
+            _ => return error("expected a map and a value for `#meta`".into()),
+        },
+        _ => return error(format!("unknown key `{}`", kind)),
+    })
+}
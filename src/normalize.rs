@@ -0,0 +1,109 @@
+//! Putting values in a canonical shape before comparing or snapshotting
+//! them.
+
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// Options for `Value::normalize_with`. The default sorts maps and sets and
+/// writes `-0.0` as `0.0`, as `Value::normalize` does.
+#[derive(Clone, Debug)]
+pub struct Normalize {
+    lists_as_vectors: bool,
+    zeros: bool,
+}
+
+impl Default for Normalize {
+    fn default() -> Normalize {
+        Normalize {
+            lists_as_vectors: false,
+            zeros: true,
+        }
+    }
+}
+
+impl Normalize {
+    pub fn new() -> Normalize {
+        Normalize::default()
+    }
+
+    /// Also turn lists into vectors, which Clojure's `=` considers equal but
+    /// `==` doesn't.
+    pub fn lists_as_vectors(mut self, lists_as_vectors: bool) -> Normalize {
+        self.lists_as_vectors = lists_as_vectors;
+        self
+    }
+
+    fn normalized(&self, value: Value) -> Value {
+        match value {
+            Value::Float(float) if self.zeros && float.into_inner() == 0.0 => Value::from(0.0),
+            Value::List(items) if self.lists_as_vectors => Value::Vector(self.items(items)),
+            Value::List(items) => Value::List(self.items(items)),
+            Value::Vector(items) => Value::Vector(self.items(items)),
+            Value::Set(items) => {
+                let mut items: ::std::vec::Vec<_> = items
+                    .into_iter()
+                    .map(|item| self.normalized(item))
+                    .collect();
+                items.sort();
+                Value::Set(items.into_iter().collect())
+            }
+            Value::Map(map) => Value::Map(self.map(map)),
+            Value::Tagged(tag, value) => Value::Tagged(tag, Box::new(self.normalized(*value))),
+            Value::WithMeta(value, meta) => {
+                Value::WithMeta(Box::new(self.normalized(*value)), self.map(meta))
+            }
+            value => value,
+        }
+    }
+
+    fn items(&self, items: Vec<Value>) -> Vec<Value> {
+        items
+            .into_iter()
+            .map(|item| self.normalized(item))
+            .collect()
+    }
+
+    fn map(&self, map: Map<Value, Value>) -> Map<Value, Value> {
+        let mut entries: ::std::vec::Vec<_> = map
+            .into_iter()
+            .map(|(key, value)| (self.normalized(key), self.normalized(value)))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.into_iter().collect()
+    }
+}
+
+impl Value {
+    /// Put the entries of every map and set inside this value in sorted
+    /// order, which matters with the `ordered` feature.
+    ///
+    /// The order is `Value`'s `Ord`, which compares variants first, in the
+    /// order they're declared: so symbol keys sort before keyword keys, e.g.
+    /// `{c 1, :a 2}`.
+    ///
+    /// With the `immutable` feature maps and sets iterate in hash order
+    /// whatever their contents were inserted in; compare them with `==`, or
+    /// write them with `ser::Config::sort_entries`, instead.
+    pub fn sort_maps(&mut self) {
+        let sort = Normalize {
+            lists_as_vectors: false,
+            zeros: false,
+        };
+        *self = sort.normalized(self.take());
+    }
+
+    /// `sort_maps`, and also write `-0.0` as `0.0`, so that equal values
+    /// are written the same way.
+    pub fn normalize(&mut self) {
+        self.normalize_with(&Normalize::new())
+    }
+
+    /// `normalize` with the given options.
+    pub fn normalize_with(&mut self, options: &Normalize) {
+        *self = options.normalized(self.take());
+    }
+}
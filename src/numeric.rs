@@ -0,0 +1,256 @@
+//! Equality and ordering which compare integers and floats by value, and
+//! checked conversions to Rust numbers.
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use {TypeError, Value};
+
+/// `float` as an `i64`, if it is a whole number in range.
+fn whole(float: f64) -> Option<i64> {
+    if float.fract() == 0.0 && float >= i64::MIN as f64 && float < i64::MAX as f64 {
+        Some(float as i64)
+    } else {
+        None
+    }
+}
+
+/// `integer` compared with `float`, exactly.
+fn cmp_integer_float(integer: i64, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        None
+    } else if float >= 9_223_372_036_854_775_808.0 {
+        Some(Ordering::Less)
+    } else if float < -9_223_372_036_854_775_808.0 {
+        Some(Ordering::Greater)
+    } else {
+        let whole = float.trunc();
+        match integer.cmp(&(whole as i64)) {
+            Ordering::Equal => 0.0.partial_cmp(&(float - whole)),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl Value {
+    /// Compares `Integer`s and `Float`s by value, so `1 < 1.5` and `1` equals
+    /// `1.0`, unlike `Ord`. `None` if either isn't an `Integer` or `Float`,
+    /// or is NaN.
+    pub fn numeric_cmp(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (&Value::Integer(a), &Value::Integer(b)) => Some(a.cmp(&b)),
+            (&Value::Float(a), &Value::Float(b)) => a.into_inner().partial_cmp(&b.into_inner()),
+            (&Value::Integer(integer), &Value::Float(float)) => {
+                cmp_integer_float(integer, float.into_inner())
+            }
+            (&Value::Float(float), &Value::Integer(integer)) => {
+                cmp_integer_float(integer, float.into_inner()).map(Ordering::reverse)
+            }
+            _ => None,
+        }
+    }
+
+    /// Like `==`, but `Integer`s equal `Float`s with the same value, also
+    /// inside collections, like Clojure's `==` for numbers. `1` and `1.0` are
+    /// different values under `==`.
+    pub fn numeric_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (&Value::Integer(integer), &Value::Float(float))
+            | (&Value::Float(float), &Value::Integer(integer)) => {
+                whole(float.into_inner()) == Some(integer)
+            }
+            (Value::List(a), Value::List(b)) | (Value::Vector(a), Value::Vector(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.numeric_eq(b))
+            }
+            (Value::Set(a), Value::Set(b)) => {
+                a.len() == b.len() && a.iter().all(|a| b.iter().any(|b| a.numeric_eq(b)))
+            }
+            (Value::Map(a), Value::Map(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(ka, va)| {
+                        b.iter()
+                            .any(|(kb, vb)| ka.numeric_eq(kb) && va.numeric_eq(vb))
+                    })
+            }
+            (Value::Tagged(ta, a), Value::Tagged(tb, b)) => ta == tb && a.numeric_eq(b),
+            (Value::WithMeta(a, ma), Value::WithMeta(b, mb)) => {
+                a.numeric_eq(b) && Value::Map(ma.clone()).numeric_eq(&Value::Map(mb.clone()))
+            }
+            _ => self == other,
+        }
+    }
+
+    fn numeric_hash<H: Hasher>(&self, state: &mut H) {
+        // Maps and sets may hold numerically equal elements in different
+        // orders, so their elements' hashes are combined with `+`.
+        fn unordered<'a, I: Iterator<Item = &'a Value>>(values: I) -> u64 {
+            values.fold(0u64, |sum, value| {
+                let mut hasher = DefaultHasher::new();
+                value.numeric_hash(&mut hasher);
+                sum.wrapping_add(hasher.finish())
+            })
+        }
+
+        match *self {
+            Value::Integer(integer) => {
+                0u8.hash(state);
+                integer.hash(state);
+            }
+            Value::Float(float) => match whole(float.into_inner()) {
+                Some(integer) => {
+                    0u8.hash(state);
+                    integer.hash(state);
+                }
+                None => self.hash(state),
+            },
+            Value::List(ref items) | Value::Vector(ref items) => {
+                1u8.hash(state);
+                for item in items.iter() {
+                    item.numeric_hash(state);
+                }
+            }
+            Value::Set(ref items) => {
+                2u8.hash(state);
+                unordered(items.iter()).hash(state);
+            }
+            Value::Map(ref map) => {
+                3u8.hash(state);
+                unordered(map.iter().flat_map(|(key, value)| vec![key, value])).hash(state);
+            }
+            Value::Tagged(ref tag, ref value) => {
+                4u8.hash(state);
+                tag.hash(state);
+                value.numeric_hash(state);
+            }
+            _ => self.hash(state),
+        }
+    }
+}
+
+/// A `Value` whose `Eq` and `Hash` use `Value::numeric_eq`, for sets and map
+/// keys which should find `1.0` when looking up `1`.
+///
+/// Whole-number floats hash like the equal integer, and collections hash
+/// their elements in any order. Hashing is therefore slower than for
+/// `Value`, and equality of maps and sets is quadratic in their size.
+#[derive(Clone, Debug)]
+pub struct NumericEq(pub Value);
+
+impl PartialEq for NumericEq {
+    fn eq(&self, other: &NumericEq) -> bool {
+        self.0.numeric_eq(&other.0)
+    }
+}
+
+impl Eq for NumericEq {}
+
+impl Hash for NumericEq {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.numeric_hash(state)
+    }
+}
+
+/// Why a checked conversion from a `Value` to a Rust number failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NumberError {
+    /// The value isn't a number which converts to the type.
+    Type(TypeError),
+    /// The number doesn't fit in the type, e.g. `300` as a `u8`, or a float
+    /// type can't hold the integer exactly.
+    OutOfRange {
+        number: String,
+        target: &'static str,
+    },
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NumberError::Type(ref err) => err.fmt(f),
+            NumberError::OutOfRange { ref number, target } => {
+                write!(f, "{} is out of range for {}", number, target)
+            }
+        }
+    }
+}
+
+fn out_of_range(value: &Value, target: &'static str) -> NumberError {
+    NumberError::OutOfRange {
+        number: ::ser::to_string(value),
+        target,
+    }
+}
+
+fn mismatch(expected: &'static str, value: &Value) -> NumberError {
+    NumberError::Type(TypeError {
+        expected,
+        found: value.kind(),
+    })
+}
+
+macro_rules! try_from_integer {
+    ($($ty:ident)*) => ($(
+        /// The value of an `Integer` or `BigInt`, if it fits.
+        impl<'a> TryFrom<&'a Value> for $ty {
+            type Error = NumberError;
+
+            fn try_from(value: &'a Value) -> Result<$ty, NumberError> {
+                match *value {
+                    Value::Integer(integer) => {
+                        $ty::try_from(integer).map_err(|_| out_of_range(value, stringify!($ty)))
+                    }
+                    #[cfg(feature = "bigint")]
+                    Value::BigInt(ref integer) => {
+                        $ty::try_from(integer).map_err(|_| out_of_range(value, stringify!($ty)))
+                    }
+                    _ => Err(mismatch("an integer", value)),
+                }
+            }
+        }
+    )*)
+}
+
+try_from_integer!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+/// The value of a `Float`, or of an `Integer` which an `f64` holds exactly.
+impl<'a> TryFrom<&'a Value> for f64 {
+    type Error = NumberError;
+
+    fn try_from(value: &'a Value) -> Result<f64, NumberError> {
+        match *value {
+            Value::Float(float) => Ok(float.into_inner()),
+            Value::Integer(integer) => match integer as f64 {
+                float if cmp_integer_float(integer, float) == Some(Ordering::Equal) => Ok(float),
+                _ => Err(out_of_range(value, "f64")),
+            },
+            _ => Err(mismatch("a number", value)),
+        }
+    }
+}
+
+/// The value of a `Float` within the range of an `f32`, rounded to the
+/// nearest `f32`, or of an `Integer` which an `f32` holds exactly.
+impl<'a> TryFrom<&'a Value> for f32 {
+    type Error = NumberError;
+
+    fn try_from(value: &'a Value) -> Result<f32, NumberError> {
+        match *value {
+            Value::Float(float) => match float.into_inner() {
+                float if float.is_finite() && float.abs() > f64::from(f32::MAX) => {
+                    Err(out_of_range(value, "f32"))
+                }
+                float => Ok(float as f32),
+            },
+            Value::Integer(integer) => match integer as f32 {
+                float if cmp_integer_float(integer, f64::from(float)) == Some(Ordering::Equal) => {
+                    Ok(float)
+                }
+                _ => Err(out_of_range(value, "f32")),
+            },
+            _ => Err(mismatch("a number", value)),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+use indexmap;
+use indexmap::{IndexMap, IndexSet};
+
+/// A map which iterates in insertion order, but compares and hashes like a
+/// `BTreeMap` so that `Value` can still be `Ord` and `Hash`.
+#[derive(Clone)]
+pub struct OrderedMap<K, V>(IndexMap<K, V>);
+
+/// A set which iterates in insertion order, but compares and hashes like a
+/// `BTreeSet`.
+#[derive(Clone)]
+pub struct OrderedSet<T>(IndexSet<T>);
+
+impl<K: Hash + Eq, V> OrderedMap<K, V> {
+    pub fn new() -> OrderedMap<K, V> {
+        OrderedMap(IndexMap::new())
+    }
+
+    /// Remove `key`, keeping the order of the other entries.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: Hash + indexmap::Equivalent<K> + ?Sized,
+    {
+        self.0.shift_remove(key)
+    }
+
+    fn sorted(&self) -> Vec<(&K, &V)>
+    where
+        K: Ord,
+        V: Ord,
+    {
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort();
+        entries
+    }
+}
+
+impl<T: Hash + Eq> OrderedSet<T> {
+    pub fn new() -> OrderedSet<T> {
+        OrderedSet(IndexSet::new())
+    }
+
+    /// Remove `value`, keeping the order of the other elements.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        Q: Hash + indexmap::Equivalent<T> + ?Sized,
+    {
+        self.0.shift_remove(value)
+    }
+
+    fn sorted(&self) -> Vec<&T>
+    where
+        T: Ord,
+    {
+        let mut items: Vec<_> = self.0.iter().collect();
+        items.sort();
+        items
+    }
+}
+
+impl<K, V> Default for OrderedMap<K, V> {
+    fn default() -> OrderedMap<K, V> {
+        OrderedMap(IndexMap::default())
+    }
+}
+
+impl<T> Default for OrderedSet<T> {
+    fn default() -> OrderedSet<T> {
+        OrderedSet(IndexSet::default())
+    }
+}
+
+impl<K, V> Deref for OrderedMap<K, V> {
+    type Target = IndexMap<K, V>;
+
+    fn deref(&self) -> &IndexMap<K, V> {
+        &self.0
+    }
+}
+
+impl<K, V> DerefMut for OrderedMap<K, V> {
+    fn deref_mut(&mut self) -> &mut IndexMap<K, V> {
+        &mut self.0
+    }
+}
+
+impl<T> Deref for OrderedSet<T> {
+    type Target = IndexSet<T>;
+
+    fn deref(&self) -> &IndexSet<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for OrderedSet<T> {
+    fn deref_mut(&mut self) -> &mut IndexSet<T> {
+        &mut self.0
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OrderedMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OrderedSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for OrderedMap<K, V> {
+    fn eq(&self, other: &OrderedMap<K, V>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for OrderedMap<K, V> {}
+
+impl<T: Hash + Eq> PartialEq for OrderedSet<T> {
+    fn eq(&self, other: &OrderedSet<T>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Hash + Eq> Eq for OrderedSet<T> {}
+
+impl<K: Hash + Ord, V: Ord> PartialOrd for OrderedMap<K, V> {
+    fn partial_cmp(&self, other: &OrderedMap<K, V>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Hash + Ord, V: Ord> Ord for OrderedMap<K, V> {
+    fn cmp(&self, other: &OrderedMap<K, V>) -> Ordering {
+        self.sorted().cmp(&other.sorted())
+    }
+}
+
+impl<T: Hash + Ord> PartialOrd for OrderedSet<T> {
+    fn partial_cmp(&self, other: &OrderedSet<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Hash + Ord> Ord for OrderedSet<T> {
+    fn cmp(&self, other: &OrderedSet<T>) -> Ordering {
+        self.sorted().cmp(&other.sorted())
+    }
+}
+
+impl<K: Hash + Ord, V: Hash + Ord> Hash for OrderedMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted().hash(state)
+    }
+}
+
+impl<T: Hash + Ord> Hash for OrderedSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted().hash(state)
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for OrderedMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> OrderedMap<K, V> {
+        OrderedMap(iter.into_iter().collect())
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for OrderedSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OrderedSet<T> {
+        OrderedSet(iter.into_iter().collect())
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for OrderedMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for OrderedSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V> IntoIterator for OrderedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = indexmap::map::IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a OrderedMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = indexmap::map::Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut OrderedMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = indexmap::map::IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for OrderedSet<T> {
+    type Item = T;
+    type IntoIter = indexmap::set::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a OrderedSet<T> {
+    type Item = &'a T;
+    type IntoIter = indexmap::set::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
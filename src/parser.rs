@@ -1,113 +1,699 @@
-use std::str::CharIndices;
+use std::any::Any;
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::ops::Range;
+use std::str;
 
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
 use ordered_float::OrderedFloat;
 
-use Value;
+use raw::Scanner;
+use read::{self, Forms, IoRead, Read};
+use readers::{self, AnyReaders, ContextReaders, ReaderRegistry};
+use symbol;
+use {Keyword, RawEdn, Symbol, Value};
 
 #[cfg(feature = "immutable")]
-use immutable::{Map, Vec, Set};
+use immutable::{Map, Set, Vec};
 
 #[cfg(not(feature = "immutable"))]
-use standard::{Map, Vec, Set};
+use standard::{Map, Set, Vec};
 
 pub struct Parser<'a> {
     str: &'a str,
-    chars: CharIndices<'a>,
+    chars: Cursor<'a>,
+    options: ParserBuilder<'a>,
+    /// Where in the form being read the parser is, with `track_path`.
+    path: ::std::vec::Vec<String>,
+    depth: usize,
+    /// The number of items in the last collection closed at each depth, the
+    /// initial capacity of the next one there, since siblings in EDN tend to
+    /// have the same shape.
+    sizes: ::std::vec::Vec<usize>,
+    /// With `read_spanned`, the children read so far of each form being
+    /// read, innermost last.
+    spans: Option<::std::vec::Vec<::std::vec::Vec<Spanned>>>,
+    /// Readers which take precedence over `options.readers`, with their
+    /// context.
+    context: Option<(&'a dyn AnyReaders, &'a mut dyn Any)>,
+    /// Whether iterating has returned an error, after which it stops.
+    failed: bool,
 }
 
+/// What kind of problem an `Error` is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    /// Input which isn't valid EDN.
+    Syntax,
+    /// Input which ends in the middle of a form.
+    Eof,
+    /// Valid EDN which can't be read as a value, e.g. an integer out of range
+    /// or a tagged element its reader rejects.
+    Data,
+    /// A failure reading the input.
+    Io,
+}
+
+/// An error at byte offsets `lo..hi` of the input, which starts at the 1-based
+/// `line` and `column`. Columns count characters, not bytes.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Error {
     pub lo: usize,
     pub hi: usize,
+    pub line: usize,
+    pub column: usize,
+    pub category: Category,
     pub message: String,
 }
 
+impl Error {
+    /// A `Category::Data` error for a tagged element reader to return. The
+    /// parser sets its position to that of the element.
+    pub fn data<M: Into<String>>(message: M) -> Error {
+        Error {
+            lo: 0,
+            hi: 0,
+            line: 1,
+            column: 1,
+            category: Category::Data,
+            message: message.into(),
+        }
+    }
+
+    /// An error at `lo..hi` of `str`.
+    pub(crate) fn at(
+        str: &str,
+        lo: usize,
+        hi: usize,
+        category: Category,
+        message: String,
+    ) -> Error {
+        let (line, column) = line_column(str, lo);
+        Error {
+            lo,
+            hi,
+            line,
+            column,
+            category,
+            message,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn byte_offset(&self) -> usize {
+        self.lo
+    }
+
+    pub fn classify(&self) -> Category {
+        self.category
+    }
+}
+
+/// The 1-based line and column of byte offset `pos` of `str`.
+fn line_column(str: &str, pos: usize) -> (usize, usize) {
+    let before = &str[..pos];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl error::Error for Error {}
+
+/// Options for parsing, shared by every parser it builds.
+///
+/// ```
+/// use edn::parser::ParserBuilder;
+/// use edn::readers::ReaderRegistry;
+///
+/// let readers = ReaderRegistry::new();
+/// let builder = ParserBuilder::new().readers(&readers);
+/// assert!(builder.from_str("#my/tag 1").is_ok());
+/// ```
+#[derive(Clone, Default)]
+pub struct ParserBuilder<'r> {
+    readers: Option<&'r ReaderRegistry>,
+    namespaces: HashMap<String, String>,
+    keep_metadata: bool,
+    strip_namespaces: ::std::vec::Vec<String>,
+    snake_case_keys: bool,
+    track_path: bool,
+    clojure_syntax: bool,
+    strict: bool,
+    duplicate_keys: DuplicateKeyPolicy,
+    /// `None` for `DEFAULT_RECURSION_LIMIT`.
+    recursion_limit: Option<usize>,
+    #[cfg(feature = "bigint")]
+    promote_overflow: bool,
+}
+
+/// What to do with a key which appears twice in a map, e.g. `{:a 1 :a 2}`,
+/// or an element which appears twice in a set. The EDN spec makes both
+/// invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    Error,
+    /// Keep the first entry, ignoring the rest.
+    FirstWins,
+    /// Keep the last entry's value, the default.
+    #[default]
+    LastWins,
+}
+
+/// How deeply forms may be nested by default before parsing fails, to keep
+/// hostile input from overflowing the stack.
+pub const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+/// The most items a collection is presized for, so that one long
+/// collection doesn't make every sibling after it reserve as much.
+const MAX_CAPACITY_HINT: usize = 1024;
+
+impl<'r> ParserBuilder<'r> {
+    pub fn new() -> ParserBuilder<'r> {
+        ParserBuilder::default()
+    }
+
+    /// Pass tagged elements through `readers` instead of the readers added
+    /// with `readers::install`.
+    pub fn readers(mut self, readers: &'r ReaderRegistry) -> ParserBuilder<'r> {
+        self.readers = Some(readers);
+        self
+    }
+
+    /// Read keywords and symbols in namespace `from` as if they were in
+    /// namespace `to`, e.g. `:user/name` as `:app.user/name`.
+    pub fn rename_namespace(mut self, from: &str, to: &str) -> ParserBuilder<'r> {
+        self.namespaces.insert(from.into(), to.into());
+        self
+    }
+
+    /// Attach `^` metadata to the values it precedes with `Value::WithMeta`
+    /// instead of dropping it.
+    pub fn keep_metadata(mut self, keep: bool) -> ParserBuilder<'r> {
+        self.keep_metadata = keep;
+        self
+    }
+
+    /// Read keyword map keys in one of `namespaces` without it, so
+    /// `{:person/first-name "Ada" :db/id 1}` is read as
+    /// `{:first-name "Ada" :id 1}` with `&["person", "db"]`, e.g. for
+    /// Datomic entity maps. Keys in other namespaces are left as they are.
+    pub fn strip_namespaces(mut self, namespaces: &[&str]) -> ParserBuilder<'r> {
+        self.strip_namespaces = namespaces.iter().map(|&ns| ns.into()).collect();
+        self
+    }
+
+    /// Read keyword map keys as snake_case strings without their namespace,
+    /// so `{:user/first-name "Ada"}` is read as `{"first_name" "Ada"}`, the
+    /// shape of a Rust struct's fields. The inverse of `ser::Config::keyword_keys`.
+    pub fn snake_case_keys(mut self, snake_case: bool) -> ParserBuilder<'r> {
+        self.snake_case_keys = snake_case;
+        self
+    }
+
+    /// Name the map keys and vector indices leading to an error in its
+    /// message, e.g. "unexpected `)`, expected `]` in `:server :ports [2]`".
+    pub fn track_path(mut self, track: bool) -> ParserBuilder<'r> {
+        self.track_path = track;
+        self
+    }
+
+    /// Also read the Clojure reader macros found in code which is mostly
+    /// data, such as Leiningen's `project.clj`, as the lists they stand for:
+    /// `'x` as `(quote x)`, `` `x `` as `(syntax-quote x)`, `~x` as
+    /// `(unquote x)`, `~@x` as `(unquote-splicing x)`, `@x` as `(deref x)`,
+    /// `#'x` as `(var x)`, `#(...)` as `(fn* (...))`, `#=x` as
+    /// `(read-eval x)`, `#"re"` as `#regex "re"` and `#:a{:b 1}` as
+    /// `{:a/b 1}`. Hex integers like `0x1F` and radix integers like
+    /// `2r1010` are read too; a leading zero still means decimal, not octal.
+    pub fn clojure_syntax(mut self, clojure_syntax: bool) -> ParserBuilder<'r> {
+        self.clojure_syntax = clojure_syntax;
+        self
+    }
+
+    /// Read the Clojure syntax which finds its way into hand-written "EDN",
+    /// as `clojure_syntax` describes, instead of failing on it. Strict EDN
+    /// is the default.
+    pub fn lenient(self) -> ParserBuilder<'r> {
+        self.clojure_syntax(true)
+    }
+
+    /// Fail on symbols, keywords and tags which the EDN spec's grammar
+    /// doesn't allow, e.g. `a/b/c`, `:1st` or `#_tag`, instead of reading
+    /// them as best we can.
+    pub fn strict(mut self, strict: bool) -> ParserBuilder<'r> {
+        self.strict = strict;
+        self
+    }
+
+    /// Fail on duplicate map keys and set elements with
+    /// `DuplicateKeyPolicy::Error`, or choose which map entry to keep.
+    /// Duplicate set elements are dropped with the other policies.
+    pub fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> ParserBuilder<'r> {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Fail with "recursion limit exceeded" on forms nested more than
+    /// `limit` deep, instead of `DEFAULT_RECURSION_LIMIT`.
+    pub fn recursion_limit(mut self, limit: usize) -> ParserBuilder<'r> {
+        self.recursion_limit = Some(limit);
+        self
+    }
+
+    /// Read forms nested to any depth. The parser is recursive, so only do
+    /// this for trusted input, on a thread with a large enough stack.
+    pub fn disable_recursion_limit(self) -> ParserBuilder<'r> {
+        self.recursion_limit(usize::MAX)
+    }
+
+    /// Read integers which don't fit in an `i64` as `Value::BigInt` instead
+    /// of failing. Integers which fit in an `i128` or `u128`, such as
+    /// 128-bit IDs, are read as `BigInt`s either way.
+    #[cfg(feature = "bigint")]
+    pub fn promote_overflow(mut self, promote: bool) -> ParserBuilder<'r> {
+        self.promote_overflow = promote;
+        self
+    }
+
+    pub fn build<'a>(&self, str: &'a str) -> Parser<'a>
+    where
+        'r: 'a,
+    {
+        Parser {
+            str,
+            chars: Cursor::new(str),
+            options: self.clone(),
+            path: vec![],
+            depth: 0,
+            sizes: vec![],
+            spans: None,
+            context: None,
+            failed: false,
+        }
+    }
+
+    /// Parse exactly one form from `str`. Anything but whitespace and
+    /// comments after it is an error.
+    pub fn from_str(&self, str: &str) -> Result<Value, Error> {
+        self.parse_one(str, Parser::read)
+    }
+
+    /// Parse exactly one form from UTF-8 `bytes`, e.g. a network message.
+    /// Input which is all ASCII, as machine-generated EDN usually is, is
+    /// checked with one fast scan instead of full UTF-8 validation.
+    pub fn from_slice(&self, bytes: &[u8]) -> Result<Value, Error> {
+        self.from_str(utf8(bytes)?)
+    }
+
+    /// Like `from_str`, but with the position of every form read.
+    pub fn parse_spanned(&self, str: &str) -> Result<Spanned, Error> {
+        self.parse_one(str, Parser::read_spanned)
+    }
+
+    /// Parse the first form in `str`, returning it and the rest of `str`
+    /// after it, for EDN embedded in other text. The rest starts right after
+    /// the form, so `str.len() - rest.len()` is the number of bytes read.
+    pub fn from_str_partial<'a>(&self, str: &'a str) -> Result<(Value, &'a str), Error>
+    where
+        'r: 'a,
+    {
+        let mut parser = self.build(str);
+        let value = parser.read_first(Parser::read)?;
+        Ok((value, &str[parser.pos()..]))
+    }
+
+    fn parse_one<'a, T, F>(&self, str: &'a str, read: F) -> Result<T, Error>
+    where
+        'r: 'a,
+        F: FnOnce(&mut Parser<'a>) -> Option<Result<T, Error>>,
+    {
+        let mut parser = self.build(str);
+        let value = parser.read_first(read)?;
+        parser.whitespace()?;
+        let end = parser.pos();
+        if end == str.len() {
+            Ok(value)
+        } else {
+            Err(parser.error(end, end, Category::Syntax, "trailing characters after form"))
+        }
+    }
+}
+
+/// Parse exactly one form from `str` with the default options.
+pub fn from_str(str: &str) -> Result<Value, Error> {
+    ParserBuilder::new().from_str(str)
+}
+
+/// Parse the first form in `str` with the default options, returning it and
+/// the rest of `str`; see `ParserBuilder::from_str_partial`.
+pub fn from_str_partial(str: &str) -> Result<(Value, &str), Error> {
+    ParserBuilder::new().from_str_partial(str)
+}
+
+/// Parse exactly one form from UTF-8 `bytes` with the default options; see
+/// `ParserBuilder::from_slice`.
+pub fn from_slice(bytes: &[u8]) -> Result<Value, Error> {
+    ParserBuilder::new().from_slice(bytes)
+}
+
+pub(crate) fn utf8(bytes: &[u8]) -> Result<&str, Error> {
+    if bytes.is_ascii() {
+        // SAFETY: ASCII is valid UTF-8.
+        return Ok(unsafe { str::from_utf8_unchecked(bytes) });
+    }
+    str::from_utf8(bytes).map_err(|err| {
+        let lo = err.valid_up_to();
+        let valid = str::from_utf8(&bytes[..lo]).expect("checked up to here");
+        Error::at(
+            valid,
+            lo,
+            lo,
+            Category::Syntax,
+            "invalid UTF-8 in input".into(),
+        )
+    })
+}
+
+/// Parse exactly one form from `str` with the default options, keeping the
+/// position of every form read.
+pub fn parse_spanned(str: &str) -> Result<Spanned, Error> {
+    ParserBuilder::new().parse_spanned(str)
+}
+
 impl<'a> Parser<'a> {
     pub fn new(str: &'a str) -> Parser<'a> {
-        Parser {
-            str: str,
-            chars: str.char_indices(),
+        ParserBuilder::new().build(str)
+    }
+
+    /// Create a parser which passes tagged elements through `readers` instead
+    /// of always producing `Value::Tagged`.
+    pub fn with_readers(str: &'a str, readers: &'a ReaderRegistry) -> Parser<'a> {
+        ParserBuilder::new().readers(readers).build(str)
+    }
+
+    /// Create a parser which passes tagged elements through `readers` with
+    /// `context`, so they can use and update caller state.
+    pub fn with_context<C: 'static>(
+        str: &'a str,
+        readers: &'a ContextReaders<C>,
+        context: &'a mut C,
+    ) -> Parser<'a> {
+        ParserBuilder::new().build(str).context(readers, context)
+    }
+
+    /// Pass tagged elements with a reader in `readers` through it with
+    /// `context`, before any readers set on the builder.
+    pub fn context<C: 'static>(
+        mut self,
+        readers: &'a ContextReaders<C>,
+        context: &'a mut C,
+    ) -> Parser<'a> {
+        self.context = Some((readers, context));
+        self
+    }
+
+    /// Read the forms of `reader` one at a time, buffering only as much
+    /// input as the current form needs, e.g. for a large file:
+    ///
+    /// ```no_run
+    /// use edn::parser::Parser;
+    ///
+    /// for form in Parser::from_reader(std::fs::File::open("data.edn").unwrap()) {
+    ///     println!("{}", edn::to_string(&form.unwrap()));
+    /// }
+    /// ```
+    pub fn from_reader<R: ::std::io::Read>(reader: R) -> IoRead<R> {
+        IoRead::new(reader)
+    }
+
+    /// Read the forms of any `read::Read` source one at a time; see
+    /// `from_reader`.
+    pub fn from_source<S: Read>(source: S) -> Forms<S> {
+        Forms::new(source)
+    }
+
+    /// Read the next form along with a copy of the text it was read from,
+    /// e.g. for logging exactly what was received.
+    pub fn read_with_source(&mut self) -> Option<Result<SourceText, Error>> {
+        if let Err(err) = self.whitespace() {
+            return Some(Err(err));
+        }
+        let lo = self.pos();
+        self.read().map(|result| {
+            result.map(|value| SourceText {
+                value,
+                text: self.str[lo..self.pos()].into(),
+            })
+        })
+    }
+
+    /// Read the next form's text without building a value, which is much
+    /// faster for forms that are only passed on. Only its syntax is checked.
+    pub fn read_raw(&mut self) -> Option<Result<RawEdn, Error>> {
+        let result = Scanner::new(self.str, self.pos()).next_raw();
+        if let Some(Ok((_, span))) = result {
+            while self.pos() < span.hi {
+                self.chars.next();
+            }
+        }
+        result.map(|result| result.map(|(raw, _)| raw))
+    }
+
+    /// Skip the next form without reading it, e.g. a map entry that isn't
+    /// needed. Only brackets are matched up, so this is much faster than
+    /// `read` or `read_raw` and doesn't allocate, but errors inside the form,
+    /// like a bad escape or a `(` closed by `]`, go unnoticed.
+    pub fn skip_form(&mut self) -> Option<Result<(), Error>> {
+        match read::skip_form(self.str, self.pos()) {
+            Ok(Some(end)) => {
+                self.chars.seek(end);
+                Some(Ok(()))
+            }
+            Ok(None) => {
+                self.chars.seek(self.str.len());
+                None
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Turn this parser into an iterator over the remaining top-level forms
+    /// and the byte range each one occupies in the input.
+    pub fn into_stream(self) -> Stream<'a> {
+        Stream {
+            parser: self,
+            failed: false,
         }
     }
 
+    /// Read all the remaining forms, carrying on past errors instead of
+    /// stopping at the first one, for showing every problem in a file at
+    /// once. Returns the forms read and a diagnostic for each error.
+    ///
+    /// A form which is well-formed but can't be read as a value, or a bad
+    /// token at the top level, is skipped by itself. After any other error,
+    /// e.g. a mismatched or missing delimiter, reading resumes at the next
+    /// line starting with something other than whitespace or a closing
+    /// delimiter, as top-level forms usually do.
+    pub fn parse_with_recovery(
+        &mut self,
+    ) -> (::std::vec::Vec<Value>, ::std::vec::Vec<Diagnostic>) {
+        let mut forms = vec![];
+        let mut diagnostics = vec![];
+        loop {
+            self.chars.skip_whitespace();
+            let lo = self.pos();
+            let err = match self.read() {
+                Some(Ok(value)) => {
+                    forms.push(value);
+                    continue;
+                }
+                Some(Err(err)) => err,
+                None => break,
+            };
+            let resume = match Scanner::new(self.str, lo).next_raw() {
+                Some(Ok((_, span))) if err.category == Category::Data => span.hi,
+                _ if err.category == Category::Syntax && err.lo == lo && err.hi > lo => err.hi,
+                _ => self.next_line_start(lo),
+            };
+            diagnostics.push(Diagnostic::from(err));
+            self.chars.seek(resume);
+        }
+        (forms, diagnostics)
+    }
+
     pub fn read(&mut self) -> Option<Result<Value, Error>> {
-        self.whitespace();
+        self.path.clear();
+        self.depth = 0;
+        self.read_form()
+    }
+
+    /// Read the next form along with its position and those of the forms
+    /// inside it.
+    pub fn read_spanned(&mut self) -> Option<Result<Spanned, Error>> {
+        self.spans = Some(vec![vec![]]);
+        let result = self.read();
+        let mut spans = self.spans.take().unwrap_or_default();
+        result.map(|result| {
+            result.map(|_| {
+                spans
+                    .pop()
+                    .and_then(|mut top| top.pop())
+                    .expect("a form read with spans has a span")
+            })
+        })
+    }
+
+    fn read_form(&mut self) -> Option<Result<Value, Error>> {
+        if self.spans.is_none() {
+            return self.read_limited();
+        }
+        if let Err(err) = self.whitespace() {
+            return Some(Err(err));
+        }
+        let lo = self.pos();
+        self.spans.as_mut().unwrap().push(vec![]);
+        let result = self.read_limited();
+        let hi = self.pos();
+        let spans = self.spans.as_mut().unwrap();
+        let children = spans.pop().unwrap_or_default();
+        if let Some(Ok(ref value)) = result {
+            let (line, column) = line_column(self.str, lo);
+            // A float with children had metadata or a tag in front of it.
+            let literal = match *value {
+                Value::Float(_) if children.is_empty() => Some(self.str[lo..hi].to_string()),
+                _ => None,
+            };
+            let spanned = Spanned {
+                value: value.clone(),
+                span: lo..hi,
+                line,
+                column,
+                children,
+                literal,
+            };
+            if let Some(parent) = spans.last_mut() {
+                parent.push(spanned);
+            }
+        }
+        result
+    }
+
+    fn read_limited(&mut self) -> Option<Result<Value, Error>> {
+        let limit = self
+            .options
+            .recursion_limit
+            .unwrap_or(DEFAULT_RECURSION_LIMIT);
+        if self.depth >= limit {
+            if let Err(err) = self.whitespace() {
+                return Some(Err(err));
+            }
+            let pos = self.pos();
+            return Some(Err(self.error(
+                pos,
+                pos,
+                Category::Syntax,
+                "recursion limit exceeded",
+            )));
+        }
+        self.depth += 1;
+        let result = self.read_value();
+        self.depth -= 1;
+        result
+    }
+
+    fn read_value(&mut self) -> Option<Result<Value, Error>> {
+        if let Err(err) = self.whitespace() {
+            return Some(Err(err));
+        }
 
         self.chars.clone().next().map(|(pos, ch)| match (pos, ch) {
-            (start, '0'...'9') => {
-                let end = self.advance_while(|ch| ch.is_digit(10));
-                if self.peek() == Some('.') {
-                    self.chars.next();
-                    let end = self.advance_while(|ch| ch.is_digit(10));
-                    Ok(Value::Float(OrderedFloat(
-                        self.str[start..end].parse().unwrap(),
-                    )))
-                } else {
-                    Ok(Value::Integer(self.str[start..end].parse().unwrap()))
-                }
+            (start, '0'..='9') => {
+                let end = self.advance_while(|ch| ch.is_ascii_digit());
+                self.number(start, end)
             }
             (start, ch @ '+') | (start, ch @ '-') => {
                 self.chars.next();
                 match self.peek() {
-                    Some('0'...'9') => {
+                    Some('0'..='9') => {
                         let start = if ch == '+' { start + 1 } else { start };
-                        let end = self.advance_while(|ch| ch.is_digit(10));
-                        if self.peek() == Some('.') {
-                            self.chars.next();
-                            let end = self.advance_while(|ch| ch.is_digit(10));
-                            Ok(Value::Float(OrderedFloat(
-                                self.str[start..end].parse().unwrap(),
-                            )))
-                        } else {
-                            Ok(Value::Integer(self.str[start..end].parse().unwrap()))
-                        }
+                        let end = self.advance_while(|ch| ch.is_ascii_digit());
+                        self.number(start, end)
                     }
                     Some(ch) if is_symbol_tail(ch) => {
                         let end = self.advance_while(is_symbol_tail);
-                        Ok(Value::Symbol(self.str[start..end].into()))
+                        self.check_name(start, end, "symbol")?;
+                        Ok(Value::Symbol(self.symbol(&self.str[start..end])))
                     }
-                    None | Some(' ') | Some('\t') | Some('\n') => Ok(Value::Symbol(ch.to_string())),
-                    _ => unimplemented!(),
+                    // Anything else, e.g. a delimiter as in `[-]`, ends the
+                    // symbol.
+                    _ => Ok(Value::Symbol(self.symbol(&self.str[start..start + 1]))),
                 }
             }
             (start, '.') => {
                 self.chars.next();
-                if let Some('0'...'9') = self.peek() {
-                    let end = self.advance_while(|ch| ch.is_digit(10));
-                    Ok(Value::Float(OrderedFloat(
-                        self.str[start..end].parse().unwrap(),
-                    )))
+                if let Some('0'..='9') = self.peek() {
+                    let end = self.advance_while(|ch| ch.is_ascii_digit());
+                    let end = self.exponent(end).unwrap_or(end);
+                    Ok(self.float(start, end))
                 } else {
                     let end = self.advance_while(is_symbol_tail);
-                    Ok(Value::Symbol(self.str[start..end].into()))
+                    self.check_name(start, end, "symbol")?;
+                    Ok(Value::Symbol(self.symbol(&self.str[start..end])))
                 }
             }
             (start, '\\') => {
                 self.chars.next();
                 let start = start + 1;
-                let end = self.advance_while(|ch| !ch.is_whitespace());
-                Ok(Value::Char(match &self.str[start..end] {
-                    "newline" => '\n',
-                    "return" => '\r',
-                    "space" => ' ',
-                    "tab" => '\t',
-                    otherwise => {
-                        if otherwise.chars().count() == 1 {
-                            otherwise.chars().next().unwrap()
-                        } else {
-                            return Err(Error {
-                                lo: start - 1,
-                                hi: end,
-                                message: format!("invalid char literal `\\{}`", otherwise),
-                            });
-                        }
-                    }
-                }))
+                // The first char is the literal even if it's a delimiter, as
+                // in `\(`; after that a delimiter ends it, as in `[\a]`.
+                let mut first = true;
+                let end = self.advance_while(|ch| {
+                    let more = !ch.is_whitespace() && (first || !"()[]{}\",;\\".contains(ch));
+                    first = false;
+                    more
+                });
+                match char_literal(&self.str[start..end]) {
+                    Some(ch) => Ok(Value::Char(ch)),
+                    None => Err(self.error(
+                        start - 1,
+                        end,
+                        Category::Syntax,
+                        format!("invalid char literal `\\{}`", &self.str[start..end]),
+                    )),
+                }
             }
             (start, '"') => {
                 self.chars.next();
                 let mut string = String::new();
                 loop {
+                    // Copy everything up to the next quote or escape at once.
+                    let pos = self.pos();
+                    let run = memchr::memchr2(b'"', b'\\', &self.str.as_bytes()[pos..])
+                        .unwrap_or(self.str.len() - pos);
+                    string.push_str(&self.str[pos..pos + run]);
+                    self.chars.seek(pos + run);
                     match self.chars.next() {
                         Some((_, '"')) => return Ok(Value::String(string)),
                         Some((_, '\\')) => {
@@ -115,25 +701,52 @@ impl<'a> Parser<'a> {
                                 Some((_, 't')) => '\t',
                                 Some((_, 'r')) => '\r',
                                 Some((_, 'n')) => '\n',
+                                Some((_, 'b')) => '\u{8}',
+                                Some((_, 'f')) => '\u{c}',
                                 Some((_, '\\')) => '\\',
                                 Some((_, '"')) => '\"',
+                                Some((pos, 'u')) => {
+                                    match unicode_escape(&self.str[pos + 1..]) {
+                                        Some((ch, len)) => {
+                                            for _ in 0..len {
+                                                self.chars.next();
+                                            }
+                                            ch
+                                        }
+                                        None => return Err(self.error(
+                                            pos - 1,
+                                            pos + 1,
+                                            Category::Syntax,
+                                            "expected four hex digits naming a char after `\\u`",
+                                        )),
+                                    }
+                                }
                                 Some((pos, ch)) => {
-                                    return Err(Error {
-                                        lo: pos - 1,
-                                        hi: pos + 1,
-                                        message: format!("invalid string escape `\\{}`", ch),
-                                    })
+                                    return Err(self.error(
+                                        pos - 1,
+                                        pos + 1,
+                                        Category::Syntax,
+                                        format!("invalid string escape `\\{}`", ch),
+                                    ))
+                                }
+                                None => {
+                                    return Err(self.error(
+                                        start,
+                                        self.str.len(),
+                                        Category::Eof,
+                                        "expected closing `\"`, found EOF",
+                                    ))
                                 }
-                                None => unimplemented!(),
                             });
                         }
                         Some((_, ch)) => string.push(ch),
                         None => {
-                            return Err(Error {
-                                lo: start,
-                                hi: self.str.len(),
-                                message: "expected closing `\"`, found EOF".into(),
-                            })
+                            return Err(self.error(
+                                start,
+                                self.str.len(),
+                                Category::Eof,
+                                "expected closing `\"`, found EOF",
+                            ))
                         }
                     }
                 }
@@ -141,7 +754,8 @@ impl<'a> Parser<'a> {
             (start, ':') => {
                 self.chars.next();
                 let end = self.advance_while(is_symbol_tail);
-                Ok(Value::Keyword(self.str[start + 1..end].into()))
+                self.check_name(start, end, "keyword")?;
+                Ok(Value::Keyword(self.keyword(&self.str[start + 1..end])))
             }
             (start, open @ '(') | (start, open @ '[') | (start, open @ '{') => {
                 let close = match open {
@@ -152,32 +766,57 @@ impl<'a> Parser<'a> {
                 };
 
                 self.chars.next();
-                let mut items = vec![];
+                let mut items = self.presized();
+                // The span of each item, to point at a duplicate key.
+                let mut spans = vec![];
                 loop {
-                    self.whitespace();
+                    self.whitespace()?;
 
                     if self.peek() == Some(close) {
                         self.chars.next();
+                        self.record_size(items.len());
+                        if open != '{' && items.capacity() > 2 * items.len() {
+                            items.shrink_to_fit();
+                        }
                         return Ok(match open {
                             '(' => Value::List(Vec::from(items)),
                             '[' => Value::Vector(Vec::from(items)),
                             '{' => {
                                 let mut map = Map::new();
                                 let mut iter = items.into_iter();
+                                let mut index = 0;
                                 while let Some(key) = iter.next() {
                                     if let Some(value) = iter.next() {
-                                        map.insert(key, value);
+                                        let key = self.map_key(key);
+                                        match self.options.duplicate_keys {
+                                            DuplicateKeyPolicy::LastWins => {
+                                                map.insert(key, value);
+                                            }
+                                            _ if !map.contains_key(&key) => {
+                                                map.insert(key, value);
+                                            }
+                                            DuplicateKeyPolicy::FirstWins => {}
+                                            DuplicateKeyPolicy::Error => {
+                                                let (lo, hi) = spans[index];
+                                                return Err(self.error(
+                                                    lo,
+                                                    hi,
+                                                    Category::Data,
+                                                    format!(
+                                                        "duplicate key `{}` in a map",
+                                                        ::ser::to_string_lossy(&key)
+                                                    ),
+                                                ));
+                                            }
+                                        }
+                                        index += 2;
                                     } else {
-                                        let end = self.chars
-                                            .clone()
-                                            .next()
-                                            .map(|(pos, _)| pos)
-                                            .unwrap_or(self.str.len());
-                                        return Err(Error {
-                                            lo: start,
-                                            hi: end,
-                                            message: "odd number of items in a Map".into(),
-                                        });
+                                        return Err(self.error(
+                                            start,
+                                            self.pos(),
+                                            Category::Data,
+                                            "odd number of items in a Map",
+                                        ));
                                     }
                                 }
                                 Value::Map(map)
@@ -186,66 +825,191 @@ impl<'a> Parser<'a> {
                         });
                     }
 
-                    match self.read() {
-                        Some(Ok(value)) => items.push(value),
+                    self.mismatched_close(match close {
+                        '}' if items.len() % 2 == 1 => "a map value",
+                        ')' => "`)`",
+                        ']' => "`]`",
+                        _ => "`}`",
+                    })?;
+
+                    let segment = match open {
+                        _ if !self.options.track_path => None,
+                        '{' if items.len() % 2 == 1 => {
+                            Some(::ser::to_string_lossy(&items[items.len() - 1]))
+                        }
+                        '{' => None,
+                        _ => Some(format!("[{}]", items.len())),
+                    };
+                    let tracked = segment.is_some();
+                    self.path.extend(segment);
+                    let lo = self.pos();
+                    match self.read_form() {
+                        Some(Ok(value)) => {
+                            if tracked {
+                                self.path.pop();
+                            }
+                            if open == '{'
+                                && self.options.duplicate_keys == DuplicateKeyPolicy::Error
+                            {
+                                spans.push((lo, self.pos()));
+                            }
+                            items.push(value)
+                        }
                         Some(Err(err)) => return Err(err),
                         None => {
-                            return Err(Error {
-                                lo: start,
-                                hi: self.str.len(),
-                                message: format!("unclosed `{}`", open),
-                            })
+                            return Err(self.error(
+                                start,
+                                self.str.len(),
+                                Category::Eof,
+                                format!("unclosed `{}`", open),
+                            ))
                         }
                     }
                 }
             }
             (start, '#') => {
                 self.chars.next();
+                let after_hash = self.chars.clone();
                 match self.chars.next() {
+                    Some((start, '#')) => {
+                        let end = self.advance_while(is_symbol_tail);
+                        let float = match &self.str[start + 1..end] {
+                            "Inf" => f64::INFINITY,
+                            "-Inf" => f64::NEG_INFINITY,
+                            "NaN" => f64::NAN,
+                            name => {
+                                return Err(self.error(
+                                    start - 1,
+                                    end,
+                                    Category::Syntax,
+                                    format!("unknown symbolic value `##{}`", name),
+                                ))
+                            }
+                        };
+                        Ok(Value::Float(OrderedFloat(float)))
+                    }
                     Some((_, open @ '{')) => {
                         let close = '}';
-                        let mut items = vec![];
+                        let mut set = Set::new();
+                        let mut len = 0;
                         loop {
-                            self.whitespace();
+                            self.whitespace()?;
 
                             if self.peek() == Some(close) {
                                 self.chars.next();
-                                return Ok(Value::Set(items.into_iter().collect()));
+                                self.record_size(len);
+                                return Ok(Value::Set(set));
                             }
 
-                            match self.read() {
-                                Some(Ok(value)) => items.push(value),
+                            self.mismatched_close("`}`")?;
+
+                            let lo = self.pos();
+                            match self.read_form() {
+                                Some(Ok(value)) => {
+                                    if self.options.duplicate_keys == DuplicateKeyPolicy::Error
+                                        && set.contains(&value)
+                                    {
+                                        return Err(self.error(
+                                            lo,
+                                            self.pos(),
+                                            Category::Data,
+                                            format!(
+                                                "duplicate element `{}` in a set",
+                                                ::ser::to_string_lossy(&value)
+                                            ),
+                                        ));
+                                    }
+                                    set.insert(value);
+                                    len += 1;
+                                }
                                 Some(Err(err)) => return Err(err),
                                 None => {
-                                    return Err(Error {
-                                        lo: start,
-                                        hi: self.str.len(),
-                                        message: format!("unclosed `#{}`", open),
-                                    })
+                                    return Err(self.error(
+                                        start,
+                                        self.str.len(),
+                                        Category::Eof,
+                                        format!("unclosed `#{}`", open),
+                                    ))
                                 }
                             }
                         }
                     }
+                    Some((_, '\'')) if self.options.clojure_syntax => self.prefixed(start, "var"),
+                    Some((_, '(')) if self.options.clojure_syntax => {
+                        // Back up so the body is read as a list.
+                        self.chars = after_hash;
+                        self.prefixed(start, "fn*")
+                    }
+                    Some((_, '"')) if self.options.clojure_syntax => self.regex(start),
+                    Some((_, ':')) if self.options.clojure_syntax => self.namespaced_map(start),
+                    Some((_, '=')) if self.options.clojure_syntax => {
+                        self.prefixed(start, "read-eval")
+                    }
+                    Some((_, ch @ '\'')) | Some((_, ch @ '(')) | Some((_, ch @ '"'))
+                    | Some((_, ch @ ':')) | Some((_, ch @ '=')) => {
+                        // Read the form as Clojure would, so the error spans
+                        // all of it and recovery resumes after it.
+                        self.chars.seek(start);
+                        self.options.clojure_syntax = true;
+                        let form = self.read_form();
+                        self.options.clojure_syntax = false;
+                        if let Some(Err(err)) = form {
+                            return Err(err);
+                        }
+                        Err(self.error(
+                            start,
+                            self.pos(),
+                            Category::Syntax,
+                            format!(
+                                "`#{}` is Clojure syntax, not EDN; \
+                                 read it with `ParserBuilder::clojure_syntax`",
+                                ch
+                            ),
+                        ))
+                    }
                     Some((start, ch)) if is_symbol_head(ch) => {
-                        self.chars.next();
                         let end = self.advance_while(is_symbol_tail);
+                        self.check_name(start - 1, end, "tag")?;
 
                         let tag = &self.str[start..end];
-                        let value = self.read();
+                        let value = self.read_form();
 
                         match value {
-                            Some(Ok(v)) => return Ok(Value::Tagged(tag.into(), Box::new(v))),
-                            Some(e) => return e,
-                            None => {
-                                return Err(Error {
-                                    lo: start,
-                                    hi: self.str.len(),
-                                    message: "malformed tagged value".into(),
+                            Some(Ok(v)) => {
+                                let result = match (&mut self.context, self.options.readers) {
+                                    (&mut Some((readers, ref mut context)), _)
+                                        if readers.contains(tag) =>
+                                    {
+                                        readers.read_any(*context, tag, v)
+                                    }
+                                    (_, Some(readers)) => readers.read(tag, v),
+                                    _ => readers::read_installed(tag, v),
+                                };
+                                result.map_err(|err| {
+                                    self.error(start - 1, self.pos(), err.category, err.message)
                                 })
                             }
+                            Some(e) => e,
+                            None => Err(self.error(
+                                start,
+                                self.str.len(),
+                                Category::Eof,
+                                "malformed tagged value",
+                            )),
                         }
                     }
-                    _ => unimplemented!(),
+                    None => Err(self.error(
+                        start,
+                        self.str.len(),
+                        Category::Eof,
+                        "expected a tag or `{` after `#`, found EOF",
+                    )),
+                    Some((pos, ch)) => Err(self.error(
+                        start,
+                        pos + ch.len_utf8(),
+                        Category::Syntax,
+                        format!("unexpected `#{}`", ch),
+                    )),
                 }
             }
             (start, ch) if is_symbol_head(ch) => {
@@ -255,32 +1019,537 @@ impl<'a> Parser<'a> {
                     "true" => Value::Boolean(true),
                     "false" => Value::Boolean(false),
                     "nil" => Value::Nil,
-                    otherwise => Value::Symbol(otherwise.into()),
+                    otherwise => {
+                        self.check_name(start, end, "symbol")?;
+                        Value::Symbol(self.symbol(otherwise))
+                    }
+                })
+            }
+            (start, '^') => {
+                self.chars.next();
+                let meta = match self.read_form() {
+                    Some(Ok(Value::Map(map))) => map,
+                    Some(Ok(key @ Value::Keyword(_))) => {
+                        let mut map = Map::new();
+                        map.insert(key, Value::Boolean(true));
+                        map
+                    }
+                    Some(Ok(tag @ Value::Symbol(_))) | Some(Ok(tag @ Value::String(_))) => {
+                        let mut map = Map::new();
+                        map.insert(Value::Keyword("tag".into()), tag);
+                        map
+                    }
+                    Some(Ok(_)) => {
+                        return Err(self.error(
+                            start,
+                            self.pos(),
+                            Category::Syntax,
+                            "metadata must be a map, keyword, symbol or string",
+                        ))
+                    }
+                    Some(Err(err)) => return Err(err),
+                    None => {
+                        return Err(self.error(
+                            start,
+                            self.str.len(),
+                            Category::Eof,
+                            "expected metadata after `^`, found EOF",
+                        ))
+                    }
+                };
+                let value = match self.read_form() {
+                    Some(result) => result?,
+                    None => {
+                        return Err(self.error(
+                            start,
+                            self.str.len(),
+                            Category::Eof,
+                            "expected a form after metadata, found EOF",
+                        ))
+                    }
+                };
+                if !self.options.keep_metadata {
+                    return Ok(value);
+                }
+                // Metadata further out takes precedence, as in Clojure.
+                Ok(match value {
+                    Value::WithMeta(value, mut inner) => {
+                        inner.extend(meta);
+                        Value::WithMeta(value, inner)
+                    }
+                    value => Value::WithMeta(Box::new(value), meta),
                 })
             }
             (_, '/') => {
                 self.chars.next();
                 Ok(Value::Symbol("/".into()))
             }
-            _ => unimplemented!(),
+            (start, ch @ '\'') | (start, ch @ '`') | (start, ch @ '~') | (start, ch @ '@')
+                if self.options.clojure_syntax =>
+            {
+                self.chars.next();
+                let name = match ch {
+                    '\'' => "quote",
+                    '`' => "syntax-quote",
+                    '@' => "deref",
+                    _ if self.peek() == Some('@') => {
+                        self.chars.next();
+                        "unquote-splicing"
+                    }
+                    _ => "unquote",
+                };
+                self.prefixed(start, name)
+            }
+            (start, ch) => Err(self.error(
+                start,
+                start + ch.len_utf8(),
+                Category::Syntax,
+                format!("unexpected `{}`", ch),
+            )),
+        })
+    }
+
+    /// `(name form)` for the form after a reader macro starting at `start`.
+    fn prefixed(&mut self, start: usize, name: &str) -> Result<Value, Error> {
+        let end = self.pos();
+        match self.read_form() {
+            Some(Ok(form)) => Ok(Value::List(Vec::from(vec![
+                Value::Symbol(name.into()),
+                form,
+            ]))),
+            Some(Err(err)) => Err(err),
+            None => Err(self.error(
+                start,
+                self.str.len(),
+                Category::Eof,
+                format!(
+                    "expected a form after `{}`, found EOF",
+                    &self.str[start..end]
+                ),
+            )),
+        }
+    }
+
+    /// The rest of a `#"..."` regex starting at `start`, whose escapes are
+    /// kept as written.
+    fn regex(&mut self, start: usize) -> Result<Value, Error> {
+        let mut escaped = false;
+        for (pos, ch) in &mut self.chars {
+            match ch {
+                '"' if !escaped => {
+                    let pattern = self.str[start + 2..pos].to_string();
+                    return Ok(Value::Tagged(
+                        "regex".into(),
+                        Box::new(Value::String(pattern)),
+                    ));
+                }
+                '\\' => escaped = !escaped,
+                _ => escaped = false,
+            }
+        }
+        Err(self.error(
+            start,
+            self.str.len(),
+            Category::Eof,
+            "expected closing `\"`, found EOF",
+        ))
+    }
+
+    /// Fail on the symbol, keyword or tag at `start..end`, including its `:`
+    /// or `#`, if it wouldn't be written back as read, like `a/b/c` or `::a`,
+    /// or with `strict`, if the EDN spec doesn't allow it.
+    fn check_name(&self, start: usize, end: usize, kind: &str) -> Result<(), Error> {
+        let text = &self.str[start..end];
+        let (name, keyword) = match kind {
+            "keyword" => (&text[1..], true),
+            "tag" => (&text[1..], false),
+            _ => (text, false),
+        };
+        let (namespace, name) = symbol::split_ref(name);
+        let valid = match kind {
+            "tag" => symbol::is_valid_tag(&text[1..]),
+            _ if self.options.strict => symbol::is_valid(namespace, name, keyword),
+            _ => symbol::is_readable(namespace, name, keyword),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(self.error(
+                start,
+                end,
+                Category::Syntax,
+                format!("invalid {} `{}`", kind, text),
+            ))
+        }
+    }
+
+    /// With `clojure_syntax`, finish reading the `#:ns{...}` map starting
+    /// at `start`. Keys without a namespace get `ns`, and keys in the `_`
+    /// namespace lose theirs, so `#:a{:b 1 :_/c 2}` is `{:a/b 1 :c 2}`.
+    fn namespaced_map(&mut self, start: usize) -> Result<Value, Error> {
+        let lo = self.pos();
+        let end = self.advance_while(is_symbol_tail);
+        let namespace = &self.str[lo..end];
+        if namespace.is_empty() || namespace.contains(&[':', '/'][..]) {
+            return Err(self.error(
+                start,
+                end,
+                Category::Syntax,
+                "expected a namespace after `#:`",
+            ));
+        }
+        let map = match self.read_form() {
+            Some(Ok(Value::Map(map))) => map,
+            Some(Err(err)) => return Err(err),
+            _ => {
+                return Err(self.error(
+                    start,
+                    self.pos(),
+                    Category::Syntax,
+                    format!("expected a map after `#:{}`", namespace),
+                ))
+            }
+        };
+        let qualify = |key: Value| match key {
+            Value::Keyword(ref keyword) if keyword.namespace().is_none() => {
+                Value::Keyword(Keyword::namespaced(namespace, keyword.name()))
+            }
+            Value::Keyword(ref keyword) if keyword.namespace() == Some("_") => {
+                Value::Keyword(Keyword::new(keyword.name()))
+            }
+            Value::Symbol(ref symbol) if symbol.namespace().is_none() => {
+                Value::Symbol(Symbol::namespaced(namespace, symbol.name()))
+            }
+            Value::Symbol(ref symbol) if symbol.namespace() == Some("_") => {
+                Value::Symbol(Symbol::new(symbol.name()))
+            }
+            key => key,
+        };
+        Ok(Value::Map(
+            map.into_iter()
+                .map(|(key, value)| (qualify(key), value))
+                .collect(),
+        ))
+    }
+
+    fn keyword(&self, str: &str) -> Keyword {
+        let keyword = Keyword::from(str);
+        match keyword
+            .namespace()
+            .and_then(|ns| self.options.namespaces.get(ns))
+        {
+            Some(namespace) => Keyword::namespaced(namespace, keyword.name()),
+            None => keyword,
+        }
+    }
+
+    fn map_key(&self, key: Value) -> Value {
+        let strip = |namespace: Option<&str>| {
+            namespace.is_some_and(|ns| {
+                self.options.strip_namespaces.iter().any(|strip| strip == ns)
+            })
+        };
+        let key = match key {
+            Value::Keyword(ref keyword) if strip(keyword.namespace()) => {
+                Value::Keyword(Keyword::new(keyword.name()))
+            }
+            key => key,
+        };
+        match key {
+            Value::Keyword(ref keyword) if self.options.snake_case_keys => {
+                Value::String(keyword.name().replace('-', "_"))
+            }
+            key => key,
+        }
+    }
+
+    fn symbol(&self, str: &str) -> Symbol {
+        let symbol = Symbol::from(str);
+        match symbol
+            .namespace()
+            .and_then(|ns| self.options.namespaces.get(ns))
+        {
+            Some(namespace) => Symbol::namespaced(namespace, symbol.name()),
+            None => symbol,
+        }
+    }
+
+    /// With `clojure_syntax`, finish reading a `0x1F` or `2r1010` integer
+    /// whose sign and leading digits are `start..end`. `None` if the digits
+    /// aren't followed by an `x` or `r`.
+    fn radix_integer(&mut self, start: usize, end: usize) -> Option<Result<Value, Error>> {
+        if !self.options.clojure_syntax {
+            return None;
+        }
+        let prefix = self.str[start..end].trim_start_matches('-');
+        let radix = match self.peek() {
+            Some('x') | Some('X') if prefix == "0" => 16,
+            Some('r') | Some('R') => match prefix.parse() {
+                Ok(radix @ 2..=36) => radix,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        self.chars.next();
+        let digits_start = self.pos();
+        let end = self.advance_while(|ch| ch.is_ascii_alphanumeric());
+        let (digits, number) = (&self.str[digits_start..end], &self.str[start..end]);
+        if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit(radix)) {
+            return Some(Err(self.error(
+                start,
+                end,
+                Category::Syntax,
+                format!("invalid number `{}`", number),
+            )));
+        }
+        let negative = self.str[start..].starts_with('-');
+        let value = u64::from_str_radix(digits, radix).ok().and_then(|value| {
+            if negative {
+                0i64.checked_sub_unsigned(value)
+            } else {
+                0i64.checked_add_unsigned(value)
+            }
+        });
+        Some(value.map(Value::Integer).ok_or_else(|| {
+            self.error(
+                start,
+                end,
+                Category::Data,
+                format!("integer `{}` out of range", number),
+            )
+        }))
+    }
+
+    /// Finish reading a number whose digits up to any `.` are at
+    /// `start..end`: a float if a fraction or exponent follows, else an
+    /// integer.
+    fn number(&mut self, start: usize, end: usize) -> Result<Value, Error> {
+        if let Some(result) = self.radix_integer(start, end) {
+            return result;
+        }
+        let mut end = end;
+        let mut float = false;
+        if self.peek() == Some('.') {
+            self.chars.next();
+            end = self.advance_while(|ch| ch.is_ascii_digit());
+            float = true;
+        }
+        if let Some(exponent_end) = self.exponent(end) {
+            end = exponent_end;
+            float = true;
+        }
+        if float {
+            Ok(self.float(start, end))
+        } else {
+            self.integer(start, end)
+        }
+    }
+
+    /// Read the exponent, like `e10` or `E-3`, of the number ending at
+    /// `end`, if it has one, returning the number's new end.
+    fn exponent(&mut self, end: usize) -> Option<usize> {
+        match exponent_len(&self.str[end..]) {
+            0 => None,
+            len => {
+                self.chars.seek(end + len);
+                Some(end + len)
+            }
+        }
+    }
+
+    /// Finish reading the integer at `start..end`, including an optional `N`
+    /// or `M` suffix, or a ratio's denominator.
+    fn integer(&mut self, start: usize, end: usize) -> Result<Value, Error> {
+        let digits = &self.str[start..end];
+        #[cfg(feature = "ratio")]
+        {
+            if self.peek() == Some('/')
+                && self.str[end + 1..].starts_with(|ch: char| ch.is_ascii_digit())
+            {
+                self.chars.next();
+                let end = self.advance_while(|ch| ch.is_ascii_digit());
+                let ratio = &self.str[start..end];
+                let (numerator, denominator) = ratio.split_at(digits.len());
+                return match (numerator.parse(), denominator[1..].parse()) {
+                    (Ok(numerator), Ok(denominator)) => Value::ratio(numerator, denominator)
+                        .ok_or_else(|| {
+                            self.error(
+                                start,
+                                end,
+                                Category::Data,
+                                format!("ratio `{}` has a zero denominator", ratio),
+                            )
+                        }),
+                    _ => Err(self.error(
+                        start,
+                        end,
+                        Category::Data,
+                        format!("ratio `{}` out of range", ratio),
+                    )),
+                };
+            }
+        }
+        if self.peek() == Some('M') {
+            return Ok(self.float(start, end));
+        }
+        let big = self.peek() == Some('N');
+        if big {
+            self.chars.next();
+        }
+        #[cfg(feature = "bigint")]
+        {
+            let overflow = digits.parse::<i64>().is_err();
+            let wide = digits.parse::<i128>().is_ok() || digits.parse::<u128>().is_ok();
+            if big || (overflow && (self.options.promote_overflow || wide))
+            {
+                return Ok(Value::BigInt(digits.parse::<BigInt>().unwrap()));
+            }
+        }
+        digits.parse().map(Value::Integer).map_err(|_| {
+            self.error(
+                start,
+                self.pos(),
+                Category::Data,
+                format!("integer `{}` out of range", digits),
+            )
         })
     }
 
+    /// Finish reading the float at `start..end`, including an optional `M`
+    /// suffix.
+    fn float(&mut self, start: usize, end: usize) -> Value {
+        let digits = &self.str[start..end];
+        let exact = self.peek() == Some('M');
+        if exact {
+            self.chars.next();
+        }
+        #[cfg(feature = "bigdecimal")]
+        {
+            if exact {
+                return Value::Decimal(digits.parse().unwrap());
+            }
+        }
+        Value::Float(OrderedFloat(digits.parse().unwrap()))
+    }
+
+    fn error<M: Into<String>>(
+        &self,
+        lo: usize,
+        hi: usize,
+        category: Category,
+        message: M,
+    ) -> Error {
+        let mut message = message.into();
+        if !self.path.is_empty() {
+            message = format!("{} in `{}`", message, self.path.join(" "));
+        }
+        Error::at(self.str, lo, hi, category, message)
+    }
+
+    /// Fail on a closing delimiter other than the one the collection being
+    /// read needs, naming what was `expected` instead.
+    fn mismatched_close(&self, expected: &str) -> Result<(), Error> {
+        match self.peek() {
+            Some(ch @ ')') | Some(ch @ ']') | Some(ch @ '}') => {
+                let pos = self.pos();
+                Err(self.error(
+                    pos,
+                    pos + 1,
+                    Category::Syntax,
+                    format!("unexpected `{}`, expected {}", ch, expected),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The result of `read`, or an error if there is no form to read.
+    fn read_first<T, F>(&mut self, read: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Parser<'a>) -> Option<Result<T, Error>>,
+    {
+        match read(self) {
+            Some(result) => result,
+            None => Err(self.error(
+                self.str.len(),
+                self.str.len(),
+                Category::Eof,
+                "expected a form, found EOF",
+            )),
+        }
+    }
+
+    /// An empty vector for the items of a collection, with room for as many
+    /// as the last one at this depth had.
+    fn presized(&self) -> ::std::vec::Vec<Value> {
+        let hint = self.sizes.get(self.depth).cloned().unwrap_or(0);
+        ::std::vec::Vec::with_capacity(hint.min(MAX_CAPACITY_HINT))
+    }
+
+    fn record_size(&mut self, len: usize) {
+        if self.sizes.len() <= self.depth {
+            self.sizes.resize(self.depth + 1, 0);
+        }
+        self.sizes[self.depth] = len;
+    }
+
+    /// The start of the first line after `pos` which starts with something
+    /// other than whitespace or a closing delimiter, or the end of the input.
+    fn next_line_start(&self, pos: usize) -> usize {
+        let mut lines = self.str[pos..].split_inclusive('\n');
+        let mut start = pos + lines.next().map_or(0, str::len);
+        for line in lines {
+            match line.chars().next() {
+                Some(ch) if !ch.is_whitespace() && !")]}".contains(ch) => return start,
+                _ => start += line.len(),
+            }
+        }
+        self.str.len()
+    }
+
+    fn pos(&self) -> usize {
+        self.chars.pos
+    }
+
     fn peek(&self) -> Option<char> {
         self.chars.clone().next().map(|(_, ch)| ch)
     }
 
-    fn whitespace(&mut self) {
+    fn whitespace(&mut self) -> Result<(), Error> {
         loop {
             // Skip whitespace.
-            self.advance_while(|ch| ch.is_whitespace() || ch == ',');
+            self.chars.skip_whitespace();
             // Skip comment if present.
-            if self.chars.clone().next().map_or(false, |(_, ch)| ch == ';') {
-                self.advance_while(|ch| ch != '\n');
-                self.chars.next();
+            if self.peek() == Some(';') {
+                let bytes = &self.str.as_bytes()[self.pos()..];
+                let end =
+                    memchr::memchr(b'\n', bytes).map_or(self.str.len(), |i| self.pos() + i + 1);
+                self.chars.seek(end);
+            } else if self.str[self.pos()..].starts_with("#_") {
+                // Skip a discarded form, which may itself be preceded by
+                // more discards.
+                let start = self.pos();
+                self.chars.nth(1);
+                match self.read_form() {
+                    Some(Ok(_)) => {
+                        if let Some(parent) = self.spans.as_mut().and_then(|spans| spans.last_mut())
+                        {
+                            parent.pop();
+                        }
+                    }
+                    Some(Err(err)) => return Err(err),
+                    None => {
+                        return Err(self.error(
+                            start,
+                            self.str.len(),
+                            Category::Eof,
+                            "expected a form after `#_`, found EOF",
+                        ))
+                    }
+                }
             } else {
                 // Otherwise we're done.
-                return;
+                return Ok(());
             }
         }
     }
@@ -301,30 +1570,259 @@ impl<'a> Parser<'a> {
     }
 }
 
-fn is_symbol_head(ch: char) -> bool {
-    match ch {
-        'a'...'z'
-        | 'A'...'Z'
-        | '.'
-        | '*'
-        | '+'
-        | '!'
-        | '-'
-        | '_'
-        | '?'
-        | '$'
-        | '%'
-        | '&'
-        | '='
-        | '<'
-        | '>' => true,
-        _ => false,
+/// A form and its source text, returned by `Parser::read_with_source`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceText {
+    pub value: Value,
+    pub text: String,
+}
+
+/// A form read by `Parser::read_spanned`, with its byte range in the input
+/// and the 1-based line and column it starts at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spanned {
+    pub value: Value,
+    pub span: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    /// The forms read inside this one in source order: items, alternating
+    /// map keys and values, the value of a tagged element, or the metadata
+    /// and form of a `^` form.
+    pub children: ::std::vec::Vec<Spanned>,
+    /// For a float, its text as written, like `1.50` or `1e3`, which
+    /// `ser::to_string_preserving` writes again if the float is unchanged.
+    pub literal: Option<String>,
+}
+
+/// An error found by `Parser::parse_with_recovery`, with the byte range of
+/// the input to mark and the 1-based line and column it starts at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub span: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub category: Category,
+    pub message: String,
+}
+
+impl From<Error> for Diagnostic {
+    fn from(err: Error) -> Diagnostic {
+        Diagnostic {
+            span: err.lo..err.hi,
+            line: err.line,
+            column: err.column,
+            category: err.category,
+            message: err.message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// An iterator over top-level forms, created by `Parser::into_stream`.
+///
+/// Iteration stops after the first error, since the parser cannot know where
+/// the next form starts.
+pub struct Stream<'a> {
+    parser: Parser<'a>,
+    failed: bool,
+}
+
+impl<'a> Iterator for Stream<'a> {
+    type Item = Result<(Value, Range<usize>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        if let Err(err) = self.parser.whitespace() {
+            self.failed = true;
+            return Some(Err(err));
+        }
+        let lo = self.parser.pos();
+        match self.parser.read() {
+            Some(Ok(value)) => Some(Ok((value, lo..self.parser.pos()))),
+            Some(Err(err)) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+            None => None,
+        }
+    }
+}
+
+/// The remaining top-level forms. Iteration stops after the first error,
+/// since the parser cannot know where the next form starts; see
+/// `parse_with_recovery` to carry on.
+impl<'a> Iterator for Parser<'a> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Result<Value, Error>> {
+        if self.failed {
+            return None;
+        }
+        let result = self.read();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+}
+
+pub(crate) fn is_symbol_head(ch: char) -> bool {
+    (ch as u32) < 128 && CLASSES[ch as usize] & HEAD != 0
+}
+
+/// The length of the exponent, like `e10` or `E-3`, at the start of `str`,
+/// or 0 if there isn't one.
+pub(crate) fn exponent_len(str: &str) -> usize {
+    let bytes = str.as_bytes();
+    if !matches!(bytes.first(), Some(b'e') | Some(b'E')) {
+        return 0;
+    }
+    let sign = matches!(bytes.get(1), Some(b'+') | Some(b'-')) as usize;
+    let digits = bytes[1 + sign..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        0
+    } else {
+        1 + sign + digits
+    }
+}
+
+/// The char a literal like `\\a`, `\\newline` or `\\u00e9` names, given
+/// the text after its `\\`.
+pub(crate) fn char_literal(name: &str) -> Option<char> {
+    match name {
+        "newline" => Some('\n'),
+        "return" => Some('\r'),
+        "space" => Some(' '),
+        "tab" => Some('\t'),
+        "backspace" => Some('\u{8}'),
+        "formfeed" => Some('\u{c}'),
+        "backslash" => Some('\\'),
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Some(ch),
+                (Some('u'), Some(_)) => match unicode_escape(&name[1..]) {
+                    Some((ch, 4)) if name.len() == 5 => Some(ch),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+    }
+}
+
+/// The char of a `\\uXXXX` escape, given the text after its `\\u`, and how
+/// many bytes of that text it takes. A UTF-16 high surrogate must be followed
+/// by an escaped low one, as in `\\ud83d\\ude00`.
+pub(crate) fn unicode_escape(str: &str) -> Option<(char, usize)> {
+    let hex = |start: usize| {
+        str.get(start..start + 4)
+            .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+    };
+    let code = hex(0)?;
+    if (0xD800..0xDC00).contains(&code) {
+        let low = match str.get(4..6) {
+            Some("\\u") => hex(6).filter(|low| (0xDC00..0xE000).contains(low))?,
+            _ => return None,
+        };
+        char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)).map(|ch| (ch, 10))
+    } else {
+        char::from_u32(code).map(|ch| (ch, 4))
+    }
+}
+
+pub(crate) fn is_symbol_tail(ch: char) -> bool {
+    (ch as u32) < 128 && CLASSES[ch as usize] & TAIL != 0
+}
+
+const HEAD: u8 = 1;
+const TAIL: u8 = 2;
+const SPACE: u8 = 4;
+
+/// What each ASCII char can be part of: `HEAD` for the first char of a
+/// symbol, `TAIL` for the rest, and `SPACE` for whitespace, including `,`.
+static CLASSES: [u8; 128] = classes();
+
+const fn classes() -> [u8; 128] {
+    let mut classes = [0; 128];
+    let mut ch = 0;
+    while ch < 128 {
+        classes[ch] = match ch as u8 {
+            b'a'..=b'z' | b'A'..=b'Z' => HEAD | TAIL,
+            b'.' | b'*' | b'+' | b'!' | b'-' | b'_' | b'?' | b'$' | b'%' | b'&' | b'=' | b'<'
+            | b'>' => HEAD | TAIL,
+            b'0'..=b'9' | b':' | b'#' | b'/' => TAIL,
+            b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c | b',' => SPACE,
+            _ => 0,
+        };
+        ch += 1;
+    }
+    classes
+}
+
+/// The parser's position in its input: a `CharIndices` which can also jump
+/// ahead to any char boundary, so runs found by scanning bytes are skipped at
+/// once.
+#[derive(Clone)]
+struct Cursor<'a> {
+    str: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(str: &'a str) -> Cursor<'a> {
+        Cursor { str, pos: 0 }
+    }
+
+    /// Move to `pos`, which must be a char boundary.
+    fn seek(&mut self, pos: usize) {
+        debug_assert!(self.str.is_char_boundary(pos));
+        self.pos = pos;
+    }
+
+    /// Skip whitespace and commas.
+    fn skip_whitespace(&mut self) {
+        let bytes = self.str.as_bytes();
+        loop {
+            match bytes.get(self.pos) {
+                Some(&byte) if byte < 128 => {
+                    if CLASSES[byte as usize] & SPACE == 0 {
+                        return;
+                    }
+                    self.pos += 1;
+                }
+                Some(_) => {
+                    let ch = self.str[self.pos..].chars().next().unwrap();
+                    if !ch.is_whitespace() {
+                        return;
+                    }
+                    self.pos += ch.len_utf8();
+                }
+                None => return,
+            }
+        }
     }
 }
 
-fn is_symbol_tail(ch: char) -> bool {
-    is_symbol_head(ch) || match ch {
-        '0'...'9' | ':' | '#' | '/' => true,
-        _ => false,
+impl<'a> Iterator for Cursor<'a> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        let pos = self.pos;
+        let ch = match *self.str.as_bytes().get(pos)? {
+            byte if byte < 128 => byte as char,
+            _ => self.str[pos..].chars().next().unwrap(),
+        };
+        self.pos += ch.len_utf8();
+        Some((pos, ch))
     }
 }
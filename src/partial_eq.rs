@@ -0,0 +1,104 @@
+//! Comparisons between `Value`s and Rust primitives, e.g. `value == 42`.
+//!
+//! These are strict like `==` between `Value`s: integers only equal
+//! `Integer`s (or `BigInt`s in range) and floats only equal `Float`s.
+
+use Value;
+
+fn eq_str(value: &Value, other: &str) -> bool {
+    match *value {
+        Value::String(ref string) => string == other,
+        _ => false,
+    }
+}
+
+fn eq_i64(value: &Value, other: i64) -> bool {
+    value.as_i64() == Some(other)
+}
+
+fn eq_u64(value: &Value, other: u64) -> bool {
+    value.as_u64() == Some(other)
+}
+
+fn eq_f64(value: &Value, other: f64) -> bool {
+    match *value {
+        Value::Float(float) => float.into_inner() == other,
+        _ => false,
+    }
+}
+
+fn eq_bool(value: &Value, other: bool) -> bool {
+    *value == Value::Boolean(other)
+}
+
+fn eq_char(value: &Value, other: char) -> bool {
+    *value == Value::Char(other)
+}
+
+impl PartialEq<str> for Value {
+    fn eq(&self, other: &str) -> bool {
+        eq_str(self, other)
+    }
+}
+
+impl PartialEq<&str> for Value {
+    fn eq(&self, other: &&str) -> bool {
+        eq_str(self, other)
+    }
+}
+
+impl PartialEq<Value> for str {
+    fn eq(&self, other: &Value) -> bool {
+        eq_str(other, self)
+    }
+}
+
+impl PartialEq<Value> for &str {
+    fn eq(&self, other: &Value) -> bool {
+        eq_str(other, self)
+    }
+}
+
+impl PartialEq<String> for Value {
+    fn eq(&self, other: &String) -> bool {
+        eq_str(self, other)
+    }
+}
+
+impl PartialEq<Value> for String {
+    fn eq(&self, other: &Value) -> bool {
+        eq_str(other, self)
+    }
+}
+
+macro_rules! partialeq_numeric {
+    ($($eq:ident [$($ty:ty)*])*) => {
+        $($(
+            impl PartialEq<$ty> for Value {
+                fn eq(&self, other: &$ty) -> bool {
+                    $eq(self, *other as _)
+                }
+            }
+
+            impl PartialEq<Value> for $ty {
+                fn eq(&self, other: &Value) -> bool {
+                    $eq(other, *self as _)
+                }
+            }
+
+            impl PartialEq<$ty> for &Value {
+                fn eq(&self, other: &$ty) -> bool {
+                    $eq(*self, *other as _)
+                }
+            }
+        )*)*
+    }
+}
+
+partialeq_numeric! {
+    eq_i64[i8 i16 i32 i64 isize]
+    eq_u64[u8 u16 u32 u64 usize]
+    eq_f64[f32 f64]
+    eq_bool[bool]
+    eq_char[char]
+}
@@ -0,0 +1,336 @@
+//! Serving Rust functions to babashka as a pod.
+//!
+//! babashka talks to a pod over its stdin and stdout with bencoded messages
+//! whose payloads are EDN strings. A `Pod` answers the `describe`, `invoke`
+//! and `shutdown` ops for the vars registered with it:
+//!
+//! ```no_run
+//! use edn::pod::{Error, Pod};
+//! use edn::Value;
+//!
+//! let mut pod = Pod::new();
+//! pod.var("pod.example/add", |args: &[Value]| {
+//!     let mut sum = 0;
+//!     for arg in args {
+//!         sum += arg.as_i64().ok_or_else(|| Error::new("expected integers"))?;
+//!     }
+//!     Ok(Value::Integer(sum))
+//! });
+//! let stdin = std::io::stdin();
+//! pod.run(stdin.lock(), std::io::stdout()).unwrap();
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use Value;
+
+/// A failed call, reported to babashka as an exception with `message` and
+/// `data` as its `ex-data`, or a failure of the protocol itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+impl Error {
+    pub fn new(message: &str) -> Error {
+        Error {
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error {
+        message,
+        data: None,
+    })
+}
+
+type Message = BTreeMap<Vec<u8>, Bencode>;
+
+type Handler = Box<dyn FnMut(&[Value]) -> Result<Value, Error>>;
+
+/// The vars a pod exposes, grouped by namespace.
+#[derive(Default)]
+pub struct Pod {
+    namespaces: BTreeMap<String, BTreeMap<String, Handler>>,
+}
+
+impl Pod {
+    pub fn new() -> Pod {
+        Pod::default()
+    }
+
+    /// Expose `f` as the var `name`, e.g. `pod.example/add`. `f` is called
+    /// with the arguments of each invocation.
+    ///
+    /// # Panics
+    ///
+    /// If `name` has no namespace.
+    pub fn var<F>(&mut self, name: &str, f: F) -> &mut Pod
+    where
+        F: FnMut(&[Value]) -> Result<Value, Error> + 'static,
+    {
+        let (namespace, name) = match name.find('/') {
+            Some(index) if index > 0 => (&name[..index], &name[index + 1..]),
+            _ => panic!("pod var `{}` needs a namespace", name),
+        };
+        self.namespaces
+            .entry(namespace.into())
+            .or_default()
+            .insert(name.into(), Box::new(f));
+        self
+    }
+
+    /// Answer messages from `reader` on `writer` until babashka sends
+    /// `shutdown` or closes `reader`.
+    pub fn run<R: BufRead, W: Write>(&mut self, mut reader: R, mut writer: W) -> Result<(), Error> {
+        loop {
+            let message = match Bencode::read(&mut reader)? {
+                Some(Bencode::Dict(message)) => message,
+                Some(_) => return error("expected a bencoded dictionary".into()),
+                None => return Ok(()),
+            };
+            let reply = match string(&message, "op").as_ref().map(|op| &op[..]) {
+                Some("describe") => self.describe(),
+                Some("invoke") => self.invoke(&message),
+                Some("shutdown") => return Ok(()),
+                op => failure(
+                    &message,
+                    Error::new(&format!("unknown op `{}`", op.unwrap_or_default())),
+                ),
+            };
+            reply.write(&mut writer).map_err(io_error)?;
+            writer.flush().map_err(io_error)?;
+        }
+    }
+
+    fn describe(&self) -> Bencode {
+        let namespaces = self
+            .namespaces
+            .iter()
+            .map(|(namespace, vars)| {
+                dict(vec![
+                    ("name", namespace.as_str().into()),
+                    (
+                        "vars",
+                        Bencode::List(
+                            vars.keys()
+                                .map(|name| dict(vec![("name", name.as_str().into())]))
+                                .collect(),
+                        ),
+                    ),
+                ])
+            })
+            .collect();
+        dict(vec![
+            ("format", "edn".into()),
+            ("namespaces", Bencode::List(namespaces)),
+            ("ops", dict(vec![("shutdown", dict(vec![]))])),
+        ])
+    }
+
+    fn invoke(&mut self, message: &Message) -> Bencode {
+        match self.call(message) {
+            Ok(value) => dict(vec![
+                (
+                    "id",
+                    string(message, "id").unwrap_or_default().as_str().into(),
+                ),
+                ("value", edn(&value).as_str().into()),
+                ("status", Bencode::List(vec!["done".into()])),
+            ]),
+            Err(err) => failure(message, err),
+        }
+    }
+
+    fn call(&mut self, message: &Message) -> Result<Value, Error> {
+        let var = string(message, "var").unwrap_or_default();
+        let handler = match var.find('/') {
+            Some(index) => self
+                .namespaces
+                .get_mut(&var[..index])
+                .and_then(|vars| vars.get_mut(&var[index + 1..])),
+            None => None,
+        };
+        let handler = match handler {
+            Some(handler) => handler,
+            None => return error(format!("unknown var `{}`", var)),
+        };
+        let args = match ::parser::from_str(&string(message, "args").unwrap_or_default()) {
+            Ok(Value::Vector(args)) | Ok(Value::List(args)) => args,
+            Ok(other) => return error(format!("expected arguments, found {}", other.kind())),
+            Err(err) => return error(format!("invalid arguments: {}", err)),
+        };
+        handler(&args.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// The reply for a failed message.
+fn failure(message: &Message, err: Error) -> Bencode {
+    let mut reply = vec![
+        (
+            "id",
+            string(message, "id").unwrap_or_default().as_str().into(),
+        ),
+        ("ex-message", err.message.as_str().into()),
+        ("status", Bencode::List(vec!["done".into(), "error".into()])),
+    ];
+    if let Some(ref data) = err.data {
+        reply.push(("ex-data", edn(data).as_str().into()));
+    }
+    dict(reply)
+}
+
+fn edn(value: &Value) -> String {
+    ::ser::to_string(value)
+}
+
+fn io_error(err: io::Error) -> Error {
+    Error::new(&err.to_string())
+}
+
+/// The string at `key` in a message.
+fn string(message: &Message, key: &str) -> Option<String> {
+    match message.get(key.as_bytes()) {
+        Some(Bencode::Bytes(bytes)) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        _ => None,
+    }
+}
+
+fn dict(entries: Vec<(&str, Bencode)>) -> Bencode {
+    Bencode::Dict(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.as_bytes().to_vec(), value))
+            .collect(),
+    )
+}
+
+/// A bencoded value: `i42e`, `4:spam`, `l...e` or `d...e`.
+#[derive(Clone, Debug, PartialEq)]
+enum Bencode {
+    Integer(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Bencode>),
+    Dict(BTreeMap<Vec<u8>, Bencode>),
+}
+
+impl<'a> From<&'a str> for Bencode {
+    fn from(str: &'a str) -> Bencode {
+        Bencode::Bytes(str.as_bytes().to_vec())
+    }
+}
+
+impl Bencode {
+    /// The next value, or `None` if `reader` is at its end.
+    fn read<R: BufRead>(reader: &mut R) -> Result<Option<Bencode>, Error> {
+        match peek(reader)? {
+            None => Ok(None),
+            Some(_) => Bencode::read_value(reader).map(Some),
+        }
+    }
+
+    fn read_value<R: BufRead>(reader: &mut R) -> Result<Bencode, Error> {
+        let byte = match peek(reader)? {
+            Some(byte) => byte,
+            None => return error("unexpected end of bencoded input".into()),
+        };
+        match byte {
+            b'i' => {
+                reader.consume(1);
+                let digits = read_until(reader, b'e')?;
+                match digits.parse() {
+                    Ok(integer) => Ok(Bencode::Integer(integer)),
+                    Err(_) => error(format!("invalid bencoded integer `{}`", digits)),
+                }
+            }
+            b'l' | b'd' => {
+                reader.consume(1);
+                let mut items = vec![];
+                while peek(reader)? != Some(b'e') {
+                    items.push(Bencode::read_value(reader)?);
+                }
+                reader.consume(1);
+                if byte == b'l' {
+                    return Ok(Bencode::List(items));
+                }
+                if items.len() % 2 != 0 {
+                    return error("odd number of items in a bencoded dictionary".into());
+                }
+                let mut dict = BTreeMap::new();
+                let mut items = items.into_iter();
+                while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                    match key {
+                        Bencode::Bytes(key) => dict.insert(key, value),
+                        _ => return error("bencoded dictionary keys must be strings".into()),
+                    };
+                }
+                Ok(Bencode::Dict(dict))
+            }
+            b'0'..=b'9' => {
+                let length = read_until(reader, b':')?;
+                let length = match length.parse() {
+                    Ok(length) => length,
+                    Err(_) => return error(format!("invalid bencoded length `{}`", length)),
+                };
+                let mut bytes = vec![0; length];
+                reader.read_exact(&mut bytes).map_err(io_error)?;
+                Ok(Bencode::Bytes(bytes))
+            }
+            _ => error(format!(
+                "unexpected byte `{}` in bencoded input",
+                byte as char
+            )),
+        }
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match *self {
+            Bencode::Integer(integer) => write!(writer, "i{}e", integer),
+            Bencode::Bytes(ref bytes) => {
+                write!(writer, "{}:", bytes.len())?;
+                writer.write_all(bytes)
+            }
+            Bencode::List(ref items) => {
+                writer.write_all(b"l")?;
+                for item in items {
+                    item.write(writer)?;
+                }
+                writer.write_all(b"e")
+            }
+            Bencode::Dict(ref dict) => {
+                writer.write_all(b"d")?;
+                for (key, value) in dict {
+                    Bencode::Bytes(key.clone()).write(writer)?;
+                    value.write(writer)?;
+                }
+                writer.write_all(b"e")
+            }
+        }
+    }
+}
+
+fn peek<R: BufRead>(reader: &mut R) -> Result<Option<u8>, Error> {
+    Ok(reader.fill_buf().map_err(io_error)?.first().cloned())
+}
+
+/// The text up to `end`, consuming `end` too.
+fn read_until<R: BufRead>(reader: &mut R, end: u8) -> Result<String, Error> {
+    let mut bytes = vec![];
+    reader.read_until(end, &mut bytes).map_err(io_error)?;
+    if bytes.pop() != Some(end) {
+        return error("unexpected end of bencoded input".into());
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
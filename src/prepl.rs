@@ -0,0 +1,190 @@
+//! Messages from a Clojure prepl.
+//!
+//! A prepl (`clojure.core.server/io-prepl`, usually served over a socket)
+//! reads forms and answers each with maps like
+//! `{:tag :ret :val "3" :ns "user" :ms 1 :form "(+ 1 2)"}`, plus `:out`,
+//! `:err` and `:tap` maps for printed output and `tap>` values.
+//!
+//! ```
+//! use edn::prepl::{Decoder, Tag};
+//!
+//! let output = r#"{:tag :out :val "hi\n"} {:tag :ret :val "nil" :ns "user" :ms 0 :form "(println \"hi\")"}"#;
+//! let messages: Vec<_> = Decoder::new(output.as_bytes()).collect::<Result<_, _>>().unwrap();
+//! assert_eq!(messages[0].tag, Tag::Out);
+//! assert_eq!(messages[1].ns.as_ref().unwrap(), "user");
+//! ```
+
+use std::fmt;
+use std::io;
+
+use read::IoRead;
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::Map;
+#[cfg(not(feature = "immutable"))]
+use standard::Map;
+
+/// The error returned by `Decoder`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// The kind of a message, from its `:tag`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tag {
+    /// The result of evaluating a form.
+    Ret,
+    /// Text printed to `*out*`.
+    Out,
+    /// Text printed to `*err*`.
+    Err,
+    /// A value passed to `tap>`.
+    Tap,
+}
+
+impl Tag {
+    fn name(&self) -> &'static str {
+        match *self {
+            Tag::Ret => "ret",
+            Tag::Out => "out",
+            Tag::Err => "err",
+            Tag::Tap => "tap",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub tag: Tag,
+    /// The result, output text or tapped value. io-prepl sends results as
+    /// strings printed with `pr-str`; see `Message::value`.
+    pub val: Value,
+    /// The namespace a form was evaluated in, for `:ret`.
+    pub ns: Option<String>,
+    /// How long a form took to evaluate in milliseconds, for `:ret`.
+    pub ms: Option<i64>,
+    /// The text of the evaluated form, for `:ret`.
+    pub form: Option<String>,
+    /// Whether evaluating the form threw, in which case `val` describes the
+    /// exception.
+    pub exception: bool,
+}
+
+impl Message {
+    /// Read a message from a prepl map. `:tag` and `:val` are required.
+    pub fn from_value(value: &Value) -> Option<Message> {
+        let map = match *value {
+            Value::Map(ref map) => map,
+            _ => return None,
+        };
+        let get = |key: &str| map.get(&Value::Keyword(key.into()));
+        let string = |key: &str| match get(key) {
+            None | Some(Value::Nil) => Some(None),
+            Some(Value::String(string)) => Some(Some(string.clone())),
+            Some(_) => None,
+        };
+        let tag = match get("tag").and_then(Value::as_keyword).map(|tag| tag.name()) {
+            Some("ret") => Tag::Ret,
+            Some("out") => Tag::Out,
+            Some("err") => Tag::Err,
+            Some("tap") => Tag::Tap,
+            _ => return None,
+        };
+        Some(Message {
+            tag,
+            val: get("val")?.clone(),
+            ns: string("ns")?,
+            ms: match get("ms") {
+                None | Some(Value::Nil) => None,
+                Some(&Value::Integer(ms)) => Some(ms),
+                Some(_) => return None,
+            },
+            form: string("form")?,
+            exception: match get("exception") {
+                None | Some(Value::Nil) => false,
+                Some(&Value::Boolean(exception)) => exception,
+                Some(_) => return None,
+            },
+        })
+    }
+
+    /// `val` read as EDN if it is a string, which is how io-prepl sends
+    /// results, or as is otherwise.
+    pub fn value(&self) -> Result<Value, ::parser::Error> {
+        match self.val {
+            Value::String(ref string) if self.tag == Tag::Ret => ::parser::from_str(string),
+            ref other => Ok(other.clone()),
+        }
+    }
+}
+
+impl From<Message> for Value {
+    fn from(s: Message) -> Self {
+        let mut map = Map::new();
+        let mut insert = |key: &str, value: Value| {
+            map.insert(Value::Keyword(key.into()), value);
+        };
+        insert("tag", Value::Keyword(s.tag.name().into()));
+        insert("val", s.val);
+        if let Some(ns) = s.ns {
+            insert("ns", Value::String(ns));
+        }
+        if let Some(ms) = s.ms {
+            insert("ms", Value::Integer(ms));
+        }
+        if let Some(form) = s.form {
+            insert("form", Value::String(form));
+        }
+        if s.exception {
+            insert("exception", Value::Boolean(true));
+        }
+        Value::Map(map)
+    }
+}
+
+/// Reads `Message`s from a prepl's output as they arrive.
+pub struct Decoder<R> {
+    forms: IoRead<R>,
+}
+
+impl<R: io::Read> Decoder<R> {
+    pub fn new(reader: R) -> Decoder<R> {
+        Decoder {
+            forms: IoRead::new(reader),
+        }
+    }
+}
+
+impl<R: io::Read> Iterator for Decoder<R> {
+    type Item = Result<Message, Error>;
+
+    fn next(&mut self) -> Option<Result<Message, Error>> {
+        Some(match self.forms.next()? {
+            Ok(value) => Message::from_value(&value).ok_or_else(|| Error {
+                message: format!("not a prepl message: {}", edn(&value)),
+            }),
+            Err(err) => Err(Error {
+                message: err.to_string(),
+            }),
+        })
+    }
+}
+
+/// Write `form` for a prepl to evaluate, followed by a newline, and flush.
+pub fn send<W: io::Write>(mut writer: W, form: &Value) -> io::Result<()> {
+    ::ser::to_writer(&mut writer, form)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
+fn edn(value: &Value) -> String {
+    ::ser::to_string(value)
+}
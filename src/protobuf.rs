@@ -0,0 +1,272 @@
+//! Conversions between `Value` and protobuf's `Struct` and `Value` well-known
+//! types, as generated by `prost-types`.
+//!
+//! Protobuf's values are JSON's: null, doubles, strings, booleans, lists and
+//! structs with string keys. EDN values with no equivalent are converted as
+//! set on `Options`, or are an error, and `Options::from_protobuf` undoes
+//! what it can of that.
+//!
+//! ```
+//! use edn::protobuf::{KeywordStyle, Options};
+//!
+//! let value = edn::parser::from_str("{:user/id 1 :roles #{:admin}}").unwrap();
+//! let options = Options::new().keywords(KeywordStyle::Colon);
+//! let proto = options.to_struct(&value).unwrap();
+//! assert!(proto.fields.contains_key(":user/id"));
+//! assert_eq!(
+//!     options.from_struct(&proto),
+//!     edn::parser::from_str("{:user/id 1 :roles [:admin]}").unwrap()
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use prost_types::value::Kind;
+use prost_types::{ListValue, NullValue, Struct};
+
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// The largest integer every smaller one of which is exactly a double.
+const MAX_EXACT: i64 = 1 << 53;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+/// How keywords are written, both as values and as struct keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeywordStyle {
+    /// `:user/name` is `"user/name"`, and is read back as a string.
+    Name,
+    /// `:user/name` is `":user/name"`, and strings like it are read back as
+    /// keywords.
+    Colon,
+    Error,
+}
+
+/// How tagged elements are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaggedStyle {
+    /// `#inst "..."` is just its value, `"..."`.
+    Value,
+    /// `#inst "..."` is `{"#inst": "..."}`, and structs like it are read
+    /// back as tagged elements.
+    Struct,
+    Error,
+}
+
+/// Options for converting between `Value` and protobuf values.
+///
+/// The default is as lenient as possible: keywords, symbols and chars are
+/// strings, sets are lists, tags are dropped and other struct keys are
+/// written as their EDN text.
+#[derive(Clone, Debug)]
+pub struct Options {
+    keywords: KeywordStyle,
+    sets: bool,
+    tagged: TaggedStyle,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            keywords: KeywordStyle::Name,
+            sets: true,
+            tagged: TaggedStyle::Value,
+        }
+    }
+}
+
+impl Options {
+    pub fn new() -> Options {
+        Options::default()
+    }
+
+    pub fn keywords(mut self, style: KeywordStyle) -> Options {
+        self.keywords = style;
+        self
+    }
+
+    /// Write sets as lists, or fail if `false`.
+    pub fn sets(mut self, as_lists: bool) -> Options {
+        self.sets = as_lists;
+        self
+    }
+
+    pub fn tagged(mut self, style: TaggedStyle) -> Options {
+        self.tagged = style;
+        self
+    }
+
+    pub fn to_protobuf(&self, value: &Value) -> Result<prost_types::Value, Error> {
+        let kind = match *value {
+            Value::Nil => Kind::NullValue(NullValue::NullValue as i32),
+            Value::Boolean(boolean) => Kind::BoolValue(boolean),
+            Value::String(ref string) => Kind::StringValue(string.clone()),
+            Value::Char(ch) => Kind::StringValue(ch.to_string()),
+            Value::Integer(integer) if (-MAX_EXACT..=MAX_EXACT).contains(&integer) => {
+                Kind::NumberValue(integer as f64)
+            }
+            Value::Integer(integer) => {
+                return error(format!("{} is out of range for protobuf", integer))
+            }
+            Value::Float(float) => Kind::NumberValue(float.into_inner()),
+            Value::Keyword(_) | Value::Symbol(_) => Kind::StringValue(self.key(value)?),
+            Value::List(ref items) | Value::Vector(ref items) => {
+                Kind::ListValue(self.list(&mut items.iter())?)
+            }
+            Value::Set(ref items) if self.sets => Kind::ListValue(self.list(&mut items.iter())?),
+            Value::Map(_) => Kind::StructValue(self.to_struct(value)?),
+            Value::Tagged(ref tag, ref value) => match self.tagged {
+                TaggedStyle::Value => return self.to_protobuf(value),
+                TaggedStyle::Struct => {
+                    let mut fields = BTreeMap::new();
+                    fields.insert(format!("#{}", tag), self.to_protobuf(value)?);
+                    Kind::StructValue(Struct { fields })
+                }
+                TaggedStyle::Error => return error(format!("#{} has no protobuf equivalent", tag)),
+            },
+            Value::WithMeta(ref value, _) => return self.to_protobuf(value),
+            _ => return error(format!("{} has no protobuf equivalent", value.kind())),
+        };
+        Ok(prost_types::Value { kind: Some(kind) })
+    }
+
+    /// `value`, which must be a map, as a `Struct`.
+    pub fn to_struct(&self, value: &Value) -> Result<Struct, Error> {
+        match *value {
+            Value::Map(ref map) => {
+                let mut fields = BTreeMap::new();
+                for (key, value) in map.iter() {
+                    fields.insert(self.key(key)?, self.to_protobuf(value)?);
+                }
+                Ok(Struct { fields })
+            }
+            Value::WithMeta(ref value, _) => self.to_struct(value),
+            _ => error(format!("expected a map, found {}", value.kind())),
+        }
+    }
+
+    /// Read `value` back, turning strings and structs written with the
+    /// `Colon` and `Struct` styles into keywords and tagged elements.
+    /// Numbers which are whole and exactly representable are integers.
+    pub fn from_protobuf(&self, value: &prost_types::Value) -> Value {
+        match value.kind {
+            None | Some(Kind::NullValue(_)) => Value::Nil,
+            Some(Kind::BoolValue(boolean)) => Value::Boolean(boolean),
+            Some(Kind::NumberValue(number)) => {
+                if number.fract() == 0.0 && number.abs() <= MAX_EXACT as f64 {
+                    Value::Integer(number as i64)
+                } else {
+                    Value::from(number)
+                }
+            }
+            Some(Kind::StringValue(ref string)) => self.string_or_keyword(string),
+            Some(Kind::ListValue(ref list)) => Value::Vector(
+                list.values
+                    .iter()
+                    .map(|value| self.from_protobuf(value))
+                    .collect::<Vec<_>>(),
+            ),
+            Some(Kind::StructValue(ref proto)) => self.from_struct(proto),
+        }
+    }
+
+    pub fn from_struct(&self, proto: &Struct) -> Value {
+        if self.tagged == TaggedStyle::Struct && proto.fields.len() == 1 {
+            let (key, value) = proto.fields.iter().next().expect("one field");
+            if key.starts_with('#') && key.len() > 1 {
+                return Value::Tagged(key[1..].into(), Box::new(self.from_protobuf(value)));
+            }
+        }
+        Value::Map(
+            proto
+                .fields
+                .iter()
+                .map(|(key, value)| (self.string_or_keyword(key), self.from_protobuf(value)))
+                .collect::<Map<_, _>>(),
+        )
+    }
+
+    fn list(&self, items: &mut dyn Iterator<Item = &Value>) -> Result<ListValue, Error> {
+        Ok(ListValue {
+            values: items
+                .map(|item| self.to_protobuf(item))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// `value` as a struct key. Keywords and symbols are also written as
+    /// strings with this.
+    fn key(&self, value: &Value) -> Result<String, Error> {
+        match *value {
+            Value::String(ref string) => Ok(string.clone()),
+            Value::Keyword(ref keyword) => match self.keywords {
+                KeywordStyle::Name => Ok(keyword.to_string()[1..].into()),
+                KeywordStyle::Colon => Ok(keyword.to_string()),
+                KeywordStyle::Error => error(format!("{} has no protobuf equivalent", keyword)),
+            },
+            Value::Symbol(ref symbol) => Ok(symbol.to_string()),
+            _ => Ok(::ser::to_string(value)),
+        }
+    }
+
+    fn string_or_keyword(&self, string: &str) -> Value {
+        if self.keywords == KeywordStyle::Colon && string.starts_with(':') {
+            if let Ok(Value::Keyword(keyword)) = ::parser::from_str(string) {
+                return Value::Keyword(keyword);
+            }
+        }
+        Value::String(string.into())
+    }
+}
+
+/// Converts with the default `Options`.
+impl From<prost_types::Value> for Value {
+    fn from(s: prost_types::Value) -> Self {
+        Options::new().from_protobuf(&s)
+    }
+}
+
+/// Converts with the default `Options`.
+impl From<Struct> for Value {
+    fn from(s: Struct) -> Self {
+        Options::new().from_struct(&s)
+    }
+}
+
+/// Converts with the default `Options`.
+impl TryFrom<Value> for prost_types::Value {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<prost_types::Value, Error> {
+        Options::new().to_protobuf(&value)
+    }
+}
+
+/// Converts with the default `Options`.
+impl TryFrom<Value> for Struct {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Struct, Error> {
+        Options::new().to_struct(&value)
+    }
+}
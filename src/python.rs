@@ -0,0 +1,309 @@
+//! A Python extension module, `edn`, so data teams can read EDN exports
+//! without JVM tooling.
+//!
+//! `edn.loads(str)` reads exactly one form and `edn.dumps(obj, pretty=False)`
+//! writes one. `nil` is `None`, booleans, integers, floats and strings are
+//! themselves, chars are one character strings, vectors are lists, lists are
+//! tuples, sets are frozensets and maps are dicts. Keywords, symbols and
+//! tagged elements are `edn.Keyword`, `edn.Symbol` and `edn.Tagged`.
+//! Metadata is dropped.
+//!
+//! Build the module as a `cdylib` depending on this crate with the `python`
+//! feature and `pyo3/extension-module`, e.g. with maturin.
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySet, PyString, PyTuple};
+
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Set, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Set, Vec};
+
+/// `edn.Keyword("user/id")` is `:user/id`.
+#[pyclass(name = "Keyword", module = "edn", frozen, eq, hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PyKeyword(pub Keyword);
+
+#[pymethods]
+impl PyKeyword {
+    #[new]
+    fn new(name: &str) -> PyKeyword {
+        PyKeyword(Keyword::from(name.trim_start_matches(':')))
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    #[getter]
+    fn namespace(&self) -> Option<&str> {
+        self.0.namespace()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Keyword('{}')", &self.0.to_string()[1..])
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// `edn.Symbol("clojure.core/inc")` is `clojure.core/inc`.
+#[pyclass(name = "Symbol", module = "edn", frozen, eq, hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PySymbol(pub Symbol);
+
+#[pymethods]
+impl PySymbol {
+    #[new]
+    fn new(name: &str) -> PySymbol {
+        PySymbol(Symbol::from(name))
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    #[getter]
+    fn namespace(&self) -> Option<&str> {
+        self.0.namespace()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Symbol('{}')", self.0)
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// `edn.Tagged("inst", "1985-04-12T23:20:50.52Z")` is
+/// `#inst "1985-04-12T23:20:50.52Z"`.
+#[pyclass(name = "Tagged", module = "edn", frozen)]
+pub struct PyTagged {
+    #[pyo3(get)]
+    pub tag: String,
+    #[pyo3(get)]
+    pub value: PyObject,
+}
+
+#[pymethods]
+impl PyTagged {
+    #[new]
+    fn new(tag: String, value: PyObject) -> PyTagged {
+        PyTagged { tag, value }
+    }
+
+    fn __eq__(&self, py: Python, other: &Bound<PyAny>) -> PyResult<bool> {
+        match other.downcast::<PyTagged>() {
+            Ok(other) => {
+                let other = other.get();
+                Ok(self.tag == other.tag && self.value.bind(py).eq(&other.value)?)
+            }
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "Tagged('{}', {})",
+            self.tag,
+            self.value.bind(py).repr()?
+        ))
+    }
+}
+
+/// `value` as a Python object.
+pub fn to_python<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    let items = |items: &mut dyn Iterator<Item = &Value>| {
+        items
+            .map(|item| to_python(py, item))
+            .collect::<PyResult<::std::vec::Vec<_>>>()
+    };
+    Ok(match *value {
+        Value::Nil => py.None().into_bound(py),
+        Value::Boolean(boolean) => PyBool::new(py, boolean).to_owned().into_any(),
+        Value::Integer(integer) => integer.into_pyobject(py)?.into_any(),
+        Value::Float(float) => PyFloat::new(py, float.into_inner()).into_any(),
+        Value::String(ref string) => PyString::new(py, string).into_any(),
+        Value::Char(ch) => PyString::new(py, &ch.to_string()).into_any(),
+        Value::Keyword(ref keyword) => Bound::new(py, PyKeyword(keyword.clone()))?.into_any(),
+        Value::Symbol(ref symbol) => Bound::new(py, PySymbol(symbol.clone()))?.into_any(),
+        Value::List(ref list) => PyTuple::new(py, items(&mut list.iter())?)?.into_any(),
+        Value::Vector(ref vector) => PyList::new(py, items(&mut vector.iter())?)?.into_any(),
+        Value::Set(ref set) => PyFrozenSet::new(py, items(&mut set.iter())?)?.into_any(),
+        Value::Map(ref map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map.iter() {
+                dict.set_item(to_python(py, key)?, to_python(py, value)?)?;
+            }
+            dict.into_any()
+        }
+        Value::Tagged(ref tag, ref value) => Bound::new(
+            py,
+            PyTagged {
+                tag: tag.clone(),
+                value: to_python(py, value)?.unbind(),
+            },
+        )?
+        .into_any(),
+        Value::WithMeta(ref value, _) => to_python(py, value)?,
+        #[cfg(feature = "bigint")]
+        Value::BigInt(ref integer) => py.get_type::<PyInt>().call1((integer.to_string(),))?,
+        #[cfg(feature = "ratio")]
+        Value::Ratio(numerator, denominator) => py
+            .import("fractions")?
+            .getattr("Fraction")?
+            .call1((numerator, denominator))?,
+        #[cfg(feature = "bigdecimal")]
+        Value::Decimal(ref decimal) => py
+            .import("decimal")?
+            .getattr("Decimal")?
+            .call1((decimal.to_string(),))?,
+    })
+}
+
+/// `object` as a `Value`.
+pub fn from_python(object: &Bound<PyAny>) -> PyResult<Value> {
+    let items = |object: &Bound<PyAny>| {
+        object
+            .try_iter()?
+            .map(|item| from_python(&item?))
+            .collect::<PyResult<::std::vec::Vec<_>>>()
+    };
+    if object.is_none() {
+        Ok(Value::Nil)
+    } else if let Ok(boolean) = object.downcast::<PyBool>() {
+        Ok(Value::Boolean(boolean.is_true()))
+    } else if object.is_instance_of::<PyInt>() {
+        integer(object)
+    } else if let Ok(float) = object.downcast::<PyFloat>() {
+        Ok(Value::from(float.value()))
+    } else if let Ok(string) = object.downcast::<PyString>() {
+        Ok(Value::String(string.to_str()?.into()))
+    } else if let Ok(keyword) = object.downcast::<PyKeyword>() {
+        Ok(Value::Keyword(keyword.get().0.clone()))
+    } else if let Ok(symbol) = object.downcast::<PySymbol>() {
+        Ok(Value::Symbol(symbol.get().0.clone()))
+    } else if let Ok(tagged) = object.downcast::<PyTagged>() {
+        let tagged = tagged.get();
+        Ok(Value::Tagged(
+            tagged.tag.clone(),
+            Box::new(from_python(tagged.value.bind(object.py()))?),
+        ))
+    } else if object.is_instance_of::<PyList>() {
+        Ok(Value::Vector(Vec::from(items(object)?)))
+    } else if object.is_instance_of::<PyTuple>() {
+        Ok(Value::List(Vec::from(items(object)?)))
+    } else if object.is_instance_of::<PySet>() || object.is_instance_of::<PyFrozenSet>() {
+        Ok(Value::Set(items(object)?.into_iter().collect::<Set<_>>()))
+    } else if let Ok(dict) = object.downcast::<PyDict>() {
+        let mut map = Map::new();
+        for (key, value) in dict.iter() {
+            map.insert(from_python(&key)?, from_python(&value)?);
+        }
+        Ok(Value::Map(map))
+    } else if let Some(value) = number(object)? {
+        Ok(value)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "{} has no EDN equivalent",
+            object.get_type().name()?
+        )))
+    }
+}
+
+#[cfg(not(feature = "bigint"))]
+fn integer(object: &Bound<PyAny>) -> PyResult<Value> {
+    Ok(Value::Integer(object.extract()?))
+}
+
+/// An `Integer`, or a `BigInt` if `object` doesn't fit in an `i64`.
+#[cfg(feature = "bigint")]
+fn integer(object: &Bound<PyAny>) -> PyResult<Value> {
+    match object.extract() {
+        Ok(integer) => Ok(Value::Integer(integer)),
+        Err(_) => {
+            object.str()?.to_str()?.parse().map(Value::BigInt).map_err(
+                |err: ::num_bigint::ParseBigIntError| PyValueError::new_err(err.to_string()),
+            )
+        }
+    }
+}
+
+#[cfg(any(feature = "ratio", feature = "bigdecimal"))]
+fn is_instance(object: &Bound<PyAny>, module: &str, name: &str) -> PyResult<bool> {
+    object.is_instance(&object.py().import(module)?.getattr(name)?)
+}
+
+/// A `fractions.Fraction` or `decimal.Decimal`, with the `ratio` and
+/// `bigdecimal` features.
+#[allow(unused_variables)]
+fn number(object: &Bound<PyAny>) -> PyResult<Option<Value>> {
+    #[cfg(feature = "ratio")]
+    {
+        if is_instance(object, "fractions", "Fraction")? {
+            let numerator: i64 = object.getattr("numerator")?.extract()?;
+            let denominator: u64 = object.getattr("denominator")?.extract()?;
+            return Ok(Some(if denominator == 1 {
+                Value::Integer(numerator)
+            } else {
+                Value::Ratio(numerator, denominator)
+            }));
+        }
+    }
+    #[cfg(feature = "bigdecimal")]
+    {
+        if is_instance(object, "decimal", "Decimal")? {
+            return object
+                .str()?
+                .to_str()?
+                .parse()
+                .map(|decimal| Some(Value::Decimal(decimal)))
+                .map_err(|err: ::bigdecimal::ParseBigDecimalError| {
+                    PyValueError::new_err(err.to_string())
+                });
+        }
+    }
+    Ok(None)
+}
+
+/// Read exactly one form from `str`.
+#[pyfunction]
+pub fn loads<'py>(py: Python<'py>, str: &str) -> PyResult<Bound<'py, PyAny>> {
+    let value = ::parser::from_str(str).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    to_python(py, &value)
+}
+
+/// `object` as compact EDN, or pretty printed if `pretty`.
+#[pyfunction]
+#[pyo3(signature = (object, pretty = false))]
+pub fn dumps(object: &Bound<PyAny>, pretty: bool) -> PyResult<String> {
+    let value = from_python(object)?;
+    let mut out = vec![];
+    let written = if pretty {
+        ::ser::to_writer_pretty(&mut out, &value)
+    } else {
+        ::ser::to_writer(&mut out, &value)
+    };
+    written.map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(String::from_utf8(out).expect("EDN output is valid UTF-8"))
+}
+
+/// The `edn` module.
+#[pymodule]
+pub fn edn(m: &Bound<PyModule>) -> PyResult<()> {
+    m.add_class::<PyKeyword>()?;
+    m.add_class::<PySymbol>()?;
+    m.add_class::<PyTagged>()?;
+    m.add_function(wrap_pyfunction!(python::loads, m)?)?;
+    m.add_function(wrap_pyfunction!(python::dumps, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use Value;
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+impl Value {
+    /// `numerator/denominator` in lowest terms, which is an `Integer` if the
+    /// denominator divides the numerator. `None` for a zero denominator.
+    pub fn ratio(numerator: i64, denominator: u64) -> Option<Value> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator.unsigned_abs(), denominator);
+        let denominator = denominator / divisor;
+        let numerator = if divisor as i128 > i64::MAX as i128 {
+            // Only `i64::MIN` has a factor this large.
+            -((numerator.unsigned_abs() / divisor) as i64)
+        } else {
+            numerator / divisor as i64
+        };
+        Some(if denominator == 1 {
+            Value::Integer(numerator)
+        } else {
+            Value::Ratio(numerator, denominator)
+        })
+    }
+
+    /// The numerator and denominator of a `Ratio`, or of an `Integer` over 1.
+    pub fn as_ratio(&self) -> Option<(i64, u64)> {
+        match *self {
+            Value::Integer(integer) => Some((integer, 1)),
+            Value::Ratio(numerator, denominator) => Some((numerator, denominator)),
+            _ => None,
+        }
+    }
+}
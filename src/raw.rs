@@ -0,0 +1,284 @@
+use std::fmt;
+
+use lexer::{Lexer, Span, Token};
+use parser::{self, Category, Error, DEFAULT_RECURSION_LIMIT};
+use Value;
+
+/// The text of one form, checked to be valid syntax but not parsed, e.g.
+/// for forwarding a message after looking at a few of its keys.
+///
+/// ```
+/// use edn::{RawEdn, Value};
+///
+/// let raw: RawEdn = r#"{:type :event :payload [1 2 3]}"#.parse().unwrap();
+/// let kind = raw.get(&Value::Keyword("type".into())).unwrap().unwrap();
+/// assert_eq!(kind.as_str(), ":event");
+/// assert_eq!(raw.to_string(), r#"{:type :event :payload [1 2 3]}"#);
+/// ```
+///
+/// Only syntax is checked, so e.g. a map with an odd number of forms is a
+/// `RawEdn` but an error for `parse`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RawEdn {
+    text: String,
+}
+
+impl RawEdn {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    pub fn parse(&self) -> Result<Value, Error> {
+        parser::from_str(&self.text)
+    }
+
+    /// The keys and values of a map, without parsing either.
+    pub fn entries(&self) -> Result<::std::vec::Vec<(RawEdn, RawEdn)>, Error> {
+        if !self.text.starts_with('{') {
+            return Err(Error::at(
+                &self.text,
+                0,
+                self.text.len(),
+                Category::Data,
+                "expected a map".into(),
+            ));
+        }
+        let mut scanner = Scanner::new(&self.text, 1);
+        let mut entries = vec![];
+        while let Next::Form(key) = scanner.next()? {
+            let value = match scanner.next()? {
+                Next::Form(value) => value,
+                _ => {
+                    return Err(Error::at(
+                        &self.text,
+                        0,
+                        self.text.len(),
+                        Category::Data,
+                        "odd number of items in a Map".into(),
+                    ))
+                }
+            };
+            entries.push((scanner.raw(key), scanner.raw(value)));
+        }
+        Ok(entries)
+    }
+
+    /// The value for `key` in a map, parsing only the keys.
+    pub fn get(&self, key: &Value) -> Result<Option<RawEdn>, Error> {
+        for (k, value) in self.entries()? {
+            if k.parse()? == *key {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl ::std::str::FromStr for RawEdn {
+    type Err = Error;
+
+    /// Exactly one form. Whitespace and comments around it are dropped.
+    fn from_str(str: &str) -> Result<RawEdn, Error> {
+        let mut scanner = Scanner::new(str, 0);
+        let raw = match scanner.next_raw() {
+            Some(result) => result?.0,
+            None => {
+                return Err(Error::at(
+                    str,
+                    str.len(),
+                    str.len(),
+                    Category::Eof,
+                    "expected a form, found EOF".into(),
+                ))
+            }
+        };
+        match scanner.next_raw() {
+            Some(Ok((_, span))) => Err(Error::at(
+                str,
+                span.lo,
+                span.lo,
+                Category::Syntax,
+                "trailing characters after form".into(),
+            )),
+            Some(Err(err)) => Err(err),
+            None => Ok(raw),
+        }
+    }
+}
+
+impl fmt::Display for RawEdn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+enum Next {
+    Form(Span),
+    /// A closing delimiter.
+    Close(Span),
+    End,
+}
+
+/// Finds where forms start and end without building values.
+pub(crate) struct Scanner<'a> {
+    str: &'a str,
+    tokens: Lexer<'a>,
+    depth: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub(crate) fn new(str: &'a str, pos: usize) -> Scanner<'a> {
+        Scanner {
+            str,
+            tokens: Lexer::at(str, pos),
+            depth: 0,
+        }
+    }
+
+    fn raw(&self, span: Span) -> RawEdn {
+        RawEdn {
+            text: self.str[span.lo..span.hi].into(),
+        }
+    }
+
+    fn error<T>(
+        &self,
+        lo: usize,
+        hi: usize,
+        category: Category,
+        message: String,
+    ) -> Result<T, Error> {
+        Err(Error::at(self.str, lo, hi, category, message))
+    }
+
+    fn unexpected<T>(&self, span: Span) -> Result<T, Error> {
+        self.error(
+            span.lo,
+            span.hi,
+            Category::Syntax,
+            format!("unexpected `{}`", &self.str[span.lo..span.hi]),
+        )
+    }
+
+    /// The next top-level form and its span.
+    pub(crate) fn next_raw(&mut self) -> Option<Result<(RawEdn, Span), Error>> {
+        match self.next() {
+            Ok(Next::Form(span)) => Some(Ok((self.raw(span), span))),
+            Ok(Next::Close(span)) => Some(self.unexpected(span)),
+            Ok(Next::End) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Skip whitespace, comments and discarded forms up to the next form or
+    /// closing delimiter.
+    fn next(&mut self) -> Result<Next, Error> {
+        loop {
+            let (token, span) = match self.tokens.next().transpose()? {
+                Some(next) => next,
+                None => return Ok(Next::End),
+            };
+            return Ok(match token {
+                Token::Whitespace(_) | Token::Comment(_) => continue,
+                Token::Discard => {
+                    self.required(span)?;
+                    continue;
+                }
+                Token::CloseList | Token::CloseVector | Token::CloseMap => Next::Close(span),
+                _ => Next::Form(self.form(token, span)?),
+            });
+        }
+    }
+
+    /// The span of the form after the prefix at `prefix`.
+    fn required(&mut self, prefix: Span) -> Result<Span, Error> {
+        match self.next()? {
+            Next::Form(span) => Ok(span),
+            Next::Close(span) => self.unexpected(span),
+            Next::End => self.error(
+                prefix.lo,
+                self.str.len(),
+                Category::Eof,
+                format!(
+                    "expected a form after `{}`, found EOF",
+                    &self.str[prefix.lo..prefix.hi]
+                ),
+            ),
+        }
+    }
+
+    /// The span of the form starting with `token`.
+    fn form(&mut self, token: Token<'a>, span: Span) -> Result<Span, Error> {
+        if self.depth >= DEFAULT_RECURSION_LIMIT {
+            return self.error(
+                span.lo,
+                span.lo,
+                Category::Syntax,
+                "recursion limit exceeded".into(),
+            );
+        }
+        self.depth += 1;
+        let result = self.nested(token, span);
+        self.depth -= 1;
+        result
+    }
+
+    fn nested(&mut self, token: Token<'a>, span: Span) -> Result<Span, Error> {
+        let close = match token {
+            Token::Tag(_) => {
+                let value = self.required(span)?;
+                return Ok(Span {
+                    lo: span.lo,
+                    hi: value.hi,
+                });
+            }
+            Token::Meta => {
+                self.required(span)?;
+                let value = self.required(span)?;
+                return Ok(Span {
+                    lo: span.lo,
+                    hi: value.hi,
+                });
+            }
+            Token::OpenList => ")",
+            Token::OpenVector => "]",
+            Token::OpenMap | Token::OpenSet => "}",
+            _ => return Ok(span),
+        };
+        loop {
+            match self.next()? {
+                Next::Form(_) => {}
+                Next::Close(end) if &self.str[end.lo..end.hi] == close => {
+                    return Ok(Span {
+                        lo: span.lo,
+                        hi: end.hi,
+                    })
+                }
+                Next::Close(end) => {
+                    return self.error(
+                        end.lo,
+                        end.hi,
+                        Category::Syntax,
+                        format!(
+                            "unexpected `{}`, expected `{}`",
+                            &self.str[end.lo..end.hi],
+                            close
+                        ),
+                    )
+                }
+                Next::End => {
+                    return self.error(
+                        span.lo,
+                        self.str.len(),
+                        Category::Eof,
+                        format!("unclosed `{}`", &self.str[span.lo..span.hi]),
+                    )
+                }
+            }
+        }
+    }
+}
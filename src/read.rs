@@ -0,0 +1,852 @@
+//! Parsing EDN directly from an `io::Read`, or any other `Read` source.
+//!
+//! `IoRead` buffers only as much input as the form currently being read
+//! needs, so large files and sockets can be processed form by form.
+//! `Forms` does the same for a custom `Read` source, e.g. one decrypting
+//! its input or draining a ring buffer.
+//! `PushParser` does the same for input which arrives in chunks, and, with
+//! the `futures` feature, `FormStream` for a `Stream` of chunks.
+
+use std::io;
+use std::str;
+
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+#[cfg(feature = "futures")]
+use std::future::Future;
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll};
+
+use parser::{Category, DuplicateKeyPolicy, Error, ParserBuilder};
+use Value;
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A source of bytes for `Forms`.
+///
+/// Only `position`, `peek` and `discard` are required:
+///
+/// * `position` is the number of bytes consumed so far, starting at 0.
+/// * `peek` returns the next byte without consuming it, so calling it again
+///   returns the same byte, or `None` at the end of the input. After `None`
+///   it must keep returning `None`.
+/// * `discard` consumes the byte returned by the last `peek`, advancing
+///   `position` by one. It is only called after `peek` returned a byte.
+///
+/// The bytes must be UTF-8, although sequences may be split between
+/// reads. An `io::Error` of kind `Interrupted` is retried; any other error
+/// ends the current form with an `Io` error.
+///
+/// ```
+/// use edn::read::{Forms, Read};
+/// use edn::Value;
+/// use std::io;
+///
+/// /// Input encrypted by XOR with a single byte.
+/// struct Xor<'a> {
+///     bytes: &'a [u8],
+///     key: u8,
+///     index: usize,
+/// }
+///
+/// impl<'a> Read for Xor<'a> {
+///     fn position(&self) -> usize {
+///         self.index
+///     }
+///
+///     fn peek(&mut self) -> io::Result<Option<u8>> {
+///         Ok(self.bytes.get(self.index).map(|byte| byte ^ self.key))
+///     }
+///
+///     fn discard(&mut self) {
+///         self.index += 1;
+///     }
+/// }
+///
+/// let bytes: Vec<u8> = b"[1 2] :a".iter().map(|byte| byte ^ 42).collect();
+/// let source = Xor { bytes: &bytes, key: 42, index: 0 };
+/// let values: Result<Vec<Value>, _> = Forms::new(source).collect();
+/// assert_eq!(values.unwrap().len(), 2);
+/// ```
+pub trait Read {
+    /// The number of bytes consumed so far.
+    fn position(&self) -> usize;
+
+    /// The next byte, without consuming it, or `None` at the end of the
+    /// input.
+    fn peek(&mut self) -> io::Result<Option<u8>>;
+
+    /// Consume the byte returned by the last call to `peek`.
+    fn discard(&mut self);
+
+    /// Consume and return the next byte, or `None` at the end of the input.
+    fn next(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.peek()?;
+        if byte.is_some() {
+            self.discard();
+        }
+        Ok(byte)
+    }
+
+    /// Consume up to `buf.len()` bytes into `buf`, returning how many were
+    /// read. Returns 0 only at the end of the input, or if `buf` is empty.
+    /// An error after some bytes were read is left for the next call.
+    ///
+    /// Sources which can read in bulk should override this; `position` must
+    /// still advance by the number of bytes returned.
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.next() {
+                Ok(Some(byte)) => buf[read] = byte,
+                Ok(None) => break,
+                Err(err) if read == 0 => return Err(err),
+                Err(_) => break,
+            }
+            read += 1;
+        }
+        Ok(read)
+    }
+}
+
+impl<S: Read + ?Sized> Read for &mut S {
+    fn position(&self) -> usize {
+        (**self).position()
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        (**self).peek()
+    }
+
+    fn discard(&mut self) {
+        (**self).discard()
+    }
+
+    fn next(&mut self) -> io::Result<Option<u8>> {
+        (**self).next()
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).fill(buf)
+    }
+}
+
+/// A `Read` source over a byte slice.
+pub struct SliceRead<'a> {
+    slice: &'a [u8],
+    index: usize,
+}
+
+impl<'a> SliceRead<'a> {
+    pub fn new(slice: &'a [u8]) -> SliceRead<'a> {
+        SliceRead { slice, index: 0 }
+    }
+}
+
+impl<'a> Read for SliceRead<'a> {
+    fn position(&self) -> usize {
+        self.index
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.slice.get(self.index).cloned())
+    }
+
+    fn discard(&mut self) {
+        self.index += 1;
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let rest = &self.slice[self.index..];
+        let read = rest.len().min(buf.len());
+        buf[..read].copy_from_slice(&rest[..read]);
+        self.index += read;
+        Ok(read)
+    }
+}
+
+/// A `Read` source over a string.
+pub struct StrRead<'a> {
+    slice: SliceRead<'a>,
+}
+
+impl<'a> StrRead<'a> {
+    pub fn new(str: &'a str) -> StrRead<'a> {
+        StrRead {
+            slice: SliceRead::new(str.as_bytes()),
+        }
+    }
+}
+
+impl<'a> Read for StrRead<'a> {
+    fn position(&self) -> usize {
+        self.slice.position()
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        self.slice.peek()
+    }
+
+    fn discard(&mut self) {
+        self.slice.discard()
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.slice.fill(buf)
+    }
+}
+
+/// A `Read` source over an `io::Read`, reading in bulk where it can.
+pub struct IoSource<R> {
+    reader: R,
+    peeked: Option<u8>,
+    index: usize,
+}
+
+impl<R: io::Read> IoSource<R> {
+    pub fn new(reader: R) -> IoSource<R> {
+        IoSource {
+            reader,
+            peeked: None,
+            index: 0,
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.reader.read(buf) {
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<R: io::Read> Read for IoSource<R> {
+    fn position(&self) -> usize {
+        self.index
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        if self.peeked.is_none() {
+            let mut byte = [0];
+            if self.read(&mut byte)? == 1 {
+                self.peeked = Some(byte[0]);
+            }
+        }
+        Ok(self.peeked)
+    }
+
+    fn discard(&mut self) {
+        self.peeked = None;
+        self.index += 1;
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let read = match self.peeked.take() {
+            Some(byte) => {
+                buf[0] = byte;
+                1
+            }
+            None => self.read(buf)?,
+        };
+        self.index += read;
+        Ok(read)
+    }
+}
+
+/// Reads top-level forms from a `Read` source.
+///
+/// Error offsets are relative to the start of the whole stream.
+pub struct Forms<S> {
+    source: S,
+    buffer: Buffer,
+    eof: bool,
+}
+
+impl<S: Read> Forms<S> {
+    pub fn new(source: S) -> Forms<S> {
+        Forms {
+            source,
+            buffer: Buffer::new(),
+            eof: false,
+        }
+    }
+
+    /// Handle duplicate map keys and set elements as
+    /// `ParserBuilder::duplicate_keys` does.
+    pub fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Forms<S> {
+        self.buffer.duplicate_keys = policy;
+        self
+    }
+
+    /// Read the next top-level form, or `None` at the end of the input.
+    pub fn read(&mut self) -> Option<Result<Value, Error>> {
+        loop {
+            if let Some(result) = self.buffer.next(self.eof) {
+                return result;
+            }
+            if let Err(err) = self.fill() {
+                return Some(Err(err));
+            }
+        }
+    }
+
+    /// Read more input into the buffer, doubling the amount read each time
+    /// a single form needs more so that re-parsing stays linear overall.
+    fn fill(&mut self) -> Result<(), Error> {
+        let mut bytes = vec![0; CHUNK_SIZE.max(self.buffer.text.len())];
+        let read = loop {
+            match self.source.fill(&mut bytes) {
+                Ok(read) => break read,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(self.buffer.error(Category::Io, err.to_string())),
+            }
+        };
+        if read == 0 {
+            self.eof = true;
+            return self.buffer.finish();
+        }
+        self.buffer.push(&bytes[..read])
+    }
+}
+
+impl<S: Read> Iterator for Forms<S> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Result<Value, Error>> {
+        self.read()
+    }
+}
+
+/// Reads top-level forms from an `io::Read` source.
+///
+/// Error offsets are relative to the start of the whole stream.
+pub struct IoRead<R> {
+    forms: Forms<IoSource<R>>,
+}
+
+impl<R: io::Read> IoRead<R> {
+    pub fn new(reader: R) -> IoRead<R> {
+        IoRead {
+            forms: Forms::new(IoSource::new(reader)),
+        }
+    }
+
+    /// Handle duplicate map keys and set elements as
+    /// `ParserBuilder::duplicate_keys` does.
+    pub fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> IoRead<R> {
+        self.forms = self.forms.duplicate_keys(policy);
+        self
+    }
+
+    /// Read the next top-level form, or `None` at the end of the input.
+    pub fn read(&mut self) -> Option<Result<Value, Error>> {
+        self.forms.read()
+    }
+}
+
+/// Reads top-level forms from input handed over in chunks, e.g. from a
+/// non-blocking socket.
+///
+/// ```
+/// use edn::read::PushParser;
+/// use edn::Value;
+///
+/// let mut parser = PushParser::new();
+/// assert_eq!(parser.feed(b"[1 2] (3").unwrap().len(), 1);
+/// assert_eq!(parser.feed(b")").unwrap().len(), 1);
+/// // `12` may be the start of a longer number until more input arrives.
+/// assert!(parser.feed(b" 12").unwrap().is_empty());
+/// assert_eq!(parser.finish().unwrap(), vec![Value::Integer(12)]);
+/// ```
+///
+/// Error offsets are relative to the start of the whole stream.
+pub struct PushParser {
+    buffer: Buffer,
+}
+
+impl PushParser {
+    pub fn new() -> PushParser {
+        PushParser {
+            buffer: Buffer::new(),
+        }
+    }
+
+    /// Add `bytes` to the input and read every form now known to be
+    /// complete. Chunks may split forms and UTF-8 sequences anywhere.
+    ///
+    /// Forms before a syntax error are returned first; the error is
+    /// returned by the next call, and every call after it.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<::std::vec::Vec<Value>, Error> {
+        self.buffer.push(bytes)?;
+        self.read(false)
+    }
+
+    /// Read the forms left at the end of the input. Anything incomplete is
+    /// an error.
+    pub fn finish(mut self) -> Result<::std::vec::Vec<Value>, Error> {
+        self.buffer.finish()?;
+        self.read(true)
+    }
+
+    fn read(&mut self, eof: bool) -> Result<::std::vec::Vec<Value>, Error> {
+        let mut values = vec![];
+        while let Some(Some(result)) = self.buffer.next(eof) {
+            match result {
+                Ok(value) => values.push(value),
+                Err(err) if values.is_empty() => return Err(err),
+                Err(_) => break,
+            }
+        }
+        Ok(values)
+    }
+}
+
+impl Default for PushParser {
+    fn default() -> PushParser {
+        PushParser::new()
+    }
+}
+
+/// Reads top-level forms from a `Stream` of byte chunks without blocking,
+/// e.g. an `AsyncRead` wrapped in tokio-util's `ReaderStream`.
+///
+/// Error offsets are relative to the start of the whole stream. The stream
+/// must be `Unpin`; pin it with `Box::pin` if it isn't.
+#[cfg(feature = "futures")]
+pub struct FormStream<S> {
+    stream: S,
+    buffer: Buffer,
+    eof: bool,
+}
+
+#[cfg(feature = "futures")]
+impl<S> FormStream<S> {
+    pub fn new(stream: S) -> FormStream<S> {
+        FormStream {
+            stream,
+            buffer: Buffer::new(),
+            eof: false,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<S, B> Stream for FormStream<S>
+where
+    S: Stream<Item = io::Result<B>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<Value, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(result) = this.buffer.next(this.eof) {
+                return Poll::Ready(result);
+            }
+            let result = match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Ok(bytes))) => this.buffer.push(bytes.as_ref()),
+                Poll::Ready(Some(Err(err))) => {
+                    Err(this.buffer.error(Category::Io, err.to_string()))
+                }
+                Poll::Ready(None) => {
+                    this.eof = true;
+                    this.buffer.finish()
+                }
+            };
+            if let Err(err) = result {
+                return Poll::Ready(Some(Err(err)));
+            }
+        }
+    }
+}
+
+/// Read exactly one form from a `Stream` of byte chunks. Anything but
+/// whitespace and comments after it is an error.
+#[cfg(feature = "futures")]
+pub fn from_stream<S>(stream: S) -> FromStream<S> {
+    FromStream {
+        forms: FormStream::new(stream),
+        value: None,
+    }
+}
+
+/// The future returned by `from_stream`.
+#[cfg(feature = "futures")]
+pub struct FromStream<S> {
+    forms: FormStream<S>,
+    value: Option<Value>,
+}
+
+#[cfg(feature = "futures")]
+impl<S, B> Future for FromStream<S>
+where
+    S: Stream<Item = io::Result<B>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Output = Result<Value, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            let (end, line, column) = (
+                this.forms.buffer.offset,
+                this.forms.buffer.line,
+                this.forms.buffer.column,
+            );
+            let next = match Pin::new(&mut this.forms).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(next) => next,
+            };
+            return Poll::Ready(match (next, this.value.take()) {
+                (Some(Err(err)), _) => Err(err),
+                (Some(Ok(value)), None) => {
+                    this.value = Some(value);
+                    continue;
+                }
+                (Some(Ok(_)), Some(_)) => Err(Error {
+                    lo: end,
+                    hi: end,
+                    line,
+                    column,
+                    category: Category::Syntax,
+                    message: "trailing characters after form".into(),
+                }),
+                (None, Some(value)) => Ok(value),
+                (None, None) => Err(this
+                    .forms
+                    .buffer
+                    .error(Category::Eof, "expected a form, found EOF".into())),
+            });
+        }
+    }
+}
+
+/// The end of the form at or after `pos` in `str`, or `None` if only
+/// whitespace, comments and discarded forms are left, found by matching up
+/// brackets without reading atoms or building values.
+///
+/// Only the nesting of brackets and the ends of strings are checked: a bad
+/// escape, an invalid number or a `(` closed by `]` isn't an error.
+pub(crate) fn skip_form(str: &str, pos: usize) -> Result<Option<usize>, Error> {
+    let bytes = str.as_bytes();
+    let error = |lo: usize, hi: usize, category: Category, message: &str| {
+        Error::at(str, lo, hi, category, message.into())
+    };
+    let unclosed = |lo: usize| {
+        error(lo, bytes.len(), Category::Eof, "expected closing `\"`, found EOF")
+    };
+    let mut start = pos;
+    let mut pos = pos;
+    let mut depth = 0;
+    // Forms left to skip at the top level: a `^` adds the metadata's.
+    let mut forms = 1;
+    let mut started = false;
+    loop {
+        let byte = match bytes.get(pos) {
+            Some(&byte) => byte,
+            None if !started => return Ok(None),
+            None => {
+                let message = "expected the end of a form, found EOF";
+                return Err(error(start, pos, Category::Eof, message));
+            }
+        };
+        if depth == 0 && !is_space(byte) && byte != b';' {
+            if bytes[pos..].starts_with(b"#_") {
+                let message = "expected a form after `#_`, found EOF";
+                pos = skip_form(str, pos + 2)?
+                    .ok_or_else(|| error(pos, bytes.len(), Category::Eof, message))?;
+                continue;
+            }
+            if !started {
+                start = pos;
+                started = true;
+            }
+        }
+        let done = match (byte, bytes.get(pos + 1).cloned()) {
+            (b';', _) => {
+                pos = memchr::memchr(b'\n', &bytes[pos..]).map_or(bytes.len(), |i| pos + i + 1);
+                false
+            }
+            (b'"', _) => {
+                pos = skip_string(bytes, pos + 1).ok_or_else(|| unclosed(pos))?;
+                true
+            }
+            (b'#', Some(b'"')) => {
+                pos = skip_string(bytes, pos + 2).ok_or_else(|| unclosed(pos))?;
+                true
+            }
+            (b'\\', _) => {
+                // The first char is the literal even if it's a delimiter.
+                pos += 1 + str[pos + 1..].chars().next().map_or(0, char::len_utf8);
+                pos = skip_atom(bytes, pos);
+                true
+            }
+            (b'(', _) | (b'[', _) | (b'{', _) => {
+                depth += 1;
+                pos += 1;
+                false
+            }
+            (b'#', Some(b'{')) => {
+                depth += 1;
+                pos += 2;
+                false
+            }
+            (b')', _) | (b']', _) | (b'}', _) if depth == 0 => {
+                let message = format!("unexpected `{}`", byte as char);
+                return Err(error(pos, pos + 1, Category::Syntax, &message));
+            }
+            (b')', _) | (b']', _) | (b'}', _) => {
+                depth -= 1;
+                pos += 1;
+                depth == 0
+            }
+            // `##Inf` and the like.
+            (b'#', Some(b'#')) => {
+                pos = skip_atom(bytes, pos + 2);
+                true
+            }
+            // A discard inside a collection, which is skipped along with it,
+            // and Clojure's `#'var` and `#(...)`.
+            (b'#', Some(b'_')) | (b'#', Some(b'\'')) => {
+                pos += 2;
+                false
+            }
+            (b'#', Some(b'(')) => {
+                pos += 1;
+                false
+            }
+            // A tag, which the form after it completes.
+            (b'#', _) => {
+                pos = skip_atom(bytes, pos + 1);
+                false
+            }
+            (b'^', _) => {
+                if depth == 0 {
+                    forms += 1;
+                }
+                pos += 1;
+                false
+            }
+            (b'\'', _) | (b'`', _) | (b'~', _) | (b'@', _) => {
+                pos += 1;
+                false
+            }
+            (byte, _) if is_space(byte) => {
+                pos += 1;
+                false
+            }
+            _ => {
+                pos = skip_atom(bytes, pos);
+                true
+            }
+        };
+        if done && depth == 0 {
+            forms -= 1;
+            if forms == 0 {
+                return Ok(Some(pos));
+            }
+        }
+    }
+}
+
+fn is_space(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || byte == b',' || byte == 0x0b
+}
+
+/// The end of the symbol, keyword, number or other atom at `pos`.
+fn skip_atom(bytes: &[u8], mut pos: usize) -> usize {
+    while let Some(&byte) = bytes.get(pos) {
+        if is_space(byte) || b"()[]{}\";\\".contains(&byte) {
+            break;
+        }
+        pos += 1;
+    }
+    pos
+}
+
+/// The position after the closing quote of the string whose contents start
+/// at `pos`, or `None` if it isn't closed.
+fn skip_string(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        pos += memchr::memchr2(b'"', b'\\', bytes.get(pos..)?)?;
+        if bytes[pos] == b'"' {
+            return Some(pos + 1);
+        }
+        pos += 2;
+    }
+}
+
+/// Input which has not been read as forms yet.
+struct Buffer {
+    /// Decoded input which has not been consumed yet.
+    text: String,
+    /// Trailing bytes of an incomplete UTF-8 sequence.
+    pending: ::std::vec::Vec<u8>,
+    /// Byte offset of `text` in the stream.
+    offset: usize,
+    /// Line and column of `text` in the stream.
+    line: usize,
+    column: usize,
+    duplicate_keys: DuplicateKeyPolicy,
+}
+
+impl Buffer {
+    fn new() -> Buffer {
+        Buffer {
+            text: String::new(),
+            pending: vec![],
+            offset: 0,
+            line: 1,
+            column: 1,
+            duplicate_keys: DuplicateKeyPolicy::default(),
+        }
+    }
+
+    /// Decode `bytes` onto the end of the text, keeping an incomplete UTF-8
+    /// sequence at the end for the next call.
+    fn push(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.pending.extend_from_slice(bytes);
+        let valid = match str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(ref err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => return Err(self.error(Category::Syntax, "invalid UTF-8 in input".into())),
+        };
+        let rest = self.pending.split_off(valid);
+        self.text.push_str(str::from_utf8(&self.pending).unwrap());
+        self.pending = rest;
+        Ok(())
+    }
+
+    /// Check that no incomplete UTF-8 sequence is left at the end of the
+    /// input.
+    fn finish(&self) -> Result<(), Error> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(Category::Syntax, "invalid UTF-8 at end of input".into()))
+        }
+    }
+
+    /// The next form, `Some(None)` if the input is used up, or `None` if
+    /// more input is needed to tell.
+    fn next(&mut self, eof: bool) -> Option<Option<Result<Value, Error>>> {
+        let len = self.text.len();
+        let result = ParserBuilder::new()
+            .duplicate_keys(self.duplicate_keys)
+            .build(&self.text)
+            .into_stream()
+            .next();
+        // A form or error reaching the end of the buffer may continue in
+        // input we haven't read yet, e.g. `12` followed by `34`, unless
+        // it ends with a closing delimiter.
+        let complete = match result {
+            None => false,
+            Some(Ok((_, ref range))) => {
+                range.end < len || self.text[..range.end].ends_with(&[')', ']', '}', '"'][..])
+            }
+            Some(Err(ref err)) => err.hi < len,
+        };
+        if !complete && !eof {
+            return None;
+        }
+        Some(result.map(|result| match result {
+            Ok((value, range)) => {
+                let (line, column) = advance(self.line, self.column, &self.text[..range.end]);
+                self.line = line;
+                self.column = column;
+                self.text.drain(..range.end);
+                self.offset += range.end;
+                Ok(value)
+            }
+            Err(err) => Err(Error {
+                lo: self.offset + err.lo,
+                hi: self.offset + err.hi,
+                line: self.line + err.line - 1,
+                column: if err.line == 1 {
+                    self.column + err.column - 1
+                } else {
+                    err.column
+                },
+                category: err.category,
+                message: err.message,
+            }),
+        }))
+    }
+
+    /// An error at the end of the input read so far.
+    fn error(&self, category: Category, message: String) -> Error {
+        let end = self.offset + self.text.len();
+        let (line, column) = advance(self.line, self.column, &self.text);
+        Error {
+            lo: end,
+            hi: end,
+            line,
+            column,
+            category,
+            message,
+        }
+    }
+}
+
+/// The line and column after `text`, starting from `line` and `column`.
+fn advance(mut line: usize, mut column: usize, text: &str) -> (usize, usize) {
+    for ch in text.chars() {
+        if ch == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column)
+}
+
+impl<R: io::Read> Iterator for IoRead<R> {
+    type Item = Result<Value, Error>;
+
+    fn next(&mut self) -> Option<Result<Value, Error>> {
+        self.read()
+    }
+}
+
+/// Read exactly one form from `reader`. Anything but whitespace and comments
+/// after it is an error.
+pub fn from_reader<R: io::Read>(reader: R) -> Result<Value, Error> {
+    from_source(IoSource::new(reader))
+}
+
+/// Read exactly one form from a `Read` source. Anything but whitespace and
+/// comments after it is an error.
+pub fn from_source<S: Read>(source: S) -> Result<Value, Error> {
+    let mut read = Forms::new(source);
+    let value = match read.read() {
+        Some(result) => result?,
+        None => {
+            return Err(read
+                .buffer
+                .error(Category::Eof, "expected a form, found EOF".into()))
+        }
+    };
+    let (end, line, column) = (read.buffer.offset, read.buffer.line, read.buffer.column);
+    match read.read() {
+        None => Ok(value),
+        Some(Err(err)) => Err(err),
+        Some(Ok(_)) => Err(Error {
+            lo: end,
+            hi: end,
+            line,
+            column,
+            category: Category::Syntax,
+            message: "trailing characters after form".into(),
+        }),
+    }
+}
@@ -0,0 +1,381 @@
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use bytes;
+use parser::{Error, DEFAULT_RECURSION_LIMIT};
+use Value;
+
+type Reader = Box<dyn Fn(Value) -> Result<Value, Error>>;
+type DefaultReader = Box<dyn Fn(&str, Value) -> Result<Value, Error>>;
+type SharedReader = Arc<dyn Fn(Value) -> Result<Value, Error> + Send + Sync>;
+
+/// The readers added with `install`.
+static INSTALLED: RwLock<BTreeMap<String, SharedReader>> = RwLock::new(BTreeMap::new());
+/// Whether `INSTALLED` may be non-empty, so that parsing doesn't take the
+/// lock for every tagged element when nothing was installed.
+static ANY_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// A table of tagged element readers, like Clojure's `*data-readers*`.
+///
+/// When a `Parser` created with `Parser::with_readers` reads `#tag value`, the
+/// reader registered for `tag` is called with the already parsed `value` and
+/// its result replaces the tagged element. Tags without a reader go to the
+/// default reader if one is set, and are otherwise left as `Value::Tagged`.
+///
+/// The `lo` and `hi` of an error returned by a reader are replaced with the
+/// span of the tagged element.
+#[derive(Default)]
+pub struct ReaderRegistry {
+    readers: HashMap<String, Reader>,
+    default: Option<DefaultReader>,
+}
+
+impl ReaderRegistry {
+    pub fn new() -> ReaderRegistry {
+        ReaderRegistry::default()
+    }
+
+    /// Register `reader` for `tag`, replacing any previously registered one.
+    pub fn register<F>(&mut self, tag: &str, reader: F)
+    where
+        F: Fn(Value) -> Result<Value, Error> + 'static,
+    {
+        self.readers.insert(tag.into(), Box::new(reader));
+    }
+
+    /// Set the reader used for tags that have no registered reader. It is
+    /// called with the tag and the value.
+    pub fn set_default<F>(&mut self, reader: F)
+    where
+        F: Fn(&str, Value) -> Result<Value, Error> + 'static,
+    {
+        self.default = Some(Box::new(reader));
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.readers.contains_key(tag)
+    }
+
+    /// Apply the reader for `tag` to `value`.
+    pub fn read(&self, tag: &str, value: Value) -> Result<Value, Error> {
+        match (self.readers.get(tag), &self.default) {
+            (Some(reader), _) => reader(value),
+            (None, Some(default)) => default(tag, value),
+            (None, None) => Ok(Value::Tagged(tag.into(), Box::new(value))),
+        }
+    }
+
+    /// Apply the readers to every tagged element in an already built
+    /// `value`, innermost first as the parser does, e.g. for values which
+    /// came from somewhere other than a `Parser`. A default reader of
+    /// `|_, value| Ok(value)` strips all tags.
+    pub fn apply(&self, value: Value) -> Result<Value, Error> {
+        Ok(match value {
+            Value::Tagged(tag, value) => {
+                let value = self.apply(*value)?;
+                self.read(&tag, value)?
+            }
+            Value::List(items) => Value::List(self.apply_all(items)?),
+            Value::Vector(items) => Value::Vector(self.apply_all(items)?),
+            Value::Set(items) => Value::Set(self.apply_all(items)?),
+            Value::Map(map) => Value::Map(
+                map.into_iter()
+                    .map(|(key, value)| Ok((self.apply(key)?, self.apply(value)?)))
+                    .collect::<Result<_, Error>>()?,
+            ),
+            Value::WithMeta(value, meta) => Value::WithMeta(Box::new(self.apply(*value)?), meta),
+            other => other,
+        })
+    }
+
+    fn apply_all<I, C>(&self, items: I) -> Result<C, Error>
+    where
+        I: IntoIterator<Item = Value>,
+        C: ::std::iter::FromIterator<Value>,
+    {
+        items.into_iter().map(|item| self.apply(item)).collect()
+    }
+}
+
+/// Register `reader` for `tag` for the whole program, replacing any reader
+/// installed for it before, e.g. from a library's initialization so that
+/// its tags are read wherever the application parses EDN.
+///
+/// Installed readers apply to every parser without a `ReaderRegistry` of
+/// its own, including `parser::from_str` and `read::IoRead`. A parser given
+/// a registry uses only that; start it from `default_readers` to include
+/// them.
+///
+/// ```
+/// use edn::readers;
+/// use edn::Value;
+///
+/// readers::install("my.app/cents", |value| match value {
+///     Value::Integer(cents) => Ok(Value::from(cents as f64 / 100.0)),
+///     _ => Err(edn::parser::Error::data("expected an integer")),
+/// });
+/// let value = edn::parser::from_str("#my.app/cents 150").unwrap();
+/// assert_eq!(value, Value::from(1.5));
+/// ```
+pub fn install<F>(tag: &str, reader: F)
+where
+    F: Fn(Value) -> Result<Value, Error> + Send + Sync + 'static,
+{
+    let mut installed = INSTALLED.write().unwrap_or_else(|err| err.into_inner());
+    installed.insert(tag.into(), Arc::new(reader));
+    ANY_INSTALLED.store(true, Ordering::Release);
+}
+
+/// A registry with readers for `#inst` with the `chrono` feature and `#uuid`
+/// with the `uuid` feature, and every reader added with `install`, which
+/// take precedence.
+pub fn default_readers() -> ReaderRegistry {
+    let mut registry = ReaderRegistry::new();
+    #[cfg(feature = "chrono")]
+    registry.register("inst", inst);
+    #[cfg(feature = "uuid")]
+    registry.register("uuid", uuid);
+    let installed = INSTALLED.read().unwrap_or_else(|err| err.into_inner());
+    for (tag, reader) in installed.iter() {
+        let reader = reader.clone();
+        registry.register(tag, move |value| reader(value));
+    }
+    registry
+}
+
+/// Apply the reader installed for `tag` to `value`, or leave it tagged.
+pub(crate) fn read_installed(tag: &str, value: Value) -> Result<Value, Error> {
+    if ANY_INSTALLED.load(Ordering::Acquire) {
+        // Clone the reader so the lock isn't held while it runs, in case it
+        // parses or installs readers itself.
+        let reader = INSTALLED
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(tag)
+            .cloned();
+        if let Some(reader) = reader {
+            return reader(value);
+        }
+    }
+    Ok(Value::Tagged(tag.into(), Box::new(value)))
+}
+
+/// Apply `readers` to every tagged element in `value` in place, innermost
+/// first, for data which was parsed without them.
+///
+/// Unlike `ReaderRegistry::apply`, a reader may translate one tag into
+/// another, e.g. `#old/date` into `#inst`: when it returns an element with a
+/// different tag, that tag's reader is applied in turn. A reader returning
+/// an element with its own tag keeps it. Fails if the readers translate
+/// tags in a cycle, or if `value` is nested more than
+/// `DEFAULT_RECURSION_LIMIT` deep, the parser's own limit; `value` is then
+/// left partly resolved, possibly with items missing.
+///
+/// ```
+/// use edn::parser::from_str;
+/// use edn::readers::{resolve_tags, ReaderRegistry};
+/// use edn::Value;
+///
+/// let mut readers = ReaderRegistry::new();
+/// readers.register("old/date", |value| Ok(Value::Tagged("date".into(), Box::new(value))));
+/// readers.register("date", |value| Ok(value));
+/// let mut value = from_str("[#old/date \"2020-01-01\" #other 1]").unwrap();
+/// resolve_tags(&mut value, &readers).unwrap();
+/// assert_eq!(value, from_str("[\"2020-01-01\" #other 1]").unwrap());
+/// ```
+pub fn resolve_tags(value: &mut Value, readers: &ReaderRegistry) -> Result<(), Error> {
+    Resolver { readers, depth: 0 }.resolve(value)
+}
+
+struct Resolver<'a> {
+    readers: &'a ReaderRegistry,
+    depth: usize,
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve(&mut self, value: &mut Value) -> Result<(), Error> {
+        if self.depth >= DEFAULT_RECURSION_LIMIT {
+            return Err(Error::data("recursion limit exceeded"));
+        }
+        self.depth += 1;
+        let result = self.resolve_inner(value);
+        self.depth -= 1;
+        result
+    }
+
+    fn resolve_inner(&mut self, value: &mut Value) -> Result<(), Error> {
+        match *value {
+            Value::Tagged(..) => {
+                let mut resolved = mem::replace(value, Value::Nil);
+                let mut chain = vec![];
+                while let Value::Tagged(tag, mut inner) = resolved {
+                    if chain.contains(&tag) {
+                        chain.push(tag);
+                        return Err(Error::data(format!(
+                            "tag readers form a cycle: #{}",
+                            chain.join(" -> #")
+                        )));
+                    }
+                    if chain.is_empty() {
+                        self.resolve(&mut inner)?;
+                    }
+                    resolved = self.readers.read(&tag, *inner)?;
+                    let keeps_tag = match resolved {
+                        Value::Tagged(ref next, _) => *next == tag,
+                        _ => true,
+                    };
+                    chain.push(tag);
+                    if keeps_tag {
+                        break;
+                    }
+                }
+                *value = resolved;
+            }
+            Value::List(ref mut items) | Value::Vector(ref mut items) => {
+                for item in items.iter_mut() {
+                    self.resolve(item)?;
+                }
+            }
+            Value::Set(ref mut items) => {
+                let mut resolved = mem::take(items).into_iter().collect::<::std::vec::Vec<_>>();
+                for item in &mut resolved {
+                    self.resolve(item)?;
+                }
+                *items = resolved.into_iter().collect();
+            }
+            Value::Map(ref mut map) => {
+                let mut entries = mem::take(map).into_iter().collect::<::std::vec::Vec<_>>();
+                for &mut (ref mut key, ref mut value) in &mut entries {
+                    self.resolve(key)?;
+                    self.resolve(value)?;
+                }
+                *map = entries.into_iter().collect();
+            }
+            Value::WithMeta(ref mut value, _) => self.resolve(value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+type ContextReader<C> = Box<dyn Fn(&mut C, Value) -> Result<Value, Error> + Send + Sync>;
+
+/// Tagged element readers which are also given a context, e.g. a table of
+/// ids to resolve `#ref` elements against, for a `Parser` created with
+/// `Parser::with_context`.
+///
+/// ```
+/// use edn::parser::Parser;
+/// use edn::readers::ContextReaders;
+/// use edn::Value;
+///
+/// let mut readers = ContextReaders::new();
+/// readers.register("ref", |seen: &mut Vec<Value>, value| {
+///     seen.push(value.clone());
+///     Ok(Value::Keyword("resolved".into()))
+/// });
+/// let mut seen = vec![];
+/// let value = Parser::with_context("[#ref 1 #ref 2]", &readers, &mut seen).read();
+/// assert_eq!(value.unwrap().unwrap(), edn::parser::from_str("[:resolved :resolved]").unwrap());
+/// assert_eq!(seen, vec![Value::Integer(1), Value::Integer(2)]);
+/// ```
+///
+/// Readers must be `Send` and `Sync`, so one `ContextReaders` can serve
+/// parsers on many threads, each with its own context.
+pub struct ContextReaders<C> {
+    readers: HashMap<String, ContextReader<C>>,
+}
+
+impl<C> Default for ContextReaders<C> {
+    fn default() -> ContextReaders<C> {
+        ContextReaders {
+            readers: HashMap::new(),
+        }
+    }
+}
+
+impl<C> ContextReaders<C> {
+    pub fn new() -> ContextReaders<C> {
+        ContextReaders::default()
+    }
+
+    /// Register `reader` for `tag`, replacing any previously registered one.
+    pub fn register<F>(&mut self, tag: &str, reader: F)
+    where
+        F: Fn(&mut C, Value) -> Result<Value, Error> + Send + Sync + 'static,
+    {
+        self.readers.insert(tag.into(), Box::new(reader));
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.readers.contains_key(tag)
+    }
+
+    /// Apply the reader for `tag` to `value` with `context`, or leave it as
+    /// `Value::Tagged` if there is none.
+    pub fn read(&self, context: &mut C, tag: &str, value: Value) -> Result<Value, Error> {
+        match self.readers.get(tag) {
+            Some(reader) => reader(context, value),
+            None => Ok(Value::Tagged(tag.into(), Box::new(value))),
+        }
+    }
+}
+
+/// `ContextReaders` for a `Parser` to call without knowing the type of
+/// their context.
+pub(crate) trait AnyReaders {
+    fn contains(&self, tag: &str) -> bool;
+
+    fn read_any(&self, context: &mut dyn Any, tag: &str, value: Value) -> Result<Value, Error>;
+}
+
+impl<C: 'static> AnyReaders for ContextReaders<C> {
+    fn contains(&self, tag: &str) -> bool {
+        ContextReaders::contains(self, tag)
+    }
+
+    fn read_any(&self, context: &mut dyn Any, tag: &str, value: Value) -> Result<Value, Error> {
+        let context = context
+            .downcast_mut()
+            .expect("the parser keeps the context with its readers");
+        self.read(context, tag, value)
+    }
+}
+
+/// A reader for `#edn.rs/base64 "..."` which checks that the string is
+/// base64 and normalizes it to the padded form. The bytes are read with
+/// `Value::as_bytes`.
+pub fn base64(value: Value) -> Result<Value, Error> {
+    let tagged = Value::Tagged(bytes::TAG.into(), Box::new(value));
+    match tagged.as_bytes() {
+        Some(bytes) => Ok(bytes::encode(&bytes)),
+        None => Err(Error::data("expected a base64 string for `#edn.rs/base64`")),
+    }
+}
+
+/// A reader for `#inst "..."` which checks that the string is an RFC 3339
+/// timestamp and normalizes it to UTC.
+#[cfg(feature = "chrono")]
+pub fn inst(value: Value) -> Result<Value, Error> {
+    let tagged = Value::Tagged("inst".into(), Box::new(value));
+    match tagged.as_inst() {
+        Some(datetime) => Ok(Value::from(datetime)),
+        None => Err(Error::data(
+            "expected an RFC 3339 timestamp string for `#inst`",
+        )),
+    }
+}
+
+/// A reader for `#uuid "..."` which checks that the string is a UUID and
+/// normalizes it to the lowercase hyphenated form.
+#[cfg(feature = "uuid")]
+pub fn uuid(value: Value) -> Result<Value, Error> {
+    let tagged = Value::Tagged("uuid".into(), Box::new(value));
+    match tagged.as_uuid() {
+        Some(uuid) => Ok(Value::from(uuid)),
+        None => Err(Error::data("expected a UUID string for `#uuid`")),
+    }
+}
@@ -0,0 +1,260 @@
+//! Converting a collection of similarly shaped maps to an Arrow
+//! `RecordBatch` and back, e.g. to query EDN datasets with DataFusion or
+//! Polars.
+//!
+//! Each keyword key is a column named after it, without the colon, and each
+//! map is a row. Column types are inferred as in `avro::Schema::infer`:
+//! booleans, `Int64` for integers, `Float64` for floats or a mix of integers
+//! and floats, and `Utf8` for strings. Missing keys and `nil` are nulls. Any
+//! other column holds the EDN text of its values, and is marked so
+//! `from_record_batch` reads them back.
+//!
+//! ```
+//! let rows = edn::parser::from_str("[{:id 1 :tags [:a]} {:id 2 :score 1.5}]").unwrap();
+//! let batch = edn::record_batch::to_record_batch(&rows).unwrap();
+//! assert_eq!(batch.num_rows(), 2);
+//! assert_eq!(batch.num_columns(), 3);
+//! assert_eq!(edn::record_batch::from_record_batch(&batch).unwrap(), rows);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, Float64Array, Int64Array, NullArray, StringArray,
+};
+use arrow::datatypes::{
+    DataType, Field, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, Schema,
+    UInt16Type, UInt32Type, UInt8Type,
+};
+use arrow::error::ArrowError;
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+
+use avro::{self, Kind};
+use {Keyword, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// The field metadata key marking a column of EDN text.
+pub const ENCODING: &str = "edn.encoding";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<ArrowError> for Error {
+    fn from(err: ArrowError) -> Error {
+        Error {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<avro::Error> for Error {
+    fn from(err: avro::Error) -> Error {
+        Error {
+            message: err.message,
+        }
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+struct Column<'a> {
+    key: &'a Keyword,
+    /// `None` while every value seen is `nil`.
+    kind: Option<Kind>,
+}
+
+/// `rows`, a list or vector of maps with keyword keys, as a `RecordBatch`
+/// with a column for every key, in order of first appearance. Under
+/// `immutable`, whose maps are unordered, each row's keys are taken in
+/// sorted order.
+pub fn to_record_batch(rows: &Value) -> Result<RecordBatch, Error> {
+    let rows = avro::rows(rows)?;
+    let mut columns: ::std::vec::Vec<Column> = vec![];
+    for row in &rows {
+        let mut entries: ::std::vec::Vec<(&Value, &Value)> = row.iter().collect();
+        if cfg!(feature = "immutable") {
+            entries.sort_unstable_by_key(|entry| entry.0);
+        }
+        for (key, value) in entries {
+            let key = match *key {
+                Value::Keyword(ref keyword) => keyword,
+                _ => return error(format!("expected keyword keys, found {}", key.kind())),
+            };
+            let index = match columns.iter().position(|column| column.key == key) {
+                Some(index) => index,
+                None => {
+                    columns.push(Column { key, kind: None });
+                    columns.len() - 1
+                }
+            };
+            let column = &mut columns[index];
+            if let Some(kind) = Kind::of(value) {
+                column.kind = Some(column.kind.map_or(kind, |other| other.merge(kind)));
+            }
+        }
+    }
+
+    let mut fields = vec![];
+    let mut arrays: ::std::vec::Vec<ArrayRef> = vec![];
+    for column in &columns {
+        let values = || {
+            rows.iter()
+                .map(|row| row.get(&Value::Keyword(column.key.clone())))
+        };
+        let nullable = values().any(|value| value.is_none_or(Value::is_nil));
+        let edn =
+            values().any(|value| value.is_some_and(|value| !value.is_nil() && !value.is_string()));
+        let name = &column.key.to_string()[1..];
+        let (field, array): (Field, ArrayRef) = match column.kind {
+            None => (
+                Field::new(name, DataType::Null, true),
+                Arc::new(NullArray::new(rows.len())),
+            ),
+            Some(Kind::Boolean) => (
+                Field::new(name, DataType::Boolean, nullable),
+                Arc::new(
+                    values()
+                        .map(|value| value.and_then(Value::as_bool))
+                        .collect::<BooleanArray>(),
+                ),
+            ),
+            Some(Kind::Long) => (
+                Field::new(name, DataType::Int64, nullable),
+                Arc::new(
+                    values()
+                        .map(|value| value.and_then(Value::as_i64))
+                        .collect::<Int64Array>(),
+                ),
+            ),
+            Some(Kind::Double) => (
+                Field::new(name, DataType::Float64, nullable),
+                Arc::new(
+                    values()
+                        .map(|value| value.and_then(Value::as_f64))
+                        .collect::<Float64Array>(),
+                ),
+            ),
+            Some(Kind::String) if !edn => (
+                Field::new(name, DataType::Utf8, nullable),
+                Arc::new(
+                    values()
+                        .map(|value| value.and_then(Value::as_str))
+                        .collect::<StringArray>(),
+                ),
+            ),
+            Some(Kind::String) => {
+                let mut metadata = HashMap::new();
+                metadata.insert(ENCODING.to_string(), "edn".to_string());
+                let texts = values()
+                    .map(|value| match value {
+                        None | Some(&Value::Nil) => Ok(None),
+                        Some(value) => {
+                            let mut text = vec![];
+                            ::ser::to_writer(&mut text, value)?;
+                            Ok(Some(
+                                String::from_utf8(text).expect("EDN output is valid UTF-8"),
+                            ))
+                        }
+                    })
+                    .collect::<Result<::std::vec::Vec<_>, Error>>()?;
+                (
+                    Field::new(name, DataType::Utf8, nullable).with_metadata(metadata),
+                    Arc::new(StringArray::from(texts)),
+                )
+            }
+        };
+        fields.push(field);
+        arrays.push(array);
+    }
+    Ok(RecordBatch::try_new_with_options(
+        Arc::new(Schema::new(fields)),
+        arrays,
+        &RecordBatchOptions::new().with_row_count(Some(rows.len())),
+    )?)
+}
+
+/// The rows of `batch` as a vector of maps, with a keyword key for each
+/// column. Nulls are left out, so a `nil` written by `to_record_batch` comes
+/// back as a missing key.
+pub fn from_record_batch(batch: &RecordBatch) -> Result<Value, Error> {
+    let mut rows = vec![Map::new(); batch.num_rows()];
+    let schema = batch.schema();
+    for (field, array) in schema.fields().iter().zip(batch.columns()) {
+        let key = Value::Keyword(Keyword::from(&field.name()[..]));
+        let edn = field.metadata().get(ENCODING).map(|encoding| &encoding[..]) == Some("edn");
+        for (i, row) in rows.iter_mut().enumerate() {
+            if array.is_null(i) {
+                continue;
+            }
+            let value = match *array.data_type() {
+                DataType::Null => continue,
+                DataType::Boolean => Value::Boolean(array.as_boolean().value(i)),
+                DataType::Int8 => Value::Integer(array.as_primitive::<Int8Type>().value(i).into()),
+                DataType::Int16 => {
+                    Value::Integer(array.as_primitive::<Int16Type>().value(i).into())
+                }
+                DataType::Int32 => {
+                    Value::Integer(array.as_primitive::<Int32Type>().value(i).into())
+                }
+                DataType::Int64 => Value::Integer(array.as_primitive::<Int64Type>().value(i)),
+                DataType::UInt8 => {
+                    Value::Integer(array.as_primitive::<UInt8Type>().value(i).into())
+                }
+                DataType::UInt16 => {
+                    Value::Integer(array.as_primitive::<UInt16Type>().value(i).into())
+                }
+                DataType::UInt32 => {
+                    Value::Integer(array.as_primitive::<UInt32Type>().value(i).into())
+                }
+                DataType::Float32 => {
+                    Value::from(f64::from(array.as_primitive::<Float32Type>().value(i)))
+                }
+                DataType::Float64 => Value::from(array.as_primitive::<Float64Type>().value(i)),
+                DataType::Utf8 if edn => {
+                    match ::parser::from_str(array.as_string::<i32>().value(i)) {
+                        Ok(value) => value,
+                        Err(err) => return error(format!("in column `{}`: {}", field.name(), err)),
+                    }
+                }
+                DataType::Utf8 => Value::String(array.as_string::<i32>().value(i).into()),
+                DataType::LargeUtf8 => Value::String(array.as_string::<i64>().value(i).into()),
+                ref other => {
+                    return error(format!(
+                        "column `{}` has the unsupported type {}",
+                        field.name(),
+                        other
+                    ))
+                }
+            };
+            row.insert(key.clone(), value);
+        }
+    }
+    Ok(Value::Vector(
+        rows.into_iter().map(Value::Map).collect::<Vec<_>>(),
+    ))
+}
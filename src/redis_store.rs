@@ -0,0 +1,113 @@
+//! Storing values in Redis as EDN text, a common lightweight cache for
+//! Clojure services.
+//!
+//! `Value` implements redis-rs's `ToRedisArgs`, writing its compact EDN, and
+//! `FromRedisValue`, reading string replies as EDN. `set` and `get` do the
+//! same for any type convertible to a `Value` and readable with
+//! `keys::FromValue`.
+
+use std::str;
+
+use redis::{
+    self, ConnectionLike, ErrorKind, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs,
+};
+
+use keys::FromValue;
+use parser;
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Set, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Set, Vec};
+
+fn type_error(detail: String) -> redis::RedisError {
+    redis::RedisError::from((
+        ErrorKind::TypeError,
+        "Response was of incompatible type",
+        detail,
+    ))
+}
+
+/// The compact EDN, as a single argument.
+impl ToRedisArgs for Value {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(&::ser::to_vec(self));
+    }
+}
+
+/// Strings are read as EDN text. Integers, doubles, booleans and `nil` are
+/// themselves, and arrays, sets and maps are vectors, sets and maps of their
+/// items read the same way.
+impl FromRedisValue for Value {
+    fn from_redis_value(value: &redis::Value) -> RedisResult<Value> {
+        let text = match *value {
+            redis::Value::Nil => return Ok(Value::Nil),
+            redis::Value::Int(integer) => return Ok(Value::Integer(integer)),
+            redis::Value::Double(double) => return Ok(Value::from(double)),
+            redis::Value::Boolean(boolean) => return Ok(Value::Boolean(boolean)),
+            redis::Value::Array(ref items) => {
+                return Ok(Value::Vector(
+                    items
+                        .iter()
+                        .map(Value::from_redis_value)
+                        .collect::<RedisResult<Vec<_>>>()?,
+                ))
+            }
+            redis::Value::Set(ref items) => {
+                return Ok(Value::Set(
+                    items
+                        .iter()
+                        .map(Value::from_redis_value)
+                        .collect::<RedisResult<Set<_>>>()?,
+                ))
+            }
+            redis::Value::Map(ref entries) => {
+                return Ok(Value::Map(
+                    entries
+                        .iter()
+                        .map(|(key, value)| {
+                            Ok((
+                                Value::from_redis_value(key)?,
+                                Value::from_redis_value(value)?,
+                            ))
+                        })
+                        .collect::<RedisResult<Map<_, _>>>()?,
+                ))
+            }
+            redis::Value::BulkString(ref bytes) => str::from_utf8(bytes)
+                .map_err(|_| type_error("expected EDN, found invalid UTF-8".into()))?,
+            redis::Value::SimpleString(ref string) => &string[..],
+            redis::Value::VerbatimString { ref text, .. } => &text[..],
+            ref other => return Err(type_error(format!("expected EDN, found {:?}", other))),
+        };
+        parser::from_str(text).map_err(|err| type_error(err.to_string()))
+    }
+}
+
+/// Store `value` at `key` as its EDN text.
+pub fn set<C, T>(con: &mut C, key: &str, value: T) -> RedisResult<()>
+where
+    C: ConnectionLike,
+    T: Into<Value>,
+{
+    redis::cmd("SET").arg(key).arg(value.into()).query(con)
+}
+
+/// Read the EDN text at `key` as a `T`, or `None` if `key` doesn't exist.
+pub fn get<C, T>(con: &mut C, key: &str) -> RedisResult<Option<T>>
+where
+    C: ConnectionLike,
+    T: FromValue,
+{
+    let value: Option<Value> = redis::cmd("GET").arg(key).query(con)?;
+    match value {
+        Some(value) => T::from_value(&value)
+            .map(Some)
+            .map_err(|err| type_error(err.to_string())),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,189 @@
+//! Ring request and response maps.
+//!
+//! Ring represents HTTP requests and responses as maps with keyword keys,
+//! e.g. `{:request-method :get :uri "/" :headers {"host" "example.com"}}`.
+//! The types here convert to and from that shape.
+
+use std::collections::BTreeMap;
+
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::Map;
+#[cfg(not(feature = "immutable"))]
+use standard::Map;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Request {
+    pub server_port: u16,
+    pub server_name: String,
+    pub remote_addr: String,
+    pub uri: String,
+    pub query_string: Option<String>,
+    /// `"http"` or `"https"`, emitted as a keyword.
+    pub scheme: String,
+    /// The lowercase method name, e.g. `"get"`, emitted as a keyword.
+    pub request_method: String,
+    pub protocol: String,
+    /// Header names are lowercased, as Ring requires.
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+}
+
+fn headers_to_value(headers: BTreeMap<String, String>) -> Value {
+    Value::Map(
+        headers
+            .into_iter()
+            .map(|(name, value)| (Value::String(name.to_lowercase()), Value::String(value)))
+            .collect(),
+    )
+}
+
+fn headers_from_value(value: Option<&Value>) -> Option<BTreeMap<String, String>> {
+    let mut headers = BTreeMap::new();
+    match value {
+        None | Some(&Value::Nil) => {}
+        Some(Value::Map(map)) => {
+            for (name, value) in map.iter() {
+                match (name, value) {
+                    (Value::String(name), Value::String(value)) => {
+                        headers.insert(name.to_lowercase(), value.clone());
+                    }
+                    _ => return None,
+                }
+            }
+        }
+        Some(_) => return None,
+    }
+    Some(headers)
+}
+
+fn optional_string(value: Option<String>) -> Value {
+    value.map(Value::String).unwrap_or(Value::Nil)
+}
+
+struct Entries<'a>(&'a Map<Value, Value>);
+
+impl<'a> Entries<'a> {
+    fn get(&self, key: &str) -> Option<&'a Value> {
+        self.0.get(&Value::Keyword(key.into()))
+    }
+
+    fn string(&self, key: &str) -> Option<String> {
+        match self.get(key) {
+            Some(Value::String(string)) => Some(string.clone()),
+            _ => None,
+        }
+    }
+
+    fn keyword(&self, key: &str) -> Option<String> {
+        match self.get(key) {
+            Some(Value::Keyword(keyword)) if keyword.namespace().is_none() => {
+                Some(keyword.name().into())
+            }
+            _ => None,
+        }
+    }
+
+    fn optional_string(&self, key: &str) -> Option<Option<String>> {
+        match self.get(key) {
+            None | Some(Value::Nil) => Some(None),
+            Some(Value::String(string)) => Some(Some(string.clone())),
+            Some(_) => None,
+        }
+    }
+
+    fn u16(&self, key: &str) -> Option<u16> {
+        match self.get(key) {
+            Some(&Value::Integer(i)) if i >= 0 && i <= i64::from(u16::MAX) => Some(i as u16),
+            _ => None,
+        }
+    }
+}
+
+impl Request {
+    /// Read a request from a Ring request map. `:uri`, `:request-method`
+    /// and `:scheme` are required; other keys default when missing.
+    pub fn from_value(value: &Value) -> Option<Request> {
+        let entries = match *value {
+            Value::Map(ref map) => Entries(map),
+            _ => return None,
+        };
+        Some(Request {
+            server_port: entries.u16("server-port").unwrap_or(0),
+            server_name: entries.string("server-name").unwrap_or_default(),
+            remote_addr: entries.string("remote-addr").unwrap_or_default(),
+            uri: entries.string("uri")?,
+            query_string: entries.optional_string("query-string")?,
+            scheme: entries.keyword("scheme")?,
+            request_method: entries.keyword("request-method")?,
+            protocol: entries.string("protocol").unwrap_or_default(),
+            headers: headers_from_value(entries.get("headers"))?,
+            body: entries.optional_string("body")?,
+        })
+    }
+}
+
+impl Response {
+    /// Read a response from a Ring response map. `:status` is required.
+    pub fn from_value(value: &Value) -> Option<Response> {
+        let entries = match *value {
+            Value::Map(ref map) => Entries(map),
+            _ => return None,
+        };
+        Some(Response {
+            status: entries.u16("status")?,
+            headers: headers_from_value(entries.get("headers"))?,
+            body: entries.optional_string("body")?,
+        })
+    }
+}
+
+impl From<Request> for Value {
+    fn from(s: Request) -> Self {
+        let entries = vec![
+            ("server-port", Value::Integer(i64::from(s.server_port))),
+            ("server-name", Value::String(s.server_name)),
+            ("remote-addr", Value::String(s.remote_addr)),
+            ("uri", Value::String(s.uri)),
+            ("query-string", optional_string(s.query_string)),
+            ("scheme", Value::Keyword(s.scheme.into())),
+            (
+                "request-method",
+                Value::Keyword(s.request_method.to_lowercase().into()),
+            ),
+            ("protocol", Value::String(s.protocol)),
+            ("headers", headers_to_value(s.headers)),
+            ("body", optional_string(s.body)),
+        ];
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (Value::Keyword(key.into()), value))
+                .collect(),
+        )
+    }
+}
+
+impl From<Response> for Value {
+    fn from(s: Response) -> Self {
+        let entries = vec![
+            ("status", Value::Integer(i64::from(s.status))),
+            ("headers", headers_to_value(s.headers)),
+            ("body", optional_string(s.body)),
+        ];
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (Value::Keyword(key.into()), value))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,365 @@
+//! Checking that a value has the shape a program expects, e.g. a config
+//! before it is read into structs, like a small `clojure.spec` or Malli.
+//!
+//! ```
+//! use edn::parser;
+//! use edn::schema::{Keys, Schema};
+//!
+//! let schema = Schema::from(
+//!     Keys::new()
+//!         .required("host", Schema::String)
+//!         .optional("port", Schema::predicate("a port", |port| {
+//!             port.as_u64().is_some_and(|port| port > 0 && port < 65536)
+//!         }))
+//!         .optional("tags", Schema::Vector(Box::new(Schema::Keyword))),
+//! );
+//! let config = parser::from_str("{:port 80000 :tags [:a \"b\"]}").unwrap();
+//! let violations: Vec<_> = schema
+//!     .validate(&config)
+//!     .iter()
+//!     .map(|violation| violation.to_string())
+//!     .collect();
+//! assert_eq!(
+//!     violations,
+//!     vec![
+//!         "[]: missing key :host",
+//!         "[:port]: expected a port, found 80000",
+//!         "[:tags 1]: expected a keyword, found a string",
+//!     ]
+//! );
+//! ```
+//!
+//! Paths are as in `diff`: map keys, list and vector indices, set elements,
+//! and tags as symbols. Metadata is ignored.
+
+use std::fmt;
+use std::sync::Arc;
+
+use ser;
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::Vec;
+#[cfg(not(feature = "immutable"))]
+use standard::Vec;
+
+/// What a value should look like.
+#[derive(Clone, Debug)]
+pub enum Schema {
+    Any,
+    Nil,
+    Boolean,
+    String,
+    Char,
+    Symbol,
+    Keyword,
+    /// An integer, including big integers.
+    Integer,
+    Float,
+    /// Any kind of number.
+    Number,
+    /// One of these values.
+    OneOf(::std::vec::Vec<Value>),
+    /// A list of these.
+    List(Box<Schema>),
+    /// A vector of these.
+    Vector(Box<Schema>),
+    /// A set of these.
+    Set(Box<Schema>),
+    /// A list, vector or set of these.
+    Coll(Box<Schema>),
+    /// A map with keys and values of these.
+    MapOf(Box<Schema>, Box<Schema>),
+    /// A map with these entries.
+    Keys(Keys),
+    /// An element with this tag, and a value of this.
+    Tagged(String, Box<Schema>),
+    /// `nil`, or this.
+    Nilable(Box<Schema>),
+    /// All of these.
+    And(::std::vec::Vec<Schema>),
+    /// Any of these.
+    Or(::std::vec::Vec<Schema>),
+    Predicate(Predicate),
+}
+
+/// The entries a map must or may have. Other entries are allowed unless the
+/// map is `closed`.
+#[derive(Clone, Debug, Default)]
+pub struct Keys {
+    entries: ::std::vec::Vec<(Value, bool, Schema)>,
+    closed: bool,
+}
+
+/// A test for values no other schema describes, e.g. a port number.
+#[derive(Clone)]
+pub struct Predicate {
+    description: String,
+    test: Arc<dyn Fn(&Value) -> bool + Send + Sync>,
+}
+
+impl fmt::Debug for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Predicate({:?})", self.description)
+    }
+}
+
+/// A place where a value doesn't match its schema.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub path: ::std::vec::Vec<Value>,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = Value::Vector(Vec::from(self.path.clone()));
+        write!(f, "{}: {}", ser::to_string(&path), self.message)
+    }
+}
+
+fn keyword(name: &str) -> Value {
+    Value::Keyword(Keyword::from(name.strip_prefix(':').unwrap_or(name)))
+}
+
+impl Keys {
+    pub fn new() -> Keys {
+        Keys::default()
+    }
+
+    /// The keyword `name`, with or without its leading `:`, must be a key,
+    /// with a value matching `schema`.
+    pub fn required(self, name: &str, schema: Schema) -> Keys {
+        self.required_key(keyword(name), schema)
+    }
+
+    /// If the keyword `name` is a key, its value must match `schema`.
+    pub fn optional(self, name: &str, schema: Schema) -> Keys {
+        self.optional_key(keyword(name), schema)
+    }
+
+    pub fn required_key(mut self, key: Value, schema: Schema) -> Keys {
+        self.entries.push((key, true, schema));
+        self
+    }
+
+    pub fn optional_key(mut self, key: Value, schema: Schema) -> Keys {
+        self.entries.push((key, false, schema));
+        self
+    }
+
+    /// Whether keys other than those given are violations.
+    pub fn closed(mut self, closed: bool) -> Keys {
+        self.closed = closed;
+        self
+    }
+}
+
+impl From<Keys> for Schema {
+    fn from(keys: Keys) -> Schema {
+        Schema::Keys(keys)
+    }
+}
+
+impl Schema {
+    /// A value `test` returns `true` for. `description` is used in
+    /// violations, e.g. `"a port"` gives `expected a port, found 80000`.
+    pub fn predicate<F>(description: &str, test: F) -> Schema
+    where
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        Schema::Predicate(Predicate {
+            description: description.into(),
+            test: Arc::new(test),
+        })
+    }
+
+    /// Every place `value` doesn't match this schema, in the order they are
+    /// found, taking map entries and set items in sorted order so it's the
+    /// same in every build. Empty if it matches.
+    pub fn validate(&self, value: &Value) -> ::std::vec::Vec<Violation> {
+        let mut violations = vec![];
+        self.check(value, &mut vec![], &mut violations);
+        violations
+    }
+
+    pub fn is_valid(&self, value: &Value) -> bool {
+        self.validate(value).is_empty()
+    }
+
+    /// What this schema matches, for violations.
+    fn describe(&self) -> String {
+        let join = |schemas: &[Schema], separator: &str| {
+            schemas
+                .iter()
+                .map(Schema::describe)
+                .collect::<::std::vec::Vec<_>>()
+                .join(separator)
+        };
+        match *self {
+            Schema::Any => "anything".into(),
+            Schema::Nil => "nil".into(),
+            Schema::Boolean => "a boolean".into(),
+            Schema::String => "a string".into(),
+            Schema::Char => "a char".into(),
+            Schema::Symbol => "a symbol".into(),
+            Schema::Keyword => "a keyword".into(),
+            Schema::Integer => "an integer".into(),
+            Schema::Float => "a float".into(),
+            Schema::Number => "a number".into(),
+            Schema::OneOf(ref values) => {
+                let values: ::std::vec::Vec<_> = values.iter().map(ser::to_string).collect();
+                format!("one of {}", values.join(", "))
+            }
+            Schema::List(_) => "a list".into(),
+            Schema::Vector(_) => "a vector".into(),
+            Schema::Set(_) => "a set".into(),
+            Schema::Coll(_) => "a list, vector or set".into(),
+            Schema::MapOf(..) | Schema::Keys(_) => "a map".into(),
+            Schema::Tagged(ref tag, _) => format!("#{}", tag),
+            Schema::Nilable(ref schema) => format!("{} or nil", schema.describe()),
+            Schema::And(ref schemas) => join(schemas, " and "),
+            Schema::Or(ref schemas) => join(schemas, " or "),
+            Schema::Predicate(ref predicate) => predicate.description.clone(),
+        }
+    }
+
+    fn check(
+        &self,
+        value: &Value,
+        path: &mut ::std::vec::Vec<Value>,
+        violations: &mut ::std::vec::Vec<Violation>,
+    ) {
+        let violation = |path: &[Value], message: String| Violation {
+            path: path.to_vec(),
+            message,
+        };
+        let mismatch = |path: &[Value], found: String| {
+            violation(
+                path,
+                format!("expected {}, found {}", self.describe(), found),
+            )
+        };
+        let value = match *value {
+            Value::WithMeta(ref value, _) => value,
+            ref value => value,
+        };
+        let matches = match (self, value) {
+            (Schema::Any, _)
+            | (Schema::Nil, Value::Nil)
+            | (Schema::Boolean, Value::Boolean(_))
+            | (Schema::String, Value::String(_))
+            | (Schema::Char, Value::Char(_))
+            | (Schema::Symbol, Value::Symbol(_))
+            | (Schema::Keyword, Value::Keyword(_))
+            | (Schema::Integer, Value::Integer(_))
+            | (Schema::Float, Value::Float(_))
+            | (Schema::Nilable(_), Value::Nil) => true,
+            #[cfg(feature = "bigint")]
+            (Schema::Integer, Value::BigInt(_)) => true,
+            (Schema::Number, value) => value.is_number(),
+            (Schema::OneOf(values), value) => {
+                if !values.contains(value) {
+                    violations.push(mismatch(path, ser::to_string(value)));
+                }
+                return;
+            }
+            (Schema::List(schema), Value::List(items))
+            | (Schema::Vector(schema), Value::Vector(items))
+            | (Schema::Coll(schema), Value::List(items))
+            | (Schema::Coll(schema), Value::Vector(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    path.push(Value::Integer(i as i64));
+                    schema.check(item, path, violations);
+                    path.pop();
+                }
+                true
+            }
+            (Schema::Set(schema), Value::Set(items))
+            | (Schema::Coll(schema), Value::Set(items)) => {
+                let mut items: ::std::vec::Vec<&Value> = items.iter().collect();
+                items.sort_unstable();
+                for item in items {
+                    path.push(item.clone());
+                    schema.check(item, path, violations);
+                    path.pop();
+                }
+                true
+            }
+            (Schema::MapOf(keys, values), Value::Map(map)) => {
+                let mut entries: ::std::vec::Vec<(&Value, &Value)> = map.iter().collect();
+                entries.sort_unstable_by_key(|entry| entry.0);
+                for (key, value) in entries {
+                    path.push(key.clone());
+                    keys.check(key, path, violations);
+                    values.check(value, path, violations);
+                    path.pop();
+                }
+                true
+            }
+            (Schema::Keys(keys), Value::Map(map)) => {
+                for (key, required, schema) in &keys.entries {
+                    match map.get(key) {
+                        Some(value) => {
+                            path.push(key.clone());
+                            schema.check(value, path, violations);
+                            path.pop();
+                        }
+                        None if *required => violations.push(violation(
+                            path,
+                            format!("missing key {}", ser::to_string(key)),
+                        )),
+                        None => {}
+                    }
+                }
+                if keys.closed {
+                    let mut unexpected: ::std::vec::Vec<&Value> = map.keys().collect();
+                    unexpected.sort_unstable();
+                    for key in unexpected {
+                        if !keys.entries.iter().any(|entry| entry.0 == *key) {
+                            path.push(key.clone());
+                            violations.push(violation(path, "unexpected key".into()));
+                            path.pop();
+                        }
+                    }
+                }
+                true
+            }
+            (Schema::Tagged(tag, schema), Value::Tagged(found, value)) => {
+                if tag != found {
+                    violations.push(mismatch(path, format!("#{}", found)));
+                    return;
+                }
+                path.push(Value::Symbol(Symbol::from(&tag[..])));
+                schema.check(value, path, violations);
+                path.pop();
+                true
+            }
+            (Schema::Nilable(schema), value) => {
+                schema.check(value, path, violations);
+                true
+            }
+            (Schema::And(schemas), value) => {
+                for schema in schemas {
+                    schema.check(value, path, violations);
+                }
+                true
+            }
+            (Schema::Or(schemas), value) => schemas.iter().any(|schema| {
+                let mut scratch = vec![];
+                schema.check(value, &mut path.clone(), &mut scratch);
+                scratch.is_empty()
+            }),
+            (Schema::Predicate(predicate), value) => {
+                if !(predicate.test)(value) {
+                    violations.push(mismatch(path, ser::to_string(value)));
+                }
+                return;
+            }
+            _ => false,
+        };
+        if !matches {
+            violations.push(mismatch(path, value.kind().into()));
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Passing values to and from Rhai scripts, so applications can let users
+//! transform EDN documents at runtime.
+//!
+//! `nil`, booleans, integers, floats, chars and strings are their Rhai
+//! equivalents. Lists, vectors and sets are arrays, and come back as
+//! vectors. Maps are object maps: Rhai keys are strings, so keyword keys
+//! lose their colon, other keys are written as their EDN text, and every key
+//! comes back as a keyword. Keywords and symbols are custom types, and any
+//! other value, such as a tagged element, is passed through as a `Value`
+//! which scripts can hold but not look inside. Metadata is dropped.
+//!
+//! ```
+//! extern crate edn;
+//! extern crate rhai;
+//!
+//! # fn main() {
+//! let mut engine = rhai::Engine::new();
+//! edn::scripting::register(&mut engine);
+//! let mut scope = rhai::Scope::new();
+//! let doc = edn::parser::from_str("{:name \"ada\" :roles [:user]}").unwrap();
+//! scope.push("doc", edn::scripting::to_dynamic(&doc));
+//! let doc = engine
+//!     .eval_with_scope::<rhai::Dynamic>(
+//!         &mut scope,
+//!         r#"doc.name = doc.name.to_upper(); doc.roles.push(keyword("admin")); doc"#,
+//!     )
+//!     .unwrap();
+//! assert_eq!(
+//!     edn::scripting::from_dynamic(&doc).unwrap(),
+//!     edn::parser::from_str("{:name \"ADA\" :roles [:user :admin]}").unwrap()
+//! );
+//! # }
+//! ```
+
+use std::fmt;
+
+use rhai::{Array, Dynamic, Engine};
+
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+/// Register the `Keyword`, `Symbol` and `Value` types with `engine`, with
+/// `keyword("user/id")` and `symbol("inc")` to make them, `==`, `to_string`
+/// and `name` and `namespace` properties for keywords and symbols.
+pub fn register(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Keyword>("Keyword")
+        .register_fn("keyword", |name: &str| Keyword::from(name))
+        .register_fn("==", |a: Keyword, b: Keyword| a == b)
+        .register_fn("!=", |a: Keyword, b: Keyword| a != b)
+        .register_fn("to_string", |keyword: &mut Keyword| keyword.to_string())
+        .register_get("name", |keyword: &mut Keyword| keyword.name().to_string())
+        .register_get("namespace", |keyword: &mut Keyword| {
+            keyword
+                .namespace()
+                .map_or(Dynamic::UNIT, |namespace| namespace.to_string().into())
+        });
+    engine
+        .register_type_with_name::<Symbol>("Symbol")
+        .register_fn("symbol", |name: &str| Symbol::from(name))
+        .register_fn("==", |a: Symbol, b: Symbol| a == b)
+        .register_fn("!=", |a: Symbol, b: Symbol| a != b)
+        .register_fn("to_string", |symbol: &mut Symbol| symbol.to_string())
+        .register_get("name", |symbol: &mut Symbol| symbol.name().to_string())
+        .register_get("namespace", |symbol: &mut Symbol| {
+            symbol
+                .namespace()
+                .map_or(Dynamic::UNIT, |namespace| namespace.to_string().into())
+        });
+    engine
+        .register_type_with_name::<Value>("Value")
+        .register_fn("==", |a: Value, b: Value| a == b)
+        .register_fn("!=", |a: Value, b: Value| a != b)
+        .register_fn("to_string", |value: &mut Value| ::ser::to_string(value));
+}
+
+/// `value` as a Rhai value.
+pub fn to_dynamic(value: &Value) -> Dynamic {
+    match *value {
+        Value::Nil => Dynamic::UNIT,
+        Value::Boolean(boolean) => boolean.into(),
+        Value::Integer(integer) => integer.into(),
+        Value::Float(float) => float.into_inner().into(),
+        Value::Char(ch) => ch.into(),
+        Value::String(ref string) => string.clone().into(),
+        Value::Keyword(ref keyword) => Dynamic::from(keyword.clone()),
+        Value::Symbol(ref symbol) => Dynamic::from(symbol.clone()),
+        Value::List(ref items) | Value::Vector(ref items) => {
+            items.iter().map(to_dynamic).collect::<Array>().into()
+        }
+        Value::Set(ref items) => items.iter().map(to_dynamic).collect::<Array>().into(),
+        Value::Map(ref map) => {
+            let mut object = rhai::Map::new();
+            for (key, value) in map.iter() {
+                let key = match *key {
+                    Value::Keyword(ref keyword) => keyword.to_string()[1..].to_string(),
+                    Value::String(ref string) => string.clone(),
+                    ref key => ::ser::to_string(key),
+                };
+                object.insert(key.into(), to_dynamic(value));
+            }
+            object.into()
+        }
+        Value::WithMeta(ref value, _) => to_dynamic(value),
+        ref other => Dynamic::from(other.clone()),
+    }
+}
+
+/// `dynamic`, as returned by a script, as a `Value`.
+pub fn from_dynamic(dynamic: &Dynamic) -> Result<Value, Error> {
+    if dynamic.is_unit() {
+        return Ok(Value::Nil);
+    }
+    if let Ok(boolean) = dynamic.as_bool() {
+        return Ok(Value::Boolean(boolean));
+    }
+    if let Ok(integer) = dynamic.as_int() {
+        return Ok(Value::Integer(integer));
+    }
+    if let Ok(float) = dynamic.as_float() {
+        return Ok(Value::from(float));
+    }
+    if let Ok(ch) = dynamic.as_char() {
+        return Ok(Value::Char(ch));
+    }
+    if dynamic.is_string() {
+        return Ok(Value::String(dynamic.to_string()));
+    }
+    if dynamic.is_array() {
+        let items = dynamic.read_lock::<Array>().expect("an array");
+        return Ok(Value::Vector(
+            items
+                .iter()
+                .map(from_dynamic)
+                .collect::<Result<Vec<_>, _>>()?,
+        ));
+    }
+    if dynamic.is_map() {
+        let object = dynamic.read_lock::<rhai::Map>().expect("a map");
+        let mut map = Map::new();
+        for (key, value) in object.iter() {
+            map.insert(
+                Value::Keyword(Keyword::from(&key[..])),
+                from_dynamic(value)?,
+            );
+        }
+        return Ok(Value::Map(map));
+    }
+    if let Some(keyword) = dynamic.clone().try_cast::<Keyword>() {
+        return Ok(Value::Keyword(keyword));
+    }
+    if let Some(symbol) = dynamic.clone().try_cast::<Symbol>() {
+        return Ok(Value::Symbol(symbol));
+    }
+    if let Some(value) = dynamic.clone().try_cast::<Value>() {
+        return Ok(value);
+    }
+    error(format!("a {} has no EDN equivalent", dynamic.type_name()))
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeSet;
+use std::iter;
+use std::ops::Range;
+
+use keys::FromValue;
+use {TypeError, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+fn expected(expected: &'static str, found: &Value) -> TypeError {
+    TypeError {
+        expected,
+        found: found.kind(),
+    }
+}
+
+impl Value {
+    /// Add `item` the way Clojure's `conj` does: to the front of a list, the
+    /// end of a vector, or into a set. Maps take a `[key value]` vector.
+    pub fn conj(&self, item: Value) -> Result<Value, TypeError> {
+        match *self {
+            Value::List(ref items) => Ok(Value::List(
+                iter::once(item).chain(items.iter().cloned()).collect(),
+            )),
+            Value::Vector(ref items) => Ok(Value::Vector(
+                items.iter().cloned().chain(iter::once(item)).collect(),
+            )),
+            Value::Set(ref items) => Ok(Value::Set(
+                items.iter().cloned().chain(iter::once(item)).collect(),
+            )),
+            Value::Map(ref map) => {
+                let (key, value) = match item {
+                    Value::Vector(ref entry) if entry.len() == 2 => {
+                        let mut entry = entry.iter().cloned();
+                        (entry.next().unwrap(), entry.next().unwrap())
+                    }
+                    ref other => return Err(expected("a [key value] vector", other)),
+                };
+                let mut map = map.clone();
+                map.insert(key, value);
+                Ok(Value::Map(map))
+            }
+            ref other => Err(expected("a collection", other)),
+        }
+    }
+
+    /// The items of `self` followed by those of `other`, as the same kind of
+    /// sequence as `self`. Both must be lists or vectors.
+    pub fn concat(&self, other: &Value) -> Result<Value, TypeError> {
+        let tail = match *other {
+            Value::List(ref items) | Value::Vector(ref items) => items,
+            ref other => return Err(expected("a list or vector", other)),
+        };
+        match *self {
+            Value::List(ref items) => Ok(Value::List(
+                items.iter().chain(tail.iter()).cloned().collect(),
+            )),
+            Value::Vector(ref items) => Ok(Value::Vector(
+                items.iter().chain(tail.iter()).cloned().collect(),
+            )),
+            ref other => Err(expected("a list or vector", other)),
+        }
+    }
+
+    /// The items in `range` of a list or vector. The range is clamped to the
+    /// length of the sequence.
+    pub fn slice(&self, range: Range<usize>) -> Result<Value, TypeError> {
+        let slice = |items: &Vec<Value>| -> Vec<Value> {
+            items
+                .iter()
+                .skip(range.start)
+                .take(range.end.saturating_sub(range.start))
+                .cloned()
+                .collect()
+        };
+        match *self {
+            Value::List(ref items) => Ok(Value::List(slice(items))),
+            Value::Vector(ref items) => Ok(Value::Vector(slice(items))),
+            ref other => Err(expected("a list or vector", other)),
+        }
+    }
+
+    /// The items of a list or vector without duplicates, keeping the first
+    /// occurrence of each.
+    pub fn distinct(&self) -> Result<Value, TypeError> {
+        let distinct = |items: &Vec<Value>| -> Vec<Value> {
+            let mut seen = BTreeSet::new();
+            items
+                .iter()
+                .filter(|item| seen.insert(*item))
+                .cloned()
+                .collect()
+        };
+        match *self {
+            Value::List(ref items) => Ok(Value::List(distinct(items))),
+            Value::Vector(ref items) => Ok(Value::Vector(distinct(items))),
+            ref other => Err(expected("a list or vector", other)),
+        }
+    }
+
+    /// A map from each item of a list, vector or set to the number of times
+    /// it occurs.
+    pub fn frequencies(&self) -> Result<Value, TypeError> {
+        let mut counts = Map::new();
+        let items: Box<dyn Iterator<Item = &Value>> = match *self {
+            Value::List(ref items) | Value::Vector(ref items) => Box::new(items.iter()),
+            Value::Set(ref items) => Box::new(items.iter()),
+            ref other => return Err(expected("a list, vector or set", other)),
+        };
+        for item in items {
+            let count = match counts.get(item) {
+                Some(&Value::Integer(count)) => count + 1,
+                _ => 1,
+            };
+            counts.insert(item.clone(), Value::Integer(count));
+        }
+        Ok(Value::Map(counts))
+    }
+
+    /// Apply `f` to each item of a list, vector or set, keeping the kind of
+    /// collection.
+    pub fn map_items<F: FnMut(&Value) -> Value>(&self, mut f: F) -> Result<Value, TypeError> {
+        match *self {
+            Value::List(ref items) => Ok(Value::List(items.iter().map(&mut f).collect())),
+            Value::Vector(ref items) => Ok(Value::Vector(items.iter().map(&mut f).collect())),
+            Value::Set(ref items) => Ok(Value::Set(items.iter().map(&mut f).collect())),
+            ref other => Err(expected("a list, vector or set", other)),
+        }
+    }
+
+    /// Apply `f` to each value of a map.
+    pub fn map_values<F: FnMut(&Value) -> Value>(&self, mut f: F) -> Result<Value, TypeError> {
+        match *self {
+            Value::Map(ref map) => Ok(Value::Map(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), f(value)))
+                    .collect(),
+            )),
+            ref other => Err(expected("a map", other)),
+        }
+    }
+
+    /// Apply `f` to each key of a map. If two keys map to the same key, the
+    /// entry which comes last in the map wins.
+    pub fn map_keys<F: FnMut(&Value) -> Value>(&self, mut f: F) -> Result<Value, TypeError> {
+        match *self {
+            Value::Map(ref map) => Ok(Value::Map(
+                map.iter()
+                    .map(|(key, value)| (f(key), value.clone()))
+                    .collect(),
+            )),
+            ref other => Err(expected("a map", other)),
+        }
+    }
+
+    /// The entries of a map for which `predicate` returns `true`.
+    pub fn filter_entries<F>(&self, mut predicate: F) -> Result<Value, TypeError>
+    where
+        F: FnMut(&Value, &Value) -> bool,
+    {
+        match *self {
+            Value::Map(ref map) => Ok(Value::Map(
+                map.iter()
+                    .filter(|&(key, value)| predicate(key, value))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            )),
+            ref other => Err(expected("a map", other)),
+        }
+    }
+}
+
+/// Collect an iterator straight into a `Value` of a chosen kind.
+///
+/// ```
+/// use edn::{CollectValue, Value};
+///
+/// let form = vec![Value::Symbol("inc".into()), Value::Integer(1)]
+///     .into_iter()
+///     .collect_list();
+/// assert!(form.is_list());
+/// ```
+pub trait CollectValue: Iterator + Sized {
+    /// Collect into a `List`.
+    fn collect_list(self) -> Value
+    where
+        Value: From<Self::Item>,
+    {
+        Value::list(self)
+    }
+
+    /// Collect into a `Vector`.
+    fn collect_vector(self) -> Value
+    where
+        Value: From<Self::Item>,
+    {
+        Value::vector(self)
+    }
+
+    /// Collect into a `Set`.
+    fn collect_set(self) -> Value
+    where
+        Value: From<Self::Item>,
+    {
+        Value::set(self)
+    }
+}
+
+impl<I: Iterator> CollectValue for I {}
+
+/// A sequence written as an EDN list, `(1 2 3)`, where a `Vec` is written as
+/// a vector, `[1 2 3]`, e.g. for code as data.
+///
+/// Reading a `List` with `FromValue` requires a list, whereas a `Vec` is read
+/// from either a list or a vector.
+///
+/// ```
+/// use edn::keys::FromValue;
+/// use edn::{List, Value};
+///
+/// let form = Value::from(List(vec![Value::Symbol("inc".into()), Value::Integer(1)]));
+/// assert_eq!(edn::to_string(&form), "(inc 1)");
+/// let parse = |str| edn::parser::from_str(str).unwrap();
+/// assert_eq!(List::<i64>::from_value(&parse("(1 2)")), Ok(List(vec![1, 2])));
+/// assert!(List::<i64>::from_value(&parse("[1 2]")).is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct List<T>(pub ::std::vec::Vec<T>);
+
+impl<T> From<List<T>> for Value
+where
+    Value: From<T>,
+{
+    fn from(list: List<T>) -> Value {
+        Value::list(list.0)
+    }
+}
+
+impl<T: FromValue> FromValue for List<T> {
+    fn from_value(value: &Value) -> Result<List<T>, TypeError> {
+        match *value {
+            Value::List(ref items) => items
+                .iter()
+                .map(T::from_value)
+                .collect::<Result<_, _>>()
+                .map(List),
+            ref value => Err(expected("a list", value)),
+        }
+    }
+}
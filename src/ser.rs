@@ -0,0 +1,1004 @@
+//! Writing `Value`s as EDN text.
+//!
+//! Symbols, keywords and tags which wouldn't read back as what was written,
+//! such as a symbol containing a space, are refused: `to_writer` and
+//! `Serializer::serialize` fail with `io::ErrorKind::InvalidData`, and the
+//! functions returning a `Vec` or `String` panic. Everything the parser
+//! reads can be written.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+
+use parser::Spanned;
+use sealed::Sealed;
+use symbol::{is_readable, is_valid_tag};
+use tagged::{self, TaggedSerialize};
+use {Keyword, RawEdn, Symbol, Value};
+
+pub mod pretty;
+
+/// Write `value` as compact EDN.
+pub fn to_writer<W: io::Write>(writer: W, value: &Value) -> io::Result<()> {
+    Serializer::new(writer).serialize(value)
+}
+
+/// Write `value` as EDN with every collection item on its own line, indented
+/// by two spaces per level.
+pub fn to_writer_pretty<W: io::Write>(writer: W, value: &Value) -> io::Result<()> {
+    Serializer::pretty(writer).serialize(value)
+}
+
+/// `value` as compact EDN.
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    let mut out = vec![];
+    to_writer(&mut out, value).expect("symbols, keywords and tags must be printable");
+    out
+}
+
+/// `value` as EDN with every collection item on its own line, indented by
+/// two spaces per level.
+pub fn to_vec_pretty(value: &Value) -> Vec<u8> {
+    to_vec_with_indent(value, 2)
+}
+
+/// `value` as EDN with every collection item on its own line, indented by
+/// `indent` spaces per level.
+pub fn to_vec_with_indent(value: &Value, indent: usize) -> Vec<u8> {
+    let mut serializer = Serializer::with_formatter(vec![], PrettyFormatter::with_indent(indent));
+    serializer
+        .serialize(value)
+        .expect("symbols, keywords and tags must be printable");
+    serializer.into_inner()
+}
+
+/// `value` as compact EDN.
+pub fn to_string(value: &Value) -> String {
+    String::from_utf8(to_vec(value)).expect("EDN output is valid UTF-8")
+}
+
+/// `value` as compact EDN for an error message, or as `{:?}` writes it if
+/// it can't be printed.
+pub(crate) fn to_string_lossy(value: &Value) -> String {
+    let mut out = vec![];
+    match to_writer(&mut out, value) {
+        Ok(()) => String::from_utf8(out).expect("EDN output is valid UTF-8"),
+        Err(_) => format!("{:?}", value),
+    }
+}
+
+/// `value` as EDN with every collection item on its own line, indented by
+/// two spaces per level.
+pub fn to_string_pretty(value: &Value) -> String {
+    String::from_utf8(to_vec_pretty(value)).expect("EDN output is valid UTF-8")
+}
+
+/// `value` as EDN with every collection item on its own line, indented by
+/// `indent` spaces per level.
+pub fn to_string_with_indent(value: &Value, indent: usize) -> String {
+    String::from_utf8(to_vec_with_indent(value, indent)).expect("EDN output is valid UTF-8")
+}
+
+/// `value` as canonical EDN, the same bytes for any two equal values however
+/// they were built, e.g. for content hashes and signatures.
+///
+/// Canonical EDN is compact EDN with a single space between the items of
+/// every collection, including the keys and values of maps, with map
+/// entries sorted by key and set items sorted. Values sort by the `Ord` of
+/// `Value`: first by kind, in the order nil, booleans, strings, chars,
+/// symbols, keywords, integers, big integers, ratios, floats, decimals,
+/// lists, vectors, maps, sets, tagged elements and values with metadata,
+/// then by content, with strings, symbols and keywords compared bytewise,
+/// numbers by value and collections item by item. Floats are written with
+/// the fewest digits that read back exactly and no exponent, with `-0.0`
+/// written as `0.0`, and decimals without trailing zeros.
+pub fn to_vec_canonical(value: &Value) -> Vec<u8> {
+    let mut serializer = Serializer::with_formatter(vec![], CanonicalFormatter);
+    serializer.set_config(Config::new().sort_entries(true));
+    serializer
+        .serialize(value)
+        .expect("symbols, keywords and tags must be printable");
+    serializer.into_inner()
+}
+
+/// `value` as canonical EDN; see `to_vec_canonical`.
+pub fn to_string_canonical(value: &Value) -> String {
+    String::from_utf8(to_vec_canonical(value)).expect("EDN output is valid UTF-8")
+}
+
+/// `value` as EDN for a file kept in version control, laid out so that a
+/// change to the value changes as few lines as possible.
+///
+/// This is `to_vec_pretty` with map entries and set items sorted as in
+/// `to_vec_canonical`, floats and decimals written as there, and a trailing
+/// newline. Every item of a collection, and every map entry, is on its own
+/// line, so adding, removing or changing one only touches that line. The
+/// output for a given value is part of the stable API: it only changes in a
+/// major release.
+pub fn to_vec_vcs(value: &Value) -> Vec<u8> {
+    let mut formatter = PrettyFormatter::new();
+    formatter.canonical = true;
+    let mut serializer = Serializer::with_formatter(vec![], formatter);
+    serializer.set_config(Config::new().sort_entries(true));
+    serializer
+        .serialize(value)
+        .expect("symbols, keywords and tags must be printable");
+    let mut out = serializer.into_inner();
+    out.push(b'\n');
+    out
+}
+
+/// `value` as EDN for a file kept in version control; see `to_vec_vcs`.
+pub fn to_string_vcs(value: &Value) -> String {
+    String::from_utf8(to_vec_vcs(value)).expect("EDN output is valid UTF-8")
+}
+
+/// `value` as compact EDN, with each float that is unchanged from
+/// `source`, the form it was read from by `Parser::read_spanned`, written
+/// as it was there. Reformatting a file then keeps `1.50` and `1e3` rather
+/// than rewriting them as `1.5` and `1000.0`.
+///
+/// ```
+/// use edn::parser::{from_str, parse_spanned};
+/// use edn::ser::to_string_preserving;
+///
+/// let source = parse_spanned("[1.50 1e3 0.10]").unwrap();
+/// assert_eq!(to_string_preserving(&source.value, &source), "[1.50 1e3 0.10]");
+/// let changed = from_str("[1.50 2e3]").unwrap();
+/// assert_eq!(to_string_preserving(&changed, &source), "[1.50 2000.0]");
+/// ```
+///
+/// Floats are matched to the source by their place in it, and kept as
+/// written only if their value is the same, so edits elsewhere don't
+/// disturb them. `RawEdn` keeps the text of whole forms.
+pub fn to_string_preserving(value: &Value, source: &Spanned) -> String {
+    let mut literals = vec![];
+    float_literals(value, Some(source), &mut literals);
+    let formatter = PreservingFormatter {
+        literals: literals.into_iter(),
+    };
+    let mut serializer = Serializer::with_formatter(vec![], formatter);
+    serializer
+        .serialize(value)
+        .expect("symbols, keywords and tags must be printable");
+    String::from_utf8(serializer.into_inner()).expect("EDN output is valid UTF-8")
+}
+
+/// Push the source text of each finite float in `value`, or `None` if it
+/// isn't in `source`, in the order `Serializer::serialize` writes them.
+fn float_literals<'a>(
+    value: &Value,
+    source: Option<&'a Spanned>,
+    literals: &mut Vec<Option<&'a str>>,
+) {
+    // Metadata which wasn't kept leaves the form it was on as the second
+    // child, with the same value.
+    let source = match source {
+        Some(source) if source.children.len() == 2 && source.children[1].value == source.value => {
+            Some(&source.children[1])
+        }
+        source => source,
+    };
+    let children = source.map_or(&[][..], |source| &source.children[..]);
+    match *value {
+        Value::Float(float) if float.is_finite() => literals.push(
+            source
+                .filter(|source| source.value == *value)
+                .and_then(|source| source.literal.as_deref()),
+        ),
+        Value::List(ref items) | Value::Vector(ref items) => {
+            for (i, item) in items.iter().enumerate() {
+                float_literals(item, children.get(i), literals);
+            }
+        }
+        Value::Set(ref items) => {
+            for item in items.iter() {
+                let child = children.iter().find(|child| child.value == *item);
+                float_literals(item, child, literals);
+            }
+        }
+        Value::Map(ref map) => map_float_literals(map.iter(), children, literals),
+        Value::Tagged(_, ref value) => float_literals(value, children.first(), literals),
+        Value::WithMeta(ref value, ref meta) => {
+            let meta_children = children.first().map_or(&[][..], |meta| &meta.children[..]);
+            map_float_literals(meta.iter(), meta_children, literals);
+            float_literals(value, children.get(1), literals);
+        }
+        _ => {}
+    }
+}
+
+/// `float_literals` for map entries, each matched to the source entry with
+/// the same key.
+fn map_float_literals<'a, 'v, I>(
+    entries: I,
+    children: &'a [Spanned],
+    literals: &mut Vec<Option<&'a str>>,
+) where
+    I: Iterator<Item = (&'v Value, &'v Value)>,
+{
+    for (key, value) in entries {
+        let entry = children
+            .chunks(2)
+            .find(|entry| entry[0].value == *key)
+            .unwrap_or(&[]);
+        float_literals(key, entry.first(), literals);
+        float_literals(value, entry.get(1), literals);
+    }
+}
+
+/// Writes compact EDN, with floats as written in the source where known,
+/// for `to_string_preserving`.
+struct PreservingFormatter<'a> {
+    literals: ::std::vec::IntoIter<Option<&'a str>>,
+}
+
+#[cfg(not(feature = "unstable"))]
+impl<'a> Sealed for PreservingFormatter<'a> {}
+
+impl<'a> Formatter for PreservingFormatter<'a> {
+    fn write_float<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        match self.literals.next() {
+            Some(Some(literal)) => writer.write_all(literal.as_bytes()),
+            _ => CompactFormatter.write_float(writer, value),
+        }
+    }
+}
+
+/// Controls the text written for each part of a value.
+///
+/// Every method has a default which writes compact EDN, so a formatter only
+/// needs to override the parts it lays out differently. `first` is `true`
+/// for the first item or entry of a collection.
+///
+/// Formatters other than this crate's can only be written with the
+/// `unstable` feature, as methods may be added to the trait.
+pub trait Formatter: Sealed {
+    fn write_nil<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"nil")
+    }
+
+    fn write_bool<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: bool) -> io::Result<()> {
+        writer.write_all(if value { b"true" } else { b"false" })
+    }
+
+    fn write_integer<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: i64,
+    ) -> io::Result<()> {
+        writer.write_all(itoa::Buffer::new().format(value).as_bytes())
+    }
+
+    fn write_float<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        write_buffered(writer, format_args!("{:?}", value))
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    fn write_decimal<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: &::bigdecimal::BigDecimal,
+    ) -> io::Result<()> {
+        write!(writer, "{}M", value.to_plain_string())
+    }
+
+    fn write_string<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: &str,
+    ) -> io::Result<()> {
+        writer.write_all(b"\"")?;
+        // Write the text between escapes in one call.
+        let mut start = 0;
+        for (pos, ch) in value.char_indices() {
+            let escape: &[u8] = match ch {
+                '"' => b"\\\"",
+                '\\' => b"\\\\",
+                '\n' => b"\\n",
+                '\r' => b"\\r",
+                '\t' => b"\\t",
+                '\u{8}' => b"\\b",
+                '\u{c}' => b"\\f",
+                ch if ch.is_control() => b"",
+                _ => continue,
+            };
+            writer.write_all(&value.as_bytes()[start..pos])?;
+            if escape.is_empty() {
+                write!(writer, "\\u{:04x}", ch as u32)?;
+            } else {
+                writer.write_all(escape)?;
+            }
+            start = pos + ch.len_utf8();
+        }
+        writer.write_all(&value.as_bytes()[start..])?;
+        writer.write_all(b"\"")
+    }
+
+    fn write_char<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: char) -> io::Result<()> {
+        match value {
+            '\n' => writer.write_all(b"\\newline"),
+            '\r' => writer.write_all(b"\\return"),
+            ' ' => writer.write_all(b"\\space"),
+            '\t' => writer.write_all(b"\\tab"),
+            '\u{8}' => writer.write_all(b"\\backspace"),
+            '\u{c}' => writer.write_all(b"\\formfeed"),
+            ch if ch.is_control() || ch.is_whitespace() => write!(writer, "\\u{:04x}", ch as u32),
+            ch => {
+                writer.write_all(b"\\")?;
+                writer.write_all(ch.encode_utf8(&mut [0; 4]).as_bytes())
+            }
+        }
+    }
+
+    fn write_symbol<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: &Symbol,
+    ) -> io::Result<()> {
+        write_name(writer, value.namespace(), value.name())
+    }
+
+    fn write_keyword<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: &Keyword,
+    ) -> io::Result<()> {
+        writer.write_all(b":")?;
+        write_name(writer, value.namespace(), value.name())
+    }
+
+    fn begin_list<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"(")
+    }
+
+    fn end_list<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b")")
+    }
+
+    fn begin_vector<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"[")
+    }
+
+    fn end_vector<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"]")
+    }
+
+    fn begin_set<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"#{")
+    }
+
+    fn end_set<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"}")
+    }
+
+    /// Called before each item of a list, vector or set.
+    fn begin_item<W: ?Sized + io::Write>(&mut self, writer: &mut W, first: bool) -> io::Result<()> {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b" ")
+        }
+    }
+
+    fn end_item<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn begin_map<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"{")
+    }
+
+    fn end_map<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"}")
+    }
+
+    fn begin_map_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        if first {
+            Ok(())
+        } else {
+            writer.write_all(b", ")
+        }
+    }
+
+    fn end_map_key<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn begin_map_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b" ")
+    }
+
+    fn end_map_value<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called before the value of a tagged element.
+    fn begin_tagged<W: ?Sized + io::Write>(&mut self, writer: &mut W, tag: &str) -> io::Result<()> {
+        writer.write_all(b"#")?;
+        writer.write_all(tag.as_bytes())?;
+        writer.write_all(b" ")
+    }
+
+    fn end_tagged<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// The indentation in spaces of the line currently being written, passed
+    /// on to `TagLayout::Custom` functions.
+    fn current_indent(&self) -> usize {
+        0
+    }
+}
+
+/// Writes everything on one line, with `, ` between map entries.
+#[derive(Clone, Debug, Default)]
+pub struct CompactFormatter;
+
+#[cfg(not(feature = "unstable"))]
+impl Sealed for CompactFormatter {}
+
+impl Formatter for CompactFormatter {}
+
+/// Writes canonical EDN, for `to_vec_canonical`.
+#[derive(Clone, Debug, Default)]
+pub struct CanonicalFormatter;
+
+#[cfg(not(feature = "unstable"))]
+impl Sealed for CanonicalFormatter {}
+
+impl Formatter for CanonicalFormatter {
+    fn write_float<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        let value = if value == 0.0 { 0.0 } else { value };
+        write_buffered(writer, format_args!("{}", value))?;
+        // `Display` writes integral floats without a fraction.
+        if value.fract() == 0.0 {
+            writer.write_all(b".0")
+        } else {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    fn write_decimal<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: &::bigdecimal::BigDecimal,
+    ) -> io::Result<()> {
+        write!(writer, "{}M", value.normalized().to_plain_string())
+    }
+
+    fn begin_map_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.begin_item(writer, first)
+    }
+}
+
+const SPACES: &[u8; 64] = &[b' '; 64];
+
+/// Writes every collection item on its own line.
+#[derive(Clone, Debug)]
+pub struct PrettyFormatter {
+    indent: usize,
+    depth: usize,
+    has_value: bool,
+    /// Write numbers as `CanonicalFormatter` does, for `to_vec_vcs`.
+    canonical: bool,
+}
+
+impl PrettyFormatter {
+    /// Indent by two spaces per level.
+    pub fn new() -> PrettyFormatter {
+        PrettyFormatter::with_indent(2)
+    }
+
+    pub fn with_indent(indent: usize) -> PrettyFormatter {
+        PrettyFormatter {
+            indent,
+            depth: 0,
+            has_value: false,
+            canonical: false,
+        }
+    }
+
+    fn begin<W: ?Sized + io::Write>(&mut self, writer: &mut W, open: &[u8]) -> io::Result<()> {
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_all(open)
+    }
+
+    fn end<W: ?Sized + io::Write>(&mut self, writer: &mut W, close: &[u8]) -> io::Result<()> {
+        self.depth -= 1;
+        if self.has_value {
+            self.newline(writer)?;
+        }
+        writer.write_all(close)
+    }
+
+    fn newline<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b"\n")?;
+        let mut indent = self.current_indent();
+        while indent > 0 {
+            let spaces = indent.min(SPACES.len());
+            writer.write_all(&SPACES[..spaces])?;
+            indent -= spaces;
+        }
+        Ok(())
+    }
+}
+
+impl Default for PrettyFormatter {
+    fn default() -> PrettyFormatter {
+        PrettyFormatter::new()
+    }
+}
+
+#[cfg(not(feature = "unstable"))]
+impl Sealed for PrettyFormatter {}
+
+impl Formatter for PrettyFormatter {
+    fn write_float<W: ?Sized + io::Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        if self.canonical {
+            CanonicalFormatter.write_float(writer, value)
+        } else {
+            CompactFormatter.write_float(writer, value)
+        }
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    fn write_decimal<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: &::bigdecimal::BigDecimal,
+    ) -> io::Result<()> {
+        if self.canonical {
+            CanonicalFormatter.write_decimal(writer, value)
+        } else {
+            CompactFormatter.write_decimal(writer, value)
+        }
+    }
+
+    fn begin_list<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.begin(writer, b"(")
+    }
+
+    fn end_list<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.end(writer, b")")
+    }
+
+    fn begin_vector<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.begin(writer, b"[")
+    }
+
+    fn end_vector<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.end(writer, b"]")
+    }
+
+    fn begin_set<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.begin(writer, b"#{")
+    }
+
+    fn end_set<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.end(writer, b"}")
+    }
+
+    fn begin_item<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        _first: bool,
+    ) -> io::Result<()> {
+        self.newline(writer)
+    }
+
+    fn end_item<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        self.has_value = true;
+        Ok(())
+    }
+
+    fn begin_map<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.begin(writer, b"{")
+    }
+
+    fn end_map<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.end(writer, b"}")
+    }
+
+    fn begin_map_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        _first: bool,
+    ) -> io::Result<()> {
+        self.newline(writer)
+    }
+
+    fn end_map_value<W: ?Sized + io::Write>(&mut self, _writer: &mut W) -> io::Result<()> {
+        self.has_value = true;
+        Ok(())
+    }
+
+    fn current_indent(&self) -> usize {
+        self.indent * self.depth
+    }
+}
+
+type LayoutFn = Box<dyn Fn(&Value, usize) -> String>;
+
+/// How the value of a tagged element is laid out.
+pub enum TagLayout {
+    /// Keep the whole element on one line, even when pretty printing.
+    Inline,
+    /// Render the value with a function, which is given the value and the
+    /// indentation in spaces of the line the element starts on. The result is
+    /// written after `#tag `.
+    Custom(LayoutFn),
+}
+
+/// Options for how a `Serializer` writes values, built up like
+/// `Config::new().keyword_keys(true).namespace("user")`.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    keyword_keys: bool,
+    namespace: Option<String>,
+    non_finite: NonFinite,
+    nil_values: NilValues,
+    sequences_as_lists: bool,
+    sort_entries: Option<bool>,
+}
+
+/// How floats which are infinite or NaN are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonFinite {
+    /// As `##Inf`, `##-Inf` and `##NaN`, which Clojure 1.9 and later read.
+    #[default]
+    Symbolic,
+    Nil,
+    /// Fail with `io::ErrorKind::InvalidData`.
+    Error,
+}
+
+/// How map entries whose value is `nil`, e.g. from a `None` field, are
+/// written.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum NilValues {
+    /// As `:key nil`.
+    #[default]
+    Keep,
+    /// Not at all, since Clojure code usually treats a missing key like one
+    /// mapped to `nil`.
+    Skip,
+    /// As `:key #tag nil`, for readers which must tell the two apart.
+    Tagged(String),
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config::default()
+    }
+
+    /// Write string map keys as kebab-case keywords, so a `first_name` or
+    /// `firstName` key is written as `:first-name`, the way Clojure expects
+    /// maps converted from structs.
+    pub fn keyword_keys(mut self, keyword_keys: bool) -> Config {
+        self.keyword_keys = keyword_keys;
+        self
+    }
+
+    /// Put the keywords written by `keyword_keys` in `namespace`, e.g.
+    /// `:user/first-name`.
+    pub fn namespace(mut self, namespace: &str) -> Config {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn non_finite(mut self, non_finite: NonFinite) -> Config {
+        self.non_finite = non_finite;
+        self
+    }
+
+    pub fn nil_values(mut self, nil_values: NilValues) -> Config {
+        self.nil_values = nil_values;
+        self
+    }
+
+    /// Write vectors as lists, e.g. for a Clojure consumer reading the
+    /// output as code. Wrap sequences in `List` to write only those as lists.
+    pub fn sequences_as_lists(mut self, as_lists: bool) -> Config {
+        self.sequences_as_lists = as_lists;
+        self
+    }
+
+    /// Write the entries of maps and the items of sets sorted, rather than in
+    /// the order the map or set iterates them. This is the default with the
+    /// `immutable` feature, whose maps and sets iterate in no particular
+    /// order, so the same value is always written the same way.
+    pub fn sort_entries(mut self, sort_entries: bool) -> Config {
+        self.sort_entries = Some(sort_entries);
+        self
+    }
+
+    fn sorts(&self) -> bool {
+        self.sort_entries.unwrap_or(cfg!(feature = "immutable"))
+    }
+
+    fn key(&self, key: &str) -> Keyword {
+        let mut name = String::with_capacity(key.len());
+        let mut prev_lower = false;
+        for ch in key.chars() {
+            if ch == '_' {
+                name.push('-');
+            } else if ch.is_uppercase() {
+                if prev_lower {
+                    name.push('-');
+                }
+                name.extend(ch.to_lowercase());
+            } else {
+                name.push(ch);
+            }
+            prev_lower = ch.is_lowercase() || ch.is_ascii_digit();
+        }
+        match self.namespace {
+            Some(ref namespace) => Keyword::namespaced(namespace, &name),
+            None => Keyword::new(&name),
+        }
+    }
+}
+
+pub struct Serializer<W, F = CompactFormatter> {
+    writer: W,
+    formatter: F,
+    layouts: HashMap<String, TagLayout>,
+    config: Config,
+}
+
+impl<W: io::Write> Serializer<W> {
+    pub fn new(writer: W) -> Serializer<W> {
+        Serializer::with_formatter(writer, CompactFormatter)
+    }
+}
+
+impl<W: io::Write> Serializer<W, PrettyFormatter> {
+    pub fn pretty(writer: W) -> Serializer<W, PrettyFormatter> {
+        Serializer::with_formatter(writer, PrettyFormatter::new())
+    }
+}
+
+impl<W: io::Write, F: Formatter> Serializer<W, F> {
+    pub fn with_formatter(writer: W, formatter: F) -> Serializer<W, F> {
+        Serializer {
+            writer,
+            formatter,
+            layouts: HashMap::new(),
+            config: Config::default(),
+        }
+    }
+
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    /// Lay out elements tagged with `tag` using `layout`.
+    pub fn set_tag_layout(&mut self, tag: &str, layout: TagLayout) {
+        self.layouts.insert(tag.into(), layout);
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write `raw`'s text as it is.
+    pub fn serialize_raw(&mut self, raw: &RawEdn) -> io::Result<()> {
+        self.writer.write_all(raw.as_str().as_bytes())
+    }
+
+    /// Write `value` as a tagged element wrapping its plain value.
+    pub fn serialize_tagged<T: TaggedSerialize + ?Sized>(&mut self, value: &T) -> io::Result<()> {
+        self.serialize(&tagged::to_value(value))
+    }
+
+    pub fn serialize(&mut self, value: &Value) -> io::Result<()> {
+        match *value {
+            Value::Nil => self.formatter.write_nil(&mut self.writer),
+            Value::Boolean(boolean) => self.formatter.write_bool(&mut self.writer, boolean),
+            Value::String(ref string) => self.formatter.write_string(&mut self.writer, string),
+            Value::Char(ch) => self.formatter.write_char(&mut self.writer, ch),
+            Value::Symbol(ref symbol) if !is_readable(symbol.namespace(), symbol.name(), false) => {
+                Err(unprintable("symbol", symbol))
+            }
+            Value::Symbol(ref symbol) => self.formatter.write_symbol(&mut self.writer, symbol),
+            Value::Keyword(ref keyword)
+                if !is_readable(keyword.namespace(), keyword.name(), true) =>
+            {
+                Err(unprintable("keyword", keyword))
+            }
+            Value::Keyword(ref keyword) => self.formatter.write_keyword(&mut self.writer, keyword),
+            Value::Integer(integer) => self.formatter.write_integer(&mut self.writer, integer),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(ref integer) => write!(self.writer, "{}N", integer),
+            #[cfg(feature = "ratio")]
+            Value::Ratio(numerator, denominator) => {
+                write!(self.writer, "{}/{}", numerator, denominator)
+            }
+            Value::Float(float) if !float.is_finite() => match self.config.non_finite {
+                NonFinite::Symbolic => self.writer.write_all(match float.into_inner() {
+                    float if float.is_nan() => b"##NaN",
+                    float if float > 0.0 => b"##Inf",
+                    _ => b"##-Inf",
+                }),
+                NonFinite::Nil => self.formatter.write_nil(&mut self.writer),
+                NonFinite::Error => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("can't write non-finite float {}", float),
+                )),
+            },
+            Value::Float(float) => self
+                .formatter
+                .write_float(&mut self.writer, float.into_inner()),
+            #[cfg(feature = "bigdecimal")]
+            Value::Decimal(ref decimal) => self.formatter.write_decimal(&mut self.writer, decimal),
+            Value::List(ref items) | Value::Vector(ref items) if self.config.sequences_as_lists => {
+                self.formatter.begin_list(&mut self.writer)?;
+                self.items(items.iter())?;
+                self.formatter.end_list(&mut self.writer)
+            }
+            Value::List(ref items) => {
+                self.formatter.begin_list(&mut self.writer)?;
+                self.items(items.iter())?;
+                self.formatter.end_list(&mut self.writer)
+            }
+            Value::Vector(ref items) => {
+                self.formatter.begin_vector(&mut self.writer)?;
+                self.items(items.iter())?;
+                self.formatter.end_vector(&mut self.writer)
+            }
+            Value::Set(ref items) => {
+                self.formatter.begin_set(&mut self.writer)?;
+                if self.config.sorts() {
+                    let mut sorted: Vec<&Value> = items.iter().collect();
+                    sorted.sort_unstable();
+                    self.items(sorted.into_iter())?;
+                } else {
+                    self.items(items.iter())?;
+                }
+                self.formatter.end_set(&mut self.writer)
+            }
+            Value::Map(ref map) => self.map(map.iter()),
+            Value::Tagged(ref tag, _) if !is_valid_tag(tag) => {
+                Err(unprintable("tag", &format!("#{}", tag)))
+            }
+            Value::Tagged(ref tag, ref value) => {
+                self.formatter.begin_tagged(&mut self.writer, tag)?;
+                match self.layouts.get(tag) {
+                    Some(TagLayout::Inline) => {
+                        let writer: &mut dyn io::Write = &mut self.writer;
+                        let mut inline = Serializer {
+                            writer,
+                            formatter: CompactFormatter,
+                            layouts: ::std::mem::take(&mut self.layouts),
+                            config: ::std::mem::take(&mut self.config),
+                        };
+                        let result = inline.serialize(value);
+                        self.layouts = inline.layouts;
+                        self.config = inline.config;
+                        result?;
+                    }
+                    Some(TagLayout::Custom(layout)) => {
+                        let indent = self.formatter.current_indent();
+                        self.writer.write_all(layout(value, indent).as_bytes())?;
+                    }
+                    None => self.serialize(value)?,
+                }
+                self.formatter.end_tagged(&mut self.writer)
+            }
+            Value::WithMeta(ref value, ref meta) => {
+                self.writer.write_all(b"^")?;
+                self.map(meta.iter())?;
+                self.writer.write_all(b" ")?;
+                self.serialize(value)
+            }
+        }
+    }
+
+    fn items<'a, I>(&mut self, items: I) -> io::Result<()>
+    where
+        I: Iterator<Item = &'a Value>,
+    {
+        for (i, item) in items.enumerate() {
+            self.formatter.begin_item(&mut self.writer, i == 0)?;
+            self.serialize(item)?;
+            self.formatter.end_item(&mut self.writer)?;
+        }
+        Ok(())
+    }
+
+    fn map<'a, I>(&mut self, entries: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (&'a Value, &'a Value)>,
+    {
+        if self.config.sorts() {
+            let mut sorted: Vec<(&Value, &Value)> = entries.collect();
+            sorted.sort_unstable_by_key(|entry| entry.0);
+            self.entries(sorted.into_iter())
+        } else {
+            self.entries(entries)
+        }
+    }
+
+    fn entries<'a, I>(&mut self, entries: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (&'a Value, &'a Value)>,
+    {
+        self.formatter.begin_map(&mut self.writer)?;
+        let mut first = true;
+        for (key, value) in entries {
+            let tagged;
+            let value = match (value, &self.config.nil_values) {
+                (&Value::Nil, NilValues::Skip) => continue,
+                (&Value::Nil, NilValues::Tagged(tag)) => {
+                    tagged = Value::Tagged(tag.clone(), Box::new(Value::Nil));
+                    &tagged
+                }
+                _ => value,
+            };
+            self.formatter.begin_map_key(&mut self.writer, first)?;
+            first = false;
+            match *key {
+                Value::String(ref key) if self.config.keyword_keys => {
+                    let key = self.config.key(key);
+                    if !is_readable(key.namespace(), key.name(), true) {
+                        return Err(unprintable("keyword", &key));
+                    }
+                    self.formatter.write_keyword(&mut self.writer, &key)?;
+                }
+                _ => self.serialize(key)?,
+            }
+            self.formatter.end_map_key(&mut self.writer)?;
+            self.formatter.begin_map_value(&mut self.writer)?;
+            self.serialize(value)?;
+            self.formatter.end_map_value(&mut self.writer)?;
+        }
+        self.formatter.end_map(&mut self.writer)
+    }
+}
+
+/// The error for a symbol, keyword or tag which wouldn't read back as what
+/// was written, e.g. `(symbol "a b")`.
+fn unprintable(kind: &str, text: &dyn fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("can't write {} `{}` as EDN", kind, text),
+    )
+}
+
+/// Write `ns/name`, or just `name`.
+fn write_name<W: ?Sized + io::Write>(
+    writer: &mut W,
+    namespace: Option<&str>,
+    name: &str,
+) -> io::Result<()> {
+    if let Some(namespace) = namespace {
+        writer.write_all(namespace.as_bytes())?;
+        writer.write_all(b"/")?;
+    }
+    writer.write_all(name.as_bytes())
+}
+
+/// Write short formatted text, such as a number, with a single `write_all`
+/// rather than one call per piece the formatter produces.
+fn write_buffered<W: ?Sized + io::Write>(writer: &mut W, args: fmt::Arguments) -> io::Result<()> {
+    let mut buffer = [0; 32];
+    let written = {
+        let mut rest = &mut buffer[..];
+        io::Write::write_fmt(&mut rest, args).map(|()| 32 - rest.len())
+    };
+    match written {
+        Ok(len) => writer.write_all(&buffer[..len]),
+        Err(_) => writer.write_all(args.to_string().as_bytes()),
+    }
+}
@@ -0,0 +1,186 @@
+//! Width-aware pretty printing, in the style of `clojure.pprint`.
+//!
+//! Collections which fit in the remaining width are kept on one line.
+//! Longer ones put each item on its own line, aligned with the first item:
+//!
+//! ```text
+//! {:name "edn",
+//!  :deps [[ordered-float "0.4.0"]
+//!         [im "12.2.0"]]}
+//! ```
+
+use std::io;
+
+use super::Config;
+use Value;
+
+/// Write `value` pretty printed to 80 columns.
+pub fn to_writer<W: io::Write>(writer: W, value: &Value) -> io::Result<()> {
+    PrettyPrinter::new().to_writer(writer, value)
+}
+
+/// `value` pretty printed to 80 columns.
+pub fn to_string(value: &Value) -> String {
+    PrettyPrinter::new().to_string(value)
+}
+
+#[derive(Clone, Debug)]
+pub struct PrettyPrinter {
+    width: usize,
+}
+
+impl PrettyPrinter {
+    pub fn new() -> PrettyPrinter {
+        PrettyPrinter { width: 80 }
+    }
+
+    /// Wrap collections which would extend past `width` columns.
+    pub fn width(mut self, width: usize) -> PrettyPrinter {
+        self.width = width;
+        self
+    }
+
+    pub fn to_writer<W: io::Write>(&self, mut writer: W, value: &Value) -> io::Result<()> {
+        // Fail on unprintable symbols rather than panicking in `to_string`.
+        super::to_writer(io::sink(), value)?;
+        writer.write_all(self.to_string(value).as_bytes())
+    }
+
+    pub fn to_string(&self, value: &Value) -> String {
+        let mut out = String::new();
+        self.print(value, &mut out);
+        out
+    }
+
+    fn print(&self, value: &Value, out: &mut String) {
+        if let Some(flat) = compact_within(value, self.width.saturating_sub(column(out))) {
+            out.push_str(&flat);
+            return;
+        }
+        match *value {
+            Value::List(ref items) => self.items("(", items.iter(), ")", out),
+            Value::Vector(ref items) => self.items("[", items.iter(), "]", out),
+            Value::Set(ref items) => {
+                let mut items: Vec<&Value> = items.iter().collect();
+                if Config::new().sorts() {
+                    items.sort_unstable();
+                }
+                self.items("#{", items.into_iter(), "}", out)
+            }
+            Value::Map(ref map) => self.map(map.iter(), out),
+            Value::Tagged(ref tag, ref value) => {
+                out.push('#');
+                out.push_str(tag);
+                out.push(' ');
+                self.print(value, out);
+            }
+            Value::WithMeta(ref value, ref meta) => {
+                out.push('^');
+                self.map(meta.iter(), out);
+                out.push(' ');
+                self.print(value, out);
+            }
+            _ => out.push_str(&compact(value)),
+        }
+    }
+
+    fn items<'a, I>(&self, open: &str, items: I, close: &str, out: &mut String)
+    where
+        I: Iterator<Item = &'a Value>,
+    {
+        out.push_str(open);
+        let indent = column(out);
+        for (i, item) in items.enumerate() {
+            if i > 0 {
+                newline(indent, out);
+            }
+            self.print(item, out);
+        }
+        out.push_str(close);
+    }
+
+    fn map<'a, I>(&self, entries: I, out: &mut String)
+    where
+        I: Iterator<Item = (&'a Value, &'a Value)>,
+    {
+        // In the same order as the flat output.
+        let mut entries: Vec<(&Value, &Value)> = entries.collect();
+        if Config::new().sorts() {
+            entries.sort_unstable_by_key(|entry| entry.0);
+        }
+        out.push('{');
+        let indent = column(out);
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+                newline(indent, out);
+            }
+            self.print(key, out);
+            out.push(' ');
+            self.print(value, out);
+        }
+        out.push('}');
+    }
+}
+
+impl Default for PrettyPrinter {
+    fn default() -> PrettyPrinter {
+        PrettyPrinter::new()
+    }
+}
+
+fn compact(value: &Value) -> String {
+    let mut bytes = vec![];
+    super::to_writer(&mut bytes, value).unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+/// `value` as compact EDN if it's at most `limit` columns wide. Writing
+/// stops once it's too wide, so trying each level of a deep value costs at
+/// most `limit` columns rather than the whole subtree.
+fn compact_within(value: &Value, limit: usize) -> Option<String> {
+    let mut writer = Limited {
+        bytes: vec![],
+        columns: 0,
+        limit,
+    };
+    super::to_writer(&mut writer, value).ok()?;
+    Some(String::from_utf8(writer.bytes).unwrap())
+}
+
+/// Collects bytes, failing once they make up more than `limit` chars.
+struct Limited {
+    bytes: Vec<u8>,
+    columns: usize,
+    limit: usize,
+}
+
+impl io::Write for Limited {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Count the first byte of each UTF-8 char.
+        self.columns += buf.iter().filter(|&&byte| byte & 0xC0 != 0x80).count();
+        if self.columns > self.limit {
+            return Err(io::Error::other("too wide"));
+        }
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn column(out: &str) -> usize {
+    match out.rfind('\n') {
+        Some(newline) => out[newline + 1..].chars().count(),
+        None => out.chars().count(),
+    }
+}
+
+fn newline(indent: usize, out: &mut String) {
+    out.push('\n');
+    for _ in 0..indent {
+        out.push(' ');
+    }
+}
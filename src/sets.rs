@@ -0,0 +1,52 @@
+use {TypeError, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::Set;
+#[cfg(not(feature = "immutable"))]
+use standard::Set;
+
+fn sets<'a>(a: &'a Value, b: &'a Value) -> Result<(&'a Set<Value>, &'a Set<Value>), TypeError> {
+    match (a, b) {
+        (Value::Set(a), Value::Set(b)) => Ok((a, b)),
+        (Value::Set(_), other) | (other, _) => Err(TypeError {
+            expected: "a set",
+            found: other.kind(),
+        }),
+    }
+}
+
+impl Value {
+    /// The elements in either `self` or `other`, which must both be sets.
+    pub fn union(&self, other: &Value) -> Result<Value, TypeError> {
+        let (a, b) = sets(self, other)?;
+        Ok(Value::Set(a.iter().chain(b.iter()).cloned().collect()))
+    }
+
+    /// The elements in both `self` and `other`, which must both be sets.
+    pub fn intersection(&self, other: &Value) -> Result<Value, TypeError> {
+        let (a, b) = sets(self, other)?;
+        Ok(Value::Set(
+            a.iter()
+                .filter(|value| b.contains(*value))
+                .cloned()
+                .collect(),
+        ))
+    }
+
+    /// The elements in `self` but not in `other`, which must both be sets.
+    pub fn difference(&self, other: &Value) -> Result<Value, TypeError> {
+        let (a, b) = sets(self, other)?;
+        Ok(Value::Set(
+            a.iter()
+                .filter(|value| !b.contains(*value))
+                .cloned()
+                .collect(),
+        ))
+    }
+
+    /// Whether every element of `self` is in `other`, which must both be sets.
+    pub fn is_subset(&self, other: &Value) -> Result<bool, TypeError> {
+        let (a, b) = sets(self, other)?;
+        Ok(a.iter().all(|value| b.contains(value)))
+    }
+}
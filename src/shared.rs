@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use ordered_float::OrderedFloat;
+
+use {Keyword, Symbol, Value};
+
+/// A `Value` whose strings and collections are behind `Arc`s, so cloning
+/// one, or any part of one, is O(1) and the clones share their contents.
+///
+/// Convert a document once with `ArcValue::from` and hand clones of it, or
+/// of the subtrees it contains, to as many consumers or threads as needed:
+///
+/// ```
+/// use edn::{parser, ArcValue};
+///
+/// let document = ArcValue::from(parser::from_str("{:users [{:name \"a\"}]}").unwrap());
+/// let users = document.get(&ArcValue::from(parser::from_str(":users").unwrap())).unwrap().clone();
+/// assert_eq!(users.nth(0).unwrap().to_value(), parser::from_str("{:name \"a\"}").unwrap());
+/// ```
+///
+/// Maps and sets keep the entries of the `Value` they came from in its
+/// iteration order, and are looked up by scanning them.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ArcValue {
+    Nil,
+    Boolean(bool),
+    String(Arc<str>),
+    Char(char),
+    Symbol(Arc<Symbol>),
+    Keyword(Arc<Keyword>),
+    Integer(i64),
+    #[cfg(feature = "bigint")]
+    BigInt(Arc<::num_bigint::BigInt>),
+    #[cfg(feature = "ratio")]
+    Ratio(i64, u64),
+    Float(OrderedFloat<f64>),
+    #[cfg(feature = "bigdecimal")]
+    Decimal(Arc<::bigdecimal::BigDecimal>),
+    List(Arc<[ArcValue]>),
+    Vector(Arc<[ArcValue]>),
+    Map(Arc<[(ArcValue, ArcValue)]>),
+    Set(Arc<[ArcValue]>),
+    Tagged(Arc<str>, Arc<ArcValue>),
+    WithMeta(Arc<ArcValue>, Arc<[(ArcValue, ArcValue)]>),
+}
+
+impl ArcValue {
+    /// The value for `key` in a map.
+    pub fn get(&self, key: &ArcValue) -> Option<&ArcValue> {
+        match *self {
+            ArcValue::Map(ref entries) => entries
+                .iter()
+                .find(|entry| entry.0 == *key)
+                .map(|entry| &entry.1),
+            ArcValue::WithMeta(ref value, _) => value.get(key),
+            _ => None,
+        }
+    }
+
+    /// The item at `index` in a list or vector.
+    pub fn nth(&self, index: usize) -> Option<&ArcValue> {
+        match *self {
+            ArcValue::List(ref items) | ArcValue::Vector(ref items) => items.get(index),
+            ArcValue::WithMeta(ref value, _) => value.nth(index),
+            _ => None,
+        }
+    }
+
+    /// A deep copy of this value as a `Value`.
+    pub fn to_value(&self) -> Value {
+        let entries = |entries: &[(ArcValue, ArcValue)]| {
+            entries
+                .iter()
+                .map(|entry| (entry.0.to_value(), entry.1.to_value()))
+                .collect()
+        };
+        match *self {
+            ArcValue::Nil => Value::Nil,
+            ArcValue::Boolean(boolean) => Value::Boolean(boolean),
+            ArcValue::String(ref string) => Value::String(string.to_string()),
+            ArcValue::Char(ch) => Value::Char(ch),
+            ArcValue::Symbol(ref symbol) => Value::Symbol((**symbol).clone()),
+            ArcValue::Keyword(ref keyword) => Value::Keyword((**keyword).clone()),
+            ArcValue::Integer(integer) => Value::Integer(integer),
+            #[cfg(feature = "bigint")]
+            ArcValue::BigInt(ref integer) => Value::BigInt((**integer).clone()),
+            #[cfg(feature = "ratio")]
+            ArcValue::Ratio(numerator, denominator) => Value::Ratio(numerator, denominator),
+            ArcValue::Float(float) => Value::Float(float),
+            #[cfg(feature = "bigdecimal")]
+            ArcValue::Decimal(ref decimal) => Value::Decimal((**decimal).clone()),
+            ArcValue::List(ref items) => {
+                Value::List(items.iter().map(ArcValue::to_value).collect())
+            }
+            ArcValue::Vector(ref items) => {
+                Value::Vector(items.iter().map(ArcValue::to_value).collect())
+            }
+            ArcValue::Map(ref map) => Value::Map(entries(map)),
+            ArcValue::Set(ref items) => Value::Set(items.iter().map(ArcValue::to_value).collect()),
+            ArcValue::Tagged(ref tag, ref value) => {
+                Value::Tagged(tag.to_string(), Box::new(value.to_value()))
+            }
+            ArcValue::WithMeta(ref value, ref meta) => {
+                Value::WithMeta(Box::new(value.to_value()), entries(meta))
+            }
+        }
+    }
+}
+
+impl From<Value> for ArcValue {
+    fn from(s: Value) -> Self {
+        match s {
+            Value::Nil => ArcValue::Nil,
+            Value::Boolean(boolean) => ArcValue::Boolean(boolean),
+            Value::String(string) => ArcValue::String(string.into()),
+            Value::Char(ch) => ArcValue::Char(ch),
+            Value::Symbol(symbol) => ArcValue::Symbol(Arc::new(symbol)),
+            Value::Keyword(keyword) => ArcValue::Keyword(Arc::new(keyword)),
+            Value::Integer(integer) => ArcValue::Integer(integer),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(integer) => ArcValue::BigInt(Arc::new(integer)),
+            #[cfg(feature = "ratio")]
+            Value::Ratio(numerator, denominator) => ArcValue::Ratio(numerator, denominator),
+            Value::Float(float) => ArcValue::Float(float),
+            #[cfg(feature = "bigdecimal")]
+            Value::Decimal(decimal) => ArcValue::Decimal(Arc::new(decimal)),
+            Value::List(list) => ArcValue::List(items(list)),
+            Value::Vector(vector) => ArcValue::Vector(items(vector)),
+            Value::Map(map) => ArcValue::Map(entries(map)),
+            Value::Set(set) => ArcValue::Set(items(set)),
+            Value::Tagged(tag, value) => ArcValue::Tagged(tag.into(), Arc::new((*value).into())),
+            Value::WithMeta(value, meta) => {
+                ArcValue::WithMeta(Arc::new((*value).into()), entries(meta))
+            }
+        }
+    }
+}
+
+fn items<I: IntoIterator<Item = Value>>(items: I) -> Arc<[ArcValue]> {
+    items.into_iter().map(ArcValue::from).collect()
+}
+
+fn entries(map: ::Map<Value, Value>) -> Arc<[(ArcValue, ArcValue)]> {
+    map.into_iter()
+        .map(|(key, value)| (ArcValue::from(key), ArcValue::from(value)))
+        .collect()
+}
+
+impl<'a> From<&'a ArcValue> for Value {
+    fn from(s: &'a ArcValue) -> Self {
+        s.to_value()
+    }
+}
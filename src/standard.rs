@@ -1,5 +1,12 @@
-use std::collections::{BTreeMap, BTreeSet};
+#[cfg(not(feature = "ordered"))]
+use std::collections::BTreeSet;
 
-pub type Map<K, V> = BTreeMap<K, V>;
+#[cfg(feature = "ordered")]
+use ordered::OrderedSet;
+
+pub use map::Map;
 pub type Vec<T> = std::vec::Vec<T>;
+#[cfg(not(feature = "ordered"))]
 pub type Set<T> = BTreeSet<T>;
+#[cfg(feature = "ordered")]
+pub type Set<T> = OrderedSet<T>;
@@ -0,0 +1,322 @@
+//! Statistics about the shape of a corpus of EDN documents.
+//!
+//! `Stats` counts the map keys, the types of their values and the tags
+//! used across every document added to it, and reports them as EDN:
+//!
+//! ```
+//! use edn::parser;
+//! use edn::stats::Stats;
+//!
+//! let mut stats = Stats::new();
+//! for document in &[r#"{:id 1 :name "a"}"#, r#"{:id 2 :name nil :at #inst "2020-01-01"}"#] {
+//!     stats.add(&parser::from_str(document).unwrap());
+//! }
+//! let report = stats.report();
+//! assert_eq!(
+//!     report["keys"]["name"],
+//!     parser::from_str("{:count 2 :types {:string 1 :nil 1}}").unwrap()
+//! );
+//! assert_eq!(report["tags"]["inst"], edn::Value::Integer(1));
+//! ```
+//!
+//! `Value::stats` describes a single value instead, e.g. to enforce limits
+//! on untrusted input before acting on it.
+
+use std::collections::{BTreeMap, HashSet};
+use std::mem;
+
+use {Keyword, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::Map;
+#[cfg(not(feature = "immutable"))]
+use standard::Map;
+
+/// Counts gathered from the documents added so far.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    sizes: Vec<usize>,
+    keys: BTreeMap<Value, KeyStats>,
+    tags: BTreeMap<String, usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct KeyStats {
+    count: usize,
+    types: BTreeMap<&'static str, usize>,
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    /// Count the keys and tags in `document`, at any depth, and its size.
+    pub fn add(&mut self, document: &Value) {
+        self.sizes.push(::ser::to_vec(document).len());
+        self.walk(document);
+    }
+
+    fn walk(&mut self, value: &Value) {
+        match *value {
+            Value::List(ref items) | Value::Vector(ref items) => {
+                items.iter().for_each(|item| self.walk(item))
+            }
+            Value::Set(ref items) => items.iter().for_each(|item| self.walk(item)),
+            Value::Map(ref map) => {
+                for (key, value) in map.iter() {
+                    let stats = self.keys.entry(key.clone()).or_default();
+                    stats.count += 1;
+                    *stats.types.entry(type_name(value)).or_insert(0) += 1;
+                    self.walk(key);
+                    self.walk(value);
+                }
+            }
+            Value::Tagged(ref tag, ref value) => {
+                *self.tags.entry(tag.clone()).or_insert(0) += 1;
+                self.walk(value);
+            }
+            Value::WithMeta(ref value, _) => self.walk(value),
+            _ => {}
+        }
+    }
+
+    /// The number of documents added.
+    pub fn documents(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// The statistics as a map:
+    ///
+    /// - `:documents`, the number of documents.
+    /// - `:keys`, for each map key, the number of entries with that key as
+    ///   `:count` and the number of values of each type, e.g. `:string` or
+    ///   `:map`, as `:types`.
+    /// - `:tags`, the number of uses of each tag, keyed by its name.
+    /// - `:sizes`, the `:min`, `:p50`, `:p90`, `:p99` and `:max` length in
+    ///   bytes of the documents written as compact EDN, which are `nil`
+    ///   before any documents are added.
+    pub fn report(&self) -> Value {
+        let keys = self
+            .keys
+            .iter()
+            .map(|(key, stats)| {
+                let types = stats
+                    .types
+                    .iter()
+                    .map(|(&name, &count)| (keyword(name), integer(count)))
+                    .collect();
+                let stats = vec![
+                    (keyword("count"), integer(stats.count)),
+                    (keyword("types"), Value::Map(types)),
+                ];
+                (key.clone(), Value::Map(stats.into_iter().collect()))
+            })
+            .collect();
+        let tags = self
+            .tags
+            .iter()
+            .map(|(tag, &count)| (Value::String(tag.clone()), integer(count)))
+            .collect();
+        let mut sizes = self.sizes.clone();
+        sizes.sort_unstable();
+        let percentile = |p: usize| match sizes.len() {
+            0 => Value::Nil,
+            n => integer(sizes[(p * n).div_ceil(100).max(1) - 1]),
+        };
+        let sizes = vec![
+            (keyword("min"), percentile(0)),
+            (keyword("p50"), percentile(50)),
+            (keyword("p90"), percentile(90)),
+            (keyword("p99"), percentile(99)),
+            (keyword("max"), percentile(100)),
+        ];
+        let report: Map<Value, Value> = vec![
+            (keyword("documents"), integer(self.documents())),
+            (keyword("keys"), Value::Map(keys)),
+            (keyword("tags"), Value::Map(tags)),
+            (keyword("sizes"), Value::Map(sizes.into_iter().collect())),
+        ]
+        .into_iter()
+        .collect();
+        Value::Map(report)
+    }
+}
+
+impl<'a> Extend<&'a Value> for Stats {
+    fn extend<I: IntoIterator<Item = &'a Value>>(&mut self, documents: I) {
+        documents
+            .into_iter()
+            .for_each(|document| self.add(document))
+    }
+}
+
+/// The report of `Stats` for `documents`.
+pub fn analyze<'a, I: IntoIterator<Item = &'a Value>>(documents: I) -> Value {
+    let mut stats = Stats::new();
+    stats.extend(documents);
+    stats.report()
+}
+
+/// Counts describing a single value, returned by `Value::stats`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValueStats {
+    /// The number of values, as `Value::deep_count` counts them.
+    pub nodes: usize,
+    /// The number of values of each type, by the names `Stats::report`
+    /// uses, e.g. `"map"`.
+    pub types: BTreeMap<&'static str, usize>,
+    /// How deeply values are nested: 1 for a scalar, 2 for `[1]`.
+    pub max_depth: usize,
+    /// The number of keywords, including repeats.
+    pub keywords: usize,
+    /// The number of different keywords, a lower bound on what interning
+    /// them would keep.
+    pub distinct_keywords: usize,
+    /// `Value::estimated_size`.
+    pub estimated_size: usize,
+}
+
+impl Value {
+    /// The number of values in this one, including itself, its items, map
+    /// keys and values, the values of tagged elements and the entries of
+    /// metadata. A value with metadata counts once.
+    pub fn deep_count(&self) -> usize {
+        let mut count = match *self {
+            Value::WithMeta(..) => 0,
+            _ => 1,
+        };
+        children(self, |child| count += child.deep_count());
+        count
+    }
+
+    /// Roughly how many bytes of heap memory this value takes up: the items
+    /// of its collections, the text of strings, keywords, symbols and tags,
+    /// and boxed values. Allocator overhead and the nodes of the map and set
+    /// backends aren't counted.
+    pub fn estimated_size(&self) -> usize {
+        let slot = mem::size_of::<Value>();
+        let own = match *self {
+            Value::String(ref string) => string.capacity(),
+            Value::Symbol(ref symbol) => {
+                symbol.namespace().map_or(0, str::len) + symbol.name().len()
+            }
+            Value::Keyword(ref keyword) => {
+                keyword.namespace().map_or(0, str::len) + keyword.name().len()
+            }
+            #[cfg(feature = "bigint")]
+            Value::BigInt(ref integer) => (integer.bits() as usize).div_ceil(8),
+            #[cfg(feature = "bigdecimal")]
+            Value::Decimal(ref decimal) => decimal.to_string().len() / 2,
+            Value::List(ref items) | Value::Vector(ref items) => items.len() * slot,
+            Value::Set(ref items) => items.len() * slot,
+            Value::Map(ref map) => map.len() * 2 * slot,
+            Value::Tagged(ref tag, _) => tag.capacity() + slot,
+            Value::WithMeta(_, ref meta) => slot + meta.len() * 2 * slot,
+            _ => 0,
+        };
+        let mut size = own;
+        children(self, |child| size += child.estimated_size());
+        size
+    }
+
+    /// Counts of the values in this one by type, how deeply they're
+    /// nested, and the keywords among them.
+    ///
+    /// ```
+    /// let value = edn::parser::from_str("[{:a 1 :b [:a]} #{:c}]").unwrap();
+    /// let stats = value.stats();
+    /// assert_eq!((stats.nodes, stats.max_depth), (9, 4));
+    /// assert_eq!((stats.keywords, stats.distinct_keywords), (4, 3));
+    /// assert_eq!(stats.types["keyword"], 4);
+    /// ```
+    pub fn stats(&self) -> ValueStats {
+        fn walk<'a>(
+            value: &'a Value,
+            depth: usize,
+            stats: &mut ValueStats,
+            keywords: &mut HashSet<&'a Keyword>,
+        ) {
+            if let Value::WithMeta(ref value, ref meta) = *value {
+                walk(value, depth, stats, keywords);
+                for (key, value) in meta.iter() {
+                    walk(key, depth + 1, stats, keywords);
+                    walk(value, depth + 1, stats, keywords);
+                }
+                return;
+            }
+            stats.nodes += 1;
+            stats.max_depth = stats.max_depth.max(depth);
+            *stats.types.entry(type_name(value)).or_insert(0) += 1;
+            if let Value::Keyword(ref keyword) = *value {
+                stats.keywords += 1;
+                keywords.insert(keyword);
+            }
+            children(value, |child| walk(child, depth + 1, stats, keywords));
+        }
+
+        let mut stats = ValueStats::default();
+        let mut keywords = HashSet::new();
+        walk(self, 1, &mut stats, &mut keywords);
+        stats.distinct_keywords = keywords.len();
+        stats.estimated_size = self.estimated_size();
+        stats
+    }
+}
+
+/// Call `f` with each value directly inside `value`.
+fn children<'a, F: FnMut(&'a Value)>(value: &'a Value, mut f: F) {
+    match *value {
+        Value::List(ref items) | Value::Vector(ref items) => items.iter().for_each(f),
+        Value::Set(ref items) => items.iter().for_each(f),
+        Value::Map(ref map) => {
+            for (key, value) in map.iter() {
+                f(key);
+                f(value);
+            }
+        }
+        Value::Tagged(_, ref value) => f(value),
+        Value::WithMeta(ref value, ref meta) => {
+            f(value);
+            for (key, value) in meta.iter() {
+                f(key);
+                f(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn keyword(name: &str) -> Value {
+    Value::Keyword(name.into())
+}
+
+fn integer(count: usize) -> Value {
+    Value::Integer(count as i64)
+}
+
+/// The name of the type of `value` in reports.
+fn type_name(value: &Value) -> &'static str {
+    match *value {
+        Value::Nil => "nil",
+        Value::Boolean(_) => "boolean",
+        Value::String(_) => "string",
+        Value::Char(_) => "char",
+        Value::Symbol(_) => "symbol",
+        Value::Keyword(_) => "keyword",
+        Value::Integer(_) => "integer",
+        #[cfg(feature = "bigint")]
+        Value::BigInt(_) => "integer",
+        #[cfg(feature = "ratio")]
+        Value::Ratio(..) => "ratio",
+        Value::Float(_) => "float",
+        #[cfg(feature = "bigdecimal")]
+        Value::Decimal(_) => "decimal",
+        Value::List(_) => "list",
+        Value::Vector(_) => "vector",
+        Value::Map(_) => "map",
+        Value::Set(_) => "set",
+        Value::Tagged(..) => "tagged",
+        Value::WithMeta(ref value, _) => type_name(value),
+    }
+}
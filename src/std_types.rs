@@ -0,0 +1,207 @@
+//! EDN forms for `Duration`, `IpAddr`, `SocketAddr` and `PathBuf`, which turn
+//! up in most service configs.
+//!
+//! `Value::from` writes them as tagged strings, e.g.
+//! `#time/duration "PT1M30S"` (the form the `time-literals` Clojure library
+//! reads), `#net/ip "10.0.0.1"`, `#net/socket "10.0.0.1:80"` and
+//! `#fs/path "/etc/app"`. The `Value::as_*` accessors read either the tagged
+//! form or a plain string, so a config can say `:timeout "PT5S"` too.
+//!
+//! ```
+//! use std::time::Duration;
+//! use edn::std_types::{self, Form};
+//! use edn::Value;
+//!
+//! let timeout = Duration::from_millis(1500);
+//! assert_eq!(edn::to_string(&Value::from(timeout)), "#time/duration \"PT1.5S\"");
+//! assert_eq!(
+//!     edn::to_string(&std_types::duration(timeout, Form::String)),
+//!     "\"PT1.5S\""
+//! );
+//! assert_eq!(Value::from("PT1.5S").as_duration(), Some(timeout));
+//! ```
+
+use std::fmt::Write;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use Value;
+
+pub const DURATION: &str = "time/duration";
+pub const IP: &str = "net/ip";
+pub const SOCKET: &str = "net/socket";
+pub const PATH: &str = "fs/path";
+
+/// How to write one of these types.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Form {
+    /// A string tagged with e.g. `DURATION`, as `Value::from` writes it.
+    #[default]
+    Tagged,
+    /// The string alone.
+    String,
+}
+
+fn write(tag: &str, string: String, form: Form) -> Value {
+    match form {
+        Form::Tagged => Value::Tagged(tag.into(), Box::new(Value::String(string))),
+        Form::String => Value::String(string),
+    }
+}
+
+/// `duration` as an ISO 8601 duration like `PT1H2M3.5S`.
+pub fn duration(duration: Duration, form: Form) -> Value {
+    write(DURATION, format_duration(duration), form)
+}
+
+pub fn ip_addr(addr: IpAddr, form: Form) -> Value {
+    write(IP, addr.to_string(), form)
+}
+
+pub fn socket_addr(addr: SocketAddr, form: Form) -> Value {
+    write(SOCKET, addr.to_string(), form)
+}
+
+/// `path` as a string. Parts which aren't valid UTF-8 are replaced with
+/// `U+FFFD`.
+pub fn path(path: &Path, form: Form) -> Value {
+    write(PATH, path.to_string_lossy().into_owned(), form)
+}
+
+fn format_duration(duration: Duration) -> String {
+    let (secs, nanos) = (duration.as_secs(), duration.subsec_nanos());
+    let mut out = String::from("PT");
+    if secs >= 3600 {
+        write!(out, "{}H", secs / 3600).unwrap();
+    }
+    if secs % 3600 >= 60 {
+        write!(out, "{}M", secs % 3600 / 60).unwrap();
+    }
+    if secs % 60 > 0 || nanos > 0 || secs == 0 {
+        write!(out, "{}", secs % 60).unwrap();
+        if nanos > 0 {
+            write!(out, ".{}", format!("{:09}", nanos).trim_end_matches('0')).unwrap();
+        }
+        out.push('S');
+    }
+    out
+}
+
+/// An ISO 8601 duration of days, hours, minutes and seconds, e.g. `P1D`,
+/// `PT90M` or `pt0.25s`. Years, months and negative durations aren't
+/// supported.
+fn parse_duration(str: &str) -> Option<Duration> {
+    let str = str.to_ascii_uppercase();
+    let rest = str.strip_prefix('P')?;
+    let (days, time) = match rest.find('T') {
+        Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+        None => (rest, None),
+    };
+    let number = |digits: &str| {
+        if digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            digits.parse::<u64>().ok()
+        } else {
+            None
+        }
+    };
+    let mut secs = 0u64;
+    let mut nanos = 0;
+    if !days.is_empty() {
+        secs = number(days.strip_suffix('D')?)?.checked_mul(86_400)?;
+    }
+    match time {
+        Some("") => return None,
+        Some(mut time) => {
+            while !time.is_empty() {
+                let end = time.find(|ch: char| ch.is_ascii_alphabetic())?;
+                let (digits, unit) = (&time[..end], &time[end..=end]);
+                time = &time[end + 1..];
+                let (digits, fraction) = match (unit, digits.find('.')) {
+                    ("S", Some(index)) if time.is_empty() => {
+                        (&digits[..index], &digits[index + 1..])
+                    }
+                    _ => (digits, ""),
+                };
+                let scale = match unit {
+                    "H" => 3600,
+                    "M" => 60,
+                    "S" => 1,
+                    _ => return None,
+                };
+                secs = secs.checked_add(number(digits)?.checked_mul(scale)?)?;
+                if !fraction.is_empty() {
+                    if fraction.len() > 9 {
+                        return None;
+                    }
+                    nanos = number(fraction)? as u32 * 10u32.pow(9 - fraction.len() as u32);
+                }
+            }
+        }
+        None if days.is_empty() => return None,
+        None => {}
+    }
+    Some(Duration::new(secs, nanos))
+}
+
+/// The string of a `#tag "..."` element or a plain string.
+fn string<'a>(value: &'a Value, tag: &str) -> Option<&'a str> {
+    match *value {
+        Value::String(ref string) => Some(string),
+        Value::Tagged(ref name, ref value) if name == tag => value.as_str(),
+        _ => None,
+    }
+}
+
+impl Value {
+    /// The duration of a `#time/duration "..."` element or an ISO 8601
+    /// duration string.
+    pub fn as_duration(&self) -> Option<Duration> {
+        string(self, DURATION).and_then(parse_duration)
+    }
+
+    /// The address of a `#net/ip "..."` element or an IP address string.
+    pub fn as_ip_addr(&self) -> Option<IpAddr> {
+        string(self, IP).and_then(|string| string.parse().ok())
+    }
+
+    /// The address of a `#net/socket "..."` element or an `ip:port` string.
+    pub fn as_socket_addr(&self) -> Option<SocketAddr> {
+        string(self, SOCKET).and_then(|string| string.parse().ok())
+    }
+
+    /// The path of a `#fs/path "..."` element or a string.
+    pub fn as_path(&self) -> Option<PathBuf> {
+        string(self, PATH).map(PathBuf::from)
+    }
+}
+
+impl From<Duration> for Value {
+    fn from(s: Duration) -> Self {
+        duration(s, Form::Tagged)
+    }
+}
+
+impl From<IpAddr> for Value {
+    fn from(s: IpAddr) -> Self {
+        ip_addr(s, Form::Tagged)
+    }
+}
+
+impl From<SocketAddr> for Value {
+    fn from(s: SocketAddr) -> Self {
+        socket_addr(s, Form::Tagged)
+    }
+}
+
+impl From<PathBuf> for Value {
+    fn from(s: PathBuf) -> Self {
+        path(&s, Form::Tagged)
+    }
+}
+
+impl<'a> From<&'a Path> for Value {
+    fn from(s: &'a Path) -> Self {
+        path(s, Form::Tagged)
+    }
+}
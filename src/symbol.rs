@@ -0,0 +1,161 @@
+use std::fmt;
+use std::str::FromStr;
+
+use parser::{is_symbol_head, is_symbol_tail};
+
+/// An EDN symbol, e.g. `foo` or `clojure.core/map`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Symbol {
+    namespace: Option<String>,
+    name: String,
+}
+
+impl Symbol {
+    pub fn new(name: &str) -> Symbol {
+        Symbol {
+            namespace: None,
+            name: name.into(),
+        }
+    }
+
+    pub fn namespaced(namespace: &str, name: &str) -> Symbol {
+        Symbol {
+            namespace: Some(namespace.into()),
+            name: name.into(),
+        }
+    }
+
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_ref().map(|namespace| &namespace[..])
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the symbol follows the EDN spec's grammar, so that it can be
+    /// written as EDN and read back as the same symbol.
+    pub fn is_valid(&self) -> bool {
+        is_valid(self.namespace(), &self.name, false)
+    }
+}
+
+/// Whether a symbol, or a keyword without its `:`, with `namespace` and
+/// `name` follows the EDN spec: each part starts with a char which can't
+/// start a number, `:` or `#`, and a leading `-`, `+` or `.` isn't followed
+/// by a digit. A lone `/` is a symbol but not a keyword, and `nil`, `true`
+/// and `false` are never symbols.
+pub(crate) fn is_valid(namespace: Option<&str>, name: &str, keyword: bool) -> bool {
+    match namespace {
+        Some(namespace) => is_valid_part(namespace) && is_valid_part(name),
+        None if name == "/" => !keyword,
+        None if !keyword && is_literal(name) => false,
+        None => is_valid_part(name),
+    }
+}
+
+/// Whether the text of a symbol, or a keyword without its `:`, is valid.
+pub(crate) fn is_valid_str(str: &str, keyword: bool) -> bool {
+    let (namespace, name) = split_ref(str);
+    is_valid(namespace, name, keyword)
+}
+
+/// Whether `tag`, without its `#`, is a valid tag: a symbol starting with a
+/// letter.
+pub(crate) fn is_valid_tag(tag: &str) -> bool {
+    tag.starts_with(|ch: char| ch.is_ascii_alphabetic()) && is_valid_str(tag, false)
+}
+
+/// Whether a symbol, or a keyword without its `:`, with `namespace` and
+/// `name` reads back as itself once written. This allows more than
+/// `is_valid`, as Clojure does: keywords and the names of namespaced symbols
+/// may start with a digit, as in `:0` or `:user/1st`, and a namespaced name
+/// may be `/`, as in `clojure.core//`.
+pub(crate) fn is_readable(namespace: Option<&str>, name: &str, keyword: bool) -> bool {
+    let readable =
+        |part: &str| !part.is_empty() && part.chars().all(|ch| ch != '/' && is_symbol_tail(ch));
+    match namespace {
+        None if name == "/" => true,
+        None if keyword => !name.starts_with(':') && readable(name),
+        None => is_valid_start(name) && !is_literal(name) && readable(name),
+        Some(namespace) => {
+            let start = if keyword {
+                !namespace.starts_with(':')
+            } else {
+                is_valid_start(namespace)
+            };
+            start && readable(namespace) && (name == "/" || readable(name))
+        }
+    }
+}
+
+fn is_valid_part(part: &str) -> bool {
+    is_valid_start(part) && part.chars().all(|ch| ch != '/' && is_symbol_tail(ch))
+}
+
+/// Whether `part` starts like a symbol rather than a number.
+fn is_valid_start(part: &str) -> bool {
+    let mut chars = part.chars();
+    match (chars.next(), chars.next()) {
+        (Some('-'), Some(second)) | (Some('+'), Some(second)) | (Some('.'), Some(second)) => {
+            !second.is_ascii_digit()
+        }
+        (Some(first), _) => is_symbol_head(first),
+        (None, _) => false,
+    }
+}
+
+fn is_literal(name: &str) -> bool {
+    name == "nil" || name == "true" || name == "false"
+}
+
+/// Split `ns/name` at the first `/`. A lone `/`, or a `/` at either end, is
+/// part of the name.
+pub(crate) fn split(str: &str) -> (Option<String>, String) {
+    let (namespace, name) = split_ref(str);
+    (namespace.map(String::from), name.into())
+}
+
+/// `str` split into its namespace, if any, and name, without copying them.
+pub(crate) fn split_ref(str: &str) -> (Option<&str>, &str) {
+    match str.find('/') {
+        Some(index) if index > 0 && index < str.len() - 1 => {
+            (Some(&str[..index]), &str[index + 1..])
+        }
+        _ => (None, str),
+    }
+}
+
+impl<'a> From<&'a str> for Symbol {
+    fn from(s: &'a str) -> Self {
+        let (namespace, name) = split(s);
+        Symbol { namespace, name }
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(s: String) -> Self {
+        Symbol::from(&s[..])
+    }
+}
+
+impl FromStr for Symbol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Symbol, String> {
+        if s.is_empty() {
+            Err("empty symbol".into())
+        } else {
+            Ok(Symbol::from(s))
+        }
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.namespace {
+            Some(ref namespace) => write!(f, "{}/{}", namespace, self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Tagged elements with a typed value, e.g. `#geo/point [1.5 2.5]` read as
+//! a `TaggedValue<Vec<f64>>`.
+//!
+//! ```
+//! use edn::keys::FromValue;
+//! use edn::tagged::TaggedValue;
+//!
+//! let value = edn::parser::from_str("#geo/point [1.5 2.5]").unwrap();
+//! let point = TaggedValue::<Vec<f64>>::from_value(&value).unwrap();
+//! assert_eq!((point.tag.as_str(), point.value), ("geo/point", vec![1.5, 2.5]));
+//! assert!(TaggedValue::<Vec<f64>>::with_tag(&value, "geo/line").is_err());
+//! ```
+
+use keys::{Error, FromValue};
+use {TypeError, Value};
+
+/// A tagged element whose value has been converted to `T`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaggedValue<T> {
+    pub tag: String,
+    pub value: T,
+}
+
+impl<T> TaggedValue<T> {
+    pub fn new(tag: &str, value: T) -> TaggedValue<T> {
+        TaggedValue {
+            tag: tag.into(),
+            value,
+        }
+    }
+}
+
+impl<T: FromValue> TaggedValue<T> {
+    /// Read a tagged element only if it has the tag `tag`.
+    pub fn with_tag(value: &Value, tag: &str) -> Result<TaggedValue<T>, Error> {
+        match *value {
+            Value::Tagged(ref t, ref inner) if t == tag => match T::from_value(inner) {
+                Ok(inner) => Ok(TaggedValue::new(tag, inner)),
+                Err(err) => Err(Error {
+                    message: format!("#{}: {}", tag, err),
+                }),
+            },
+            Value::Tagged(ref t, _) => Err(Error {
+                message: format!("expected #{}, found #{}", tag, t),
+            }),
+            ref value => Err(Error {
+                message: format!("expected #{}, found {}", tag, value.kind()),
+            }),
+        }
+    }
+}
+
+/// Any tag, so that a `TaggedValue` can be an entry read by `edn_keys!`.
+impl<T: FromValue> FromValue for TaggedValue<T> {
+    fn from_value(value: &Value) -> Result<TaggedValue<T>, TypeError> {
+        match *value {
+            Value::Tagged(ref tag, ref inner) => Ok(TaggedValue::new(tag, T::from_value(inner)?)),
+            ref value => Err(TypeError {
+                expected: "a tagged element",
+                found: value.kind(),
+            }),
+        }
+    }
+}
+
+impl<T: Into<Value>> From<TaggedValue<T>> for Value {
+    fn from(tagged: TaggedValue<T>) -> Value {
+        Value::Tagged(tagged.tag, Box::new(tagged.value.into()))
+    }
+}
+
+/// A Rust type which is written as a tagged element wrapping its plain
+/// value, so that e.g. a `Money` is always written as
+/// `#my.app/money {:amount 100 :currency :usd}`. Use `to_value` in its
+/// `From` impl, or wherever it's put in a larger value.
+///
+/// ```
+/// use edn::tagged::{self, TaggedSerialize};
+/// use edn::{Keyword, Value};
+///
+/// struct Money {
+///     amount: i64,
+///     currency: &'static str,
+/// }
+///
+/// impl TaggedSerialize for Money {
+///     const TAG: &'static str = "my.app/money";
+///
+///     fn to_untagged(&self) -> Value {
+///         Value::map(vec![
+///             (Keyword::new("amount"), Value::from(self.amount)),
+///             (Keyword::new("currency"), Keyword::new(self.currency).into()),
+///         ])
+///     }
+/// }
+///
+/// let price = Money { amount: 100, currency: "usd" };
+/// assert_eq!(
+///     edn::to_string(&tagged::to_value(&price)),
+///     "#my.app/money {:amount 100, :currency :usd}"
+/// );
+/// ```
+pub trait TaggedSerialize {
+    /// The tag, without its `#`.
+    const TAG: &'static str;
+
+    /// The value written after the tag.
+    fn to_untagged(&self) -> Value;
+}
+
+/// `value` as a tagged element with its `TaggedSerialize::TAG`.
+pub fn to_value<T: TaggedSerialize + ?Sized>(value: &T) -> Value {
+    Value::Tagged(T::TAG.into(), Box::new(value.to_untagged()))
+}
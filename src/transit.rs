@@ -0,0 +1,419 @@
+//! Reading and writing transit-json, the wire format most Clojure services
+//! use instead of raw EDN.
+//!
+//! Keywords, symbols, chars and numbers which don't fit in JSON are written
+//! as `~` prefixed strings, lists, sets and maps with non-string keys as
+//! `["~#tag", ...]` arrays, and repeated keywords, symbols, tags and map keys
+//! are replaced by cache codes like `^0`. `#uuid` and `#inst` elements are
+//! written as transit's `~u` and `~t` strings.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{self, Number};
+
+use {Keyword, Symbol, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Set, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Set, Vec};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+const MAP: &str = "^ ";
+const CACHE_SIZE: usize = 44 * 44;
+/// Integers outside this range lose precision as JavaScript numbers.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+fn cacheable(str: &str, as_key: bool) -> bool {
+    str.len() > 3
+        && (as_key || str.starts_with("~:") || str.starts_with("~$") || str.starts_with("~#"))
+}
+
+fn cache_code(index: usize) -> String {
+    let digit = |n: usize| (b'0' + n as u8) as char;
+    if index < 44 {
+        format!("^{}", digit(index))
+    } else {
+        format!("^{}{}", digit(index / 44), digit(index % 44))
+    }
+}
+
+fn cache_index(code: &str) -> Option<usize> {
+    let digits: ::std::vec::Vec<usize> = code[1..]
+        .bytes()
+        .map(|byte| byte.wrapping_sub(b'0') as usize)
+        .collect();
+    match digits[..] {
+        [a] if a < 44 => Some(a),
+        [a, b] if a < 44 && b < 44 => Some(a * 44 + b),
+        _ => None,
+    }
+}
+
+/// Write `value` as transit-json.
+pub fn to_string(value: &Value) -> String {
+    let mut writer = Writer {
+        cache: HashMap::new(),
+    };
+    let json = match writer.encode(value) {
+        serde_json::Value::Array(items) => serde_json::Value::Array(items),
+        scalar => serde_json::Value::Array(vec!["~#'".into(), scalar]),
+    };
+    json.to_string()
+}
+
+struct Writer {
+    cache: HashMap<String, usize>,
+}
+
+impl Writer {
+    fn string(&mut self, str: String, as_key: bool) -> serde_json::Value {
+        if !cacheable(&str, as_key) {
+            return serde_json::Value::String(str);
+        }
+        if let Some(&index) = self.cache.get(&str) {
+            return serde_json::Value::String(cache_code(index));
+        }
+        if self.cache.len() == CACHE_SIZE {
+            self.cache.clear();
+        }
+        let index = self.cache.len();
+        self.cache.insert(str.clone(), index);
+        serde_json::Value::String(str)
+    }
+
+    fn tagged(&mut self, tag: &str, value: &Value) -> serde_json::Value {
+        let tag = self.string(format!("~#{}", tag), false);
+        serde_json::Value::Array(vec![tag, self.encode(value)])
+    }
+
+    fn items<'a, I>(&mut self, items: I) -> serde_json::Value
+    where
+        I: Iterator<Item = &'a Value>,
+    {
+        serde_json::Value::Array(items.map(|item| self.encode(item)).collect())
+    }
+
+    fn encode(&mut self, value: &Value) -> serde_json::Value {
+        match *value {
+            Value::Nil => serde_json::Value::Null,
+            Value::Boolean(boolean) => serde_json::Value::Bool(boolean),
+            Value::Integer(integer) if integer.abs() <= MAX_SAFE_INTEGER => integer.into(),
+            Value::Float(float) if float.is_finite() => {
+                Number::from_f64(float.into_inner()).unwrap().into()
+            }
+            Value::List(ref items) => {
+                let tag = self.string("~#list".into(), false);
+                serde_json::Value::Array(vec![tag, self.items(items.iter())])
+            }
+            Value::Vector(ref items) => self.items(items.iter()),
+            Value::Set(ref items) => {
+                let tag = self.string("~#set".into(), false);
+                serde_json::Value::Array(vec![tag, self.items(items.iter())])
+            }
+            Value::Map(ref map) if map.keys().all(|key| scalar(key).is_some()) => {
+                let mut items = vec![MAP.into()];
+                for (key, value) in map.iter() {
+                    items.push(self.string(scalar(key).unwrap(), true));
+                    items.push(self.encode(value));
+                }
+                serde_json::Value::Array(items)
+            }
+            Value::Map(ref map) => {
+                let tag = self.string("~#cmap".into(), false);
+                let mut items = vec![];
+                for (key, value) in map.iter() {
+                    items.push(self.encode(key));
+                    items.push(self.encode(value));
+                }
+                serde_json::Value::Array(vec![tag, serde_json::Value::Array(items)])
+            }
+            #[cfg(feature = "ratio")]
+            Value::Ratio(numerator, denominator) => {
+                let tag = self.string("~#ratio".into(), false);
+                let parts = vec![
+                    self.string(format!("~n{}", numerator), false),
+                    self.string(format!("~n{}", denominator), false),
+                ];
+                serde_json::Value::Array(vec![tag, serde_json::Value::Array(parts)])
+            }
+            Value::WithMeta(ref value, _) => self.encode(value),
+            _ => match scalar(value) {
+                Some(str) => self.string(str, false),
+                None => match *value {
+                    Value::Tagged(ref tag, ref value) => self.tagged(tag, value),
+                    _ => unreachable!(),
+                },
+            },
+        }
+    }
+}
+
+/// The string form of a scalar, which is also how it's written as a map key.
+fn scalar(value: &Value) -> Option<String> {
+    Some(match *value {
+        Value::Nil => "~_".into(),
+        Value::Boolean(boolean) => format!("~?{}", if boolean { 't' } else { 'f' }),
+        Value::String(ref string) => match string.chars().next() {
+            Some('~') | Some('^') | Some('`') => format!("~{}", string),
+            _ => string.clone(),
+        },
+        Value::Char(ch) => format!("~c{}", ch),
+        Value::Symbol(ref symbol) => format!("~${}", symbol),
+        Value::Keyword(ref keyword) => format!("~{}", keyword),
+        Value::Integer(integer) => format!("~i{}", integer),
+        #[cfg(feature = "bigint")]
+        Value::BigInt(ref integer) => format!("~n{}", integer),
+        Value::Float(float) if float.is_nan() => "~zNaN".into(),
+        Value::Float(float) if float.is_infinite() && float.into_inner() > 0.0 => "~zINF".into(),
+        Value::Float(float) if float.is_infinite() => "~z-INF".into(),
+        Value::Float(float) => format!("~d{}", float),
+        #[cfg(feature = "bigdecimal")]
+        Value::Decimal(ref decimal) => format!("~f{}", decimal.to_plain_string()),
+        Value::Tagged(ref tag, ref value) => match (&tag[..], &**value) {
+            ("uuid", Value::String(string)) => format!("~u{}", string),
+            ("inst", Value::String(string)) => format!("~t{}", string),
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// Read a value from transit-json.
+pub fn from_str(str: &str) -> Result<Value, Error> {
+    let json: serde_json::Value = match serde_json::from_str(str) {
+        Ok(json) => json,
+        Err(err) => return error(err.to_string()),
+    };
+    Reader { cache: vec![] }.decode(json, false)
+}
+
+struct Reader {
+    cache: ::std::vec::Vec<String>,
+}
+
+impl Reader {
+    /// `str` with cache codes replaced, caching it if it's cacheable.
+    fn resolve(&mut self, str: String, as_key: bool) -> Result<String, Error> {
+        if str.starts_with('^') && str != MAP {
+            return match cache_index(&str).and_then(|index| self.cache.get(index)) {
+                Some(cached) => Ok(cached.clone()),
+                None => error(format!("unknown cache code `{}`", str)),
+            };
+        }
+        if cacheable(&str, as_key) {
+            if self.cache.len() == CACHE_SIZE {
+                self.cache.clear();
+            }
+            self.cache.push(str.clone());
+        }
+        Ok(str)
+    }
+
+    fn decode(&mut self, json: serde_json::Value, as_key: bool) -> Result<Value, Error> {
+        Ok(match json {
+            serde_json::Value::Null => Value::Nil,
+            serde_json::Value::Bool(boolean) => Value::Boolean(boolean),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(integer) => Value::Integer(integer),
+                None => Value::from(number.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(str) => {
+                let str = self.resolve(str, as_key)?;
+                parse_string(&str)?
+            }
+            serde_json::Value::Array(items) => {
+                let mut items = items.into_iter();
+                let first = match items.next() {
+                    Some(serde_json::Value::String(first)) => self.resolve(first, false)?,
+                    Some(first) => {
+                        let first = self.decode(first, false)?;
+                        return self.vector(Some(first), items);
+                    }
+                    None => return Ok(Value::Vector(Vec::new())),
+                };
+                if first == MAP {
+                    let mut map = Map::new();
+                    while let Some(key) = items.next() {
+                        let key = self.decode(key, true)?;
+                        match items.next() {
+                            Some(value) => map.insert(key, self.decode(value, false)?),
+                            None => return error("odd number of items in a map".into()),
+                        };
+                    }
+                    return Ok(Value::Map(map));
+                }
+                match first.strip_prefix("~#") {
+                    Some(tag) if items.len() == 1 => {
+                        let value = self.decode(items.next().unwrap(), false)?;
+                        tagged(tag, value)?
+                    }
+                    _ => {
+                        let first = parse_string(&first)?;
+                        self.vector(Some(first), items)?
+                    }
+                }
+            }
+            serde_json::Value::Object(object) => {
+                let mut map = Map::new();
+                for (key, value) in object {
+                    let key = self.decode(serde_json::Value::String(key), true)?;
+                    map.insert(key, self.decode(value, false)?);
+                }
+                Value::Map(map)
+            }
+        })
+    }
+
+    fn vector<I>(&mut self, first: Option<Value>, rest: I) -> Result<Value, Error>
+    where
+        I: Iterator<Item = serde_json::Value>,
+    {
+        let mut items: ::std::vec::Vec<Value> = first.into_iter().collect();
+        for item in rest {
+            items.push(self.decode(item, false)?);
+        }
+        Ok(Value::Vector(Vec::from(items)))
+    }
+}
+
+fn tagged(tag: &str, value: Value) -> Result<Value, Error> {
+    let items = |value: Value| match value {
+        Value::Vector(items) => Ok(items),
+        _ => error(format!("expected an array for `~#{}`", tag)),
+    };
+    Ok(match tag {
+        "'" => value,
+        "list" => Value::List(items(value)?),
+        "set" => Value::Set(items(value)?.into_iter().collect::<Set<_>>()),
+        "cmap" => {
+            let items = items(value)?;
+            if items.len() % 2 != 0 {
+                return error("odd number of items in a map".into());
+            }
+            let mut map = Map::new();
+            let mut items = items.into_iter();
+            while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                map.insert(key, value);
+            }
+            Value::Map(map)
+        }
+        #[cfg(feature = "ratio")]
+        "ratio" => match items(value)?
+            .iter()
+            .map(Value::as_i64)
+            .collect::<Option<::std::vec::Vec<_>>>()
+        {
+            Some(ref parts) if parts.len() == 2 && parts[1] >= 0 => {
+                match Value::ratio(parts[0], parts[1] as u64) {
+                    Some(ratio) => ratio,
+                    None => return error("ratio with a zero denominator".into()),
+                }
+            }
+            _ => return error("expected a numerator and denominator for `~#ratio`".into()),
+        },
+        _ => Value::Tagged(tag.into(), Box::new(value)),
+    })
+}
+
+fn parse_string(str: &str) -> Result<Value, Error> {
+    let rest = match str.strip_prefix('~') {
+        Some(rest) => rest,
+        None => return Ok(Value::String(str.into())),
+    };
+    let mut chars = rest.chars();
+    let tag = chars.next();
+    let body = chars.as_str();
+    let invalid = || error(format!("invalid transit string `{}`", str));
+    Ok(match tag {
+        Some('~') | Some('^') | Some('`') => Value::String(rest.into()),
+        Some('_') => Value::Nil,
+        Some('?') => Value::Boolean(body == "t"),
+        Some(':') => Value::Keyword(Keyword::from(body)),
+        Some('$') => Value::Symbol(Symbol::from(body)),
+        Some('c') => match body.chars().next() {
+            Some(ch) if ch.len_utf8() == body.len() => Value::Char(ch),
+            _ => return invalid(),
+        },
+        Some('i') | Some('n') => match body.parse::<i64>() {
+            Ok(integer) => Value::Integer(integer),
+            #[cfg(feature = "bigint")]
+            Err(_) => match body.parse::<::num_bigint::BigInt>() {
+                Ok(integer) => Value::BigInt(integer),
+                Err(_) => return invalid(),
+            },
+            #[cfg(not(feature = "bigint"))]
+            Err(_) => return invalid(),
+        },
+        Some('d') => match body.parse::<f64>() {
+            Ok(float) => Value::from(float),
+            Err(_) => return invalid(),
+        },
+        #[cfg(feature = "bigdecimal")]
+        Some('f') => match Value::from_decimal_str(body) {
+            Some(decimal) => decimal,
+            None => return invalid(),
+        },
+        #[cfg(not(feature = "bigdecimal"))]
+        Some('f') => match body.parse::<f64>() {
+            Ok(float) => Value::from(float),
+            Err(_) => return invalid(),
+        },
+        Some('z') => match body {
+            "NaN" => Value::from(f64::NAN),
+            "INF" => Value::from(f64::INFINITY),
+            "-INF" => Value::from(f64::NEG_INFINITY),
+            _ => return invalid(),
+        },
+        Some('u') => Value::Tagged("uuid".into(), Box::new(Value::String(body.into()))),
+        Some('t') => Value::Tagged("inst".into(), Box::new(Value::String(body.into()))),
+        Some('m') => match body.parse::<i64>() {
+            Ok(millis) => Value::Tagged("inst".into(), Box::new(Value::String(inst(millis)))),
+            Err(_) => return invalid(),
+        },
+        Some(tag) => Value::Tagged(tag.to_string(), Box::new(Value::String(body.into()))),
+        None => return invalid(),
+    })
+}
+
+/// Milliseconds since the epoch as an RFC 3339 timestamp in UTC, the way
+/// Clojure prints `#inst`s.
+fn inst(millis: i64) -> String {
+    let days = millis.div_euclid(86_400_000);
+    let millis = millis.rem_euclid(86_400_000);
+    // Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}-00:00",
+        year,
+        month,
+        day,
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
@@ -0,0 +1,25 @@
+use uuid::Uuid;
+
+use Value;
+
+impl From<Uuid> for Value {
+    fn from(s: Uuid) -> Self {
+        Value::Tagged(
+            "uuid".into(),
+            Box::new(Value::String(s.hyphenated().to_string())),
+        )
+    }
+}
+
+impl Value {
+    /// The UUID of a `#uuid "..."` tagged element.
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        match *self {
+            Value::Tagged(ref tag, ref value) if tag == "uuid" => match **value {
+                Value::String(ref string) => Uuid::parse_str(string).ok(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Checking that files parse, e.g. every `.edn` file in a repository as a
+//! CI step. Files are read and parsed on several threads at once.
+//!
+//! See `examples/validate.rs` for a command line front end.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use parser::{Category, Error, Parser};
+use {Keyword, Value};
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+/// The first error in a file. Parsing stops there, as the parser can't know
+/// where the next form starts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    /// The number of files checked.
+    pub files: usize,
+    /// One diagnostic for every file with an error, in the order the files
+    /// were given.
+    pub diagnostics: ::std::vec::Vec<Diagnostic>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// The report as EDN, e.g. for other tools to read:
+    /// `{:files 2 :errors [{:file "a.edn" :line 1 :column 4 :lo 3 :hi 4
+    /// :category :syntax :message "..."}]}`.
+    pub fn to_value(&self) -> Value {
+        let entry = |key: &str, value: Value| (Value::Keyword(Keyword::from(key)), value);
+        let errors = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let error = &diagnostic.error;
+                let category = match error.category {
+                    Category::Syntax => "syntax",
+                    Category::Eof => "eof",
+                    Category::Data => "data",
+                    Category::Io => "io",
+                };
+                Value::Map(
+                    vec![
+                        entry("file", Value::String(diagnostic.path.display().to_string())),
+                        entry("line", Value::Integer(error.line as i64)),
+                        entry("column", Value::Integer(error.column as i64)),
+                        entry("lo", Value::Integer(error.lo as i64)),
+                        entry("hi", Value::Integer(error.hi as i64)),
+                        entry("category", Value::Keyword(Keyword::from(category))),
+                        entry("message", Value::String(error.message.clone())),
+                    ]
+                    .into_iter()
+                    .collect::<Map<_, _>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        Value::Map(
+            vec![
+                entry("files", Value::Integer(self.files as i64)),
+                entry("errors", Value::Vector(errors)),
+            ]
+            .into_iter()
+            .collect::<Map<_, _>>(),
+        )
+    }
+}
+
+/// The first error in `str`, which may contain any number of forms.
+pub fn check_str(str: &str) -> Option<Error> {
+    Parser::new(str).into_stream().find_map(Result::err)
+}
+
+fn check_file(path: &Path) -> Option<Error> {
+    match fs::read_to_string(path) {
+        Ok(str) => check_str(&str),
+        Err(err) => Some(Error {
+            lo: 0,
+            hi: 0,
+            line: 1,
+            column: 1,
+            category: Category::Io,
+            message: err.to_string(),
+        }),
+    }
+}
+
+/// Check every file in `paths`, spread over as many threads as there are
+/// CPUs.
+pub fn check_files(paths: &[PathBuf]) -> Report {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = paths.len().div_ceil(threads).max(1);
+    let results: ::std::vec::Vec<Option<Error>> = thread::scope(|scope| {
+        let handles: ::std::vec::Vec<_> = paths
+            .chunks(chunk)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|path| check_file(path))
+                        .collect::<::std::vec::Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("checking a file panicked"))
+            .collect()
+    });
+    Report {
+        files: paths.len(),
+        diagnostics: paths
+            .iter()
+            .zip(results)
+            .filter_map(|(path, error)| {
+                error.map(|error| Diagnostic {
+                    path: path.clone(),
+                    error,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// The `.edn` files under `dir`, sorted.
+pub fn edn_files(dir: &Path) -> io::Result<::std::vec::Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "edn") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Check every `.edn` file under `dir`.
+pub fn check_dir(dir: &Path) -> io::Result<Report> {
+    Ok(check_files(&edn_files(dir)?))
+}
@@ -0,0 +1,77 @@
+use Value;
+
+/// Call `f` on `value` and then on each value inside it.
+fn walk<F: FnMut(&Value)>(value: &Value, f: &mut F) {
+    f(value);
+    match *value {
+        Value::List(ref items) | Value::Vector(ref items) => {
+            for item in items.iter() {
+                walk(item, f);
+            }
+        }
+        Value::Map(ref map) => {
+            for (key, value) in map.iter() {
+                walk(key, f);
+                walk(value, f);
+            }
+        }
+        Value::Set(ref items) => {
+            for item in items.iter() {
+                walk(item, f);
+            }
+        }
+        Value::Tagged(_, ref value) | Value::WithMeta(ref value, _) => walk(value, f),
+        _ => {}
+    }
+}
+
+/// `value` with `f` applied to each value inside it, and then to the result.
+fn postwalk<F: FnMut(Value) -> Value>(value: Value, f: &mut F) -> Value {
+    let value = children(value, |child| postwalk(child, f));
+    f(value)
+}
+
+/// `f` applied to `value`, and then to each value inside the result.
+fn prewalk<F: FnMut(Value) -> Value>(value: Value, f: &mut F) -> Value {
+    let value = f(value);
+    children(value, |child| prewalk(child, f))
+}
+
+/// `value` with `f` applied to each of its immediate children.
+fn children<F: FnMut(Value) -> Value>(value: Value, mut f: F) -> Value {
+    match value {
+        Value::List(items) => Value::List(items.into_iter().map(f).collect()),
+        Value::Vector(items) => Value::Vector(items.into_iter().map(f).collect()),
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(key, value)| (f(key), f(value)))
+                .collect(),
+        ),
+        Value::Set(items) => Value::Set(items.into_iter().map(f).collect()),
+        Value::Tagged(tag, value) => Value::Tagged(tag, Box::new(f(*value))),
+        Value::WithMeta(value, meta) => Value::WithMeta(Box::new(f(*value)), meta),
+        value => value,
+    }
+}
+
+impl Value {
+    /// Call `f` on this value and every value inside it, parents before
+    /// children. Map keys are visited as well as values, but not metadata.
+    pub fn walk<F: FnMut(&Value)>(&self, mut f: F) {
+        walk(self, &mut f)
+    }
+
+    /// Rebuild this value bottom up, like Clojure's `postwalk`: `f` is
+    /// applied to every value inside it, and then to the collection holding
+    /// the results. Map keys are transformed too; if two become equal, the
+    /// later entry wins. Metadata is left alone.
+    pub fn transform<F: FnMut(Value) -> Value>(self, mut f: F) -> Value {
+        postwalk(self, &mut f)
+    }
+
+    /// Rebuild this value top down, like Clojure's `prewalk`: `f` is applied
+    /// to this value, and then to every value inside what it returns.
+    pub fn transform_pre<F: FnMut(Value) -> Value>(self, mut f: F) -> Value {
+        prewalk(self, &mut f)
+    }
+}
@@ -0,0 +1,295 @@
+//! Conversions between `Value` and JavaScript values, and `parse_edn` and
+//! `write_edn` for JavaScript through `wasm-bindgen`.
+//!
+//! Build for the browser with
+//! `wasm-pack build --target web -- --features wasm`.
+//!
+//! ```js
+//! import init, { parse_edn, write_edn } from "./pkg/edn.js";
+//!
+//! await init();
+//! const value = parse_edn('[:user/id #{1 2} #inst "2020-01-01"]');
+//! // [{"#kw": "user/id"}, Set {1, 2}, {"#tag": ["inst", "2020-01-01"]}]
+//! write_edn(value);
+//! // '[:user/id #{1 2} #inst "2020-01-01"]'
+//! ```
+//!
+//! `Map` keys are compared by identity, so look up keyword keys by iterating
+//! over a map's entries.
+//!
+//! Values are converted like `json::to_lossless_json`, except for values
+//! JavaScript has a type for:
+//!
+//! - `nil` is `null`, and booleans, strings and floats are their JavaScript
+//!   equivalents
+//! - integers are numbers, or `BigInt`s outside `Number.MAX_SAFE_INTEGER`,
+//!   as are `N` integers with the `bigint` feature
+//! - vectors are arrays, maps are `Map`s and sets are `Set`s
+//! - every other value is an object with a single key naming its kind:
+//!   `{"#kw": "user/id"}`, `{"#sym": "inc"}`, `{"#char": "a"}`,
+//!   `{"#list": [...]}`, `{"#tag": ["inst", value]}`,
+//!   `{"#meta": [map, value]}`, and `{"#decimal": "1.5"}` and
+//!   `{"#ratio": [22, 7]}` with those features
+//!
+//! `from_js` also reads any other object as a map with string keys, so
+//! `write_edn({a: 1})` is `{"a" 1}`.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use js_sys::{Array, BigInt, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use parser;
+use ser;
+use Value;
+
+#[cfg(feature = "immutable")]
+use immutable::{Map, Vec};
+#[cfg(not(feature = "immutable"))]
+use standard::{Map, Vec};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Error {
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn error<T>(message: String) -> Result<T, Error> {
+    Err(Error { message })
+}
+
+/// The largest integer a JavaScript number holds exactly.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Parse one EDN form. Throws an `Error` with the parser's message if `edn`
+/// isn't valid.
+#[wasm_bindgen]
+pub fn parse_edn(edn: &str) -> Result<JsValue, JsError> {
+    match parser::from_str(edn) {
+        Ok(value) => Ok(to_js(&value)),
+        Err(err) => Err(JsError::new(&err.to_string())),
+    }
+}
+
+/// `value` as EDN. Throws an `Error` if it can't be converted.
+#[wasm_bindgen]
+pub fn write_edn(value: JsValue) -> Result<String, JsError> {
+    match from_js(&value) {
+        Ok(value) => Ok(ser::to_string(&value)),
+        Err(err) => Err(JsError::new(&err.message)),
+    }
+}
+
+fn wrap(key: &str, value: &JsValue) -> JsValue {
+    let object = Object::new();
+    Reflect::set(&object, &key.into(), value).expect("objects are extensible");
+    object.into()
+}
+
+fn array<'a, I: Iterator<Item = &'a Value>>(items: I) -> JsValue {
+    let array = Array::new();
+    for item in items {
+        array.push(&to_js(item));
+    }
+    array.into()
+}
+
+/// `value` as a JavaScript value.
+pub fn to_js(value: &Value) -> JsValue {
+    match *value {
+        Value::Nil => JsValue::NULL,
+        Value::Boolean(boolean) => JsValue::from_bool(boolean),
+        Value::String(ref string) => JsValue::from_str(string),
+        Value::Char(ch) => wrap("#char", &ch.to_string().into()),
+        Value::Symbol(ref symbol) => wrap("#sym", &symbol.to_string().into()),
+        Value::Keyword(ref keyword) => wrap("#kw", &keyword.to_string()[1..].into()),
+        Value::Integer(integer) if integer.abs() <= MAX_SAFE_INTEGER => {
+            JsValue::from_f64(integer as f64)
+        }
+        Value::Integer(integer) => BigInt::from(integer).into(),
+        #[cfg(feature = "bigint")]
+        Value::BigInt(ref integer) => match integer.to_string().parse::<BigInt>() {
+            Ok(integer) => integer.into(),
+            Err(_) => unreachable!(),
+        },
+        #[cfg(feature = "ratio")]
+        Value::Ratio(numerator, denominator) => wrap(
+            "#ratio",
+            &array(
+                [
+                    Value::Integer(numerator),
+                    Value::Integer(denominator as i64),
+                ]
+                .iter(),
+            ),
+        ),
+        Value::Float(float) => JsValue::from_f64(float.into_inner()),
+        #[cfg(feature = "bigdecimal")]
+        Value::Decimal(ref decimal) => wrap("#decimal", &decimal.to_string().into()),
+        Value::List(ref items) => wrap("#list", &array(items.iter())),
+        Value::Vector(ref items) => array(items.iter()),
+        Value::Set(ref items) => {
+            let set = js_sys::Set::new(&JsValue::UNDEFINED);
+            for item in items.iter() {
+                set.add(&to_js(item));
+            }
+            set.into()
+        }
+        Value::Map(ref map) => {
+            let object = js_sys::Map::new();
+            for (key, value) in map.iter() {
+                object.set(&to_js(key), &to_js(value));
+            }
+            object.into()
+        }
+        Value::Tagged(ref tag, ref value) => wrap(
+            "#tag",
+            &array([Value::String(tag.clone()), (**value).clone()].iter()),
+        ),
+        Value::WithMeta(ref value, ref meta) => wrap(
+            "#meta",
+            &array([Value::Map(meta.clone()), (**value).clone()].iter()),
+        ),
+    }
+}
+
+/// A JavaScript value as a `Value`.
+pub fn from_js(js: &JsValue) -> Result<Value, Error> {
+    if js.is_null() || js.is_undefined() {
+        return Ok(Value::Nil);
+    }
+    if let Some(boolean) = js.as_bool() {
+        return Ok(Value::Boolean(boolean));
+    }
+    if let Some(string) = js.as_string() {
+        return Ok(Value::String(string));
+    }
+    if let Some(number) = js.as_f64() {
+        return Ok(
+            if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER as f64 {
+                Value::Integer(number as i64)
+            } else {
+                Value::from(number)
+            },
+        );
+    }
+    if let Some(integer) = js.dyn_ref::<BigInt>() {
+        return big_integer(integer);
+    }
+    if let Some(array) = js.dyn_ref::<Array>() {
+        return Ok(Value::Vector(items(array)?.into_iter().collect()));
+    }
+    if let Some(set) = js.dyn_ref::<js_sys::Set>() {
+        let mut items = vec![];
+        set.for_each(&mut |item, _, _| items.push(from_js(&item)));
+        return Ok(Value::Set(items.into_iter().collect::<Result<_, _>>()?));
+    }
+    if let Some(object) = js.dyn_ref::<js_sys::Map>() {
+        let mut entries = vec![];
+        object.for_each(&mut |value, key| entries.push((from_js(&key), from_js(&value))));
+        let mut map = Map::new();
+        for (key, value) in entries {
+            map.insert(key?, value?);
+        }
+        return Ok(Value::Map(map));
+    }
+    match js.dyn_ref::<Object>() {
+        Some(object) if !js.is_function() => from_object(object),
+        _ => error(format!(
+            "{} has no EDN equivalent",
+            js.js_typeof().as_string().unwrap_or_default()
+        )),
+    }
+}
+
+fn big_integer(integer: &BigInt) -> Result<Value, Error> {
+    match i64::try_from(integer.clone()) {
+        Ok(integer) => Ok(Value::Integer(integer)),
+        #[cfg(feature = "bigint")]
+        Err(integer) => match String::from(integer.to_string(10).unwrap()).parse() {
+            Ok(integer) => Ok(Value::BigInt(integer)),
+            Err(_) => unreachable!(),
+        },
+        #[cfg(not(feature = "bigint"))]
+        Err(_) => error("BigInt is out of range".into()),
+    }
+}
+
+fn items(array: &Array) -> Result<::std::vec::Vec<Value>, Error> {
+    array.iter().map(|item| from_js(&item)).collect()
+}
+
+/// A single key object as written by `to_js`, or any other object as a map
+/// with string keys.
+fn from_object(object: &Object) -> Result<Value, Error> {
+    let entries = Object::entries(object);
+    let entry: Array = entries.get(0).unchecked_into();
+    let kind = match entry.get(0).as_string() {
+        Some(kind) if entries.length() == 1 && kind.starts_with('#') => kind,
+        _ => {
+            let mut map = Map::new();
+            for entry in entries.iter() {
+                let entry: Array = entry.unchecked_into();
+                map.insert(from_js(&entry.get(0))?, from_js(&entry.get(1))?);
+            }
+            return Ok(Value::Map(map));
+        }
+    };
+    let body = entry.get(1);
+    let string = || match body.as_string() {
+        Some(string) => Ok(string),
+        None => error(format!("expected a string for `{}`", kind)),
+    };
+    let items = || match body.dyn_ref::<Array>() {
+        Some(array) => items(array),
+        None => error(format!("expected an array for `{}`", kind)),
+    };
+    let pair = || match items()? {
+        ref items if items.len() == 2 => Ok((items[0].clone(), items[1].clone())),
+        _ => error(format!("expected two items for `{}`", kind)),
+    };
+    Ok(match &kind[..] {
+        "#kw" => Value::Keyword(string()?[..].into()),
+        "#sym" => Value::Symbol(string()?[..].into()),
+        "#char" => {
+            let string = string()?;
+            let mut chars = string.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Value::Char(ch),
+                _ => return error("expected one character for `#char`".into()),
+            }
+        }
+        #[cfg(feature = "bigdecimal")]
+        "#decimal" => match string()?.parse() {
+            Ok(decimal) => Value::Decimal(decimal),
+            Err(_) => return error(format!("invalid decimal `{}`", string()?)),
+        },
+        #[cfg(feature = "ratio")]
+        "#ratio" => match pair()? {
+            (Value::Integer(numerator), Value::Integer(denominator)) if denominator > 0 => {
+                match Value::ratio(numerator, denominator as u64) {
+                    Some(ratio) => ratio,
+                    None => unreachable!(),
+                }
+            }
+            _ => return error("expected a numerator and denominator for `#ratio`".into()),
+        },
+        "#list" => Value::List(Vec::from(items()?)),
+        "#tag" => match pair()? {
+            (Value::String(tag), value) => Value::Tagged(tag, Box::new(value)),
+            _ => return error("expected a tag and a value for `#tag`".into()),
+        },
+        "#meta" => match pair()? {
+            (Value::Map(meta), value) => Value::WithMeta(Box::new(value), meta),
+            _ => return error("expected a map and a value for `#meta`".into()),
+        },
+        _ => return error(format!("unknown key `{}`", kind)),
+    })
+}
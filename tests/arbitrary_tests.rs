@@ -0,0 +1,102 @@
+#![cfg(feature = "arbitrary")]
+
+extern crate edn;
+
+use edn::arbitrary::{Arbitrary, Generator};
+use edn::{Keyword, Value};
+
+/// Bytes from a xorshift generator, so runs are repeatable.
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn depth(value: &Value) -> usize {
+    let items: Vec<&Value> = match *value {
+        Value::List(ref items) | Value::Vector(ref items) => items.iter().collect(),
+        Value::Set(ref items) => items.iter().collect(),
+        Value::Map(ref map) => map.iter().flat_map(|(k, v)| vec![k, v]).collect(),
+        Value::Tagged(_, ref value) => vec![&**value],
+        _ => return 0,
+    };
+    1 + items.into_iter().map(depth).max().unwrap_or(0)
+}
+
+fn len(value: &Value) -> usize {
+    match *value {
+        Value::List(ref items) | Value::Vector(ref items) => {
+            items.iter().map(len).max().unwrap_or(0).max(items.len())
+        }
+        Value::Set(ref items) => items.iter().map(len).max().unwrap_or(0).max(items.len()),
+        Value::Map(ref map) => map
+            .iter()
+            .map(|(k, v)| len(k).max(len(v)))
+            .max()
+            .unwrap_or(0)
+            .max(map.len()),
+        Value::Tagged(_, ref value) => len(value),
+        _ => 0,
+    }
+}
+
+fn has_nonzero_integer(value: &Value) -> bool {
+    match *value {
+        Value::Integer(integer) => integer != 0,
+        _ => value.shrink().iter().any(has_nonzero_integer),
+    }
+}
+
+#[test]
+fn test_generator() {
+    assert_eq!(Generator::new(&[]).value(), Value::List(vec![]));
+    assert_eq!(
+        Keyword::arbitrary(&mut Generator::new(&[0, 0, 0, 1, 0])),
+        Keyword::namespaced("a", "b")
+    );
+    for seed in 0..500 {
+        let bytes = bytes(seed, 512);
+        let value = Value::arbitrary(&mut Generator::new(&bytes));
+        assert_eq!(Generator::new(&bytes).value(), value);
+        assert!(depth(&value) <= 5);
+        assert!(len(&value) <= 4);
+
+        let value = Generator::new(&bytes).max_depth(1).max_len(20).value();
+        assert!(depth(&value) <= 2);
+        assert!(len(&value) <= 20);
+        assert!(depth(&Generator::new(&bytes).max_depth(0).value()) <= 1);
+    }
+}
+
+#[test]
+fn test_shrink() {
+    for seed in 0..200 {
+        let value = Generator::new(&bytes(seed, 256)).value();
+        let mut smaller = value.shrink();
+        // Shrinking terminates.
+        let mut steps = 0;
+        while let Some(first) = smaller.into_iter().next() {
+            smaller = first.shrink();
+            steps += 1;
+            assert!(steps < 1000);
+        }
+        for shrunk in value.shrink() {
+            assert_ne!(shrunk, value);
+        }
+    }
+
+    // Greedy shrinking of a value which fails a property.
+    let mut value = edn::parser::from_str("{:a [0 \"x\" #{:b 81}] :c (nil)}").unwrap();
+    while let Some(smaller) = value.shrink().into_iter().find(has_nonzero_integer) {
+        value = smaller;
+    }
+    assert_eq!(value, Value::Integer(1));
+    assert!(Value::Nil.shrink().is_empty());
+    assert!(Value::Vector(vec![]).shrink().is_empty());
+}
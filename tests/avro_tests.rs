@@ -0,0 +1,75 @@
+extern crate edn;
+
+use edn::avro::{self, Kind, Schema};
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_infer() {
+    let rows = parse(r#"[{:id 1 :name "a" :score 1.5 :tags [:x]} {:id 2 :score 2 :ok true :name nil}]"#);
+    let schema = Schema::infer("log-entry", &rows).unwrap();
+    assert_eq!(schema.name, "log_entry");
+    assert_eq!(
+        schema
+            .fields
+            .iter()
+            .map(|field| (&field.name[..], field.kind, field.nullable))
+            .collect::<Vec<_>>(),
+        vec![
+            ("id", Kind::Long, false),
+            ("name", Kind::String, true),
+            ("score", Kind::Double, false),
+            ("tags", Kind::String, true),
+            ("ok", Kind::Boolean, true),
+        ]
+    );
+    assert_eq!(
+        schema.to_json(),
+        r#"{"type":"record","name":"log_entry","fields":[{"name":"id","type":"long"},{"name":"name","type":["null","string"],"default":null},{"name":"score","type":"double"},{"name":"tags","type":["null","string"],"default":null},{"name":"ok","type":["null","boolean"],"default":null}]}"#
+    );
+
+    assert!(Schema::infer("x", &parse("[{:a 1} 2]")).is_err());
+    assert!(Schema::infer("x", &parse("[{:a/b 1 :c/b 2}]")).is_err());
+}
+
+#[test]
+fn test_to_writer() {
+    let rows = parse(r#"[{:id 1 :name "a" :tags [:x]} {:id -2}]"#);
+    let schema = Schema::infer("entry", &rows).unwrap();
+    let mut out = vec![];
+    avro::to_writer(&mut out, &schema, &rows).unwrap();
+
+    assert!(out.starts_with(b"Obj\x01"));
+    let json = schema.to_json();
+    let header = out
+        .windows(5)
+        .position(|window| window == b"null\x00")
+        .unwrap()
+        + 5
+        + 16;
+    assert!(out[..header]
+        .windows(json.len())
+        .any(|window| window == json.as_bytes()));
+    let sync = &out[header - 16..header];
+    // The same in every build.
+    assert_eq!(
+        sync,
+        &[190, 169, 76, 124, 56, 218, 253, 235, 149, 88, 110, 167, 213, 217, 230, 84]
+    );
+
+    let mut records = vec![2, 2, 2, b'a', 2, 8];
+    records.extend_from_slice(b"[:x]");
+    records.extend_from_slice(&[3, 0, 0]);
+    let mut block = vec![4, records.len() as u8 * 2];
+    block.extend_from_slice(&records);
+    block.extend_from_slice(sync);
+    assert_eq!(&out[header..], &block[..]);
+
+    let schema = Schema::infer("entry", &parse("[{:id 1}]")).unwrap();
+    assert!(avro::to_writer(vec![], &schema, &parse("[{:id 1.5}]")).is_err());
+    assert!(avro::to_writer(vec![], &schema, &parse("[{}]")).is_err());
+}
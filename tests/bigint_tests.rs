@@ -0,0 +1,81 @@
+#![cfg(all(feature = "bigint", not(feature = "immutable")))]
+
+extern crate edn;
+extern crate num_bigint;
+
+use edn::parser::{Category, Error, Parser, ParserBuilder};
+use edn::ser;
+use edn::Value;
+use num_bigint::BigInt;
+use std::convert::TryFrom;
+
+fn big(digits: &str) -> Value {
+    Value::BigInt(digits.parse().unwrap())
+}
+
+#[test]
+fn test_bigint() {
+    let mut parser = Parser::new("123N -4N +5N 123456789012345678901234567890N 7");
+    assert_eq!(parser.read(), Some(Ok(big("123"))));
+    assert_eq!(parser.read(), Some(Ok(big("-4"))));
+    assert_eq!(parser.read(), Some(Ok(big("5"))));
+    assert_eq!(
+        parser.read(),
+        Some(Ok(big("123456789012345678901234567890")))
+    );
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(7))));
+    assert_eq!(parser.read(), None);
+
+    assert_eq!(big("-4").as_bigint(), Some(BigInt::from(-4)));
+    assert_eq!(Value::Integer(7).as_bigint(), Some(BigInt::from(7)));
+    assert_eq!(Value::Nil.as_bigint(), None);
+}
+
+#[test]
+fn test_overflow() {
+    let mut parser = Parser::new(
+        "9223372036854775808 18446744073709551615 340282366920938463463374607431768211455 \
+         340282366920938463463374607431768211456",
+    );
+    assert_eq!(parser.read(), Some(Ok(big("9223372036854775808"))));
+    let max = parser.read().unwrap().unwrap();
+    assert_eq!((max.as_u64(), max.as_i64()), (Some(u64::MAX), None));
+    assert_eq!(max, u64::MAX);
+    let max = parser.read().unwrap().unwrap();
+    assert_eq!(u128::try_from(&max), Ok(u128::MAX));
+    assert!(i128::try_from(&max).is_err());
+    assert_eq!(Value::from(u128::MAX), max);
+    assert_eq!(Value::from(-5i128), Value::Integer(-5));
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 81,
+            hi: 120,
+            line: 1,
+            column: 82,
+            category: Category::Data,
+            message: "integer `340282366920938463463374607431768211456` out of range".into(),
+        }))
+    );
+    assert_eq!(big("-5").as_i64(), Some(-5));
+
+    let mut parser = ParserBuilder::new()
+        .promote_overflow(true)
+        .build("9223372036854775807 -9223372036854775809");
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(i64::MAX))));
+    assert_eq!(parser.read(), Some(Ok(big("-9223372036854775809"))));
+}
+
+#[test]
+fn test_round_trip() {
+    let value = Value::Vector(vec![
+        big("1"),
+        big("-99999999999999999999"),
+        Value::Integer(1),
+    ]);
+    let mut out = vec![];
+    ser::to_writer(&mut out, &value).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out, "[1N -99999999999999999999N 1]");
+    assert_eq!(Parser::new(&out).read(), Some(Ok(value)));
+}
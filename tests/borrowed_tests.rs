@@ -0,0 +1,71 @@
+extern crate edn;
+
+use std::borrow::Cow;
+
+use edn::borrowed::{parse_borrowed, Value};
+use edn::parser::{self, Category};
+
+#[test]
+fn test_parse_borrowed() {
+    let str = r#"{:id 7, :tags #{"a" "b\"c"}, :at #inst "2020-01-01", :big 1N :v ^:m [x]} ; end"#;
+    let value = parse_borrowed(str).unwrap();
+    assert_eq!(value.get(&Value::Keyword("id")).and_then(Value::as_i64), Some(7));
+    match value.get(&Value::Keyword("tags")) {
+        Some(Value::Set(items)) => {
+            assert!(matches!(items[0], Value::String(Cow::Borrowed("a"))));
+            assert_eq!(items[1], Value::String(Cow::Owned("b\"c".into())));
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(
+        value.get(&Value::Keyword("at")),
+        Some(&Value::Tagged(
+            "inst",
+            Box::new(Value::String("2020-01-01".into()))
+        ))
+    );
+    assert_eq!(value.get(&Value::Keyword("big")), Some(&Value::Number("1N")));
+    assert_eq!(
+        value.get(&Value::Keyword("v")),
+        Some(&Value::Vector(vec![Value::Symbol("x")]))
+    );
+    assert_eq!(value.get(&Value::Symbol("x")), None);
+
+    let expected = parser::from_str(str).unwrap();
+    assert_eq!(value.into_owned().unwrap(), expected);
+
+    let value = parse_borrowed(r#"(f \a "é" -2.5 ##Inf) #_ignored"#).unwrap();
+    assert_eq!(
+        value,
+        Value::List(vec![
+            Value::Symbol("f"),
+            Value::Char('a'),
+            Value::String("é".into()),
+            Value::Float(-2.5),
+            Value::Float(f64::INFINITY),
+        ])
+    );
+}
+
+#[test]
+fn test_parse_borrowed_errors() {
+    let err = parse_borrowed("{:a 1 :b}").unwrap_err();
+    assert_eq!(err.category, Category::Syntax);
+
+    let err = parse_borrowed("[1 2").unwrap_err();
+    assert_eq!(err.category, Category::Eof);
+
+    let err = parse_borrowed("[1 2)").unwrap_err();
+    assert_eq!(err.category, Category::Syntax);
+
+    for str in &["a/b/c", ":a/b/c", "foo/", ":", "::a", "#?(:clj 1)"] {
+        let err = parse_borrowed(str).unwrap_err();
+        assert_eq!(err.category, Category::Syntax, "{}", str);
+    }
+
+    assert!(parse_borrowed("1 2").is_err());
+    assert!(parse_borrowed("  ").is_err());
+
+    let deep = format!("{}{}", "[".repeat(1000), "]".repeat(1000));
+    assert!(parse_borrowed(&deep).is_err());
+}
@@ -0,0 +1,71 @@
+extern crate edn;
+
+use edn::bytes;
+use edn::parser::{Error, Parser};
+use edn::readers::{self, ReaderRegistry};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_base64() {
+    for &(bytes, base64) in &[
+        (&b""[..], ""),
+        (b"f", "Zg=="),
+        (b"fo", "Zm8="),
+        (b"foo", "Zm9v"),
+        (b"foob", "Zm9vYg=="),
+        (b"\x00\xff\xfe\x7f", "AP/+fw=="),
+    ] {
+        assert_eq!(bytes::to_base64(bytes), base64);
+        assert_eq!(bytes::from_base64(base64), Some(bytes.to_vec()));
+        assert_eq!(
+            bytes::from_base64(base64.trim_end_matches('=')),
+            Some(bytes.to_vec())
+        );
+    }
+    for invalid in &["Z", "Zg=", "Zg===", "Z===", "Zm9v-A==", "Zm 9v", "=Zg="] {
+        assert_eq!(bytes::from_base64(invalid), None, "{}", invalid);
+    }
+
+    let all: Vec<u8> = (0..=255).collect();
+    assert_eq!(bytes::from_base64(&bytes::to_base64(&all)), Some(all));
+}
+
+#[test]
+fn test_tagged() {
+    let value = bytes::encode(b"hello");
+    assert_eq!(edn::to_string(&value), "#edn.rs/base64 \"aGVsbG8=\"");
+    assert_eq!(
+        parse(&edn::to_string(&value)).as_bytes(),
+        Some(b"hello".to_vec())
+    );
+    assert_eq!(
+        parse("#edn.rs/base64 \"aGVsbG8\"").as_bytes(),
+        Some(b"hello".to_vec())
+    );
+    assert_eq!(parse("#edn.rs/base64 1").as_bytes(), None);
+    assert_eq!(parse("\"aGVsbG8=\"").as_bytes(), None);
+
+    let value = bytes::encode_tagged(b"hi", "bytes");
+    assert_eq!(edn::to_string(&value), "#bytes \"aGk=\"");
+    assert_eq!(bytes::decode_tagged(&value, "bytes"), Some(b"hi".to_vec()));
+    assert_eq!(value.as_bytes(), None);
+}
+
+#[test]
+fn test_reader() {
+    let mut registry = ReaderRegistry::new();
+    registry.register(bytes::TAG, readers::base64);
+    let value = Parser::with_readers("[#edn.rs/base64 \"aGk\"]", &registry)
+        .read()
+        .unwrap()
+        .unwrap();
+    assert_eq!(value, parse("[#edn.rs/base64 \"aGk=\"]"));
+    assert_eq!(
+        readers::base64(Value::from("!")),
+        Err(Error::data("expected a base64 string for `#edn.rs/base64`"))
+    );
+}
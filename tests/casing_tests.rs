@@ -0,0 +1,83 @@
+extern crate edn;
+
+use edn::casing::{self, Case};
+use edn::lexer::Span;
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_case() {
+    assert_eq!(Case::of("foo-bar"), Some(Case::Kebab));
+    assert_eq!(Case::of("foo_bar"), Some(Case::Snake));
+    assert_eq!(Case::of("fooBar"), Some(Case::Camel));
+    assert_eq!(Case::of("FooBar"), Some(Case::Pascal));
+    assert_eq!(Case::of("foo"), None);
+    assert_eq!(Case::of("foo_Bar-baz"), None);
+
+    for &(name, kebab, snake, camel, pascal) in &[
+        ("fooBar", "foo-bar", "foo_bar", "fooBar", "FooBar"),
+        (
+            "HTTPServer",
+            "http-server",
+            "http_server",
+            "httpServer",
+            "HttpServer",
+        ),
+        ("valid?", "valid?", "valid?", "valid?", "Valid?"),
+        ("user_id-2", "user-id-2", "user_id_2", "userId2", "UserId2"),
+    ] {
+        assert_eq!(Case::Kebab.convert(name), kebab);
+        assert_eq!(Case::Snake.convert(name), snake);
+        assert_eq!(Case::Camel.convert(name), camel);
+        assert_eq!(Case::Pascal.convert(name), pascal);
+    }
+}
+
+#[test]
+fn test_audit() {
+    let str = "{:user/firstName \"a\" :last_name \"b\" :age 3 :zipCode 1} ; :notThis";
+    let audit = casing::audit(str).unwrap();
+    assert_eq!(audit.convention, Some(Case::Camel));
+    assert_eq!(audit.findings.len(), 1);
+    let finding = &audit.findings[0];
+    assert_eq!(finding.keyword, ":last_name");
+    assert_eq!(finding.span, Span { lo: 21, hi: 31 });
+    assert_eq!(finding.case, Some(Case::Snake));
+    assert_eq!(finding.suggestion, ":lastName");
+
+    let audit = casing::audit("[:a-b :c_d :e]").unwrap();
+    assert_eq!(audit.convention, Some(Case::Kebab));
+    assert_eq!(audit.findings[0].suggestion, ":c-d");
+    assert_eq!(casing::audit("[:a :b]").unwrap().convention, None);
+    assert!(casing::audit("[:a").is_ok());
+    assert!(casing::audit("\"unterminated").is_err());
+}
+
+#[test]
+fn test_fix() {
+    let str = "{:user/firstName \"a\", :last_name #_:skipMe 2}";
+    assert_eq!(
+        casing::check_case(str, Case::Kebab)
+            .unwrap()
+            .iter()
+            .map(|finding| &finding.suggestion[..])
+            .collect::<Vec<_>>(),
+        vec![":user/first-name", ":last-name", ":skip-me"]
+    );
+    assert_eq!(
+        casing::rewrite(str, Case::Kebab).unwrap(),
+        "{:user/first-name \"a\", :last-name #_:skip-me 2}"
+    );
+    assert_eq!(
+        casing::convert(&parse(str), Case::Snake),
+        parse("{:user/first_name \"a\" :last_name 2}")
+    );
+    assert_eq!(
+        casing::convert(&parse("#tag [:fooBar \"fooBar\" (:x-y)]"), Case::Pascal),
+        parse("#tag [:FooBar \"fooBar\" (:XY)]")
+    );
+}
@@ -0,0 +1,65 @@
+extern crate edn;
+
+use edn::code::{call, fill, gensym, quote};
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_call_and_quote() {
+    assert_eq!(call("inc", vec![1]), parse("(inc 1)"));
+    assert_eq!(call("rand", Vec::<Value>::new()), parse("(rand)"));
+    assert_eq!(quote(parse("(a b)")), parse("(quote (a b))"));
+    assert_ne!(gensym("x"), gensym("x"));
+    assert!(gensym("x").name().starts_with("x__"));
+}
+
+#[test]
+fn test_fill() {
+    let template = parse(
+        "(let [x# (unquote v)] {:k (unquote v)} #{(unquote-splicing args)} (f x# (unquote-splicing args) y#))",
+    );
+    let filled = fill(
+        &template,
+        &[("v", parse("(g 1)")), ("args", parse("[2 3]"))],
+    )
+    .unwrap();
+    let items = filled.as_list().unwrap();
+    let x = items[1].as_vector().unwrap()[0].clone();
+    assert!(x.as_symbol().unwrap().name().ends_with("__auto__"));
+    assert_eq!(items[1], Value::from(vec![x.clone(), parse("(g 1)")]));
+    assert_eq!(items[2], parse("{:k (g 1)}"));
+    assert_eq!(items[3], parse("#{2 3}"));
+    let call = items[4].as_list().unwrap();
+    assert_eq!(call[1], x);
+    assert_eq!(
+        call.iter().skip(2).take(2).collect::<Vec<_>>(),
+        vec![&Value::Integer(2), &Value::Integer(3)]
+    );
+    assert!(call[4] != x && call[4].is_symbol());
+
+    // Each fill gets fresh gensyms.
+    assert_ne!(fill(&parse("a#"), &[]), fill(&parse("a#"), &[]));
+    // `#` alone and namespaced symbols are left alone.
+    let untouched = Value::list(vec![Value::Symbol("#".into()), parse("ns/a#")]);
+    assert_eq!(fill(&untouched, &[]).unwrap(), untouched);
+}
+
+#[test]
+fn test_fill_errors() {
+    let message = |template: &str, bindings: &[(&str, Value)]| {
+        fill(&parse(template), bindings).unwrap_err().message
+    };
+    assert_eq!(message("(f (unquote x))", &[]), "no value bound to `x`");
+    assert_eq!(
+        message("(unquote-splicing xs)", &[("xs", parse("[1]"))]),
+        "unquote-splicing outside of a list, vector or set"
+    );
+    assert_eq!(
+        message("[(unquote-splicing xs)]", &[("xs", parse("{}"))]),
+        "expected a list or vector to splice for `xs`, found a map"
+    );
+}
@@ -0,0 +1,89 @@
+#![cfg(not(feature = "immutable"))]
+
+//! Test vectors from the EDN spec, which this crate reads as `clojure.edn`
+//! does, and a check of the divergences in `edn::compat`.
+
+extern crate edn;
+
+use edn::compat::{divergence, DIVERGENCES};
+use edn::parser::Parser;
+use edn::ser;
+
+/// Read every form in `str` and write each back as compact EDN, or `ERR` and
+/// the message for the first error.
+fn read_all(str: &str) -> Vec<String> {
+    Parser::new(str)
+        .into_stream()
+        .map(|result| match result {
+            Ok((value, _)) => {
+                let mut bytes = vec![];
+                ser::to_writer(&mut bytes, &value).unwrap();
+                String::from_utf8(bytes).unwrap()
+            }
+            Err(err) => format!("ERR {}", err.message),
+        })
+        .collect()
+}
+
+/// Forms read the same by both, with their canonical compact output.
+const VECTORS: &[(&str, &str)] = &[
+    ("nil", "nil"),
+    ("true", "true"),
+    ("false", "false"),
+    ("\"hi\\n\\\"there\\\"\"", "\"hi\\n\\\"there\\\"\""),
+    ("\\c", "\\c"),
+    ("\\newline", "\\newline"),
+    ("\\space", "\\space"),
+    ("\\tab", "\\tab"),
+    ("\\return", "\\return"),
+    ("\\u0041", "\\A"),
+    ("\"\\u00e9\"", "\"é\""),
+    ("foo", "foo"),
+    ("my.ns/foo", "my.ns/foo"),
+    ("/", "/"),
+    (":kw", ":kw"),
+    (":my.ns/kw", ":my.ns/kw"),
+    ("0", "0"),
+    ("-7", "-7"),
+    ("+7", "7"),
+    ("1.5", "1.5"),
+    ("-0.25", "-0.25"),
+    ("(a b 42)", "(a b 42)"),
+    ("[a b 42]", "[a b 42]"),
+    #[cfg(not(feature = "ordered"))]
+    (
+        "{:a 1, \"foo\" :bar, [1 2 3] four}",
+        "{\"foo\" :bar, :a 1, [1 2 3] four}",
+    ),
+    #[cfg(feature = "ordered")]
+    (
+        "{:a 1, \"foo\" :bar, [1 2 3] four}",
+        "{:a 1, \"foo\" :bar, [1 2 3] four}",
+    ),
+    ("#{a b [1 2 3]}", "#{a b [1 2 3]}"),
+    ("[1 ; comment\n 2]", "[1 2]"),
+    ("[1,2,,3]", "[1 2 3]"),
+    ("#_ 1 2", "2"),
+    ("1e10", "10000000000.0"),
+    ("1.5e3", "1500.0"),
+    ("[1 #_ 2 3 #_ #_ 4 5 #_6]", "[1 3]"),
+];
+
+#[test]
+fn test_vectors() {
+    for &(input, output) in VECTORS {
+        assert_eq!(read_all(input), vec![output], "reading {}", input);
+        assert_eq!(read_all(output), vec![output], "reading {}", output);
+    }
+}
+
+#[test]
+fn test_divergences() {
+    for expected in DIVERGENCES {
+        let input = expected.input;
+        assert_eq!(read_all(input), expected.ours, "reading {}", input);
+        assert!(expected.ours != [expected.clojure], "{}", input);
+        assert_eq!(divergence(input), Some(expected));
+    }
+    assert_eq!(divergence("nil"), None);
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "unstable")]
+
+extern crate edn;
+
+use edn::compress;
+use edn::parser::{self, Category, ParserBuilder};
+use edn::readers::ReaderRegistry;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    parser::from_str(str).unwrap()
+}
+
+#[test]
+fn test_round_trip() {
+    let record = "{:order/id 1 :order/status :order.status/shipped :order/items [{:item/sku \"a\" :item/quantity 2}]}";
+    let value = parse(&format!("[{} {} {}]", record, record, record));
+    let plain = edn::to_string(&value);
+    let compressed = compress::to_string(&value);
+    assert!(compressed.len() < plain.len() * 3 / 4);
+    assert_eq!(compress::from_str(&compressed).unwrap(), value);
+    assert_eq!(compress::decode(compress::encode(&value)).unwrap(), value);
+}
+
+#[test]
+fn test_nothing_worth_replacing() {
+    let value = parse("[:a {:b 1} \"c\"]");
+    assert_eq!(compress::encode(&value), value);
+    assert_eq!(compress::to_string(&value), edn::to_string(&value));
+    assert_eq!(compress::from_str("[:a {:b 1} \"c\"]").unwrap(), value);
+}
+
+#[test]
+fn test_keywords_like_references() {
+    let value = parse("[:0 :1 ^{:12 true} (:long-keyword :long-keyword :long-keyword) #{:0}]");
+    let encoded = compress::encode(&value);
+    match encoded {
+        Value::Tagged(ref tag, _) => assert_eq!(tag, compress::TAG),
+        _ => panic!("expected an encoded document"),
+    }
+    assert_eq!(compress::decode(encoded).unwrap(), value);
+}
+
+#[test]
+fn test_register() {
+    let mut readers = ReaderRegistry::new();
+    compress::register(&mut readers);
+    let value = ParserBuilder::new()
+        .readers(&readers)
+        .from_str("[#edn/keywords [[:a/b] {:0 [:0 :c]}] 1]")
+        .unwrap();
+    assert_eq!(value, parse("[{:a/b [:a/b :c]} 1]"));
+}
+
+#[test]
+fn test_errors() {
+    for &(str, message) in &[
+        (
+            "#edn/keywords [[:a] :1]",
+            "no keyword in the dictionary for :1",
+        ),
+        (
+            "#edn/keywords [[\"a\"] :0]",
+            "expected a vector of keywords as the dictionary",
+        ),
+        (
+            "#edn/keywords [:a :0]",
+            "expected a vector of keywords as the dictionary",
+        ),
+        (
+            "#edn/keywords {:a 1}",
+            "expected [dictionary value] after #edn/keywords",
+        ),
+    ] {
+        let err = compress::from_str(str).unwrap_err();
+        assert_eq!(err.classify(), Category::Data);
+        assert_eq!(err.message, message);
+    }
+}
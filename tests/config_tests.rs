@@ -0,0 +1,95 @@
+extern crate edn;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use edn::config;
+use edn::parser;
+use edn::Value;
+
+fn dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("edn-config-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for &(path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn pattern(dir: &Path, glob: &str) -> String {
+    format!("{}/{}", dir.display(), glob)
+}
+
+#[test]
+fn test_load_dir() {
+    let dir = dir(
+        "load",
+        &[
+            (
+                "a.edn",
+                "{:db {:host \"localhost\" :port 5432} :name \"app\"}",
+            ),
+            (
+                "b.edn",
+                "{:db {:port 6543} :url #ref [:db :host] :shared #include \"shared/x.edn\"}",
+            ),
+            ("shared/x.edn", "{:port #ref [:db :port] :tags [1 2]}"),
+            ("notes.txt", "("),
+        ],
+    );
+    assert_eq!(
+        config::load_dir(&pattern(&dir, "*.edn")),
+        Ok(parser::from_str(
+            "{:db {:host \"localhost\" :port 6543} :name \"app\" :url \"localhost\"
+              :shared {:port 6543 :tags [1 2]}}"
+        )
+        .unwrap())
+    );
+    assert_eq!(
+        config::glob(&pattern(&dir, "**/*.edn")).unwrap(),
+        vec![
+            dir.join("a.edn"),
+            dir.join("b.edn"),
+            dir.join("shared/x.edn")
+        ]
+    );
+    assert_eq!(
+        config::glob(&pattern(&dir, "?.e*")).unwrap(),
+        vec![dir.join("a.edn"), dir.join("b.edn")]
+    );
+
+    let name: String = config::load_dir_as::<Value>(&pattern(&dir, "a.edn"))
+        .unwrap()
+        .get_kw("name")
+        .and_then(Value::as_str)
+        .unwrap()
+        .into();
+    assert_eq!(name, "app");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_errors() {
+    let dir = dir(
+        "errors",
+        &[
+            ("cycle/a.edn", "{:b #include \"b.edn\"}"),
+            ("cycle/b.edn", "{:a #include \"a.edn\"}"),
+            ("refs/a.edn", "{:x #ref [:y] :y #ref [:x] }"),
+            ("missing/a.edn", "{:x #ref [:nope 0]}"),
+            ("vector/a.edn", "[1]"),
+            ("syntax/a.edn", "{:a"),
+        ],
+    );
+    let message = |glob: &str| config::load_dir(&pattern(&dir, glob)).unwrap_err().message;
+    assert!(message("cycle/a.edn").ends_with("a.edn includes itself"));
+    assert_eq!(message("refs/*.edn"), "#ref cycle [:y] -> [:x] -> [:y]");
+    assert_eq!(message("missing/*.edn"), "#ref [:nope 0] not found");
+    assert!(message("vector/*.edn").ends_with("a.edn: expected a map, found a vector"));
+    assert!(message("syntax/*.edn").contains("unclosed `{`"));
+    assert!(message("none/*.edn").contains("none"));
+    assert!(message("*.json").starts_with("no files match"));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "unstable")]
+
+extern crate edn;
+
+use edn::cst::{Cst, Delimiter, Node};
+use edn::parser::Category;
+
+#[test]
+fn test_round_trip() {
+    for str in &[
+        "",
+        "  ; only a comment",
+        "{:a 1, :b [2 3]}\n",
+        "(defn f\n  ;; docs\n  [x] #_(debug x)\n  x)",
+        "#{1 2} #inst \"2020\" ^:private ^{:a 1} [x] #_ #_ a b c",
+        "[\"s\\\"x\" \\space \\) 1.5M -3N :a/b sym/c]",
+    ] {
+        assert_eq!(Cst::parse(str).unwrap().to_string(), *str);
+    }
+}
+
+#[test]
+fn test_nodes() {
+    let cst = Cst::parse("#tag ;c\n [1,2] #_x").unwrap();
+    assert_eq!(
+        cst.nodes,
+        vec![
+            Node::Tagged {
+                tag: "tag".into(),
+                children: vec![
+                    Node::Whitespace(" ".into()),
+                    Node::Comment(";c".into()),
+                    Node::Whitespace("\n ".into()),
+                    Node::Collection {
+                        delimiter: Delimiter::Vector,
+                        children: vec![
+                            Node::Atom("1".into()),
+                            Node::Whitespace(",".into()),
+                            Node::Atom("2".into()),
+                        ],
+                    },
+                ],
+            },
+            Node::Whitespace(" ".into()),
+            Node::Discard {
+                children: vec![Node::Atom("x".into())],
+            },
+        ]
+    );
+    assert_eq!(cst.forms().count(), 1);
+}
+
+#[test]
+fn test_edit() {
+    let mut cst = Cst::parse("{:port 80 ; default\n}").unwrap();
+    if let Node::Collection {
+        ref mut children, ..
+    } = cst.nodes[0]
+    {
+        children[2] = Node::Atom("8080".into());
+    }
+    assert_eq!(cst.to_string(), "{:port 8080 ; default\n}");
+}
+
+#[test]
+fn test_errors() {
+    let err = Cst::parse("[1 (2]").unwrap_err();
+    assert_eq!(err.message, "unexpected `]`, expected `)`");
+    assert_eq!((err.lo, err.category), (5, Category::Syntax));
+
+    let err = Cst::parse("{:a\n [1").unwrap_err();
+    assert_eq!(err.message, "unclosed `[`");
+    assert_eq!((err.line, err.column, err.category), (2, 2, Category::Eof));
+
+    assert_eq!(Cst::parse("[#_ ]").unwrap_err().message, "unexpected `]`");
+    assert_eq!(
+        Cst::parse("^:a").unwrap_err().message,
+        "expected a form after `^`, found EOF"
+    );
+    assert_eq!(
+        Cst::parse(&"[".repeat(1000)).unwrap_err().message,
+        "recursion limit exceeded"
+    );
+}
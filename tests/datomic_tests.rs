@@ -0,0 +1,80 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::datomic::{self, Datom, TxReport};
+use edn::parser::{Category, Error, Parser};
+use edn::readers::ReaderRegistry;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_datom_from_value() {
+    let datom = Datom {
+        e: 17,
+        a: Value::Keyword("person/name".into()),
+        v: Value::String("Ada".into()),
+        tx: 1000,
+        added: true,
+    };
+    assert_eq!(
+        Datom::from_value(&parse("#datom [17 :person/name \"Ada\" 1000 true]")),
+        Some(datom.clone())
+    );
+    assert_eq!(
+        Datom::from_value(&parse("[17 :person/name \"Ada\" 1000]")),
+        Some(datom.clone())
+    );
+    assert_eq!(
+        Value::from(datom),
+        parse("#datom [17 :person/name \"Ada\" 1000 true]")
+    );
+
+    assert_eq!(Datom::from_value(&parse("[17 :a 1]")), None);
+    assert_eq!(Datom::from_value(&parse("[:x :a 1 1000]")), None);
+    assert_eq!(Datom::from_value(&parse("[17 :a 1 1000 :yes]")), None);
+}
+
+#[test]
+fn test_tx_report_from_value() {
+    let report = TxReport::from_value(&parse(
+        "{:db-before #db {:t 999}
+          :db-after #db {:t 1000}
+          :tx-data [#datom [13194139534312 50 #inst \"2023-01-01T00:00:00Z\" 13194139534312 true]
+                    [17 :person/name \"Ada\" 13194139534312 true]]
+          :tempids {\"ada\" 17}}",
+    )).unwrap();
+    assert_eq!(report.db_before, parse("#db {:t 999}"));
+    assert_eq!(report.tx_data.len(), 2);
+    assert_eq!(report.tx_data[1].e, 17);
+    assert_eq!(
+        report.tempids.get(&Value::String("ada".into())),
+        Some(&Value::Integer(17))
+    );
+
+    assert_eq!(TxReport::from_value(&parse("{:tx-data [[1 2]]}")), None);
+    assert_eq!(TxReport::from_value(&parse("[]")), None);
+}
+
+#[test]
+fn test_datom_reader() {
+    let mut readers = ReaderRegistry::new();
+    readers.register("datom", datomic::read_datom);
+
+    let mut parser = Parser::with_readers("#datom [1 :a 2 3] #datom [1]", &readers);
+    assert_eq!(parser.read(), Some(Ok(parse("#datom [1 :a 2 3 true]"))));
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 18,
+            hi: 28,
+            line: 1,
+            column: 19,
+            category: Category::Data,
+            message: "expected `[e a v tx added]` for `#datom`".into(),
+        }))
+    );
+}
@@ -0,0 +1,58 @@
+#![cfg(all(feature = "bigdecimal", not(feature = "immutable")))]
+
+extern crate bigdecimal;
+extern crate edn;
+
+use bigdecimal::BigDecimal;
+use edn::parser::Parser;
+use edn::ser;
+use edn::Value;
+
+fn decimal(digits: &str) -> Value {
+    Value::Decimal(digits.parse().unwrap())
+}
+
+#[test]
+fn test_decimal() {
+    let mut parser = Parser::new("3.14159M -0.1M 12M .5M 1.5");
+    assert_eq!(parser.read(), Some(Ok(decimal("3.14159"))));
+    assert_eq!(parser.read(), Some(Ok(decimal("-0.1"))));
+    assert_eq!(parser.read(), Some(Ok(decimal("12"))));
+    assert_eq!(parser.read(), Some(Ok(decimal("0.5"))));
+    assert_eq!(parser.read(), Some(Ok(Value::Float(1.5.into()))));
+    assert_eq!(parser.read(), None);
+
+    assert_eq!(Value::from_decimal_str("0.10M"), Some(decimal("0.10")));
+    assert_eq!(Value::from_decimal_str("nope"), None);
+    assert_eq!(
+        decimal("0.1").as_decimal(),
+        Some("0.1".parse::<BigDecimal>().unwrap())
+    );
+    assert_eq!(Value::Integer(3).as_decimal(), Some(BigDecimal::from(3)));
+    assert_eq!(Value::Float(0.1.into()).as_decimal(), None);
+}
+
+#[test]
+fn test_round_trip() {
+    let value = Value::Vector(vec![
+        decimal("0.1"),
+        decimal("123456789012345678901234567890.000000001"),
+        decimal("1E+10"),
+    ]);
+    let mut out = vec![];
+    ser::to_writer(&mut out, &value).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(
+        out,
+        "[0.1M 123456789012345678901234567890.000000001M 10000000000M]"
+    );
+    assert_eq!(Parser::new(&out).read(), Some(Ok(value)));
+}
+
+#[test]
+fn test_canonical() {
+    assert_eq!(decimal("1.50"), decimal("1.5"));
+    assert_eq!(ser::to_string_canonical(&decimal("1.50")), "1.5M");
+    assert_eq!(ser::to_string_canonical(&decimal("100")), "100M");
+    assert_eq!(ser::to_string_canonical(&decimal("-0.0010")), "-0.001M");
+}
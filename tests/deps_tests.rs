@@ -0,0 +1,95 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::deps::{combine_aliases, resolve};
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+const DEPS_EDN: &str = r#"
+{:paths ["src" "resources"]
+ :deps {org.clojure/clojure {:mvn/version "1.11.1"}
+        cheshire/cheshire nil}
+ :mvn/repos {"clojars" {:url "https://repo.clojars.org/"}}
+ :aliases {:dev {:extra-paths ["dev"]
+                 :extra-deps {nrepl/nrepl {:mvn/version "1.0.0"}}
+                 :jvm-opts ["-Xmx1g"]}
+           :test {:extra-paths ["test" "dev"]
+                  :extra-deps {nrepl/nrepl {:mvn/version "1.1.0"}}
+                  :override-deps {org.clojure/clojure {:mvn/version "1.12.0"}
+                                  not/present {:mvn/version "1"}}
+                  :default-deps {cheshire/cheshire {:mvn/version "5.12.0"}}
+                  :jvm-opts ["-ea"]
+                  :main-opts ["-m" "kaocha.runner"]}
+           :tool {:replace-deps {io.github/tool {:git/sha "abc"}}
+                  :replace-paths ["."]}
+           :broken {:extra-deps [1]}}}
+"#;
+
+#[test]
+fn test_combine_aliases() {
+    assert_eq!(
+        combine_aliases(&parse(DEPS_EDN), &["dev", "test"]).unwrap(),
+        parse(
+            r#"{:extra-paths ["dev" "test"]
+                :extra-deps {nrepl/nrepl {:mvn/version "1.1.0"}}
+                :override-deps {org.clojure/clojure {:mvn/version "1.12.0"}
+                                not/present {:mvn/version "1"}}
+                :default-deps {cheshire/cheshire {:mvn/version "5.12.0"}}
+                :jvm-opts ["-Xmx1g" "-ea"]
+                :main-opts ["-m" "kaocha.runner"]}"#
+        )
+    );
+}
+
+#[test]
+fn test_resolve() {
+    let deps_edn = parse(DEPS_EDN);
+    assert_eq!(
+        resolve(&deps_edn, &["dev", "test"]).unwrap(),
+        parse(
+            r#"{:paths ["dev" "test" "src" "resources"]
+                :deps {org.clojure/clojure {:mvn/version "1.12.0"}
+                       cheshire/cheshire {:mvn/version "5.12.0"}
+                       nrepl/nrepl {:mvn/version "1.1.0"}}
+                :mvn/repos {"clojars" {:url "https://repo.clojars.org/"}}
+                :jvm-opts ["-Xmx1g" "-ea"]
+                :main-opts ["-m" "kaocha.runner"]}"#
+        )
+    );
+    assert_eq!(
+        resolve(&deps_edn, &["tool"]).unwrap(),
+        parse(
+            r#"{:paths ["."]
+                :deps {io.github/tool {:git/sha "abc"}}
+                :mvn/repos {"clojars" {:url "https://repo.clojars.org/"}}}"#
+        )
+    );
+    assert_eq!(
+        resolve(&parse("{}"), &[]).unwrap(),
+        parse("{:paths [] :deps {}}")
+    );
+}
+
+#[test]
+fn test_resolve_errors() {
+    let deps_edn = parse(DEPS_EDN);
+    let message = |aliases: &[&str]| resolve(&deps_edn, aliases).unwrap_err().message;
+    assert_eq!(message(&["nope"]), "unknown alias `nope`");
+    assert_eq!(
+        message(&["broken"]),
+        "`:extra-deps` must be a map, found a vector"
+    );
+    assert_eq!(
+        message(&["dev", "broken"]),
+        "`:extra-deps` of alias `broken` must be a map, found a vector"
+    );
+    assert_eq!(
+        resolve(&parse("[]"), &[]).unwrap_err().message,
+        "deps.edn must be a map, found a vector"
+    );
+}
@@ -0,0 +1,136 @@
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::ser::{self, Config, Serializer};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn serialize(value: &Value, config: Config) -> String {
+    let mut serializer = Serializer::new(vec![]);
+    serializer.set_config(config);
+    serializer.serialize(value).unwrap();
+    String::from_utf8(serializer.into_inner()).unwrap()
+}
+
+const SORTED: &str = "{\"d\" 4, :a 1, :b #{1 2 3}, :c {:x [1 2], :y {:z nil}}}";
+
+#[test]
+fn test_sorted_whatever_the_input_order() {
+    for str in &[
+        "{:a 1 :b #{1 2 3} :c {:x [1 2] :y {:z nil}} \"d\" 4}",
+        "{\"d\" 4 :c {:y {:z nil} :x [1 2]} :b #{3 2 1} :a 1}",
+        "{:b #{2 3 1} \"d\" 4 :a 1 :c {:x [1 2] :y {:z nil}}}",
+    ] {
+        let value = parse(str);
+        assert_eq!(serialize(&value, Config::new().sort_entries(true)), SORTED);
+        assert_eq!(parse(SORTED), value);
+    }
+}
+
+#[test]
+#[cfg(not(feature = "ordered"))]
+fn test_sorted_by_default() {
+    let value = parse("{\"d\" 4 :c {:y {:z nil} :x [1 2]} :b #{3 2 1} :a 1}");
+    assert_eq!(edn::to_string(&value), SORTED);
+    assert_eq!(serialize(&value, Config::new()), SORTED);
+}
+
+#[test]
+#[cfg(not(feature = "ordered"))]
+fn test_pretty_sorted_by_default() {
+    use edn::ser::pretty::PrettyPrinter;
+
+    let value = parse("{\"d\" 4 :c {:y {:z nil} :x [1 2]} :b #{3 2 1} :a 1}");
+    let pretty = "\
+{\"d\" 4,
+ :a 1,
+ :b #{1
+      2
+      3},
+ :c {:x [1
+         2],
+     :y {:z nil}}}";
+    assert_eq!(PrettyPrinter::new().width(10).to_string(&value), pretty);
+    assert_eq!(parse(pretty), value);
+}
+
+#[test]
+#[cfg(all(feature = "ordered", not(feature = "immutable")))]
+fn test_insertion_order_unless_sorted() {
+    let str = "{\"d\" 4, :c {:y {:z nil}, :x [1 2]}, :b #{3 2 1}, :a 1}";
+    assert_eq!(edn::to_string(&parse(str)), str);
+    assert_eq!(
+        serialize(&parse(str), Config::new().sort_entries(false)),
+        str
+    );
+    assert_eq!(
+        serialize(&parse(str), Config::new().sort_entries(true)),
+        SORTED
+    );
+}
+
+#[test]
+#[cfg(feature = "immutable")]
+fn test_opt_out() {
+    let value = parse(SORTED);
+    let unsorted = serialize(&value, Config::new().sort_entries(false));
+    assert_eq!(parse(&unsorted), value);
+}
+
+#[test]
+fn test_canonical() {
+    let a =
+        parse("{:b #{[2 1] \"x\" :k nil} :a {\"z\" -0.0 \"y\" 150000000000000000000.0} 3 (1 2)}");
+    let b =
+        parse("{3 (1 2) :a {\"y\" 150000000000000000000.0 \"z\" 0.0} :b #{:k \"x\" nil [2 1]}}");
+    let canonical =
+        "{:a {\"y\" 150000000000000000000.0 \"z\" 0.0} :b #{nil \"x\" :k [2 1]} 3 (1 2)}";
+    assert_eq!(ser::to_string_canonical(&a), canonical);
+    assert_eq!(ser::to_string_canonical(&b), canonical);
+    assert_eq!(ser::to_vec_canonical(&a), canonical.as_bytes());
+    assert_eq!(parse(canonical), a);
+}
+
+#[test]
+fn test_vcs() {
+    let a = parse("{:b #{2 1} :a {\"z\" -0.0 \"y\" 150000000000000000000.0} :c [] 3 (1 [2])}");
+    let b = parse("{3 (1 [2]) :c [] :a {\"y\" 150000000000000000000.0 \"z\" 0.0} :b #{1 2}}");
+    let vcs = "\
+{
+  :a {
+    \"y\" 150000000000000000000.0
+    \"z\" 0.0
+  }
+  :b #{
+    1
+    2
+  }
+  :c []
+  3 (
+    1
+    [
+      2
+    ]
+  )
+}
+";
+    assert_eq!(ser::to_string_vcs(&a), vcs);
+    assert_eq!(ser::to_string_vcs(&b), vcs);
+    assert_eq!(ser::to_vec_vcs(&a), vcs.as_bytes());
+    assert_eq!(parse(vcs), a);
+
+    // Changing one entry changes one line.
+    let mut changed = a.clone();
+    changed["c"] = Value::Integer(1);
+    let lines = |str: &str| str.lines().map(String::from).collect::<Vec<_>>();
+    let (before, after) = (lines(vcs), lines(&ser::to_string_vcs(&changed)));
+    assert_eq!(before.len(), after.len());
+    let changed: Vec<_> = before.iter().zip(&after).filter(|&(a, b)| a != b).collect();
+    assert_eq!(
+        changed,
+        vec![(&"  :c []".to_string(), &"  :c 1".to_string())]
+    );
+}
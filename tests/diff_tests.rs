@@ -0,0 +1,119 @@
+extern crate edn;
+
+use edn::diff::{Diff, Edit};
+use edn::parser::{self, ParserBuilder};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    ParserBuilder::new()
+        .keep_metadata(true)
+        .from_str(str)
+        .unwrap()
+}
+
+fn check(old: &str, new: &str) -> Diff {
+    let (old, new) = (parse(old), parse(new));
+    let diff = edn::diff(&old, &new);
+    let mut patched = old.clone();
+    edn::patch(&mut patched, &diff).unwrap();
+    assert_eq!(edn::to_string(&patched), edn::to_string(&new));
+    assert_eq!(Diff::from_value(&diff.to_value()), Ok(diff.clone()));
+    diff
+}
+
+#[test]
+fn test_round_trip() {
+    for &(old, new) in &[
+        ("1", "2"),
+        ("{:a 1}", "[:a 1]"),
+        ("{:a {:b [1 2 3]}}", "{:a {:b [1 2 3 4]}}"),
+        ("{:a {:b [1 2 3]}}", "{:a {:b [0 1 2 3]}}"),
+        ("[1 2 3 4 5]", "[1 5]"),
+        ("[1 2 3 4 5]", "[1 9 4 5 6]"),
+        ("[1 2 3 4 5 6 7]", "[0 2 3 9 5 7 8]"),
+        ("[{:a 1} {:b 2} {:c 3}]", "[{:b 2} {:c 4} {:d 5}]"),
+        ("[1 2 1 2 1]", "[2 1 2 1 2 1 2]"),
+        ("(1 (2 3) 4)", "(1 (2 :x) 4)"),
+        ("[]", "[1 2]"),
+        ("[1 2]", "[]"),
+        ("#{1 2 3}", "#{2 3 4}"),
+        ("{:s #{:a}}", "{:s #{:a :b} :t nil}"),
+        ("#inst \"2020\" ", "#inst \"2021\""),
+        ("#point [1 2]", "#point [1 3]"),
+        ("#a 1", "#b 1"),
+        ("^:x {:a 1}", "^:y {:a 1}"),
+        ("{:a ^:x [1 2]}", "{:a ^:x [1 2 3]}"),
+        ("{[1 2] 3 \"k\" {nil 1}}", "{[1 2] 4 \"k\" {nil 2}}"),
+    ] {
+        check(old, new);
+    }
+}
+
+#[test]
+fn test_small_diffs() {
+    assert!(check("{:a [1 2]}", "{:a [1 2]}").is_empty());
+    assert_eq!(
+        check("{:a {:b [1 2 3]}}", "{:a {:b [0 1 2 3]}}").edits,
+        vec![Edit::Add(
+            vec![parse(":a"), parse(":b"), Value::Integer(0)],
+            Value::Integer(0)
+        )]
+    );
+    assert_eq!(
+        check("#point [1 2]", "#point [1 3]").edits,
+        vec![Edit::Replace(
+            vec![parse("point"), Value::Integer(1)],
+            Value::Integer(3)
+        )]
+    );
+    assert_eq!(
+        edn::to_string(&check("[1 2 3 4 5]", "[1 9 4 5 6]").to_value()),
+        "[[:r [1] 9] [:- [2]] [:+ [4] 6]]"
+    );
+    assert_eq!(
+        edn::to_string(&check("#{1 2}", "#{2 3}").to_value()),
+        "[[:- [1]] [:+ [3] 3]]"
+    );
+}
+
+#[test]
+fn test_patch_errors() {
+    let diff = edn::diff(&parse("{:a 1}"), &parse("{}"));
+    let mut value = parse("{:b 1}");
+    assert_eq!(
+        edn::patch(&mut value, &diff).unwrap_err().message,
+        "can't remove :a"
+    );
+
+    let diff = Diff::from_value(&parse("[[:r [:a 5] 1]]")).unwrap();
+    let mut value = parse("{:a [1 2]}");
+    assert_eq!(
+        edn::patch(&mut value, &diff).unwrap_err().message,
+        "can't find 5"
+    );
+
+    let diff = Diff::from_value(&parse("[[:+ [:a 3] 1]]")).unwrap();
+    assert_eq!(
+        edn::patch(&mut value, &diff).unwrap_err().message,
+        "can't insert at 3"
+    );
+
+    let diff = Diff::from_value(&parse("[[:+ [:a] 1]]")).unwrap();
+    assert_eq!(
+        edn::patch(&mut value, &diff).unwrap_err().message,
+        "can't add :a"
+    );
+
+    assert_eq!(
+        Diff::from_value(&parse("[[:x [] 1]]")).unwrap_err().message,
+        "expected [:+ path value], [:- path] or [:r path value], found [:x [] 1]"
+    );
+    assert_eq!(
+        Diff::from_value(&parse("{}")).unwrap_err().message,
+        "expected a vector of edits, found a map"
+    );
+    assert_eq!(
+        parser::from_str("1").map(|v| edn::diff(&v, &v).is_empty()),
+        Ok(true)
+    );
+}
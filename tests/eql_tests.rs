@@ -0,0 +1,124 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::eql::{self, JoinQuery, Key, Node, Query};
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn prop(name: &str) -> Node {
+    Node::Prop {
+        key: Key::Prop(name.into()),
+        params: None,
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        eql::parse(&parse("[:a {:b [:c]} {[:user/id 1] [:d]} {:e ...} {:f 3}]")),
+        Ok(Query {
+            children: vec![
+                prop("a"),
+                Node::Join {
+                    key: Key::Prop("b".into()),
+                    query: JoinQuery::Query(Query {
+                        children: vec![prop("c")],
+                    }),
+                    params: None,
+                },
+                Node::Join {
+                    key: Key::Ident("user/id".into(), Value::Integer(1)),
+                    query: JoinQuery::Query(Query {
+                        children: vec![prop("d")],
+                    }),
+                    params: None,
+                },
+                Node::Join {
+                    key: Key::Prop("e".into()),
+                    query: JoinQuery::Recursion(None),
+                    params: None,
+                },
+                Node::Join {
+                    key: Key::Prop("f".into()),
+                    query: JoinQuery::Recursion(Some(3)),
+                    params: None,
+                },
+            ],
+        })
+    );
+}
+
+#[test]
+fn test_parse_params_unions_and_mutations() {
+    let query = eql::parse(&parse(
+        "[(:a {:x 1})
+          ({:b [:c]} {:limit 10})
+          {:feed {:photo [:url] :video [:src]}}
+          (app/save {:id 1})
+          {(app/create {:name \"x\"}) [:id]}]",
+    ))
+    .unwrap();
+
+    match query.children[0] {
+        Node::Prop {
+            params: Some(ref params),
+            ..
+        } => assert_eq!(Value::Map(params.clone()), parse("{:x 1}")),
+        _ => panic!(),
+    }
+    match query.children[1] {
+        Node::Join {
+            params: Some(_), ..
+        } => {}
+        _ => panic!(),
+    }
+    match query.children[2] {
+        Node::Join {
+            query: JoinQuery::Union(ref union),
+            ..
+        } => assert_eq!(union.len(), 2),
+        _ => panic!(),
+    }
+    match query.children[3] {
+        Node::Call {
+            ref name,
+            query: None,
+            ..
+        } => assert_eq!(name.to_string(), "app/save"),
+        _ => panic!(),
+    }
+    match query.children[4] {
+        Node::Call {
+            ref name,
+            query: Some(_),
+            ..
+        } => assert_eq!(name.to_string(), "app/create"),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    for str in &[
+        "[:a {:b [:c]} {[:user/id 1] [:d]} {:e ...} {:f 3}]",
+        "[(:a {:x 1}) ({:b [:c]} {:limit 10}) {:feed {:photo [:url] :video [:src]}}]",
+        "[(app/save {:id 1}) {(app/create {:name \"x\"}) [:id]}]",
+    ] {
+        let value = parse(str);
+        assert_eq!(Value::from(eql::parse(&value).unwrap()), value);
+    }
+}
+
+#[test]
+fn test_errors() {
+    assert!(eql::parse(&parse(":a")).is_err());
+    assert!(eql::parse(&parse("[\"a\"]")).is_err());
+    assert!(eql::parse(&parse("[{:a [:b] :c [:d]}]")).is_err());
+    assert!(eql::parse(&parse("[(:a)]")).is_err());
+    assert!(eql::parse(&parse("[{:a {\"b\" [:c]}}]")).is_err());
+}
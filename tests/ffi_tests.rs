@@ -0,0 +1,88 @@
+#![cfg(feature = "ffi")]
+
+extern crate edn;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use edn::ffi::*;
+
+unsafe fn take(str: *mut c_char) -> String {
+    assert!(!str.is_null());
+    let string = CStr::from_ptr(str).to_str().unwrap().to_string();
+    edn_string_free(str);
+    string
+}
+
+#[test]
+fn test_navigate() {
+    unsafe {
+        let input =
+            CString::new(r#"{:name "edn" :tags #{:a} :ports [80 443] :at #inst "2020"}"#).unwrap();
+        let value = edn_parse(input.as_ptr(), ptr::null_mut());
+        assert!(!value.is_null());
+        assert_eq!(edn_type(value), EdnType::Map);
+        assert_eq!(edn_count(value), 4);
+
+        let name = CString::new("name").unwrap();
+        assert_eq!(take(edn_as_string(edn_get_kw(value, name.as_ptr()))), "edn");
+
+        let ports = CString::new("ports").unwrap();
+        let ports = edn_get_kw(value, ports.as_ptr());
+        assert_eq!(edn_type(ports), EdnType::Vector);
+        let mut port = 0;
+        assert!(edn_as_i64(edn_nth(ports, 1), &mut port));
+        assert_eq!(port, 443);
+        assert!(edn_nth(ports, 2).is_null());
+
+        let mut float = 0.0;
+        assert!(edn_as_f64(edn_nth(ports, 0), &mut float));
+        assert_eq!(float, 80.0);
+        let mut boolean = false;
+        assert!(!edn_as_bool(edn_nth(ports, 0), &mut boolean));
+
+        let at = CString::new("at").unwrap();
+        let at = edn_get_kw(value, at.as_ptr());
+        assert_eq!(edn_type(at), EdnType::Tagged);
+        assert_eq!(take(edn_tag(at)), "inst");
+        assert_eq!(take(edn_as_string(edn_tagged_value(at))), "2020");
+
+        let key = edn_map_key(value, 0);
+        assert_eq!(edn_type(key), EdnType::Keyword);
+        let entry = edn_get(value, key);
+        assert_eq!(entry, edn_map_value(value, 0));
+        assert!(edn_map_key(value, 4).is_null());
+
+        edn_free(value);
+    }
+}
+
+#[test]
+fn test_write() {
+    unsafe {
+        let input = CString::new("^:private [1 \\a (nil true)]").unwrap();
+        let value = edn_parse(input.as_ptr(), ptr::null_mut());
+        assert_eq!(edn_type(value), EdnType::Vector);
+        assert_eq!(take(edn_as_string(edn_nth(value, 1))), "a");
+        assert_eq!(take(edn_write(edn_nth(value, 2))), "(nil true)");
+        edn_free(value);
+
+        assert!(edn_write(ptr::null()).is_null());
+        assert_eq!(edn_type(ptr::null()), EdnType::Nil);
+        edn_free(ptr::null_mut());
+        edn_string_free(ptr::null_mut());
+    }
+}
+
+#[test]
+fn test_parse_error() {
+    unsafe {
+        let input = CString::new("[1 2").unwrap();
+        let mut error = ptr::null_mut();
+        assert!(edn_parse(input.as_ptr(), &mut error).is_null());
+        assert!(take(error).contains("line 1"));
+
+        assert!(edn_parse(ptr::null(), ptr::null_mut()).is_null());
+    }
+}
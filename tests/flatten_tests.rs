@@ -0,0 +1,52 @@
+extern crate edn;
+
+use edn::flatten::{from_properties, to_properties};
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_to_properties() {
+    let config = parse(
+        r#"{:db {:host "localhost" :ports [5432 5433]}
+            :app/name "edn"
+            "tags" #{:a}
+            :empty {}}"#,
+    );
+    let mut properties = to_properties(&config);
+    properties.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        properties,
+        vec![
+            ("app/name".to_string(), parse("\"edn\"")),
+            ("db.host".to_string(), parse("\"localhost\"")),
+            ("db.ports.0".to_string(), parse("5432")),
+            ("db.ports.1".to_string(), parse("5433")),
+            ("empty".to_string(), parse("{}")),
+            ("tags".to_string(), parse("#{:a}")),
+        ]
+    );
+    assert_eq!(to_properties(&parse("1")), vec![(String::new(), parse("1"))]);
+}
+
+#[test]
+fn test_from_properties() {
+    let config = parse(r#"{:db {:host "localhost" :ports [5432 5433]} :app/name "edn"}"#);
+    assert_eq!(from_properties(to_properties(&config)), config);
+
+    assert_eq!(
+        from_properties(vec![
+            ("a.1", parse("1")),
+            ("a.0", parse("0")),
+            ("b.01", parse("1")),
+            ("c", parse("1")),
+            ("c.d", parse("2")),
+        ]),
+        parse("{:a [0 1] :b {:01 1} :c {:d 2}}")
+    );
+    assert_eq!(from_properties(vec![("", parse("1"))]), parse("1"));
+    assert_eq!(from_properties(Vec::<(String, Value)>::new()), parse("{}"));
+}
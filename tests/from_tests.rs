@@ -1,10 +1,11 @@
+#![cfg(not(feature = "immutable"))]
+
 extern crate edn;
 extern crate ordered_float;
 
-use edn::parser::{Error, Parser};
-use edn::Value;
+use edn::{CollectValue, Value};
 use ordered_float::OrderedFloat;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 #[test]
 fn from_bool() {
@@ -67,7 +68,7 @@ fn from_map() {
 
     let mut n = BTreeMap::new();
     n.insert(Value::Integer(1), Value::Integer(2));
-    assert_eq!(Value::from(m), Value::Map(n));
+    assert_eq!(Value::from(m), Value::Map(n.into_iter().collect()));
 }
 #[test]
 fn from_set() {
@@ -78,5 +79,61 @@ fn from_set() {
     let mut n = BTreeSet::new();
     n.insert(Value::Integer(1));
     n.insert(Value::Integer(2));
-    assert_eq!(Value::from(m), Value::Set(n));
+    assert_eq!(Value::from(m), Value::Set(n.into_iter().collect()));
+}
+
+#[test]
+fn from_hash_map() {
+    let mut m = HashMap::new();
+    m.insert("a", 1);
+    m.insert("b", 2);
+
+    let mut n = BTreeMap::new();
+    n.insert(Value::from("a"), Value::Integer(1));
+    n.insert(Value::from("b"), Value::Integer(2));
+    assert_eq!(Value::from(m), Value::Map(n.into_iter().collect()));
+}
+
+#[test]
+fn from_hash_set() {
+    let mut m = HashSet::new();
+    m.insert('a');
+
+    let mut n = BTreeSet::new();
+    n.insert(Value::Char('a'));
+    assert_eq!(Value::from(m), Value::Set(n.into_iter().collect()));
+}
+
+#[test]
+fn from_option() {
+    assert_eq!(Value::from(Some(1)), Value::Integer(1));
+    assert_eq!(Value::from(None::<i64>), Value::Nil);
+    assert_eq!(
+        Value::from(vec![Some("a"), None]),
+        Value::Vector(vec![Value::from("a"), Value::Nil])
+    );
+}
+
+#[test]
+fn from_constructors() {
+    assert_eq!(
+        Value::list(vec!["inc", "x"]),
+        Value::List(vec![Value::from("inc"), Value::from("x")])
+    );
+    assert_eq!(Value::vector(1..3), Value::Vector(vec![Value::Integer(1), Value::Integer(2)]));
+    assert_eq!(Value::set(vec![1, 1]), Value::Set(vec![Value::Integer(1)].into_iter().collect()));
+    assert_eq!(
+        Value::map(vec![("a", 1), ("a", 2)]),
+        Value::Map(vec![(Value::from("a"), Value::Integer(2))].into_iter().collect())
+    );
+}
+
+#[test]
+fn collect_value() {
+    let form = vec![Value::Symbol("+".into()), Value::Integer(1)]
+        .into_iter()
+        .collect_list();
+    assert_eq!(form, Value::List(vec![Value::Symbol("+".into()), Value::Integer(1)]));
+    assert!((0..3).collect_vector().is_vector());
+    assert_eq!((0..3).map(|n| n % 2).collect_set().as_set().unwrap().len(), 2);
 }
@@ -0,0 +1,66 @@
+extern crate edn;
+
+use edn::header;
+use edn::parser::{self, Category};
+use edn::{Map, Value};
+
+#[test]
+fn test_read() {
+    let (meta, body) =
+        header::read("^{:edn/version 2 :generated-at #inst \"2024-01-01\"} [1 ^:tag {:a 1}]")
+            .unwrap();
+    assert_eq!(header::version(&meta), Some(2));
+    assert_eq!(
+        meta.get_kw("generated-at"),
+        Some(&Value::Tagged(
+            "inst".into(),
+            Box::new(Value::String("2024-01-01".into()))
+        ))
+    );
+    let items = body.as_vector().unwrap();
+    assert_eq!(items[0], Value::Integer(1));
+    match items[1] {
+        Value::WithMeta(_, ref meta) => assert_eq!(meta.get_kw("tag"), Some(&Value::Boolean(true))),
+        _ => panic!("expected metadata on the map"),
+    }
+
+    let (meta, body) = header::read("{:a 1}").unwrap();
+    assert!(meta.is_empty());
+    assert_eq!(header::version(&meta), None);
+    assert_eq!(body, parser::from_str("{:a 1}").unwrap());
+
+    let (meta, _) = header::read("^:draft []").unwrap();
+    assert_eq!(meta.get_kw("draft"), Some(&Value::Boolean(true)));
+
+    assert_eq!(
+        header::read("^{:edn/version 1}").unwrap_err().classify(),
+        Category::Eof
+    );
+}
+
+#[test]
+fn test_write() {
+    let body = parser::from_str("{:a 1}").unwrap();
+    assert_eq!(header::write(&Map::new(), &body), "{:a 1}");
+    let meta = header::with_version(Map::new(), 2);
+    let written = header::write(&meta, &body);
+    assert_eq!(written, "^{:edn/version 2} {:a 1}");
+    assert_eq!(header::read(&written).unwrap(), (meta, body));
+}
+
+#[test]
+fn test_strip_and_inject() {
+    let value = parser::from_str("[1]").unwrap();
+    let (meta, stripped) = header::strip(value.clone());
+    assert!(meta.is_empty());
+    assert_eq!(stripped, value);
+
+    let injected = header::inject(value.clone(), header::with_version(Map::new(), 1));
+    let injected = header::inject(injected, header::with_version(Map::new(), 2));
+    let (meta, stripped) = header::strip(injected);
+    assert_eq!(header::version(&meta), Some(2));
+    assert_eq!(meta.len(), 1);
+    assert_eq!(stripped, value);
+
+    assert_eq!(header::inject(value.clone(), Map::new()), value);
+}
@@ -0,0 +1,103 @@
+extern crate edn;
+
+use edn::ids::{self, FormId};
+use edn::iter::PathSegment;
+use edn::parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    parser::from_str(str).unwrap()
+}
+
+fn ids(value: &Value) -> Vec<FormId> {
+    ids::form_ids(value).into_iter().map(|(_, id)| id).collect()
+}
+
+#[test]
+fn test_form_ids() {
+    let value = parse("{:a [1 #{2}] :b #tag 3}");
+    let paths: Vec<_> = ids::form_ids(&value)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    let a = Value::Keyword("a".into());
+    let b = Value::Keyword("b".into());
+    let two = Value::Integer(2);
+    assert_eq!(
+        paths,
+        vec![
+            vec![],
+            vec![PathSegment::Key(&a)],
+            vec![PathSegment::Key(&a), PathSegment::Index(0)],
+            vec![PathSegment::Key(&a), PathSegment::Index(1)],
+            vec![
+                PathSegment::Key(&a),
+                PathSegment::Index(1),
+                PathSegment::Element(&two)
+            ],
+            vec![PathSegment::Key(&b)],
+            vec![PathSegment::Key(&b), PathSegment::Tag("tag")],
+        ]
+    );
+
+    let ids = ids(&value);
+    assert_eq!(ids[0].path, ids::path_hash(&[]));
+    assert_eq!(ids[0].content, ids::content_hash(&value));
+    assert_eq!(ids[2].content, ids::content_hash(&Value::Integer(1)));
+    assert_eq!(
+        ids.iter()
+            .map(|id| id.path)
+            .collect::<std::collections::HashSet<_>>()
+            .len(),
+        7
+    );
+}
+
+#[test]
+fn test_stability() {
+    // Fixed across runs and versions.
+    assert_eq!(
+        ids::form_ids(&parse("[1]"))[0].1.to_string(),
+        format!(
+            "{:016x}-{:016x}",
+            ids::path_hash(&[]),
+            ids::content_hash(&parse("[1]"))
+        )
+    );
+    assert_eq!(ids::path_hash(&[]), 0xcbf2_9ce4_8422_2325);
+
+    let before = ids(&parse("{:a [1 2] :b {:c 3}}"));
+    let edited = ids(&parse("{:a [1 5] :b {:c 3}}"));
+    // Editing a form keeps its path hash and that of everything around it,
+    // and only changes the content hash of it and its parents.
+    assert_eq!(
+        before.iter().map(|id| id.path).collect::<Vec<_>>(),
+        edited.iter().map(|id| id.path).collect::<Vec<_>>()
+    );
+    let changed: Vec<bool> = before
+        .iter()
+        .zip(&edited)
+        .map(|(before, edited)| before.content != edited.content)
+        .collect();
+    assert_eq!(changed, vec![true, true, false, true, false, false]);
+
+    // Moving a form keeps its content hash.
+    let moved = ids(&parse("{:x {:c 3}}"));
+    assert_eq!(moved[1].content, before[4].content);
+    assert_ne!(moved[1].path, before[4].path);
+
+    // Metadata and the order of map entries don't matter.
+    assert_eq!(
+        ids::content_hash(
+            &parser::ParserBuilder::new()
+                .keep_metadata(true)
+                .from_str("^:m {:b 2 :a 1}")
+                .unwrap()
+        ),
+        ids::content_hash(&parse("{:a 1 :b 2}"))
+    );
+    assert_ne!(
+        ids::content_hash(&parse("(1 2)")),
+        ids::content_hash(&parse("[1 2]"))
+    );
+}
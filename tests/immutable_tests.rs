@@ -3,9 +3,9 @@ extern crate ordered_float;
 #[macro_use]
 extern crate im;
 
-use edn::parser::{Error, Parser};
-use edn::Value;
-use im::{Vector,HashMap};
+use edn::parser::{Category, Error, Parser};
+use edn::{Map, Value};
+use im::Vector;
 
 #[test]
 fn test_read_empty() {
@@ -66,6 +66,9 @@ fn test_read_chars() {
         Some(Err(Error {
             lo: 2,
             hi: 6,
+            line: 1,
+            column: 3,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -94,6 +97,9 @@ quux"
         Some(Err(Error {
             lo: 4,
             hi: 6,
+            line: 1,
+            column: 5,
+            category: Category::Syntax,
             message: "invalid string escape `\\x`".into()
         }))
     );
@@ -104,6 +110,9 @@ quux"
         Some(Err(Error {
             lo: 3,
             hi: 7,
+            line: 1,
+            column: 4,
+            category: Category::Eof,
             message: "expected closing `\"`, found EOF".into()
         }))
     );
@@ -228,6 +237,9 @@ fn test_read_lists() {
         Some(Err(Error {
             lo: 4,
             hi: 8,
+            line: 1,
+            column: 5,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -238,6 +250,9 @@ fn test_read_lists() {
         Some(Err(Error {
             lo: 2,
             hi: 10,
+            line: 1,
+            column: 3,
+            category: Category::Eof,
             message: "unclosed `(`".into()
         }))
     );
@@ -285,6 +300,9 @@ fn test_read_vectors() {
         Some(Err(Error {
             lo: 4,
             hi: 8,
+            line: 1,
+            column: 5,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -295,6 +313,9 @@ fn test_read_vectors() {
         Some(Err(Error {
             lo: 2,
             hi: 10,
+            line: 1,
+            column: 3,
+            category: Category::Eof,
             message: "unclosed `[`".into()
         }))
     );
@@ -302,19 +323,17 @@ fn test_read_vectors() {
 
 #[test]
 fn test_read_maps() {
-    use std::collections::BTreeMap;
-
     let mut parser = Parser::new(
         "{} {1 2} {true, false}
                                   {{\"foo\" \"bar\"} \"baz\"}",
     );
 
-    assert_eq!(parser.read(), Some(Ok(Value::Map(HashMap::new()))));
+    assert_eq!(parser.read(), Some(Ok(Value::Map(Map::new()))));
 
     assert_eq!(
         parser.read(),
         Some(Ok(Value::Map({
-            let mut map = HashMap::new();
+            let mut map = Map::new();
             map.insert(Value::Integer(1), Value::Integer(2));
             map
         })))
@@ -323,7 +342,7 @@ fn test_read_maps() {
     assert_eq!(
         parser.read(),
         Some(Ok(Value::Map({
-            let mut map = HashMap::new();
+            let mut map = Map::new();
             map.insert(Value::Boolean(true), Value::Boolean(false));
             map
         })))
@@ -332,10 +351,10 @@ fn test_read_maps() {
     assert_eq!(
         parser.read(),
         Some(Ok(Value::Map({
-            let mut map = HashMap::new();
+            let mut map = Map::new();
             map.insert(
                 Value::Map({
-                    let mut map = HashMap::new();
+                    let mut map = Map::new();
                     map.insert(Value::String("foo".into()), Value::String("bar".into()));
                     map
                 }),
@@ -351,6 +370,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 1,
             hi: 5,
+            line: 1,
+            column: 2,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -361,6 +383,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 2,
             hi: 9,
+            line: 1,
+            column: 3,
+            category: Category::Eof,
             message: "unclosed `{`".into()
         }))
     );
@@ -371,6 +396,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 0,
             hi: 7,
+            line: 1,
+            column: 1,
+            category: Category::Data,
             message: "odd number of items in a Map".into()
         }))
     );
@@ -381,6 +409,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 1,
             hi: 8,
+            line: 1,
+            column: 2,
+            category: Category::Data,
             message: "odd number of items in a Map".into()
         }))
     );
@@ -431,6 +462,9 @@ fn test_read_sets() {
         Some(Err(Error {
             lo: 6,
             hi: 10,
+            line: 1,
+            column: 7,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -441,6 +475,9 @@ fn test_read_sets() {
         Some(Err(Error {
             lo: 3,
             hi: 11,
+            line: 1,
+            column: 4,
+            category: Category::Eof,
             message: "unclosed `#{`".into()
         }))
     );
@@ -489,6 +526,9 @@ fn test_tagged_values() {
         Some(Err(Error {
             lo: 60,
             hi: 68,
+            line: 5,
+            column: 2,
+            category: Category::Eof,
             message: "malformed tagged value".into(),
         }))
     );
@@ -515,6 +555,6 @@ fn test_comments() {
     assert_eq!(parser.read(), Some(Ok(Value::Symbol("--".into()))));
     assert_eq!(parser.read(), Some(Ok(Value::Integer(0))));
     assert_eq!(parser.read(), Some(Ok(Value::Vector(Vector::new()))));
-    assert_eq!(parser.read(), Some(Ok(Value::Map(HashMap::new()))));
+    assert_eq!(parser.read(), Some(Ok(Value::Map(Map::new()))));
     assert_eq!(parser.read(), None);
 }
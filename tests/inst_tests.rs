@@ -0,0 +1,60 @@
+#![cfg(feature = "chrono")]
+
+extern crate chrono;
+extern crate edn;
+
+use chrono::{TimeZone, Utc};
+use edn::parser::{Category, Error, Parser};
+use edn::readers::{self, ReaderRegistry};
+use edn::Value;
+
+#[test]
+fn test_from_datetime() {
+    let datetime = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let value = Value::from(datetime);
+    assert_eq!(
+        value,
+        Value::Tagged(
+            "inst".into(),
+            Box::new(Value::String("2023-01-01T00:00:00Z".into()))
+        )
+    );
+    assert_eq!(value.as_inst(), Some(datetime));
+}
+
+#[test]
+fn test_as_inst() {
+    let mut parser = Parser::new(r#"#inst "2023-01-01T02:00:00+02:00" #inst "nope" #uuid "x""#);
+    assert_eq!(
+        parser.read().unwrap().unwrap().as_inst(),
+        Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(parser.read().unwrap().unwrap().as_inst(), None);
+    assert_eq!(parser.read().unwrap().unwrap().as_inst(), None);
+}
+
+#[test]
+fn test_inst_reader() {
+    let mut registry = ReaderRegistry::new();
+    registry.register("inst", readers::inst);
+
+    let mut parser = Parser::with_readers(
+        r#"#inst "2023-01-01T02:00:00+02:00" #inst 1"#,
+        &registry,
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::from(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap())))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 34,
+            hi: 41,
+            line: 1,
+            column: 35,
+            category: Category::Data,
+            message: "expected an RFC 3339 timestamp string for `#inst`".into(),
+        }))
+    );
+}
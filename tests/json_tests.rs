@@ -0,0 +1,87 @@
+#![cfg(feature = "json")]
+
+extern crate edn;
+extern crate serde_json;
+
+use std::convert::TryFrom;
+
+use edn::json::{from_lossless_json, to_lossless_json, KeywordStyle, Options, TaggedStyle};
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn json(str: &str) -> serde_json::Value {
+    serde_json::from_str(str).unwrap()
+}
+
+#[test]
+fn test_from_json() {
+    assert_eq!(
+        Value::from(json(r#"{"a": [1, 2.5, null, true, "s"], "b": {}, "c": 18446744073709551615}"#)),
+        parse(r#"{"a" [1 2.5 nil true "s"] "b" {} "c" 18446744073709551615.0}"#)
+    );
+}
+
+#[test]
+fn test_to_json() {
+    let value = parse(r#"{:user/name "Ada" sym \x :tags #{:a} [1] #inst "1815-12-10" nil 1.5}"#);
+    assert_eq!(
+        serde_json::Value::try_from(value.clone()),
+        Ok(json(
+            r#"{"user/name": "Ada", "sym": "x", "tags": ["a"], "[1]": "1815-12-10", "nil": 1.5}"#
+        ))
+    );
+
+    let options = Options::new()
+        .keywords(KeywordStyle::Colon)
+        .tagged(TaggedStyle::Object);
+    assert_eq!(
+        options.to_json(&parse(r#"[:a #inst "1815-12-10"]"#)),
+        Ok(json(r##"[":a", {"#inst": "1815-12-10"}]"##))
+    );
+
+    assert!(Options::new().keywords(KeywordStyle::Error).to_json(&value).is_err());
+    assert!(Options::new().symbols(false).to_json(&value).is_err());
+    assert!(Options::new().chars(false).to_json(&value).is_err());
+    assert!(Options::new().sets(false).to_json(&value).is_err());
+    assert!(Options::new().non_string_keys(false).to_json(&value).is_err());
+    assert!(Options::new().tagged(TaggedStyle::Error).to_json(&value).is_err());
+    assert!(Options::new().to_json(&Value::from(f64::NAN)).is_err());
+}
+
+#[test]
+fn test_lossless_json() {
+    let source = r#"^{:doc "x"} {:user/id (1 sym) #{\a } ["s" nil true 1.5 ##NaN] {[1] 2} #inst "1815-12-10"}"#;
+    let value = edn::parser::ParserBuilder::new()
+        .keep_metadata(true)
+        .from_str(source)
+        .unwrap();
+    let encoded = to_lossless_json(&value);
+    assert_eq!(
+        encoded.pointer("/#meta/0"),
+        Some(&json(r##"{"#map": [{"#kw": "doc"}, "x"]}"##))
+    );
+    assert_eq!(from_lossless_json(&encoded).unwrap(), value);
+    let text = serde_json::to_string(&encoded).unwrap();
+    assert_eq!(from_lossless_json(&json(&text)).unwrap(), value);
+    assert_eq!(
+        to_lossless_json(&parse("[#inst \"x\" (:a)]")),
+        json(r##"[{"#tag": ["inst", "x"]}, {"#list": [{"#kw": "a"}]}]"##)
+    );
+}
+
+#[test]
+fn test_lossless_json_errors() {
+    for (json_str, message) in [
+        (r#"{"a": 1, "b": 2}"#, "expected an object with a single key"),
+        (r##"{"#nope": 1}"##, "unknown key `#nope`"),
+        (r##"{"#kw": 1}"##, "expected a string for `#kw`"),
+        (r##"{"#map": [1]}"##, "odd number of items in `#map`"),
+        (r##"{"#char": "ab"}"##, "expected one character for `#char`"),
+    ] {
+        assert_eq!(from_lossless_json(&json(json_str)).unwrap_err().message, message);
+    }
+}
@@ -0,0 +1,48 @@
+extern crate edn;
+
+use edn::kafka;
+use edn::parser::{Category, Error, Parser};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_round_trip() {
+    let value = parse(r#"{:event/type :user/created :user/id 42}"#);
+    let message = kafka::encode(7, &value);
+    assert_eq!(&message[..5], &[0, 0, 0, 0, 7]);
+    assert_eq!(kafka::schema_id(&message), Ok(7));
+    assert_eq!(kafka::decode(&message), Ok((7, value)));
+}
+
+#[test]
+fn test_errors() {
+    let error = |lo, hi, column, message: &str| {
+        Err(Error {
+            lo,
+            hi,
+            line: 1,
+            column,
+            category: Category::Syntax,
+            message: message.into(),
+        })
+    };
+    assert_eq!(
+        kafka::decode(&[0, 0, 0]),
+        error(0, 3, 1, "message is shorter than its 5 byte header")
+    );
+    assert_eq!(
+        kafka::decode(b"\x01\0\0\0\x01nil"),
+        error(0, 1, 1, "expected magic byte 0")
+    );
+    assert_eq!(
+        kafka::decode(b"\0\0\0\0\x01[1 \xff]"),
+        error(8, 8, 1, "invalid UTF-8 in message")
+    );
+    assert_eq!(
+        kafka::decode(b"\0\0\0\0\x01[1 2] 3"),
+        error(11, 11, 7, "trailing characters after form")
+    );
+}
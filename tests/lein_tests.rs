@@ -0,0 +1,178 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::lein::{self, Dependency};
+use edn::parser::{Category, Parser, ParserBuilder};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+const PROJECT: &str = r#"
+(def jackson-version "2.15.0")
+
+(defproject my-app "1.0.0"
+  :description "Example"
+  :dependencies [[org.clojure/clojure "1.11.1"]
+                 [com.fasterxml.jackson.core/jackson-core ~jackson-version :scope "provided"]]
+  :plugins [[lein-ancient "1.0.0-RC3"]]
+  :jvm-opts ^:replace ["-Xmx1g"]
+  :aliases {"lint" ["run" "-m" 'clj-kondo.main]}
+  :injections [@(delay #'clojure.core/inc) #(println %)]
+  :manifest {"Built-By" #=(eval (System/getenv "USER"))}
+  :profiles {:dev {:source-paths ~(concat ["dev"] ["env"])
+                   :jar-exclusions [#"\.java$" #"a\"b"]}})
+"#;
+
+#[test]
+fn test_parse() {
+    let project = lein::parse(PROJECT).unwrap();
+    assert_eq!((project.group(), project.artifact()), ("my-app", "my-app"));
+    assert_eq!(project.version, Value::from("1.0.0"));
+    assert_eq!(project.get("description"), Some(&Value::from("Example")));
+    assert_eq!(project.get("jvm-opts"), Some(&parse(r#"["-Xmx1g"]"#)));
+    assert_eq!(
+        project.get("aliases"),
+        Some(&parse(r#"{"lint" ["run" "-m" (quote clj-kondo.main)]}"#))
+    );
+    assert_eq!(
+        project.get("injections"),
+        Some(&parse(
+            "[(deref (delay (var clojure.core/inc))) (fn* (println %))]"
+        ))
+    );
+    assert_eq!(
+        project.get("profiles"),
+        Some(&parse(
+            r#"{:dev {:source-paths (unquote (concat ["dev"] ["env"]))
+                      :jar-exclusions [#regex "\\.java$" #regex "a\\\"b"]}}"#
+        ))
+    );
+    assert_eq!(
+        project.evaluated,
+        vec![
+            parse("(unquote jackson-version)"),
+            parse(r#"(read-eval (eval (System/getenv "USER")))"#),
+            parse(r#"(unquote (concat ["dev"] ["env"]))"#),
+        ]
+    );
+
+    let dependencies = project.dependencies().unwrap();
+    assert_eq!(
+        dependencies[1],
+        Dependency {
+            name: "com.fasterxml.jackson.core/jackson-core".into(),
+            version: parse("(unquote jackson-version)"),
+            options: parse(r#"{:scope "provided"}"#).into_map().unwrap(),
+        }
+    );
+    assert_eq!(project.plugins().unwrap()[0].name.name(), "lein-ancient");
+}
+
+#[test]
+fn test_parse_errors() {
+    let message = |str: &str| lein::parse(str).unwrap_err().message;
+    assert_eq!(message("(def x 1)"), "no `defproject` form found");
+    assert_eq!(
+        message("(defproject \"x\")"),
+        "expected a project name after `defproject`"
+    );
+    assert_eq!(
+        message("(defproject x \"1\" :a)"),
+        "odd number of options in `defproject`"
+    );
+    assert_eq!(
+        lein::parse("(defproject x \"1\" :dependencies [[a \"1\" :scope]])")
+            .unwrap()
+            .dependencies()
+            .unwrap_err()
+            .message,
+        "odd number of options in dependency `a`"
+    );
+    assert!(message("(def x 1) ~@").starts_with("expected a form after `~@`, found EOF"));
+}
+
+#[test]
+fn test_clojure_syntax_is_opt_in() {
+    assert!(Parser::new("'x").read().unwrap().is_err());
+    for &(str, dispatch) in &[
+        ("#\"re\"", "#\""),
+        ("#(inc %)", "#("),
+        ("#'a", "#'"),
+        ("#:a{:b 1}", "#:"),
+        ("#=(+ 1 2)", "#="),
+    ] {
+        let err = Parser::new(str).read().unwrap().unwrap_err();
+        assert_eq!(err.category, Category::Syntax, "{}", str);
+        assert_eq!(
+            err.message,
+            format!(
+                "`{}` is Clojure syntax, not EDN; read it with `ParserBuilder::clojure_syntax`",
+                dispatch
+            )
+        );
+    }
+    assert_eq!(
+        Parser::new("#1").read().unwrap().unwrap_err().message,
+        "unexpected `#1`"
+    );
+    assert_eq!(
+        ParserBuilder::new()
+            .clojure_syntax(true)
+            .from_str("`(a ~@b ~c)")
+            .unwrap(),
+        parse("(syntax-quote (a (unquote-splicing b) (unquote c)))")
+    );
+}
+
+#[test]
+fn test_lenient() {
+    let lenient = ParserBuilder::new().lenient();
+    assert_eq!(
+        lenient
+            .from_str("[0x1F -0XfF +2r1010 36rZZ 017 @a 'b #\"c+\"]")
+            .unwrap(),
+        parse("[31 -255 10 1295 17 (deref a) (quote b) #regex \"c+\"]")
+    );
+    assert_eq!(
+        lenient.from_str("0x8000000000000000").unwrap_err().message,
+        "integer `0x8000000000000000` out of range"
+    );
+    assert_eq!(
+        lenient.from_str("-0x8000000000000000").unwrap(),
+        Value::Integer(i64::MIN)
+    );
+    for str in &["0x", "0xG", "2r102"] {
+        let err = lenient.from_str(str).unwrap_err();
+        assert_eq!(err.message, format!("invalid number `{}`", str));
+    }
+    // Radixes go up to 36, as in Clojure.
+    assert!(lenient.from_str("37r1").is_err());
+
+    assert!(ParserBuilder::new().from_str("0x1F").is_err());
+}
+
+#[test]
+fn test_namespaced_maps() {
+    let lenient = ParserBuilder::new().lenient();
+    assert_eq!(
+        lenient
+            .from_str("#:a{:b 1, :c/d 2, :_/e 3, f 4, \"g\" 5}")
+            .unwrap(),
+        parse("{:a/b 1, :c/d 2, :e 3, a/f 4, \"g\" 5}")
+    );
+    assert_eq!(
+        lenient.from_str("#:a.b {:c 1}").unwrap(),
+        parse("{:a.b/c 1}")
+    );
+    for &(str, message) in &[
+        ("#::{:b 1}", "expected a namespace after `#:`"),
+        ("#:{:b 1}", "expected a namespace after `#:`"),
+        ("#:a [1]", "expected a map after `#:a`"),
+        ("#:a", "expected a map after `#:a`"),
+    ] {
+        assert_eq!(lenient.from_str(str).unwrap_err().message, message, "{}", str);
+    }
+}
@@ -0,0 +1,120 @@
+extern crate edn;
+
+use edn::lexer::{Lexer, Span, Token};
+use edn::parser::Category;
+
+fn tokens(str: &str) -> Vec<Token<'_>> {
+    Lexer::new(str).map(|result| result.unwrap().0).collect()
+}
+
+#[test]
+fn test_tokens() {
+    assert_eq!(
+        tokens(r#"{:a/b [1 -2.5 3N 4M ##-Inf 22/7], "s\"x" #{nil true}} ; done"#),
+        vec![
+            Token::OpenMap,
+            Token::Keyword("a/b"),
+            Token::Whitespace(" "),
+            Token::OpenVector,
+            Token::Integer("1"),
+            Token::Whitespace(" "),
+            Token::Float("-2.5"),
+            Token::Whitespace(" "),
+            Token::Integer("3N"),
+            Token::Whitespace(" "),
+            Token::Float("4M"),
+            Token::Whitespace(" "),
+            Token::Float("##-Inf"),
+            Token::Whitespace(" "),
+            Token::Ratio("22/7"),
+            Token::CloseVector,
+            Token::Whitespace(", "),
+            Token::String(r#"s\"x"#),
+            Token::Whitespace(" "),
+            Token::OpenSet,
+            Token::Nil,
+            Token::Whitespace(" "),
+            Token::Boolean(true),
+            Token::CloseMap,
+            Token::CloseMap,
+            Token::Whitespace(" "),
+            Token::Comment(" done"),
+        ]
+    );
+    assert_eq!(
+        tokens(r#"^:m #_x #inst "2020" (- + / \newline \))"#),
+        vec![
+            Token::Meta,
+            Token::Keyword("m"),
+            Token::Whitespace(" "),
+            Token::Discard,
+            Token::Symbol("x"),
+            Token::Whitespace(" "),
+            Token::Tag("inst"),
+            Token::Whitespace(" "),
+            Token::String("2020"),
+            Token::Whitespace(" "),
+            Token::OpenList,
+            Token::Symbol("-"),
+            Token::Whitespace(" "),
+            Token::Symbol("+"),
+            Token::Whitespace(" "),
+            Token::Symbol("/"),
+            Token::Whitespace(" "),
+            Token::Char("newline"),
+            Token::Whitespace(" "),
+            Token::Char(")"),
+            Token::CloseList,
+        ]
+    );
+    assert_eq!(
+        tokens(r#""\u0041\b" \u00e9 \backslash"#),
+        vec![
+            Token::String(r#"\u0041\b"#),
+            Token::Whitespace(" "),
+            Token::Char("u00e9"),
+            Token::Whitespace(" "),
+            Token::Char("backslash"),
+        ]
+    );
+}
+
+#[test]
+fn test_spans() {
+    let str = "(a ;x\n \"é\")";
+    let spans: Vec<Span> = Lexer::new(str).map(|result| result.unwrap().1).collect();
+    assert_eq!(
+        spans,
+        vec![
+            Span { lo: 0, hi: 1 },
+            Span { lo: 1, hi: 2 },
+            Span { lo: 2, hi: 3 },
+            Span { lo: 3, hi: 5 },
+            Span { lo: 5, hi: 7 },
+            Span { lo: 7, hi: 11 },
+            Span { lo: 11, hi: 12 },
+        ]
+    );
+    let text: String = spans.iter().map(|span| &str[span.lo..span.hi]).collect();
+    assert_eq!(text, str);
+}
+
+#[test]
+fn test_errors() {
+    let mut lexer = Lexer::new("[1\n \"abc");
+    assert!(lexer.by_ref().take(3).all(|result| result.is_ok()));
+    let err = lexer.next().unwrap().unwrap_err();
+    assert_eq!((err.lo, err.hi, err.line, err.column), (4, 8, 2, 2));
+    assert_eq!(err.category, Category::Eof);
+    assert!(lexer.next().is_none());
+
+    let err = Lexer::new("\\abc").next().unwrap().unwrap_err();
+    assert_eq!(err.message, "invalid char literal `\\abc`");
+    assert_eq!(err.category, Category::Syntax);
+    let err = Lexer::new("\"\\u00\"").next().unwrap().unwrap_err();
+    assert_eq!((err.lo, err.hi, err.category), (1, 3, Category::Syntax));
+    let err = Lexer::new("##inf").next().unwrap().unwrap_err();
+    assert_eq!(err.message, "unknown symbolic value `##inf`");
+    let err = Lexer::new("#1").next().unwrap().unwrap_err();
+    assert_eq!(err.message, "unexpected `#1`");
+}
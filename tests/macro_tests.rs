@@ -0,0 +1,98 @@
+#![cfg(not(feature = "immutable"))]
+
+#[macro_use]
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_scalars() {
+    assert_eq!(edn!(nil), Value::Nil);
+    assert_eq!(edn!(true), Value::Boolean(true));
+    assert_eq!(edn!(false), Value::Boolean(false));
+    assert_eq!(edn!(42), Value::Integer(42));
+    assert_eq!(edn!(-42), Value::Integer(-42));
+    assert_eq!(edn!(1.5), parse("1.5"));
+    assert_eq!(edn!('c'), Value::Char('c'));
+    assert_eq!(edn!("foo"), Value::String("foo".into()));
+}
+
+#[test]
+fn test_keywords_and_symbols() {
+    assert_eq!(edn!(:foo), Value::Keyword("foo".into()));
+    assert_eq!(edn!(:first-name), Value::Keyword("first-name".into()));
+    assert_eq!(edn!(:my.ns/foo), Value::Keyword("my.ns/foo".into()));
+    assert_eq!(edn!(foo), Value::Symbol("foo".into()));
+    assert_eq!(edn!(clojure.core/map), Value::Symbol("clojure.core/map".into()));
+}
+
+#[test]
+fn test_collections() {
+    assert_eq!(
+        edn!({:name "John" :tags #{:a :b} :scores [1 2 3]}),
+        parse("{:name \"John\" :tags #{:a :b} :scores [1 2 3]}")
+    );
+    assert_eq!(
+        edn!((defn sum [xs] (reduce + 0 xs))),
+        parse("(defn sum [xs] (reduce + 0 xs))")
+    );
+    assert_eq!(edn!({:a -1 :b [] :c ()}), parse("{:a -1 :b [] :c ()}"));
+}
+
+#[test]
+fn test_interpolation() {
+    let name = "John";
+    let scores = vec![1_i64, 2, 3];
+    assert_eq!(
+        edn!({:name ~name :scores ~(scores.clone()) :total ~(scores.iter().sum::<i64>())}),
+        parse("{:name \"John\" :scores [1 2 3] :total 6}")
+    );
+}
+
+#[test]
+#[should_panic(expected = "odd number of items in a Map")]
+fn test_odd_map() {
+    edn!({:a 1 :b});
+}
+
+#[test]
+fn test_keys() {
+    use edn::{Keyword, Symbol};
+
+    let value = parse(
+        "{:name \"Ada\" :age 36 :user/first-name \"A\" :admin? true :tags [:a :b] :score 1.5 :sym x :nick nil}",
+    );
+    let (name, age, first, admin, tags): (String, i64, String, bool, Vec<Keyword>) =
+        edn_keys!(value, :name, :age, :user/first-name, :admin?, :tags).unwrap();
+    assert_eq!(
+        (name.as_str(), age, first.as_str(), admin),
+        ("Ada", 36, "A", true)
+    );
+    assert_eq!(tags, vec![Keyword::new("a"), Keyword::new("b")]);
+
+    let (score, sym, nick, email, raw): (f64, Symbol, Option<String>, Option<String>, Value) =
+        edn_keys!(&value, :score, :sym, :nick, :email, :tags,).unwrap();
+    assert_eq!(
+        (score, sym, nick, email),
+        (1.5, Symbol::new("x"), None, None)
+    );
+    assert_eq!(raw, parse("[:a :b]"));
+
+    let missing: Result<(String, i64), _> = edn_keys!(value, :name, :email);
+    assert_eq!(missing.unwrap_err().message, "missing key :email");
+    let mistyped: Result<(i64,), _> = edn_keys!(value, :name);
+    assert_eq!(
+        mistyped.unwrap_err().message,
+        ":name: expected an integer, found a string"
+    );
+    let not_map: Result<(i64,), _> = edn_keys!(parse("[1]"), :a);
+    assert_eq!(
+        not_map.unwrap_err().message,
+        "expected a map, found a vector"
+    );
+}
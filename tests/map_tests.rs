@@ -0,0 +1,122 @@
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::{Map, Value};
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn map(str: &str) -> Map<Value, Value> {
+    parse(str).into_map().unwrap()
+}
+
+#[test]
+fn test_methods() {
+    let mut map = map("{:a 1 :person/name \"x\" \"s\" 2}");
+    assert_eq!(map.len(), 3);
+    assert!(!map.is_empty());
+    assert_eq!(map.get_kw("a"), Some(&Value::Integer(1)));
+    assert_eq!(map.get_kw(":person/name"), Some(&Value::from("x")));
+    assert_eq!(map.get_kw("s"), None);
+    assert!(map.contains_key(&Value::from("s")));
+    assert_eq!(
+        map.get_key_value(&Value::from("s")),
+        Some((&Value::from("s"), &Value::Integer(2)))
+    );
+    assert_eq!(map[&Value::from("s")], Value::Integer(2));
+
+    assert_eq!(
+        map.insert(Value::from("s"), Value::Nil),
+        Some(Value::Integer(2))
+    );
+    assert_eq!(map.remove(&Value::from("s")), Some(Value::Nil));
+    assert_eq!(map.remove(&Value::from("s")), None);
+    map.retain(|key, _| {
+        key.as_keyword()
+            .is_some_and(|key| key.namespace().is_none())
+    });
+    assert_eq!(Value::Map(map.clone()), parse("{:a 1}"));
+    map.clear();
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_entry() {
+    let mut counts = Map::new();
+    for word in &["a", "b", "a"] {
+        *counts.entry(Value::from(*word)).or_insert(0) += 1;
+    }
+    counts
+        .entry(Value::from("b"))
+        .and_modify(|count| *count *= 10)
+        .or_default();
+    counts
+        .entry(Value::from("c"))
+        .and_modify(|count| *count *= 10)
+        .or_default();
+    assert_eq!(counts.entry(Value::from("c")).key(), &Value::from("c"));
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![
+            (Value::from("a"), 2),
+            (Value::from("b"), 10),
+            (Value::from("c"), 0),
+        ]
+    );
+}
+
+#[test]
+fn test_iterators() {
+    let mut map = map("{:a 1 :b 2}");
+    for (_, value) in map.iter_mut() {
+        *value = Value::Integer(value.as_i64().unwrap() * 2);
+    }
+    let mut keys: Vec<_> = map.keys().cloned().collect();
+    keys.sort();
+    assert_eq!(keys, vec![parse(":a"), parse(":b")]);
+    let mut values: Vec<_> = map.values().cloned().collect();
+    values.sort();
+    assert_eq!(values, vec![Value::Integer(2), Value::Integer(4)]);
+    assert_eq!((&map).into_iter().count(), 2);
+
+    let map: Map<Value, Value> = map
+        .into_iter()
+        .chain(vec![(parse(":c"), Value::Nil)])
+        .collect();
+    assert_eq!(Value::Map(map), parse("{:a 2 :b 4 :c nil}"));
+}
+
+#[test]
+fn test_borrowed_lookups() {
+    let mut map = map(
+        "{nil 0 true 1 \"a\" 2 \"zz\" 3 \\c 4 sym 5 :a 6 :zz 7 :ns/a 8 \
+         :a.b/c 9 10 10 2.5 11 [1] 12 {:a 1} 13 #{\"a\"} 14 \"\" 15 :0 16}",
+    );
+    assert_eq!(map.get_str("a"), Some(&Value::Integer(2)));
+    assert_eq!(map.get_str("zz"), Some(&Value::Integer(3)));
+    assert_eq!(map.get_str(""), Some(&Value::Integer(15)));
+    assert_eq!(map.get_str("b"), None);
+    assert_eq!(map.get_str("sym"), None);
+    assert_eq!(map.get_kw("a"), Some(&Value::Integer(6)));
+    assert_eq!(map.get_kw(":zz"), Some(&Value::Integer(7)));
+    assert_eq!(map.get_kw("ns/a"), Some(&Value::Integer(8)));
+    assert_eq!(map.get_kw("a.b/c"), Some(&Value::Integer(9)));
+    assert_eq!(map.get_kw("c"), None);
+    assert_eq!(map.get_kw("other/a"), None);
+    assert_eq!(map.get_kw("sym"), None);
+
+    *map.get_kw_mut("ns/a").unwrap() = Value::Nil;
+    *map.get_str_mut("zz").unwrap() = Value::Nil;
+    assert_eq!(map.get_kw_mut("b"), None);
+    assert_eq!(map.get_str_mut("b"), None);
+    assert_eq!(map[&parse(":ns/a")], Value::Nil);
+    assert_eq!(map[&Value::from("zz")], Value::Nil);
+    assert_eq!(map.len(), 17);
+
+    let value = Value::Map(map);
+    assert_eq!(value["a"], Value::Integer(6));
+    assert_eq!(value[":ns/a"], Value::Nil);
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "mmap")]
+
+extern crate edn;
+
+use std::env;
+use std::fs;
+
+use edn::mmap::{self, MmapFile};
+use edn::parser::{self, Category};
+
+#[test]
+fn test_mmap() {
+    let path = env::temp_dir().join(format!("edn-mmap-test-{}.edn", std::process::id()));
+    fs::write(&path, "{:a 1}\n[2 3] ; comment\n#{4}\n").unwrap();
+
+    let file = MmapFile::open(&path).unwrap();
+    let forms: Result<Vec<_>, _> = file.forms().unwrap().collect();
+    assert_eq!(
+        forms.unwrap(),
+        vec![
+            parser::from_str("{:a 1}").unwrap(),
+            parser::from_str("[2 3]").unwrap(),
+            parser::from_str("#{4}").unwrap(),
+        ]
+    );
+    let err = mmap::from_mmap(&path).unwrap_err();
+    assert_eq!(err.message, "trailing characters after form");
+
+    fs::write(&path, b"[1 \xff]").unwrap();
+    let file = MmapFile::open(&path).unwrap();
+    assert_eq!(file.forms().err().map(|err| err.category), Some(Category::Syntax));
+    drop(file);
+    fs::remove_file(&path).unwrap();
+
+    let err = mmap::from_mmap(&path).unwrap_err();
+    assert_eq!(err.category, Category::Io);
+}
@@ -1,7 +1,9 @@
+#![cfg(not(feature = "immutable"))]
+
 extern crate edn;
 extern crate ordered_float;
 
-use edn::parser::{Error, Parser};
+use edn::parser::{Category, Error, Parser};
 use edn::Value;
 
 #[test]
@@ -44,6 +46,19 @@ fn test_read_floats() {
     assert_eq!(parser.read(), Some(Ok(Value::Float(OrderedFloat(-1.23)))));
     assert_eq!(parser.read(), Some(Ok(Value::Float(OrderedFloat(0.125)))));
     assert_eq!(parser.read(), None);
+
+    let mut parser = Parser::new("1e16 1e-7 1.5e300 -2.5E+3 .5e2");
+    assert_eq!(parser.read(), Some(Ok(Value::Float(OrderedFloat(1e16)))));
+    assert_eq!(parser.read(), Some(Ok(Value::Float(OrderedFloat(1e-7)))));
+    assert_eq!(parser.read(), Some(Ok(Value::Float(OrderedFloat(1.5e300)))));
+    assert_eq!(parser.read(), Some(Ok(Value::Float(OrderedFloat(-2500.0)))));
+    assert_eq!(parser.read(), Some(Ok(Value::Float(OrderedFloat(50.0)))));
+    assert_eq!(parser.read(), None);
+
+    for &float in &[1e16, 1e-7, 1.5e300, 5e-324, f64::MAX] {
+        let value = Value::Float(OrderedFloat(float));
+        assert_eq!(edn::parser::from_str(&edn::to_string(&value)), Ok(value));
+    }
 }
 
 #[test]
@@ -57,12 +72,36 @@ fn test_read_chars() {
     assert_eq!(parser.read(), Some(Ok(Value::Char('\t'))));
     assert_eq!(parser.read(), None);
 
+    let mut parser = Parser::new("\\backspace \\formfeed \\backslash \\u0041 \\u00E9 \\u");
+    assert_eq!(parser.read(), Some(Ok(Value::Char('\u{8}'))));
+    assert_eq!(parser.read(), Some(Ok(Value::Char('\u{c}'))));
+    assert_eq!(parser.read(), Some(Ok(Value::Char('\\'))));
+    assert_eq!(parser.read(), Some(Ok(Value::Char('A'))));
+    assert_eq!(parser.read(), Some(Ok(Value::Char('é'))));
+    assert_eq!(parser.read(), Some(Ok(Value::Char('u'))));
+    assert_eq!(parser.read(), None);
+    for str in &["\\u004", "\\u00411", "\\ud800", "\\uzzzz"] {
+        assert!(Parser::new(str).read().unwrap().is_err(), "{}", str);
+    }
+
+    let mut parser = Parser::new("[\\a \\space]\\(\\,");
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::Vector(vec![Value::Char('a'), Value::Char(' ')])))
+    );
+    assert_eq!(parser.read(), Some(Ok(Value::Char('('))));
+    assert_eq!(parser.read(), Some(Ok(Value::Char(','))));
+    assert_eq!(parser.read(), None);
+
     let mut parser = Parser::new("  \\foo  ");
     assert_eq!(
         parser.read(),
         Some(Err(Error {
             lo: 2,
             hi: 6,
+            line: 1,
+            column: 3,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -85,12 +124,35 @@ quux"
     assert_eq!(parser.read(), Some(Ok(Value::String("\t\r\n\\\"".into()))));
     assert_eq!(parser.read(), None);
 
+    let mut parser = Parser::new(r#""\u0041\b\f\u00e9\ud83d\ude00" "\uD83D""#);
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::String("A\u{8}\u{c}é😀".into())))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 32,
+            hi: 34,
+            line: 1,
+            column: 33,
+            category: Category::Syntax,
+            message: "expected four hex digits naming a char after `\\u`".into()
+        }))
+    );
+    for str in &[r#""\u12""#, r#""\uzzzz""#, r#""\udc00""#, r#""\ud800\u0041""#] {
+        assert!(Parser::new(str).read().unwrap().is_err(), "{}", str);
+    }
+
     let mut parser = Parser::new("\"foo\\x\"");
     assert_eq!(
         parser.read(),
         Some(Err(Error {
             lo: 4,
             hi: 6,
+            line: 1,
+            column: 5,
+            category: Category::Syntax,
             message: "invalid string escape `\\x`".into()
         }))
     );
@@ -101,6 +163,9 @@ quux"
         Some(Err(Error {
             lo: 3,
             hi: 7,
+            line: 1,
+            column: 4,
+            category: Category::Eof,
             message: "expected closing `\"`, found EOF".into()
         }))
     );
@@ -139,6 +204,30 @@ namespaced/symbol
     assert_eq!(parser.read(), None);
 }
 
+#[test]
+fn test_read_sign_before_delimiter() {
+    let minus = || Value::Symbol("-".into());
+    assert_eq!(
+        edn::parser::from_str("[-]"),
+        Ok(Value::Vector(vec![minus()]))
+    );
+    assert_eq!(edn::parser::from_str("(-)"), Ok(Value::List(vec![minus()])));
+    assert_eq!(
+        edn::parser::from_str("{:a -}"),
+        Ok(Value::Map(
+            vec![(Value::Keyword("a".into()), minus())]
+                .into_iter()
+                .collect()
+        ))
+    );
+    assert_eq!(edn::parser::from_str("-,"), Ok(minus()));
+    assert_eq!(edn::parser::from_str("-\r"), Ok(minus()));
+
+    let mut parser = Parser::new("+)");
+    assert_eq!(parser.read(), Some(Ok(Value::Symbol("+".into()))));
+    assert!(parser.read().unwrap().is_err());
+}
+
 #[test]
 fn test_read_keywords() {
     let mut parser = Parser::new(
@@ -225,6 +314,9 @@ fn test_read_lists() {
         Some(Err(Error {
             lo: 4,
             hi: 8,
+            line: 1,
+            column: 5,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -235,6 +327,9 @@ fn test_read_lists() {
         Some(Err(Error {
             lo: 2,
             hi: 10,
+            line: 1,
+            column: 3,
+            category: Category::Eof,
             message: "unclosed `(`".into()
         }))
     );
@@ -282,6 +377,9 @@ fn test_read_vectors() {
         Some(Err(Error {
             lo: 4,
             hi: 8,
+            line: 1,
+            column: 5,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -292,6 +390,9 @@ fn test_read_vectors() {
         Some(Err(Error {
             lo: 2,
             hi: 10,
+            line: 1,
+            column: 3,
+            category: Category::Eof,
             message: "unclosed `[`".into()
         }))
     );
@@ -306,14 +407,14 @@ fn test_read_maps() {
                                   {{\"foo\" \"bar\"} \"baz\"}",
     );
 
-    assert_eq!(parser.read(), Some(Ok(Value::Map(BTreeMap::new()))));
+    assert_eq!(parser.read(), Some(Ok(Value::Map(Default::default()))));
 
     assert_eq!(
         parser.read(),
         Some(Ok(Value::Map({
             let mut map = BTreeMap::new();
             map.insert(Value::Integer(1), Value::Integer(2));
-            map
+            map.into_iter().collect()
         })))
     );
 
@@ -322,7 +423,7 @@ fn test_read_maps() {
         Some(Ok(Value::Map({
             let mut map = BTreeMap::new();
             map.insert(Value::Boolean(true), Value::Boolean(false));
-            map
+            map.into_iter().collect()
         })))
     );
 
@@ -334,11 +435,11 @@ fn test_read_maps() {
                 Value::Map({
                     let mut map = BTreeMap::new();
                     map.insert(Value::String("foo".into()), Value::String("bar".into()));
-                    map
+                    map.into_iter().collect()
                 }),
                 Value::String("baz".into()),
             );
-            map
+            map.into_iter().collect()
         })))
     );
 
@@ -348,6 +449,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 1,
             hi: 5,
+            line: 1,
+            column: 2,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -358,6 +462,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 2,
             hi: 9,
+            line: 1,
+            column: 3,
+            category: Category::Eof,
             message: "unclosed `{`".into()
         }))
     );
@@ -368,6 +475,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 0,
             hi: 7,
+            line: 1,
+            column: 1,
+            category: Category::Data,
             message: "odd number of items in a Map".into()
         }))
     );
@@ -378,6 +488,9 @@ fn test_read_maps() {
         Some(Err(Error {
             lo: 1,
             hi: 8,
+            line: 1,
+            column: 2,
+            category: Category::Data,
             message: "odd number of items in a Map".into()
         }))
     );
@@ -441,6 +554,9 @@ fn test_read_sets() {
         Some(Err(Error {
             lo: 6,
             hi: 10,
+            line: 1,
+            column: 7,
+            category: Category::Syntax,
             message: "invalid char literal `\\foo`".into()
         }))
     );
@@ -451,6 +567,9 @@ fn test_read_sets() {
         Some(Err(Error {
             lo: 3,
             hi: 11,
+            line: 1,
+            column: 4,
+            category: Category::Eof,
             message: "unclosed `#{`".into()
         }))
     );
@@ -499,6 +618,9 @@ fn test_tagged_values() {
         Some(Err(Error {
             lo: 60,
             hi: 68,
+            line: 5,
+            column: 2,
+            category: Category::Eof,
             message: "malformed tagged value".into(),
         }))
     );
@@ -508,8 +630,6 @@ fn test_tagged_values() {
 
 #[test]
 fn test_comments() {
-    use std::collections::BTreeMap;
-
     let mut parser = Parser::new(
         "
         ; 0
@@ -527,6 +647,954 @@ fn test_comments() {
     assert_eq!(parser.read(), Some(Ok(Value::Symbol("--".into()))));
     assert_eq!(parser.read(), Some(Ok(Value::Integer(0))));
     assert_eq!(parser.read(), Some(Ok(Value::Vector(Vec::new()))));
-    assert_eq!(parser.read(), Some(Ok(Value::Map(BTreeMap::new()))));
+    assert_eq!(parser.read(), Some(Ok(Value::Map(Default::default()))));
+    assert_eq!(parser.read(), None);
+}
+
+#[test]
+fn test_readers() {
+    use edn::readers::ReaderRegistry;
+
+    let mut readers = ReaderRegistry::new();
+    readers.register("double", |value| match value {
+        Value::Integer(i) => Ok(Value::Integer(i * 2)),
+        _ => Err(Error {
+            lo: 0,
+            hi: 0,
+            line: 1,
+            column: 1,
+            category: Category::Syntax,
+            message: "expected an integer".into(),
+        }),
+    });
+
+    let mut parser = Parser::with_readers("#double 21 [#double 1] #other 1 #double :x", &readers);
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(42))));
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::Vector(vec![Value::Integer(2)])))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::Tagged(
+            "other".into(),
+            Box::new(Value::Integer(1))
+        )))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 32,
+            hi: 42,
+            line: 1,
+            column: 33,
+            category: Category::Syntax,
+            message: "expected an integer".into(),
+        }))
+    );
+
+    readers.set_default(|tag, value| Ok(Value::Vector(vec![Value::Symbol(tag.into()), value])));
+    let mut parser = Parser::with_readers("#other 1", &readers);
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::Vector(vec![
+            Value::Symbol("other".into()),
+            Value::Integer(1),
+        ])))
+    );
+}
+
+#[test]
+fn test_readers_apply() {
+    use edn::readers::ReaderRegistry;
+
+    let value = Parser::new("{:p #my.app/point [1 #double 2] #double 3 [#double 4]}")
+        .read()
+        .unwrap()
+        .unwrap();
+    let mut readers = ReaderRegistry::new();
+    readers.register("double", |value| match value {
+        Value::Integer(i) => Ok(Value::Integer(i * 2)),
+        _ => Err(Error::data("expected an integer")),
+    });
+    assert_eq!(
+        readers.apply(value.clone()).unwrap(),
+        Parser::new("{:p #my.app/point [1 4] 6 [8]}").read().unwrap().unwrap()
+    );
+
+    let mut untag = ReaderRegistry::new();
+    untag.set_default(|_, value| Ok(value));
+    assert_eq!(
+        untag.apply(value).unwrap(),
+        Parser::new("{:p [1 2] 3 [4]}").read().unwrap().unwrap()
+    );
+    assert_eq!(
+        readers.apply(Value::Tagged("double".into(), Box::new(Value::Nil))),
+        Err(Error::data("expected an integer"))
+    );
+}
+
+#[test]
+fn test_resolve_tags() {
+    use edn::parser::{self, DEFAULT_RECURSION_LIMIT};
+    use edn::readers::{resolve_tags, ReaderRegistry};
+
+    let mut readers = ReaderRegistry::new();
+    readers.register("double", |value| match value {
+        Value::Integer(i) => Ok(Value::Integer(i * 2)),
+        _ => Err(Error::data("expected an integer")),
+    });
+    readers.register("twice", |value| {
+        Ok(Value::Tagged("double".into(), Box::new(value)))
+    });
+    readers.register("keep", |value| {
+        Ok(Value::Tagged("keep".into(), Box::new(value)))
+    });
+    readers.register("ping", |value| {
+        Ok(Value::Tagged("pong".into(), Box::new(value)))
+    });
+    readers.register("pong", |value| {
+        Ok(Value::Tagged("ping".into(), Box::new(value)))
+    });
+
+    let mut value = parser::from_str(
+        "{#double 1 #{#twice #double 2}, :k ^:m #keep #double 3, :other #my/tag [#twice 4]}",
+    )
+    .unwrap();
+    resolve_tags(&mut value, &readers).unwrap();
+    assert_eq!(
+        value,
+        parser::from_str("{2 #{8}, :k #keep 6, :other #my/tag [8]}").unwrap()
+    );
+
+    let mut value = parser::from_str("[#ping 1]").unwrap();
+    assert_eq!(
+        resolve_tags(&mut value, &readers),
+        Err(Error::data(
+            "tag readers form a cycle: #ping -> #pong -> #ping"
+        ))
+    );
+    let mut value = parser::from_str("#twice :a").unwrap();
+    assert_eq!(
+        resolve_tags(&mut value, &readers),
+        Err(Error::data("expected an integer"))
+    );
+
+    let mut value = Value::Nil;
+    for _ in 0..DEFAULT_RECURSION_LIMIT {
+        value = Value::Tagged("double".into(), Box::new(value));
+    }
+    assert_eq!(
+        resolve_tags(&mut value, &ReaderRegistry::new()),
+        Err(Error::data("recursion limit exceeded"))
+    );
+}
+
+#[test]
+fn test_context_readers() {
+    use edn::parser::ParserBuilder;
+    use edn::readers::{ContextReaders, ReaderRegistry};
+    use std::collections::HashMap;
+
+    let mut readers = ContextReaders::new();
+    readers.register("id", |ids: &mut HashMap<i64, Value>, value| {
+        let id = value.as_i64().ok_or_else(|| Error::data("expected an integer id"))?;
+        ids.insert(id, Value::Nil);
+        Ok(value)
+    });
+    readers.register("ref", |ids: &mut HashMap<i64, Value>, value| {
+        match value.as_i64().and_then(|id| ids.get(&id)) {
+            Some(_) => Ok(Value::Keyword(format!("ref-{}", value.as_i64().unwrap()).into())),
+            None => Err(Error::data("unknown id")),
+        }
+    });
+    let mut registry = ReaderRegistry::new();
+    registry.register("ref", |_| Ok(Value::Nil));
+    registry.register("double", |value| Ok(Value::Integer(value.as_i64().unwrap() * 2)));
+
+    let mut ids = HashMap::new();
+    let builder = ParserBuilder::new().readers(&registry);
+    let mut parser = builder
+        .build("[#id 1 #ref 1 #double 2 #other 3] #ref 2")
+        .context(&readers, &mut ids);
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Parser::new("[1 :ref-1 4 #other 3]").read().unwrap().unwrap()))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 34,
+            hi: 40,
+            line: 1,
+            column: 35,
+            category: Category::Data,
+            message: "unknown id".into(),
+        }))
+    );
+    assert_eq!(ids.len(), 1);
+
+    let values: Vec<_> = std::thread::scope(|scope| {
+        let readers = &readers;
+        let threads: Vec<_> = (0..2)
+            .map(|i| {
+                scope.spawn(move || {
+                    let mut ids = HashMap::new();
+                    let str = format!("[#id {} #ref {}]", i, i);
+                    Parser::with_context(&str, readers, &mut ids).read().unwrap().unwrap()
+                })
+            })
+            .collect();
+        threads.into_iter().map(|thread| thread.join().unwrap()).collect()
+    });
+    assert_eq!(
+        values,
+        vec![
+            Parser::new("[0 :ref-0]").read().unwrap().unwrap(),
+            Parser::new("[1 :ref-1]").read().unwrap().unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_stream() {
+    let forms = Parser::new(" 1 [2 3] ; comment\n:a ").into_stream().collect::<Vec<_>>();
+    assert_eq!(
+        forms,
+        vec![
+            Ok((Value::Integer(1), 1..2)),
+            Ok((
+                Value::Vector(vec![Value::Integer(2), Value::Integer(3)]),
+                3..8
+            )),
+            Ok((Value::Keyword("a".into()), 19..21)),
+        ]
+    );
+
+    let mut stream = Parser::new("1 \\foo 2").into_stream();
+    assert_eq!(stream.next(), Some(Ok((Value::Integer(1), 0..1))));
+    assert!(stream.next().unwrap().is_err());
+    assert_eq!(stream.next(), None);
+}
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn test_integer_overflow() {
+    let mut parser = Parser::new("12N 99999999999999999999");
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(12))));
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 4,
+            hi: 24,
+            line: 1,
+            column: 5,
+            category: Category::Data,
+            message: "integer `99999999999999999999` out of range".into(),
+        }))
+    );
+}
+
+#[test]
+fn test_from_str() {
+    use edn::parser::{self, ParserBuilder};
+    use edn::readers::ReaderRegistry;
+
+    assert_eq!(parser::from_str(" [1] ; done\n"), Ok(Value::Vector(vec![Value::Integer(1)])));
+    assert_eq!(
+        parser::from_str(" ; nothing"),
+        Err(Error {
+            lo: 10,
+            hi: 10,
+            line: 1,
+            column: 11,
+            category: Category::Eof,
+            message: "expected a form, found EOF".into(),
+        })
+    );
+    assert_eq!(
+        parser::from_str("1 2"),
+        Err(Error {
+            lo: 2,
+            hi: 2,
+            line: 1,
+            column: 3,
+            category: Category::Syntax,
+            message: "trailing characters after form".into(),
+        })
+    );
+
+    let mut readers = ReaderRegistry::new();
+    readers.register("inc", |value| match value {
+        Value::Integer(i) => Ok(Value::Integer(i + 1)),
+        value => Ok(value),
+    });
+    let builder = ParserBuilder::new().readers(&readers);
+    assert_eq!(builder.from_str("#inc 1"), Ok(Value::Integer(2)));
+    assert_eq!(builder.build("#inc 2").read(), Some(Ok(Value::Integer(3))));
+}
+
+#[test]
+fn test_from_slice() {
+    use edn::parser;
+
+    assert_eq!(parser::from_slice(b"[1 \"a\"]"), parser::from_str("[1 \"a\"]"));
+    assert_eq!(
+        parser::from_slice("\"café\"".as_bytes()),
+        Ok(Value::String("café".into()))
+    );
+    assert_eq!(
+        parser::from_slice(b"[:a\n \"\xff\"]"),
+        Err(Error {
+            lo: 6,
+            hi: 6,
+            line: 2,
+            column: 3,
+            category: Category::Syntax,
+            message: "invalid UTF-8 in input".into(),
+        })
+    );
+    assert_eq!(
+        parser::from_slice(b"1 2").unwrap_err().message,
+        "trailing characters after form"
+    );
+}
+
+#[test]
+fn test_from_str_partial() {
+    use edn::parser;
+
+    let str = "  {:a [1 2]} rest of the text";
+    let (value, rest) = parser::from_str_partial(str).unwrap();
+    assert_eq!(value, parser::from_str("{:a [1 2]}").unwrap());
+    assert_eq!(rest, " rest of the text");
+    assert_eq!(str.len() - rest.len(), 12);
+
+    assert_eq!(
+        parser::from_str_partial("foo)bar"),
+        Ok((Value::Symbol("foo".into()), ")bar"))
+    );
+    assert_eq!(parser::from_str_partial("42"), Ok((Value::Integer(42), "")));
+    assert_eq!(
+        parser::from_str_partial("; nothing\n")
+            .unwrap_err()
+            .classify(),
+        Category::Eof
+    );
+    assert_eq!(
+        parser::from_str_partial("[1 2").unwrap_err().classify(),
+        Category::Eof
+    );
+}
+
+#[test]
+fn test_read_with_source() {
+    use edn::parser::SourceText;
+
+    let mut parser = Parser::new("  {:a  1} ; note\n [1.50, \"x\"]");
+    assert_eq!(
+        parser.read_with_source(),
+        Some(Ok(SourceText {
+            value: Value::Map(vec![(Value::Keyword("a".into()), Value::Integer(1))]
+                .into_iter()
+                .collect()),
+            text: "{:a  1}".into(),
+        }))
+    );
+    let source = parser.read_with_source().unwrap().unwrap();
+    assert_eq!(source.text, "[1.50, \"x\"]");
+    assert_eq!(parser.read_with_source(), None);
+}
+
+#[test]
+fn test_discard() {
+    let mut parser = Parser::new(
+        "#_ 1 2 [#_ #_ :a :b] {:a #_ :x 1 #_ :y} #{#_ #_ 1 2 ; 3\n 4} #tag #_ x y #_(1 #_ 2) ",
+    );
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(2))));
+    assert_eq!(parser.read(), Some(Ok(Value::Vector(vec![]))));
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::Map(
+            vec![(Value::Keyword("a".into()), Value::Integer(1))]
+                .into_iter()
+                .collect()
+        )))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::Set(vec![Value::Integer(4)].into_iter().collect())))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::Tagged(
+            "tag".into(),
+            Box::new(Value::Symbol("y".into()))
+        )))
+    );
     assert_eq!(parser.read(), None);
+
+    assert_eq!(
+        Parser::new("[1 #_]").read(),
+        Some(Err(Error {
+            lo: 5,
+            hi: 6,
+            line: 1,
+            column: 6,
+            category: Category::Syntax,
+            message: "unexpected `]`".into(),
+        }))
+    );
+    assert_eq!(
+        Parser::new("1 #_ ; nothing").into_stream().nth(1),
+        Some(Err(Error {
+            lo: 2,
+            hi: 14,
+            line: 1,
+            column: 3,
+            category: Category::Eof,
+            message: "expected a form after `#_`, found EOF".into(),
+        }))
+    );
+}
+
+#[test]
+fn test_rename_namespace() {
+    use edn::parser::ParserBuilder;
+    use edn::{Keyword, Symbol};
+
+    let builder = ParserBuilder::new()
+        .rename_namespace("user", "app.user")
+        .rename_namespace("db", "app.db");
+    assert_eq!(
+        builder.from_str("[:user/name user/save! :db/id :other/id :user #user/tag 1]"),
+        Ok(Value::Vector(vec![
+            Value::Keyword(Keyword::namespaced("app.user", "name")),
+            Value::Symbol(Symbol::namespaced("app.user", "save!")),
+            Value::Keyword(Keyword::namespaced("app.db", "id")),
+            Value::Keyword(Keyword::namespaced("other", "id")),
+            Value::Keyword(Keyword::new("user")),
+            Value::Tagged("user/tag".into(), Box::new(Value::Integer(1))),
+        ]))
+    );
+}
+
+#[test]
+fn test_snake_case_keys() {
+    use edn::parser::{self, ParserBuilder};
+
+    let str = r#"{:user/first-name "Ada" :last-name "Lovelace" :address {:zip-code 1} "x-y" 1 [:a-b] 2}"#;
+    assert_eq!(
+        ParserBuilder::new().snake_case_keys(true).from_str(str),
+        parser::from_str(r#"{"first_name" "Ada" "last_name" "Lovelace" "address" {"zip_code" 1} "x-y" 1 [:a-b] 2}"#)
+    );
+    assert_eq!(ParserBuilder::new().snake_case_keys(false).from_str(str), parser::from_str(str));
+}
+
+#[test]
+fn test_strip_namespaces() {
+    use edn::parser::{self, ParserBuilder};
+
+    let str = "{:person/first-name \"Ada\" :db/id 1 :other/x 2 :y 3 :person 4 [:person/z] 5}";
+    let builder = ParserBuilder::new().strip_namespaces(&["person", "db"]);
+    assert_eq!(
+        builder.from_str(str),
+        parser::from_str("{:first-name \"Ada\" :id 1 :other/x 2 :y 3 :person 4 [:person/z] 5}")
+    );
+    assert_eq!(
+        builder.snake_case_keys(true).from_str("{:person/first-name 1 :other/last-name 2}"),
+        parser::from_str("{\"first_name\" 1 \"last_name\" 2}")
+    );
+}
+
+#[test]
+fn test_metadata() {
+    use edn::parser::{self, ParserBuilder};
+
+    fn kw(name: &str) -> Value {
+        Value::Keyword(name.into())
+    }
+
+    let input = "[^:private ^{:doc \"x\" :private false} foo ^String bar ^\"[B\" baz]";
+    assert_eq!(
+        parser::from_str(input),
+        Ok(Value::Vector(vec![
+            Value::Symbol("foo".into()),
+            Value::Symbol("bar".into()),
+            Value::Symbol("baz".into()),
+        ]))
+    );
+
+    let meta = |entries: Vec<(Value, Value)>| entries.into_iter().collect();
+    let builder = ParserBuilder::new().keep_metadata(true);
+    assert_eq!(
+        builder.from_str(input),
+        Ok(Value::Vector(vec![
+            Value::WithMeta(
+                Box::new(Value::Symbol("foo".into())),
+                meta(vec![
+                    (kw("doc"), Value::String("x".into())),
+                    (kw("private"), Value::Boolean(true)),
+                ])
+            ),
+            Value::WithMeta(
+                Box::new(Value::Symbol("bar".into())),
+                meta(vec![(kw("tag"), Value::Symbol("String".into()))])
+            ),
+            Value::WithMeta(
+                Box::new(Value::Symbol("baz".into())),
+                meta(vec![(kw("tag"), Value::String("[B".into()))])
+            ),
+        ]))
+    );
+
+    assert_eq!(
+        parser::from_str("^1 foo"),
+        Err(Error {
+            lo: 0,
+            hi: 2,
+            line: 1,
+            column: 1,
+            category: Category::Syntax,
+            message: "metadata must be a map, keyword, symbol or string".into(),
+        })
+    );
+    assert_eq!(
+        parser::from_str("^:a"),
+        Err(Error {
+            lo: 0,
+            hi: 3,
+            line: 1,
+            column: 1,
+            category: Category::Eof,
+            message: "expected a form after metadata, found EOF".into(),
+        })
+    );
+}
+
+#[test]
+fn test_error_positions() {
+    use edn::parser;
+
+    let err = parser::from_str("{:a 1\n :b [1 2\n   3)}").unwrap_err();
+    assert_eq!(err.message, "unexpected `)`, expected `]`");
+    assert_eq!((err.line(), err.column(), err.byte_offset()), (3, 5, 19));
+    assert_eq!(err.classify(), Category::Syntax);
+    assert_eq!(
+        err.to_string(),
+        "unexpected `)`, expected `]` at line 3 column 5"
+    );
+
+    let err = parser::from_str("{:a\n\n ]").unwrap_err();
+    assert_eq!(err.message, "unexpected `]`, expected a map value");
+    assert_eq!((err.line(), err.column()), (3, 2));
+
+    let err = parser::from_str("#{1 ]").unwrap_err();
+    assert_eq!(err.message, "unexpected `]`, expected `}`");
+
+    let err = parser::from_str("[\"λ\" (1 2").unwrap_err();
+    assert_eq!((err.line(), err.column(), err.byte_offset()), (1, 6, 6));
+    assert_eq!(err.classify(), Category::Eof);
+
+    // Too wide even for the `BigInt` promotion of the `bigint` feature.
+    let err = parser::from_str("9999999999999999999999999999999999999999").unwrap_err();
+    assert_eq!(err.classify(), Category::Data);
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert!(err.source().is_none());
+}
+
+#[test]
+fn test_track_path() {
+    use edn::parser::ParserBuilder;
+
+    let builder = ParserBuilder::new().track_path(true);
+    let str = "{:server {:name \"a\" :ports [80 443 \\foo ]}}";
+    let err = builder.from_str(str).unwrap_err();
+    assert_eq!(
+        err.message,
+        "invalid char literal `\\foo` in `:server :ports [2]`"
+    );
+    assert_eq!(
+        ParserBuilder::new().from_str(str).unwrap_err().message,
+        "invalid char literal `\\foo`"
+    );
+
+    let err = builder.from_str("[{\"a\" (1 ]}]").unwrap_err();
+    assert_eq!(err.message, "unexpected `]`, expected `)` in `[0] \"a\"`");
+    let err = builder.from_str("[1 {:a}]").unwrap_err();
+    assert_eq!(err.message, "odd number of items in a Map in `[1]`");
+
+    let mut parser = builder.build("[1 2] [3 \\bar ]");
+    parser.read();
+    assert_eq!(
+        parser.read().unwrap().unwrap_err().message,
+        "invalid char literal `\\bar` in `[1]`"
+    );
+}
+
+#[test]
+fn test_recursion_limit() {
+    use edn::parser::{self, ParserBuilder, DEFAULT_RECURSION_LIMIT};
+
+    let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+    assert!(parser::from_str(&nested(DEFAULT_RECURSION_LIMIT)).is_ok());
+    let err = parser::from_str(&nested(DEFAULT_RECURSION_LIMIT + 1)).unwrap_err();
+    assert_eq!(err.message, "recursion limit exceeded");
+    assert_eq!(err.lo, DEFAULT_RECURSION_LIMIT);
+    let err = parser::from_str(&"#{(#tag ^:m ".repeat(50)).unwrap_err();
+    assert_eq!(err.message, "recursion limit exceeded");
+
+    let builder = ParserBuilder::new().recursion_limit(3);
+    assert!(builder.from_str("[[1] {:a #{}}]").is_ok());
+    assert!(builder.from_str("[[[1]]]").is_err());
+    assert!(builder.from_str("[[#_[[1]] 1]]").is_err());
+
+    let deep = nested(DEFAULT_RECURSION_LIMIT * 4);
+    let parsed = std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || {
+            ParserBuilder::new()
+                .disable_recursion_limit()
+                .from_str(&deep)
+                .is_ok()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(parsed);
+}
+
+#[test]
+fn test_parse_spanned() {
+    use edn::parser::{self, Spanned};
+
+    let str = "{:a [1 #_x 2]\n :b #tag \"é\"}";
+    let spanned = parser::parse_spanned(str).unwrap();
+    assert_eq!(spanned.value, parser::from_str(str).unwrap());
+    assert_eq!((spanned.span.clone(), spanned.line, spanned.column), (0..28, 1, 1));
+
+    let spans: Vec<_> = spanned
+        .children
+        .iter()
+        .map(|child| (&str[child.span.clone()], child.line, child.column))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            (":a", 1, 2),
+            ("[1 #_x 2]", 1, 5),
+            (":b", 2, 2),
+            ("#tag \"é\"", 2, 5)
+        ]
+    );
+
+    let vector = &spanned.children[1];
+    let items: Vec<_> = vector.children.iter().map(|child| child.span.clone()).collect();
+    assert_eq!(items, vec![5..6, 11..12]);
+    assert_eq!(
+        spanned.children[3].children,
+        vec![Spanned {
+            value: Value::String("é".into()),
+            span: 23..27,
+            line: 2,
+            column: 10,
+            children: vec![],
+            literal: None,
+        }]
+    );
+
+    let mut parser = Parser::new(" 1 ;c\n [2]");
+    assert_eq!(parser.read_spanned().unwrap().unwrap().span, 1..2);
+    let second = parser.read_spanned().unwrap().unwrap();
+    assert_eq!((second.span, second.line, second.children.len()), (7..10, 2, 1));
+    assert_eq!(parser.read_spanned(), None);
+    assert!(parser::parse_spanned("[1 2").is_err());
+}
+
+#[test]
+fn test_symbolic_values() {
+    use edn::parser;
+
+    assert_eq!(parser::from_str("##Inf"), Ok(Value::from(f64::INFINITY)));
+    assert_eq!(parser::from_str("##-Inf"), Ok(Value::from(f64::NEG_INFINITY)));
+    assert!(parser::from_str("[##NaN]").unwrap()[0]
+        .as_f64()
+        .unwrap()
+        .is_nan());
+    let err = parser::from_str("[##Infinity]").unwrap_err();
+    assert_eq!(err.message, "unknown symbolic value `##Infinity`");
+    assert_eq!((err.lo, err.hi, err.category), (1, 11, Category::Syntax));
+}
+
+#[test]
+fn test_unreadable_names() {
+    for &(str, message) in &[
+        ("a/b/c", "invalid symbol `a/b/c`"),
+        ("[:a/b/c]", "invalid keyword `:a/b/c`"),
+        ("foo/", "invalid symbol `foo/`"),
+        (":", "invalid keyword `:`"),
+        ("::a", "invalid keyword `::a`"),
+        ("#?(:clj 1)", "invalid tag `#?`"),
+    ] {
+        let err = edn::parser::from_str(str).unwrap_err();
+        assert_eq!(
+            (err.message.as_str(), err.category),
+            (message, Category::Syntax)
+        );
+    }
+    let value = edn::parser::from_str("[:0 :user/1st user/1st a:b# / clojure.core//]").unwrap();
+    assert_eq!(
+        edn::to_string(&value),
+        "[:0 :user/1st user/1st a:b# / clojure.core//]"
+    );
+}
+
+#[test]
+fn test_strict() {
+    use edn::parser::ParserBuilder;
+    use edn::{Keyword, Symbol};
+
+    let strict = ParserBuilder::new().strict(true);
+    for valid in &[
+        "foo",
+        "a.b/c-d",
+        "/",
+        "-",
+        "+x",
+        ".a",
+        "a:b#",
+        "nil/x",
+        ":foo",
+        ":a/b",
+        ":-x",
+        "#my/tag 1",
+        "[nil true false]",
+    ] {
+        assert!(strict.from_str(valid).is_ok(), "{}", valid);
+    }
+    for &(invalid, message) in &[
+        ("a/b/c", "invalid symbol `a/b/c`"),
+        ("foo/", "invalid symbol `foo/`"),
+        ("-a/1b", "invalid symbol `-a/1b`"),
+        ("[:1st]", "invalid keyword `:1st`"),
+        ("::foo", "invalid keyword `::foo`"),
+        (":/", "invalid keyword `:/`"),
+        ("#-tag 1", "invalid tag `#-tag`"),
+    ] {
+        let err = strict.from_str(invalid).unwrap_err();
+        assert_eq!(
+            (err.message.as_str(), err.category),
+            (message, Category::Syntax)
+        );
+    }
+    assert_eq!(strict.from_str("[:1st]").unwrap_err().lo, 1);
+    assert!(ParserBuilder::new().from_str("[:1st user/1st]").is_ok());
+
+    assert!(Symbol::namespaced("a.b", "c").is_valid());
+    assert!(Symbol::new("/").is_valid());
+    for invalid in &["", "1a", "-1", ".5", "a b", "a/b", "nil", ":a", "#a"] {
+        assert!(!Symbol::new(invalid).is_valid(), "{}", invalid);
+    }
+    assert!(!Symbol::namespaced("a", "").is_valid());
+    assert!(Keyword::new("nil").is_valid());
+    assert!(!Keyword::new("/").is_valid());
+    assert!(!Keyword::new("0").is_valid());
+    assert!(!Keyword::namespaced("user", "1st").is_valid());
+}
+
+#[test]
+fn test_scanning() {
+    use edn::parser;
+
+    let long = "résumé ".repeat(1000);
+    assert_eq!(
+        parser::from_str(&format!("\"{}\\n{}\\\"\"", long, long)),
+        Ok(Value::String(format!("{}\n{}\"", long, long)))
+    );
+    let unclosed = "[1\u{a0}\u{2028}2\u{b}\u{c}\r,3 ; comment\n4 ;; at the end";
+    assert_eq!(
+        parser::from_str(unclosed),
+        Err(Error {
+            lo: 0,
+            hi: unclosed.len(),
+            line: 1,
+            column: 1,
+            category: Category::Eof,
+            message: "unclosed `[`".into(),
+        })
+    );
+    assert_eq!(
+        parser::from_str("[1\u{a0}\u{2028}2\u{b}\u{c}\r,3 ; comment\n4] ;; at the end"),
+        parser::from_str("[1 2 3 4]")
+    );
+    let err = parser::from_str("\"abc\\q\"").unwrap_err();
+    assert_eq!((err.lo, err.hi), (4, 6));
+}
+
+#[test]
+fn test_capacity_hints() {
+    use edn::parser;
+
+    let long = (0..2000)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let value = parser::from_str(&format!("[[{}] [1] #{{{}}} {{:a 1}} ()]", long, long)).unwrap();
+    let items = match value {
+        Value::Vector(items) => items,
+        _ => panic!(),
+    };
+    match items[1] {
+        Value::Vector(ref short) => assert!(short.capacity() <= 2),
+        _ => panic!(),
+    }
+    assert_eq!(items[2].as_set().map(|set| set.len()), Some(2000));
+    assert_eq!(items[3], parser::from_str("{:a 1}").unwrap());
+    assert_eq!(items[4], Value::List(vec![]));
+
+    let mut parser = Parser::new("[1 2 3] [4 5 6] [7]");
+    let mut capacities = vec![];
+    while let Some(Ok(Value::Vector(items))) = parser.read() {
+        capacities.push(items.capacity());
+    }
+    assert_eq!(capacities[1], 3);
+    assert!(capacities[2] <= 2);
+}
+
+#[test]
+fn test_duplicate_keys() {
+    use edn::parser::{DuplicateKeyPolicy, ParserBuilder};
+
+    let parse = |policy, str| ParserBuilder::new().duplicate_keys(policy).from_str(str);
+    let map = "{:a 1 :b 2 :a 3}";
+    assert_eq!(
+        Parser::new(map).read(),
+        Some(parse(DuplicateKeyPolicy::LastWins, map))
+    );
+    assert_eq!(
+        parse(DuplicateKeyPolicy::LastWins, map),
+        edn::parser::from_str("{:a 3 :b 2}")
+    );
+    assert_eq!(
+        parse(DuplicateKeyPolicy::FirstWins, map),
+        edn::parser::from_str("{:a 1 :b 2}")
+    );
+    assert_eq!(
+        parse(DuplicateKeyPolicy::Error, map),
+        Err(Error {
+            lo: 11,
+            hi: 13,
+            line: 1,
+            column: 12,
+            category: Category::Data,
+            message: "duplicate key `:a` in a map".into(),
+        })
+    );
+    let err = parse(DuplicateKeyPolicy::Error, "[#{1 [2]\n [2]}]").unwrap_err();
+    assert_eq!((err.lo, err.hi, err.line, err.column), (10, 13, 2, 2));
+    assert_eq!(err.message, "duplicate element `[2]` in a set");
+    assert_eq!(
+        parse(DuplicateKeyPolicy::FirstWins, "#{1 1 2}"),
+        edn::parser::from_str("#{1 2}")
+    );
+    assert!(parse(DuplicateKeyPolicy::Error, "{:a {:a 1} 1 #{:a}}").is_ok());
+    assert!(parse(DuplicateKeyPolicy::Error, "{1 2 #_1 3 4}").is_ok());
+    assert!(parse(DuplicateKeyPolicy::Error, "{[1] 2 [1] 3}").is_err());
+}
+
+#[test]
+fn test_parse_with_recovery() {
+    let mut parser = Parser::new(
+        "[1 2)\n:ok\n9999999999999999999999999999999999999999 \\bad 3\n{:a\n ]}\n#{4}",
+    );
+    let (forms, diagnostics) = parser.parse_with_recovery();
+    assert_eq!(
+        forms,
+        vec![
+            edn::parser::from_str(":ok").unwrap(),
+            Value::Integer(3),
+            edn::parser::from_str("#{4}").unwrap(),
+        ]
+    );
+    let summary: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.span.clone(), diagnostic.line, diagnostic.category))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (4..5, 1, Category::Syntax),
+            (10..50, 3, Category::Data),
+            (51..55, 3, Category::Syntax),
+            (63..64, 5, Category::Syntax),
+        ]
+    );
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "1:5: unexpected `)`, expected `]`"
+    );
+    assert_eq!(parser.read(), None);
+
+    let (forms, diagnostics) = Parser::new("(1\n[2] 3").parse_with_recovery();
+    assert_eq!(forms, vec![Value::vector(vec![2]), Value::Integer(3)]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].category, Category::Eof);
+
+    let (forms, diagnostics) =
+        Parser::new("[-] #\"a\\\"b\" (+) #(inc %)\n:ok").parse_with_recovery();
+    assert_eq!(
+        forms,
+        vec![
+            edn::parser::from_str("[-]").unwrap(),
+            edn::parser::from_str("(+)").unwrap(),
+            edn::parser::from_str(":ok").unwrap(),
+        ]
+    );
+    let summary: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.span.clone(), diagnostic.category))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![(4..11, Category::Syntax), (16..24, Category::Syntax)]
+    );
+}
+
+#[test]
+fn test_iterate() {
+    let forms: Vec<_> = Parser::new("1 :a ) [2]").collect();
+    assert_eq!(forms.len(), 3);
+    assert_eq!(forms[1], Ok(edn::parser::from_str(":a").unwrap()));
+    assert_eq!(forms[2].as_ref().unwrap_err().message, "unexpected `)`");
+
+    let input = "{:a 1}\n[2 3] ; comment\n#{4}";
+    let forms: Result<Vec<_>, _> = Parser::from_reader(input.as_bytes()).collect();
+    assert_eq!(forms, Parser::new(input).collect());
+    assert_eq!(forms.unwrap().len(), 3);
+}
+
+#[test]
+fn test_skip_form() {
+    let mut parser = Parser::new(
+        "{:a \"}\\\"\" :b [\\] #{1} #_ (x]]} ^:m #tag (1 ; )\n) ##Inf \\space :next #_ 1 ; end",
+    );
+    assert_eq!(parser.skip_form(), Some(Ok(())));
+    assert_eq!(parser.skip_form(), Some(Ok(())));
+    assert_eq!(parser.skip_form(), Some(Ok(())));
+    assert_eq!(parser.skip_form(), Some(Ok(())));
+    assert_eq!(parser.read(), Some(Ok(Value::Keyword("next".into()))));
+    assert_eq!(parser.skip_form(), None);
+
+    let mut parser = Parser::new("[1 (2 3)] x");
+    assert_eq!(parser.skip_form(), Some(Ok(())));
+    assert_eq!(parser.read(), Some(Ok(Value::Symbol("x".into()))));
+
+    let err = Parser::new("  (1 [2]").skip_form().unwrap().unwrap_err();
+    assert_eq!((err.lo, err.category), (2, Category::Eof));
+    let err = Parser::new("\"abc").skip_form().unwrap().unwrap_err();
+    assert_eq!(err.category, Category::Eof);
+    let err = Parser::new(") 1").skip_form().unwrap().unwrap_err();
+    assert_eq!(err.message, "unexpected `)`");
 }
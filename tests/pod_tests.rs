@@ -0,0 +1,100 @@
+#![cfg(feature = "pod")]
+
+extern crate edn;
+
+use edn::pod::{Error, Pod};
+use edn::Value;
+
+/// A bencoded dictionary of string entries, with keys in sorted order.
+fn dict(entries: &[(&str, &str)]) -> String {
+    let mut out = String::from("d");
+    for &(key, value) in entries {
+        out += &format!("{}:{}{}:{}", key.len(), key, value.len(), value);
+    }
+    out + "e"
+}
+
+fn pod() -> Pod {
+    let mut pod = Pod::new();
+    pod.var("pod.test/add", |args: &[Value]| {
+        let mut sum = 0;
+        for arg in args {
+            sum += arg.as_i64().ok_or_else(|| Error {
+                message: "expected integers".into(),
+                data: Some(arg.clone()),
+            })?;
+        }
+        Ok(Value::Integer(sum))
+    })
+    .var("pod.test/echo", |args: &[Value]| {
+        Ok(Value::vector(args.to_vec()))
+    });
+    pod
+}
+
+fn run(input: &str) -> String {
+    let mut out = vec![];
+    pod().run(input.as_bytes(), &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_describe() {
+    assert_eq!(
+        run(&dict(&[("op", "describe")])),
+        "d6:format3:edn10:namespacesld4:name8:pod.test4:varsld4:name3:added4:name4:echoeeee\
+         3:opsd8:shutdowndeee"
+    );
+}
+
+#[test]
+fn test_invoke() {
+    let input = dict(&[
+        ("args", "[1 2 3]"),
+        ("id", "a1"),
+        ("op", "invoke"),
+        ("var", "pod.test/add"),
+    ]) + &dict(&[
+        ("args", "(:k \"s\")"),
+        ("id", "a2"),
+        ("op", "invoke"),
+        ("var", "pod.test/echo"),
+    ]);
+    assert_eq!(
+        run(&input),
+        "d2:id2:a16:statusl4:donee5:value1:6e\
+         d2:id2:a26:statusl4:donee5:value8:[:k \"s\"]e"
+    );
+}
+
+#[test]
+fn test_invoke_errors() {
+    let failed = run(&dict(&[
+        ("args", "[1 :x]"),
+        ("id", "1"),
+        ("op", "invoke"),
+        ("var", "pod.test/add"),
+    ]));
+    assert_eq!(
+        failed,
+        "d7:ex-data2::x10:ex-message17:expected integers2:id1:16:statusl4:done5:erroree"
+    );
+    assert!(run(&dict(&[
+        ("id", "2"),
+        ("op", "invoke"),
+        ("var", "pod.test/nope")
+    ]))
+    .contains("27:unknown var `pod.test/nope`"));
+}
+
+#[test]
+fn test_shutdown() {
+    // Nothing after `shutdown` is read.
+    let input = dict(&[("op", "shutdown")]) + &dict(&[("op", "describe")]);
+    assert_eq!(run(&input), "");
+    let mut out = vec![];
+    assert_eq!(
+        pod().run(&b"d2:op"[..], &mut out).unwrap_err().message,
+        "unexpected end of bencoded input"
+    );
+}
@@ -0,0 +1,76 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::prepl::{self, Decoder, Message, Tag};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+const OUTPUT: &str = r#"{:tag :out, :val "hi\n"}
+{:tag :ret, :val "{:a [1 2]}", :ns "user", :ms 3, :form "(f)"}
+{:tag :ret, :val "{:cause \"boom\"}", :ns "user", :ms 0, :form "(throw x)", :exception true}
+{:tag :tap, :val [1 2]}
+"#;
+
+#[test]
+fn test_decoder() {
+    let messages = Decoder::new(OUTPUT.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(messages.len(), 4);
+    assert_eq!(
+        messages[0],
+        Message {
+            tag: Tag::Out,
+            val: Value::from("hi\n"),
+            ns: None,
+            ms: None,
+            form: None,
+            exception: false,
+        }
+    );
+    assert_eq!(messages[1].value().unwrap(), parse("{:a [1 2]}"));
+    assert_eq!(
+        (
+            messages[1].ns.as_deref(),
+            messages[1].ms,
+            messages[1].form.as_deref()
+        ),
+        (Some("user"), Some(3), Some("(f)"))
+    );
+    assert!(messages[2].exception);
+    assert_eq!(messages[3].tag, Tag::Tap);
+    assert_eq!(messages[3].value().unwrap(), parse("[1 2]"));
+
+    for message in messages {
+        assert_eq!(
+            Message::from_value(&Value::from(message.clone())),
+            Some(message)
+        );
+    }
+}
+
+#[test]
+fn test_decoder_errors() {
+    let mut decoder = Decoder::new(&b"{:tag :nope :val 1}"[..]);
+    assert_eq!(
+        decoder.next().unwrap().unwrap_err().message,
+        "not a prepl message: {:tag :nope, :val 1}"
+    );
+    assert_eq!(Message::from_value(&parse("{:tag :ret}")), None);
+    assert_eq!(
+        Message::from_value(&parse("{:tag :ret :val 1 :ms \"1\"}")),
+        None
+    );
+}
+
+#[test]
+fn test_send() {
+    let mut out = vec![];
+    prepl::send(&mut out, &parse("(+ 1 2)")).unwrap();
+    assert_eq!(out, b"(+ 1 2)\n");
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "prost")]
+
+extern crate edn;
+extern crate prost_types;
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use edn::parser::Parser;
+use edn::protobuf::{KeywordStyle, Options, TaggedStyle};
+use edn::Value;
+use prost_types::value::Kind;
+use prost_types::{ListValue, Struct};
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn proto<T: Into<prost_types::Value>>(value: T) -> prost_types::Value {
+    value.into()
+}
+
+#[test]
+fn test_to_protobuf() {
+    let value = parse(r#"{:user/name "Ada" sym \x :tags #{:a} [1] #inst "1815-12-10" nil 1.5}"#);
+    let mut fields = BTreeMap::new();
+    fields.insert("user/name".to_string(), proto("Ada"));
+    fields.insert("sym".to_string(), proto("x"));
+    fields.insert("tags".to_string(), proto(vec![proto("a")]));
+    fields.insert("[1]".to_string(), proto("1815-12-10"));
+    fields.insert("nil".to_string(), proto(1.5));
+    assert_eq!(Struct::try_from(value.clone()), Ok(Struct { fields }));
+
+    let options = Options::new()
+        .keywords(KeywordStyle::Colon)
+        .tagged(TaggedStyle::Struct);
+    let mut tagged = BTreeMap::new();
+    tagged.insert("#inst".to_string(), proto("1815-12-10"));
+    assert_eq!(
+        options.to_protobuf(&parse(r#"[:a #inst "1815-12-10" nil]"#)),
+        Ok(proto(Kind::ListValue(ListValue {
+            values: vec![proto(":a"), proto(tagged), proto(Kind::NullValue(0))],
+        })))
+    );
+
+    assert!(Options::new()
+        .keywords(KeywordStyle::Error)
+        .to_struct(&value)
+        .is_err());
+    assert!(Options::new().sets(false).to_struct(&value).is_err());
+    assert!(Options::new()
+        .tagged(TaggedStyle::Error)
+        .to_struct(&value)
+        .is_err());
+    assert!(Options::new().to_struct(&parse("[]")).is_err());
+    assert!(prost_types::Value::try_from(Value::Integer(1 << 60)).is_err());
+}
+
+#[test]
+fn test_from_protobuf() {
+    let mut fields = BTreeMap::new();
+    fields.insert(
+        ":a".to_string(),
+        proto(vec![proto(1.0), proto(2.5), proto(true)]),
+    );
+    fields.insert("#uuid".to_string(), proto(Kind::NullValue(0)));
+    let proto = Struct { fields };
+    assert_eq!(
+        Value::from(proto.clone()),
+        parse(r##"{":a" [1 2.5 true] "#uuid" nil}"##)
+    );
+    assert_eq!(Value::from(prost_types::Value { kind: None }), Value::Nil);
+    assert_eq!(
+        Options::new()
+            .keywords(KeywordStyle::Colon)
+            .from_struct(&proto),
+        parse(r##"{:a [1 2.5 true] "#uuid" nil}"##)
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let options = Options::new()
+        .keywords(KeywordStyle::Colon)
+        .tagged(TaggedStyle::Struct);
+    for str in &[
+        r#"{:user/id 1 :name "Ada" :scores [1.5 -2 0] :active true :manager nil}"#,
+        r#"{:nested {:deeper {"string key" [[] {}]}}}"#,
+        r#"{:created #inst "1815-12-10" :id #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"}"#,
+    ] {
+        let value = parse(str);
+        let proto = options.to_struct(&value).unwrap();
+        assert_eq!(options.from_struct(&proto), value);
+        let proto = options.to_protobuf(&value).unwrap();
+        assert_eq!(options.from_protobuf(&proto), value);
+    }
+
+    // Sets are lists, so they come back as vectors.
+    let proto = options.to_protobuf(&parse("#{:a}")).unwrap();
+    assert_eq!(options.from_protobuf(&proto), parse("[:a]"));
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "python")]
+
+extern crate edn;
+extern crate pyo3;
+
+use std::ffi::CString;
+
+use pyo3::prelude::*;
+use pyo3::types::PyModule;
+
+fn with_edn<F>(f: F)
+where
+    F: for<'py> FnOnce(Python<'py>, &Bound<'py, PyModule>) -> PyResult<()>,
+{
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "edn")?;
+        edn::python::edn(&module)?;
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("edn", &module)?;
+        f(py, &module)
+    })
+    .unwrap();
+}
+
+fn run(py: Python, code: &str) -> PyResult<()> {
+    py.run(&CString::new(code).unwrap(), None, None)
+}
+
+#[test]
+fn test_round_trip() {
+    with_edn(|_, module| {
+        for str in &[
+            "nil",
+            "true",
+            "-12",
+            "1.5",
+            r#""s""#,
+            ":user/id",
+            "clojure.core/inc",
+            "[1 [2 :x] {:a nil}]",
+            "(1 \"a\" (:b))",
+            "#{:a}",
+            r#"#inst "1815-12-10""#,
+        ] {
+            let object = module.getattr("loads")?.call1((*str,))?;
+            let written: String = module.getattr("dumps")?.call1((object,))?.extract()?;
+            assert_eq!(&written, str);
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn test_types() {
+    with_edn(|py, _| {
+        run(
+            py,
+            r#"
+import edn
+
+doc = edn.loads('{:id 1 :tags #{:a} :xs [1 2.5] :l (nil "a") :t #inst "2020" :c \c}')
+assert doc[edn.Keyword("id")] == 1
+assert doc[edn.Keyword("tags")] == frozenset([edn.Keyword("a")])
+assert doc[edn.Keyword("xs")] == [1, 2.5]
+assert doc[edn.Keyword("l")] == (None, "a")
+assert doc[edn.Keyword("t")] == edn.Tagged("inst", "2020")
+assert doc[edn.Keyword("c")] == "c"
+assert edn.loads(edn.dumps(doc)) == doc
+assert edn.loads(edn.dumps(doc, pretty=True)) == doc
+assert edn.loads("^:private x") == edn.Symbol("x")
+"#,
+        )
+    });
+}
+
+#[test]
+fn test_keyword_and_symbol() {
+    with_edn(|py, _| {
+        run(
+            py,
+            r#"
+import edn
+
+assert edn.Keyword("user/id") == edn.Keyword(":user/id")
+assert edn.Keyword("user/id").namespace == "user"
+assert edn.Keyword("user/id").name == "id"
+assert edn.Keyword("id").namespace is None
+assert str(edn.Keyword("user/id")) == ":user/id"
+assert repr(edn.Keyword("user/id")) == "Keyword('user/id')"
+assert edn.Symbol("clojure.core/inc").namespace == "clojure.core"
+assert str(edn.Symbol("inc")) == "inc"
+assert repr(edn.Symbol("inc")) == "Symbol('inc')"
+assert edn.Keyword("a") != edn.Symbol("a")
+assert len({edn.Keyword("a"), edn.Keyword("a"), edn.Symbol("a")}) == 2
+assert edn.dumps([edn.Keyword("a"), edn.Symbol("b"), (True, False)]) == "[:a b (true false)]"
+"#,
+        )
+    });
+}
+
+#[test]
+fn test_errors() {
+    with_edn(|py, _| {
+        run(
+            py,
+            r#"
+import edn
+
+try:
+    edn.loads("{:a")
+    assert False
+except ValueError:
+    pass
+
+try:
+    edn.dumps(object())
+    assert False
+except TypeError:
+    pass
+"#,
+        )
+    });
+}
@@ -0,0 +1,61 @@
+#![cfg(all(feature = "ratio", not(feature = "immutable")))]
+
+extern crate edn;
+
+use edn::parser::{Category, Error, Parser};
+use edn::ser;
+use edn::Value;
+
+#[test]
+fn test_ratio() {
+    let mut parser = Parser::new("22/7 -1/2 +3/9 4/2 0/5 1 / 2");
+    assert_eq!(parser.read(), Some(Ok(Value::Ratio(22, 7))));
+    assert_eq!(parser.read(), Some(Ok(Value::Ratio(-1, 2))));
+    assert_eq!(parser.read(), Some(Ok(Value::Ratio(1, 3))));
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(2))));
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(0))));
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(1))));
+    assert_eq!(parser.read(), Some(Ok(Value::Symbol("/".into()))));
+    assert_eq!(parser.read(), Some(Ok(Value::Integer(2))));
+
+    assert_eq!(Value::ratio(6, 4), Some(Value::Ratio(3, 2)));
+    assert_eq!(Value::ratio(i64::MIN, 1 << 63), Some(Value::Integer(-1)));
+    assert_eq!(Value::ratio(1, 0), None);
+    assert_eq!(Value::Ratio(-1, 4).as_f64(), Some(-0.25));
+    assert_eq!(Value::Ratio(22, 7).as_ratio(), Some((22, 7)));
+    assert_eq!(Value::Integer(3).as_ratio(), Some((3, 1)));
+    assert!(Value::Ratio(1, 2).is_number());
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        Parser::new("1/0").read(),
+        Some(Err(Error {
+            lo: 0,
+            hi: 3,
+            line: 1,
+            column: 1,
+            category: Category::Data,
+            message: "ratio `1/0` has a zero denominator".into(),
+        }))
+    );
+    assert_eq!(
+        Parser::new("1/99999999999999999999")
+            .read()
+            .unwrap()
+            .unwrap_err()
+            .message,
+        "ratio `1/99999999999999999999` out of range"
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let value = Value::Vector(vec![Value::Ratio(22, 7), Value::Ratio(-1, 3)]);
+    let mut out = vec![];
+    ser::to_writer(&mut out, &value).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out, "[22/7 -1/3]");
+    assert_eq!(Parser::new(&out).read(), Some(Ok(value)));
+}
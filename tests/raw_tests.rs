@@ -0,0 +1,84 @@
+extern crate edn;
+
+use edn::parser::{self, Category, Parser};
+use edn::ser::Serializer;
+use edn::{RawEdn, Value};
+
+fn raw(str: &str) -> RawEdn {
+    str.parse().unwrap()
+}
+
+#[test]
+fn test_from_str() {
+    assert_eq!(raw("  [1 2] ; c\n").as_str(), "[1 2]");
+    assert_eq!(raw("#_x ^:m #tag {:a #{1}}").as_str(), "^:m #tag {:a #{1}}");
+    assert_eq!(raw("{:a}").as_str(), "{:a}");
+    assert_eq!(
+        raw("{:a [1 2.5 \"s\"]}").parse(),
+        parser::from_str("{:a [1 2.5 \"s\"]}")
+    );
+
+    let err = "[1 2".parse::<RawEdn>().unwrap_err();
+    assert_eq!(
+        (err.message.as_str(), err.category),
+        ("unclosed `[`", Category::Eof)
+    );
+    let err = "(1 ]".parse::<RawEdn>().unwrap_err();
+    assert_eq!(
+        (err.message.as_str(), err.lo),
+        ("unexpected `]`, expected `)`", 3)
+    );
+    let err = "1 2".parse::<RawEdn>().unwrap_err();
+    assert_eq!(
+        (err.message.as_str(), err.lo),
+        ("trailing characters after form", 2)
+    );
+    assert_eq!("".parse::<RawEdn>().unwrap_err().category, Category::Eof);
+    assert_eq!(
+        "[#_]".parse::<RawEdn>().unwrap_err().message,
+        "unexpected `]`"
+    );
+    assert_eq!(
+        "[".repeat(1000).parse::<RawEdn>().unwrap_err().message,
+        "recursion limit exceeded"
+    );
+}
+
+#[test]
+fn test_entries() {
+    let message = raw("{:type :event, :id 7 ; comment\n :payload {:big [1 2 3]}}");
+    let entries: Vec<_> = message
+        .entries()
+        .unwrap()
+        .into_iter()
+        .map(|(key, value)| (key.into_string(), value.into_string()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (":type".into(), ":event".into()),
+            (":id".into(), "7".into()),
+            (":payload".into(), "{:big [1 2 3]}".into()),
+        ]
+    );
+    assert_eq!(
+        message.get(&Value::Keyword("id".into())).unwrap(),
+        Some(raw("7"))
+    );
+    assert_eq!(message.get(&Value::Keyword("nope".into())).unwrap(), None);
+    assert!(raw("[1]").entries().is_err());
+    assert!(raw("{:a}").entries().is_err());
+}
+
+#[test]
+fn test_read_raw() {
+    let mut parser = Parser::new("{:a 1} #_skip [2 3] :last");
+    assert_eq!(parser.read_raw(), Some(Ok(raw("{:a 1}"))));
+    assert_eq!(parser.read(), Some(Ok(parser::from_str("[2 3]").unwrap())));
+    assert_eq!(parser.read_raw(), Some(Ok(raw(":last"))));
+    assert_eq!(parser.read_raw(), None);
+
+    let mut serializer = Serializer::new(vec![]);
+    serializer.serialize_raw(&raw("{:a   1}")).unwrap();
+    assert_eq!(serializer.into_inner(), b"{:a   1}");
+}
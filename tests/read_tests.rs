@@ -0,0 +1,446 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+#[cfg(feature = "futures")]
+extern crate futures_core;
+
+use std::io;
+
+use edn::parser::{Category, Error, Parser};
+use edn::read::{self, Forms, IoRead, IoSource, PushParser, Read, SliceRead, StrRead};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+/// Hands out its input one byte per `read` call.
+struct Trickle<'a>(&'a [u8]);
+
+impl<'a> io::Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.0[0];
+        self.0 = &self.0[1..];
+        Ok(1)
+    }
+}
+
+const INPUT: &str = "1234 #_ 5678 {:a [1 #_x 2.5 \"π\"]} ; comment\n#tag sym #_ #_ 1 2 \\newline :end #_ 9";
+
+#[test]
+fn test_io_read() {
+    let expected: Vec<Value> = Parser::new(INPUT)
+        .into_stream()
+        .map(|result| result.unwrap().0)
+        .collect();
+    assert_eq!(expected.len(), 5);
+
+    let forms: Vec<Value> = IoRead::new(INPUT.as_bytes())
+        .map(|result| result.unwrap())
+        .collect();
+    assert_eq!(forms, expected);
+
+    let forms: Vec<Value> = IoRead::new(Trickle(INPUT.as_bytes()))
+        .map(|result| result.unwrap())
+        .collect();
+    assert_eq!(forms, expected);
+}
+
+#[test]
+fn test_io_read_errors() {
+    let mut read = IoRead::new(Trickle(b"[1 2] (3 \\foo)"));
+    assert_eq!(read.next(), Some(Ok(parse("[1 2]"))));
+    assert_eq!(
+        read.next(),
+        Some(Err(Error {
+            lo: 9,
+            hi: 13,
+            line: 1,
+            column: 10,
+            category: Category::Syntax,
+            message: "invalid char literal `\\foo`".into(),
+        }))
+    );
+
+    let mut read = IoRead::new(Trickle(b"[1 2] (3 4"));
+    read.next();
+    assert_eq!(
+        read.next(),
+        Some(Err(Error {
+            lo: 6,
+            hi: 10,
+            line: 1,
+            column: 7,
+            category: Category::Eof,
+            message: "unclosed `(`".into(),
+        }))
+    );
+
+    let mut read = IoRead::new(Trickle(b"[1]\n[2] ; two\n  (3"));
+    read.next();
+    read.next();
+    let err = read.next().unwrap().unwrap_err();
+    assert_eq!((err.lo, err.line, err.column), (16, 3, 3));
+
+    let mut read = IoRead::new(&b"[\xff]"[..]);
+    assert_eq!(
+        read.next(),
+        Some(Err(Error {
+            lo: 0,
+            hi: 0,
+            line: 1,
+            column: 1,
+            category: Category::Syntax,
+            message: "invalid UTF-8 in input".into(),
+        }))
+    );
+}
+
+#[test]
+fn test_from_reader() {
+    assert_eq!(
+        read::from_reader(Trickle(b" [1 2] ; done\n")),
+        Ok(parse("[1 2]"))
+    );
+    assert_eq!(
+        read::from_reader(Trickle(b" ; nothing\n")),
+        Err(Error {
+            lo: 11,
+            hi: 11,
+            line: 2,
+            column: 1,
+            category: Category::Eof,
+            message: "expected a form, found EOF".into(),
+        })
+    );
+    assert_eq!(
+        read::from_reader(Trickle(b"[1 2] 3")),
+        Err(Error {
+            lo: 5,
+            hi: 5,
+            line: 1,
+            column: 6,
+            category: Category::Syntax,
+            message: "trailing characters after form".into(),
+        })
+    );
+}
+
+/// A ring buffer holding at most four bytes, refilled from `input` as the
+/// reader drains it. Only implements the required `Read` methods.
+struct Ring<'a> {
+    input: &'a [u8],
+    ring: [u8; 4],
+    start: usize,
+    len: usize,
+    position: usize,
+}
+
+impl<'a> Ring<'a> {
+    fn new(input: &'a [u8]) -> Ring<'a> {
+        Ring {
+            input,
+            ring: [0; 4],
+            start: 0,
+            len: 0,
+            position: 0,
+        }
+    }
+}
+
+impl<'a> Read for Ring<'a> {
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        while self.len < self.ring.len() && !self.input.is_empty() {
+            self.ring[(self.start + self.len) % 4] = self.input[0];
+            self.input = &self.input[1..];
+            self.len += 1;
+        }
+        Ok(if self.len == 0 {
+            None
+        } else {
+            Some(self.ring[self.start])
+        })
+    }
+
+    fn discard(&mut self) {
+        self.start = (self.start + 1) % 4;
+        self.len -= 1;
+        self.position += 1;
+    }
+}
+
+/// Fails after handing out its input.
+struct Broken<'a>(SliceRead<'a>);
+
+impl<'a> Read for Broken<'a> {
+    fn position(&self) -> usize {
+        self.0.position()
+    }
+
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        match self.0.peek()? {
+            None => Err(io::Error::other("connection reset")),
+            byte => Ok(byte),
+        }
+    }
+
+    fn discard(&mut self) {
+        self.0.discard()
+    }
+}
+
+#[test]
+fn test_sources() {
+    let expected: Vec<Value> = IoRead::new(INPUT.as_bytes())
+        .map(|result| result.unwrap())
+        .collect();
+
+    let sources: Vec<Box<dyn Read>> = vec![
+        Box::new(SliceRead::new(INPUT.as_bytes())),
+        Box::new(StrRead::new(INPUT)),
+        Box::new(IoSource::new(Trickle(INPUT.as_bytes()))),
+        Box::new(Ring::new(INPUT.as_bytes())),
+    ];
+    for mut source in sources {
+        let forms: Vec<Value> = Forms::new(&mut *source)
+            .map(|result| result.unwrap())
+            .collect();
+        assert_eq!(forms, expected);
+        assert_eq!(source.position(), INPUT.len());
+    }
+
+    let mut source = SliceRead::new(b"ab");
+    assert_eq!(source.peek().unwrap(), Some(b'a'));
+    assert_eq!(source.peek().unwrap(), Some(b'a'));
+    assert_eq!(source.position(), 0);
+    source.discard();
+    assert_eq!(source.next().unwrap(), Some(b'b'));
+    assert_eq!(source.position(), 2);
+    assert_eq!(source.next().unwrap(), None);
+    assert_eq!(source.peek().unwrap(), None);
+
+    let mut source = IoSource::new(Trickle(b"xyz"));
+    assert_eq!(source.peek().unwrap(), Some(b'x'));
+    let mut buf = [0; 8];
+    assert_eq!(source.fill(&mut buf).unwrap(), 1);
+    assert_eq!(source.fill(&mut buf).unwrap(), 1);
+    assert_eq!(&buf[..1], b"y");
+    assert_eq!(source.position(), 2);
+}
+
+#[test]
+fn test_source_errors() {
+    let mut read = Forms::new(Ring::new(b"[1]\n[2] ; two\n  (3"));
+    read.next();
+    read.next();
+    let err = read.next().unwrap().unwrap_err();
+    assert_eq!((err.lo, err.line, err.column), (16, 3, 3));
+
+    let mut read = Forms::new(Broken(SliceRead::new(b"[1 2] (3")));
+    assert_eq!(read.next(), Some(Ok(parse("[1 2]"))));
+    assert_eq!(
+        read.next(),
+        Some(Err(Error {
+            lo: 8,
+            hi: 8,
+            line: 1,
+            column: 9,
+            category: Category::Io,
+            message: "connection reset".into(),
+        }))
+    );
+
+    assert_eq!(
+        read::from_source(StrRead::new(" {:a 1} ")),
+        Ok(parse("{:a 1}"))
+    );
+    assert_eq!(
+        read::from_source(Ring::new(b"[1 2] 3")),
+        Err(Error {
+            lo: 5,
+            hi: 5,
+            line: 1,
+            column: 6,
+            category: Category::Syntax,
+            message: "trailing characters after form".into(),
+        })
+    );
+}
+
+#[test]
+fn test_push_parser() {
+    let expected: Vec<Value> = Parser::new(INPUT)
+        .into_stream()
+        .map(|result| result.unwrap().0)
+        .collect();
+    for size in [1, 3, 7, INPUT.len()] {
+        let mut parser = PushParser::new();
+        let mut forms = vec![];
+        for chunk in INPUT.as_bytes().chunks(size) {
+            forms.extend(parser.feed(chunk).unwrap());
+        }
+        forms.extend(parser.finish().unwrap());
+        assert_eq!(forms, expected);
+    }
+
+    let mut parser = PushParser::new();
+    assert!(parser.feed(b"(1 2").unwrap().is_empty());
+    assert_eq!(parser.feed(b") [3").unwrap(), vec![parse("(1 2)")]);
+    assert_eq!(parser.feed(b"]").unwrap(), vec![parse("[3]")]);
+    assert!(parser.finish().unwrap().is_empty());
+}
+
+#[test]
+fn test_push_parser_errors() {
+    let mut parser = PushParser::new();
+    assert_eq!(
+        parser.feed(b"[1] (3 \\foo) [2]").unwrap(),
+        vec![parse("[1]")]
+    );
+    let err = parser.feed(b"").unwrap_err();
+    assert_eq!((err.lo, err.hi, err.category), (7, 11, Category::Syntax));
+    assert_eq!(parser.feed(b"[4]").unwrap_err(), err);
+
+    let mut parser = PushParser::new();
+    assert!(parser.feed(b"[1 2").unwrap().is_empty());
+    assert_eq!(parser.finish().unwrap_err().category, Category::Eof);
+
+    let mut parser = PushParser::new();
+    assert!(parser.feed(b"\"\xcf").unwrap().is_empty());
+    assert_eq!(parser.feed(b"\x80\"").unwrap(), vec![Value::from("π")]);
+    assert_eq!(
+        parser.feed(b"\xff").unwrap_err().message,
+        "invalid UTF-8 in input"
+    );
+}
+
+/// Yields its chunks one at a time, returning `Pending` before each.
+#[cfg(feature = "futures")]
+struct Chunks {
+    chunks: Vec<&'static [u8]>,
+    ready: bool,
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for Chunks {
+    type Item = io::Result<&'static [u8]>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::task::Poll;
+        if !self.ready {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.ready = false;
+        if self.chunks.is_empty() {
+            return Poll::Ready(None);
+        }
+        match self.chunks.remove(0) {
+            b"!" => Poll::Ready(Some(Err(io::Error::other("boom")))),
+            chunk => Poll::Ready(Some(Ok(chunk))),
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+fn chunks(chunks: Vec<&'static [u8]>) -> Chunks {
+    Chunks {
+        chunks,
+        ready: false,
+    }
+}
+
+/// Poll `future` to completion, counting how often it was pending.
+#[cfg(feature = "futures")]
+fn block_on<F: std::future::Future + Unpin>(mut future: F) -> (F::Output, usize) {
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    let mut pending = 0;
+    loop {
+        match std::pin::Pin::new(&mut future).poll(&mut cx) {
+            std::task::Poll::Ready(output) => return (output, pending),
+            std::task::Poll::Pending => pending += 1,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn test_form_stream() {
+    use futures_core::Stream;
+
+    let mut forms = read::FormStream::new(chunks(vec![b"[1 2] (3", b" 4) 12", b"34", b" [!"]));
+    let mut next = || {
+        block_on(std::future::poll_fn(|cx| {
+            std::pin::Pin::new(&mut forms).poll_next(cx)
+        }))
+        .0
+    };
+    assert_eq!(next(), Some(Ok(parse("[1 2]"))));
+    assert_eq!(next(), Some(Ok(parse("(3 4)"))));
+    assert_eq!(next(), Some(Ok(Value::Integer(1234))));
+    assert_eq!(next().unwrap().unwrap_err().category, Category::Eof);
+
+    let mut forms = read::FormStream::new(chunks(vec![b"[1", b"!"]));
+    let err = block_on(std::future::poll_fn(|cx| {
+        std::pin::Pin::new(&mut forms).poll_next(cx)
+    }))
+    .0
+    .unwrap()
+    .unwrap_err();
+    assert_eq!(
+        (err.category, err.lo, &err.message[..]),
+        (Category::Io, 2, "boom")
+    );
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn test_from_stream() {
+    let (value, pending) = block_on(read::from_stream(chunks(vec![b"{:a \"\xcf", b"\x80\"}"])));
+    assert_eq!(value, Ok(parse("{:a \"π\"}")));
+    assert!(pending > 0);
+    assert_eq!(
+        block_on(read::from_stream(chunks(vec![b"1 2"])))
+            .0
+            .unwrap_err()
+            .message,
+        "trailing characters after form"
+    );
+    assert_eq!(
+        block_on(read::from_stream(chunks(vec![b" "])))
+            .0
+            .unwrap_err()
+            .category,
+        Category::Eof
+    );
+}
+
+#[test]
+fn test_duplicate_keys() {
+    use edn::parser::DuplicateKeyPolicy;
+
+    let mut forms =
+        IoRead::new("{:a 1}\n{:a 1 :a 2}".as_bytes()).duplicate_keys(DuplicateKeyPolicy::Error);
+    assert!(forms.read().unwrap().is_ok());
+    let err = forms.read().unwrap().unwrap_err();
+    assert_eq!((err.lo, err.hi, err.line, err.column), (13, 15, 2, 7));
+    assert_eq!(err.message, "duplicate key `:a` in a map");
+
+    let mut forms =
+        Forms::new(StrRead::new("#{1 1}")).duplicate_keys(DuplicateKeyPolicy::FirstWins);
+    assert_eq!(
+        forms.read(),
+        Some(Ok(Parser::new("#{1}").read().unwrap().unwrap()))
+    );
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "arrow")]
+
+extern crate arrow;
+extern crate edn;
+
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, Int32Array};
+use arrow::datatypes::{DataType, Field, Float64Type, Int64Type, Schema};
+use arrow::record_batch::RecordBatch;
+use edn::parser::Parser;
+use edn::record_batch::{from_record_batch, to_record_batch, ENCODING};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_to_record_batch() {
+    let rows = parse(
+        r#"[{:gone nil :id 1 :score 1.5 :tags [:x] :user/name "a"}
+            {:gone nil :id 2 :ok true :score 2 :user/name nil}]"#,
+    );
+    let batch = to_record_batch(&rows).unwrap();
+    assert_eq!(batch.num_rows(), 2);
+    let schema = batch.schema();
+    let columns: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            (
+                &field.name()[..],
+                field.data_type().clone(),
+                field.is_nullable(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        columns,
+        vec![
+            ("gone", DataType::Null, true),
+            ("id", DataType::Int64, false),
+            ("score", DataType::Float64, false),
+            ("tags", DataType::Utf8, true),
+            ("user/name", DataType::Utf8, true),
+            ("ok", DataType::Boolean, true),
+        ]
+    );
+    assert_eq!(
+        schema.field_with_name("tags").unwrap().metadata()[ENCODING],
+        "edn"
+    );
+    assert!(schema
+        .field_with_name("user/name")
+        .unwrap()
+        .metadata()
+        .is_empty());
+
+    let ids = batch.column(1).as_primitive::<Int64Type>();
+    assert_eq!(ids.values().to_vec(), vec![1, 2]);
+    let scores = batch.column(2).as_primitive::<Float64Type>();
+    assert_eq!(scores.values().to_vec(), vec![1.5, 2.0]);
+    let tags = batch.column(3).as_string::<i32>();
+    assert_eq!(tags.value(0), "[:x]");
+    assert!(tags.is_null(1));
+
+    assert!(to_record_batch(&parse(r#"[{"id" 1}]"#)).is_err());
+    assert!(to_record_batch(&parse("[{:a 1} 2]")).is_err());
+    assert_eq!(to_record_batch(&parse("[{} {}]")).unwrap().num_rows(), 2);
+}
+
+#[test]
+fn test_from_record_batch() {
+    let batch = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![Field::new(
+            "user/id",
+            DataType::Int32,
+            true,
+        )])),
+        vec![Arc::new(Int32Array::from(vec![Some(1), None]))],
+    )
+    .unwrap();
+    assert_eq!(
+        from_record_batch(&batch).unwrap(),
+        parse("[{:user/id 1} {}]")
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    for str in &[
+        r#"[{:id 1 :name "a" :active true :score 0.5} {:id 2 :name "b" :active false :score 2.0}]"#,
+        r#"[{:id 1 :tags #{:a :b}} {:id 2 :meta {:nested [1 "x"]}} {:id 3 :tags "plain"}]"#,
+        r#"[{:mixed 1} {:mixed "one"} {:mixed :one}]"#,
+        "[]",
+    ] {
+        let rows = parse(str);
+        let batch = to_record_batch(&rows).unwrap();
+        assert_eq!(from_record_batch(&batch).unwrap(), rows);
+    }
+
+    // `nil` comes back as a missing key, and integers in a float column as
+    // floats.
+    let batch = to_record_batch(&parse("[{:a nil :b 1} {:b 1.5}]")).unwrap();
+    assert_eq!(
+        from_record_batch(&batch).unwrap(),
+        parse("[{:b 1.0} {:b 1.5}]")
+    );
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "redis")]
+
+extern crate edn;
+extern crate redis;
+
+use std::collections::HashMap;
+
+use edn::keys::FromValue;
+use edn::parser::Parser;
+use edn::{redis_store, TypeError, Value};
+use redis::{Arg, Cmd, ConnectionLike, FromRedisValue, RedisResult, ToRedisArgs};
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+/// Just enough of Redis for `SET` and `GET`.
+#[derive(Default)]
+struct Memory(HashMap<Vec<u8>, Vec<u8>>);
+
+impl ConnectionLike for Memory {
+    fn req_packed_command(&mut self, _: &[u8]) -> RedisResult<redis::Value> {
+        unimplemented!()
+    }
+
+    fn req_packed_commands(
+        &mut self,
+        _: &[u8],
+        _: usize,
+        _: usize,
+    ) -> RedisResult<Vec<redis::Value>> {
+        unimplemented!()
+    }
+
+    fn req_command(&mut self, cmd: &Cmd) -> RedisResult<redis::Value> {
+        let args: Vec<&[u8]> = cmd
+            .args_iter()
+            .map(|arg| match arg {
+                Arg::Simple(arg) => arg,
+                Arg::Cursor => unimplemented!(),
+            })
+            .collect();
+        Ok(match &args[..] {
+            [b"SET", key, value] => {
+                self.0.insert(key.to_vec(), value.to_vec());
+                redis::Value::Okay
+            }
+            [b"GET", key] => match self.0.get(*key) {
+                Some(value) => redis::Value::BulkString(value.clone()),
+                None => redis::Value::Nil,
+            },
+            _ => unimplemented!(),
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        0
+    }
+
+    fn check_connection(&mut self) -> bool {
+        true
+    }
+
+    fn is_open(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct User {
+    name: String,
+    admin: bool,
+}
+
+impl From<User> for Value {
+    fn from(user: User) -> Value {
+        Value::map(vec![
+            (Value::Keyword("admin".into()), Value::from(user.admin)),
+            (Value::Keyword("name".into()), Value::from(user.name)),
+        ])
+    }
+}
+
+impl FromValue for User {
+    fn from_value(value: &Value) -> Result<User, TypeError> {
+        Ok(User {
+            name: String::from_value(&value[":name"])?,
+            admin: bool::from_value(&value[":admin"])?,
+        })
+    }
+}
+
+#[test]
+fn test_to_redis_args() {
+    let value = parse(r#"{:tags #{:a} :user/id 1}"#);
+    assert_eq!(
+        value.to_redis_args(),
+        vec![br#"{:tags #{:a}, :user/id 1}"#.to_vec()]
+    );
+}
+
+#[test]
+fn test_from_redis_value() {
+    let value = |reply: redis::Value| Value::from_redis_value(&reply).unwrap();
+    assert_eq!(
+        value(redis::Value::BulkString(b"{:a [1 2]}".to_vec())),
+        parse("{:a [1 2]}")
+    );
+    assert_eq!(
+        value(redis::Value::SimpleString(":ok".into())),
+        parse(":ok")
+    );
+    assert_eq!(value(redis::Value::Nil), Value::Nil);
+    assert_eq!(value(redis::Value::Int(3)), Value::Integer(3));
+    assert_eq!(
+        value(redis::Value::Array(vec![
+            redis::Value::Int(1),
+            redis::Value::BulkString(b"\"s\"".to_vec()),
+        ])),
+        parse(r#"[1 "s"]"#)
+    );
+    assert_eq!(
+        value(redis::Value::Map(vec![(
+            redis::Value::BulkString(b":k".to_vec()),
+            redis::Value::Boolean(true),
+        )])),
+        parse("{:k true}")
+    );
+
+    assert!(Value::from_redis_value(&redis::Value::BulkString(b"{:a".to_vec())).is_err());
+    assert!(Value::from_redis_value(&redis::Value::BulkString(vec![0xff])).is_err());
+    assert!(Value::from_redis_value(&redis::Value::Okay).is_err());
+}
+
+#[test]
+fn test_set_and_get() {
+    let mut con = Memory::default();
+    let ada = User {
+        name: "Ada".into(),
+        admin: true,
+    };
+    redis_store::set(&mut con, "user:1", ada).unwrap();
+    assert_eq!(
+        con.0[&b"user:1"[..]],
+        br#"{:admin true, :name "Ada"}"#.to_vec()
+    );
+    assert_eq!(
+        redis_store::get(&mut con, "user:1"),
+        Ok(Some(User {
+            name: "Ada".into(),
+            admin: true,
+        }))
+    );
+    assert_eq!(redis_store::get::<_, User>(&mut con, "user:2"), Ok(None));
+
+    redis_store::set(&mut con, "user:3", parse("{:name 1}")).unwrap();
+    assert!(redis_store::get::<_, User>(&mut con, "user:3").is_err());
+    assert_eq!(
+        redis_store::get(&mut con, "user:3"),
+        Ok(Some(parse("{:name 1}")))
+    );
+}
@@ -0,0 +1,74 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::ring::{Request, Response};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn request() -> Request {
+    Request {
+        server_port: 8080,
+        server_name: "localhost".into(),
+        remote_addr: "127.0.0.1".into(),
+        uri: "/users".into(),
+        query_string: Some("page=2".into()),
+        scheme: "http".into(),
+        request_method: "get".into(),
+        protocol: "HTTP/1.1".into(),
+        headers: vec![("host".to_string(), "localhost".to_string())]
+            .into_iter()
+            .collect(),
+        body: None,
+    }
+}
+
+#[test]
+fn test_request() {
+    let value = parse(
+        "{:server-port 8080 :server-name \"localhost\" :remote-addr \"127.0.0.1\"
+          :uri \"/users\" :query-string \"page=2\" :scheme :http
+          :request-method :get :protocol \"HTTP/1.1\"
+          :headers {\"host\" \"localhost\"} :body nil}",
+    );
+    assert_eq!(Value::from(request()), value);
+    assert_eq!(Request::from_value(&value), Some(request()));
+
+    let minimal =
+        Request::from_value(&parse("{:uri \"/\" :scheme :https :request-method :post}")).unwrap();
+    assert_eq!(minimal.request_method, "post");
+    assert_eq!(minimal.headers.len(), 0);
+
+    assert_eq!(
+        Request::from_value(&parse("{:uri \"/\" :scheme :https}")),
+        None
+    );
+    assert_eq!(
+        Request::from_value(&parse(
+            "{:uri \"/\" :scheme :https :request-method :get :headers {\"a\" 1}}"
+        )),
+        None
+    );
+}
+
+#[test]
+fn test_response() {
+    let response = Response {
+        status: 200,
+        headers: vec![("Content-Type".to_string(), "text/plain".to_string())]
+            .into_iter()
+            .collect(),
+        body: Some("ok".into()),
+    };
+    let value = Value::from(response);
+    assert_eq!(
+        value,
+        parse("{:status 200 :headers {\"content-type\" \"text/plain\"} :body \"ok\"}")
+    );
+    assert_eq!(Response::from_value(&value).unwrap().status, 200);
+    assert_eq!(Response::from_value(&parse("{:status 99999}")), None);
+}
@@ -0,0 +1,93 @@
+extern crate edn;
+
+use std::fs;
+use std::path::Path;
+
+use edn::parser::Parser;
+use edn::ser;
+use edn::Value;
+
+type Write = fn(&Value) -> String;
+
+/// Every way of writing a value which should read back as the same value.
+fn writers() -> Vec<(&'static str, Write)> {
+    vec![
+        ("compact", ser::to_string),
+        ("pretty", ser::to_string_pretty),
+        ("canonical", ser::to_string_canonical),
+        ("vcs", ser::to_string_vcs),
+        ("indent 0", |value| ser::to_string_with_indent(value, 0)),
+        ("pretty printer", ser::pretty::to_string),
+        ("narrow pretty printer", |value| {
+            ser::pretty::PrettyPrinter::new().width(1).to_string(value)
+        }),
+        ("sorted", |value| {
+            let mut serializer = ser::Serializer::new(vec![]);
+            serializer.set_config(ser::Config::new().sort_entries(true));
+            serializer.serialize(value).unwrap();
+            String::from_utf8(serializer.into_inner()).unwrap()
+        }),
+    ]
+}
+
+fn assert_round_trips(value: &Value) {
+    for (name, write) in writers() {
+        let written = write(value);
+        let mut parser = Parser::new(&written);
+        match parser.read() {
+            Some(Ok(ref read)) if read == value => {}
+            read => panic!(
+                "{} output of {} was {:?}, which read as {:?}",
+                name,
+                ser::to_string(value),
+                written,
+                read.map(|read| read.map(|read| ser::to_string(&read)))
+            ),
+        }
+        assert_eq!(parser.read(), None, "{} output {:?}", name, written);
+    }
+}
+
+/// Bytes from a xorshift generator, so runs are repeatable.
+#[cfg(feature = "arbitrary")]
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+#[test]
+#[cfg(feature = "arbitrary")]
+fn test_generated() {
+    use edn::arbitrary::Generator;
+
+    for seed in 0..2000 {
+        let bytes = bytes(seed, 256);
+        assert_round_trips(&Generator::new(&bytes).value());
+    }
+}
+
+#[test]
+fn test_corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut count = 0;
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let source = fs::read_to_string(&path).unwrap();
+        let mut parser = Parser::new(&source);
+        while let Some(value) = parser.read() {
+            match value {
+                Ok(value) => assert_round_trips(&value),
+                Err(err) => panic!("{}: {:?}", path.display(), err),
+            }
+            count += 1;
+        }
+    }
+    assert!(count > 0);
+}
@@ -0,0 +1,119 @@
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::schema::{Keys, Schema, Violation};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn messages(schema: &Schema, str: &str) -> Vec<String> {
+    schema
+        .validate(&parse(str))
+        .iter()
+        .map(Violation::to_string)
+        .collect()
+}
+
+#[test]
+fn test_scalars() {
+    assert!(Schema::Any.is_valid(&parse("#{1}")));
+    assert!(Schema::Integer.is_valid(&parse("1")));
+    assert!(Schema::Number.is_valid(&parse("1.5")));
+    assert!(Schema::Nilable(Box::new(Schema::String)).is_valid(&parse("nil")));
+    assert_eq!(
+        messages(&Schema::Integer, "1.5"),
+        vec!["[]: expected an integer, found a float"]
+    );
+    assert_eq!(
+        messages(&Schema::Nilable(Box::new(Schema::String)), ":a"),
+        vec!["[]: expected a string, found a keyword"]
+    );
+
+    let level = Schema::OneOf(vec![parse(":debug"), parse(":info")]);
+    assert!(level.is_valid(&parse(":info")));
+    assert_eq!(
+        messages(&level, ":trace"),
+        vec!["[]: expected one of :debug, :info, found :trace"]
+    );
+}
+
+#[test]
+fn test_collections() {
+    let schema = Schema::MapOf(
+        Box::new(Schema::Keyword),
+        Box::new(Schema::Coll(Box::new(Schema::Integer))),
+    );
+    assert!(schema.is_valid(&parse("{:a [1] :b (2 3) :c #{}}")));
+    assert_eq!(
+        messages(&schema, "{\"b\" #{3} :a [1 \"2\"] :c {}}"),
+        vec![
+            "[\"b\"]: expected a keyword, found a string",
+            "[:a 1]: expected an integer, found a string",
+            "[:c]: expected a list, vector or set, found a map",
+        ]
+    );
+    assert_eq!(
+        messages(&Schema::Set(Box::new(Schema::Symbol)), "#{a :b}"),
+        vec!["[:b]: expected a symbol, found a keyword"]
+    );
+    assert_eq!(
+        messages(&Schema::Vector(Box::new(Schema::Any)), "(1)"),
+        vec!["[]: expected a vector, found a list"]
+    );
+}
+
+#[test]
+fn test_keys() {
+    let schema = Schema::from(
+        Keys::new()
+            .required(":db/host", Schema::String)
+            .optional("port", Schema::Integer)
+            .required_key(Value::from("name"), Schema::String)
+            .closed(true),
+    );
+    assert!(schema.is_valid(&parse("{:db/host \"h\" \"name\" \"n\"}")));
+    assert_eq!(
+        messages(&schema, "{:port \"80\" :user \"u\"}"),
+        vec![
+            "[]: missing key :db/host",
+            "[:port]: expected an integer, found a string",
+            "[]: missing key \"name\"",
+            "[:user]: unexpected key",
+        ]
+    );
+}
+
+#[test]
+fn test_tags_and_combinators() {
+    let schema = Schema::Tagged("inst".into(), Box::new(Schema::String));
+    assert!(schema.is_valid(&parse("#inst \"2020\"")));
+    assert_eq!(
+        messages(&schema, "#inst 1"),
+        vec!["[inst]: expected a string, found an integer"]
+    );
+    assert_eq!(
+        messages(&schema, "#uuid \"\""),
+        vec!["[]: expected #inst, found #uuid"]
+    );
+
+    let id = Schema::Or(vec![Schema::Integer, Schema::String]);
+    assert!(id.is_valid(&parse("1")));
+    assert!(id.is_valid(&parse("\"a\"")));
+    assert_eq!(
+        messages(&id, ":a"),
+        vec!["[]: expected an integer or a string, found a keyword"]
+    );
+
+    let even = Schema::predicate("an even number", |value| {
+        value.as_i64().is_some_and(|n| n % 2 == 0)
+    });
+    let schema = Schema::And(vec![Schema::Integer, even]);
+    assert!(schema.is_valid(&parse("2")));
+    assert_eq!(
+        messages(&schema, "3"),
+        vec!["[]: expected an even number, found 3"]
+    );
+    assert_eq!(messages(&schema, "^:a 3").len(), 1);
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "rhai")]
+
+extern crate edn;
+extern crate rhai;
+
+use edn::parser::Parser;
+use edn::scripting::{self, from_dynamic, to_dynamic};
+use edn::Value;
+use rhai::{Dynamic, Engine, Scope};
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn run(script: &str, doc: &str) -> Value {
+    let mut engine = Engine::new();
+    scripting::register(&mut engine);
+    let mut scope = Scope::new();
+    scope.push("doc", to_dynamic(&parse(doc)));
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, script)
+        .unwrap();
+    from_dynamic(&result).unwrap()
+}
+
+#[test]
+fn test_round_trip() {
+    for str in &[
+        "nil",
+        "true",
+        "-12",
+        "1.5",
+        r"\a",
+        r#""s""#,
+        ":user/id",
+        "inc",
+        "[1 [2 :x] {:a nil}]",
+        r#"{:name "Ada" :user/roles [:admin] :created #inst "1815-12-10"}"#,
+    ] {
+        let value = parse(str);
+        assert_eq!(from_dynamic(&to_dynamic(&value)), Ok(value));
+    }
+
+    // Lists and sets come back as vectors, and every key as a keyword.
+    assert_eq!(
+        from_dynamic(&to_dynamic(&parse(r#"{"a" (1) 2 #{3}}"#))),
+        Ok(parse("{:a [1] :2 [3]}"))
+    );
+    assert_eq!(
+        from_dynamic(&to_dynamic(&parse("^:private [1]"))),
+        Ok(parse("[1]"))
+    );
+}
+
+#[test]
+fn test_scripts() {
+    assert_eq!(
+        run(
+            r#"doc.filter(|row| row.level != keyword("debug")).map(|row| row.msg)"#,
+            r#"[{:level :info :msg "a"} {:level :debug :msg "b"} {:level :warn :msg "c"}]"#,
+        ),
+        parse(r#"["a" "c"]"#)
+    );
+    assert_eq!(
+        run(
+            "[doc.name, doc.namespace, doc.to_string(), symbol(\"inc\")]",
+            ":user/id",
+        ),
+        parse(r#"["id" "user" ":user/id" inc]"#)
+    );
+    assert_eq!(run("keyword(\"id\").namespace", "nil"), Value::Nil);
+    // Tagged elements pass through untouched.
+    assert_eq!(
+        run(
+            "[doc.id.to_string(), doc.id]",
+            r#"{:id #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"}"#
+        ),
+        parse(
+            r##"["#uuid \"f81d4fae-7dec-11d0-a765-00a0c91e6bf6\""
+                #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"]"##
+        )
+    );
+}
+
+#[test]
+fn test_errors() {
+    let engine = Engine::new();
+    let function = engine.eval::<Dynamic>("Fn(\"f\")").unwrap();
+    assert!(from_dynamic(&function).is_err());
+    assert!(from_dynamic(&Dynamic::from(vec![1u8])).is_err());
+}
@@ -0,0 +1,427 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::ser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+fn to_string(value: &Value) -> String {
+    let mut bytes = vec![];
+    ser::to_writer(&mut bytes, value).unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+fn to_string_pretty(value: &Value) -> String {
+    let mut bytes = vec![];
+    ser::to_writer_pretty(&mut bytes, value).unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn test_to_writer() {
+    for str in &[
+        "nil",
+        "true",
+        "\"a \\\"quoted\\\" \\\\ string\\n\\t\\r\"",
+        "\\a",
+        "\\newline",
+        "\\space",
+        "\"\\b\\f\\u0000\\u007f\u{a0}\"",
+        "\\backspace",
+        "\\u0001",
+        "\\u00a0",
+        "foo/bar",
+        ":foo/bar",
+        "-42",
+        "1.0",
+        "0.125",
+        "(1 [2 3] #{4})",
+        "{:a 1, :b {:c [nil]}}",
+        "#my/tag {:a 1}",
+        "[]",
+        "{}",
+    ] {
+        assert_eq!(to_string(&parse(str)), *str);
+    }
+}
+
+#[test]
+fn test_to_writer_pretty() {
+    assert_eq!(
+        to_string_pretty(&parse("{:a [1 2] :b {} :c #tag (3)}")),
+        "{\n  :a [\n    1\n    2\n  ]\n  :b {}\n  :c #tag (\n    3\n  )\n}"
+    );
+}
+
+#[test]
+fn test_to_string_and_vec() {
+    let value = parse("{:a [1 2] :b \"c\"}");
+    assert_eq!(edn::to_string(&value), to_string(&value));
+    assert_eq!(edn::to_vec(&value), to_string(&value).into_bytes());
+    assert_eq!(edn::to_string_pretty(&value), to_string_pretty(&value));
+    assert_eq!(edn::to_vec_pretty(&value), to_string_pretty(&value).into_bytes());
+    assert_eq!(
+        ser::to_string_with_indent(&value, 4),
+        "{\n    :a [\n        1\n        2\n    ]\n    :b \"c\"\n}"
+    );
+    assert_eq!(
+        ser::to_vec_with_indent(&value, 0),
+        b"{\n:a [\n1\n2\n]\n:b \"c\"\n}".to_vec()
+    );
+
+    let mut out = vec![];
+    edn::to_writer_pretty(&mut out, &value).unwrap();
+    edn::to_writer(&mut out, &value).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        to_string_pretty(&value) + to_string(&value).as_str()
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let value = parse("{:users [{:name \"Ann\" :tags #{:a :b}} {:name \"Bo\\n\" :age 3.5}]}");
+    assert_eq!(parse(&to_string(&value)), value);
+    assert_eq!(parse(&to_string_pretty(&value)), value);
+}
+
+#[test]
+fn test_tag_layouts() {
+    use edn::ser::{Serializer, TagLayout};
+
+    // Print `#matrix [[...] ...]` as one row per line with aligned columns.
+    fn matrix(value: &Value, indent: usize) -> String {
+        let rows: Vec<Vec<String>> = value
+            .clone()
+            .into_iter()
+            .map(|row| row.into_iter().map(|cell| to_string(&cell)).collect())
+            .collect();
+        let width = rows
+            .iter()
+            .flat_map(|row| row.iter().map(|cell| cell.len()))
+            .max()
+            .unwrap_or(0);
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .map(|cell| format!("{:>width$}", cell, width = width))
+                    .collect();
+                format!("[{}]", cells.join(" "))
+            })
+            .collect();
+        format!("[{}]", rows.join(&format!("\n{}", " ".repeat(indent + 9))))
+    }
+
+    let mut serializer = Serializer::pretty(vec![]);
+    serializer.set_tag_layout("inst", TagLayout::Inline);
+    serializer.set_tag_layout("matrix", TagLayout::Custom(Box::new(matrix)));
+    serializer
+        .serialize(&parse(
+            "[#inst [2023 1 1] #matrix [[1 20 3] [400 5 6]] #other [1]]",
+        ))
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(serializer.into_inner()).unwrap(),
+        "[
+  #inst [2023 1 1]
+  #matrix [[  1  20   3]
+           [400   5   6]]
+  #other [
+    1
+  ]
+]"
+    );
+}
+
+#[test]
+fn test_floats_keep_shortest_form() {
+    for str in &["0.1", "0.3", "123.456", "-2.5", "1.0", "3.141592653589793"] {
+        assert_eq!(to_string(&parse(str)), *str);
+    }
+    // Very large and small floats are written with an exponent, which the
+    // parser reads back.
+    for str in &["1e16", "1e-7", "1.5e300"] {
+        assert_eq!(to_string(&parse(str)), *str);
+        assert_eq!(parse(&to_string(&parse(str))), parse(str));
+    }
+}
+
+#[test]
+fn test_to_string_preserving() {
+    use edn::parser::{parse_spanned, ParserBuilder};
+
+    let str = "{:a 1.50, :b [+2.0 1e3 #{0.10}], :c #tag 3.0E0, :d ^:m (4.00)}";
+    let source = parse_spanned(str).unwrap();
+    assert_eq!(
+        ser::to_string_preserving(&source.value, &source),
+        "{:a 1.50, :b [+2.0 1e3 #{0.10}], :c #tag 3.0E0, :d (4.00)}"
+    );
+
+    // Only floats whose value and place are unchanged keep their text.
+    let changed = parse("{:a 1.5, :b [2.0 1000.5 #{0.10 7.0}], :c 3.0E0, :d (4.00)}");
+    assert_eq!(
+        ser::to_string_preserving(&changed, &source),
+        "{:a 1.50, :b [+2.0 1000.5 #{0.10 7.0}], :c 3.0, :d (4.00)}"
+    );
+
+    let keep = ParserBuilder::new().keep_metadata(true);
+    let source = keep.parse_spanned("^{:x 0.50} [1.50]").unwrap();
+    assert_eq!(
+        ser::to_string_preserving(&source.value, &source),
+        "^{:x 0.50} [1.50]"
+    );
+}
+
+#[test]
+fn test_metadata() {
+    use edn::parser::ParserBuilder;
+
+    let str = "^{:doc \"x\", :private true} [^{:tag String} foo]";
+    let value = ParserBuilder::new().keep_metadata(true).from_str(str).unwrap();
+    assert_eq!(to_string(&value), str);
+}
+
+#[test]
+#[cfg(feature = "unstable")]
+fn test_custom_formatter() {
+    use edn::ser::{Formatter, Serializer};
+    use std::io;
+
+    // Compact, but without commas between map entries.
+    struct NoCommas;
+
+    impl Formatter for NoCommas {
+        fn begin_map_key<W: ?Sized + io::Write>(
+            &mut self,
+            writer: &mut W,
+            first: bool,
+        ) -> io::Result<()> {
+            if first {
+                Ok(())
+            } else {
+                writer.write_all(b" ")
+            }
+        }
+    }
+
+    let value = parse("{:a [1 #{2}] :b {:c nil}}");
+
+    let mut serializer = Serializer::with_formatter(vec![], NoCommas);
+    serializer.serialize(&value).unwrap();
+    assert_eq!(
+        String::from_utf8(serializer.into_inner()).unwrap(),
+        "{:a [1 #{2}] :b {:c nil}}"
+    );
+}
+
+#[test]
+fn test_formatters() {
+    use edn::ser::{PrettyFormatter, Serializer};
+
+    let value = parse("{:a [1 #{2}] :b {:c nil}}");
+    let mut serializer = Serializer::with_formatter(vec![], PrettyFormatter::with_indent(4));
+    serializer.serialize(&value).unwrap();
+    assert_eq!(
+        String::from_utf8(serializer.into_inner()).unwrap(),
+        "{\n    :a [\n        1\n        #{\n            2\n        }\n    ]\n    :b {\n        :c nil\n    }\n}"
+    );
+}
+
+#[test]
+fn test_pprint() {
+    use edn::ser::pretty::{self, PrettyPrinter};
+
+    let value = parse(
+        "{:deps [[ordered-float \"0.4.0\"] [im \"12.2.0\"]] :features #{:immutable} :name \"edn\"}",
+    );
+    assert_eq!(
+        pretty::to_string(&value),
+        "{:deps [[ordered-float \"0.4.0\"] [im \"12.2.0\"]],
+ :features #{:immutable},
+ :name \"edn\"}"
+    );
+    assert_eq!(
+        pretty::to_string(&parse("{:a [1 2 3] :b #{}}")),
+        "{:a [1 2 3], :b #{}}"
+    );
+    assert_eq!(
+        PrettyPrinter::new().width(40).to_string(&value),
+        "{:deps [[ordered-float \"0.4.0\"]
+        [im \"12.2.0\"]],
+ :features #{:immutable},
+ :name \"edn\"}"
+    );
+    assert_eq!(
+        PrettyPrinter::new()
+            .width(12)
+            .to_string(&parse("(defn f [x] #tag (inc x))")),
+        "(defn
+ f
+ [x]
+ #tag (inc
+       x))"
+    );
+    assert_eq!(parse(&pretty::to_string(&value)), value);
+}
+
+#[test]
+fn test_keyword_keys() {
+    use edn::ser::{Config, Serializer};
+
+    let value = parse(r#"{"first_name" "Ada" "lastName" "Lovelace" "tags" {"born_in" 1815} :id 1}"#);
+    let serialize = |config: Config| {
+        let mut serializer = Serializer::new(vec![]);
+        serializer.set_config(config);
+        serializer.serialize(&value).unwrap();
+        String::from_utf8(serializer.into_inner()).unwrap()
+    };
+
+    assert_eq!(serialize(Config::new()), to_string(&value));
+    assert_eq!(
+        serialize(Config::new().keyword_keys(true)),
+        r#"{:first-name "Ada", :last-name "Lovelace", :tags {:born-in 1815}, :id 1}"#
+    );
+    assert_eq!(
+        serialize(Config::new().keyword_keys(true).namespace("user")),
+        r#"{:user/first-name "Ada", :user/last-name "Lovelace", :user/tags {:user/born-in 1815}, :id 1}"#
+    );
+}
+
+#[test]
+fn test_non_finite() {
+    use edn::ser::{Config, NonFinite, Serializer};
+
+    let value = parse("[##Inf ##-Inf ##NaN 1.5]");
+    assert_eq!(to_string(&value), "[##Inf ##-Inf ##NaN 1.5]");
+    assert_eq!(parse(&to_string(&value)), value);
+
+    let serialize = |config: Config| {
+        let mut serializer = Serializer::new(vec![]);
+        serializer.set_config(config);
+        serializer
+            .serialize(&value)
+            .map(|_| String::from_utf8(serializer.into_inner()).unwrap())
+    };
+    assert_eq!(
+        serialize(Config::new().non_finite(NonFinite::Nil)).unwrap(),
+        "[nil nil nil 1.5]"
+    );
+    let err = serialize(Config::new().non_finite(NonFinite::Error)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_nil_values() {
+    use edn::ser::{Config, NilValues, Serializer};
+
+    let value = parse("{:a nil :b [nil] :c {:d nil}}");
+    let serialize = |nil_values: NilValues| {
+        let mut serializer = Serializer::new(vec![]);
+        serializer.set_config(Config::new().sort_entries(true).nil_values(nil_values));
+        serializer.serialize(&value).unwrap();
+        String::from_utf8(serializer.into_inner()).unwrap()
+    };
+    assert_eq!(
+        serialize(NilValues::Keep),
+        "{:a nil, :b [nil], :c {:d nil}}"
+    );
+    assert_eq!(serialize(NilValues::Skip), "{:b [nil], :c {}}");
+    assert_eq!(
+        serialize(NilValues::Tagged("none".into())),
+        "{:a #none nil, :b [nil], :c {:d #none nil}}"
+    );
+}
+
+#[test]
+fn test_sequences_as_lists() {
+    use edn::ser::{Config, Serializer};
+    use edn::List;
+
+    let value = parse("[1 (2 [3]) {:a [4]} #{[5]}]");
+    let mut serializer = Serializer::new(vec![]);
+    serializer.set_config(Config::new().sequences_as_lists(true));
+    serializer.serialize(&value).unwrap();
+    assert_eq!(
+        String::from_utf8(serializer.into_inner()).unwrap(),
+        "(1 (2 (3)) {:a (4)} #{(5)})"
+    );
+
+    let value = Value::from(vec![List(vec![1, 2]), List(vec![])]);
+    assert_eq!(to_string(&value), "[(1 2) ()]");
+}
+
+#[test]
+fn test_composite_map_keys() {
+    let mut map = std::collections::HashMap::new();
+    map.insert(vec![1, 2], "a");
+    let value = Value::from(map);
+    assert_eq!(to_string(&value), r#"{[1 2] "a"}"#);
+
+    let value = parse(r#"{{:x 1} #{:a} (1 "b") [nil]}"#);
+    assert_eq!(parse(&to_string(&value)), value);
+    assert_eq!(parse(&to_string_pretty(&value)), value);
+}
+
+#[cfg(feature = "ordered")]
+#[test]
+fn test_ordered_output() {
+    let value = parse("{:b 1 :a #{3 1 2} :c {:z 1 :y 2}}");
+    assert_eq!(to_string(&value), "{:b 1, :a #{3 1 2}, :c {:z 1, :y 2}}");
+    assert_eq!(value, parse("{:a #{1 2 3} :b 1 :c {:y 2 :z 1}}"));
+}
+
+#[test]
+fn test_unprintable_names() {
+    use edn::{Keyword, Symbol};
+    use std::io;
+
+    for value in vec![
+        Value::Symbol(Symbol::new("a b")),
+        Value::Symbol(Symbol::new("1a")),
+        Value::Symbol(Symbol::new("nil")),
+        Value::Symbol(Symbol::namespaced("a", "b/c")),
+        Value::Keyword(Keyword::new("")),
+        Value::Keyword(Keyword::new(":a")),
+        Value::Keyword(Keyword::namespaced("", "a")),
+        Value::Tagged("_".into(), Box::new(Value::Nil)),
+        Value::Tagged("1".into(), Box::new(Value::Nil)),
+        Value::Vector(vec![Value::Symbol(Symbol::new("(x)"))].into_iter().collect()),
+    ] {
+        let err = ser::to_writer(vec![], &value).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(ser::pretty::to_writer(vec![], &value).is_err());
+    }
+    let err = ser::to_writer(vec![], &Value::Symbol(Symbol::new("a b"))).unwrap_err();
+    assert_eq!(err.to_string(), "can't write symbol `a b` as EDN");
+
+    // Clojure reads these, though the spec doesn't allow them.
+    for text in &["[:0 :user/1st a/1 :- :/]", "(/ -a .b)"] {
+        assert_eq!(&to_string(&parse(text)), text);
+    }
+}
+
+#[test]
+fn test_buffered_output() {
+    assert_eq!(to_string(&Value::from(i64::MIN)), "-9223372036854775808");
+    assert_eq!(to_string(&Value::from(-1.5e300)), "-1.5e300");
+    let canonical = ser::to_string_canonical(&Value::from(1e300));
+    assert_eq!(canonical.len(), 303);
+    assert!(canonical.starts_with("1000") && canonical.ends_with("000.0"));
+    assert_eq!(ser::to_string_canonical(&Value::from(-0.25)), "-0.25");
+    assert_eq!(
+        to_string(&Value::from("é\"ü\u{1}\u{85}x")),
+        "\"é\\\"ü\\u0001\\u0085x\""
+    );
+    assert_eq!(to_string(&Value::Char('é')), "\\é");
+    assert_eq!(
+        ser::to_string_with_indent(&parse("[[1]]"), 70),
+        format!("[\n{0}[\n{0}{0}1\n{0}]\n]", " ".repeat(70))
+    );
+}
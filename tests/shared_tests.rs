@@ -0,0 +1,39 @@
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::{ArcValue, Value};
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_round_trip() {
+    for str in &[
+        "nil",
+        "[1 2.5 \\c \"s\" sym :kw true]",
+        "(a (b #{c}))",
+        "{:a {:b [1 2]} \"k\" #inst \"2020-01-01T00:00:00Z\"}",
+    ] {
+        let value = parse(str);
+        assert_eq!(ArcValue::from(value.clone()).to_value(), value, "{}", str);
+    }
+}
+
+#[test]
+fn test_clones_share() {
+    let document = ArcValue::from(parse("{:users [{:name \"a\"} {:name \"b\"}]}"));
+    let users = document.get(&ArcValue::from(parse(":users"))).unwrap();
+    let clone = users.clone();
+    match (users, &clone) {
+        (ArcValue::Vector(a), ArcValue::Vector(b)) => {
+            assert!(std::sync::Arc::ptr_eq(a, b))
+        }
+        _ => panic!("expected vectors"),
+    }
+    let name = clone.nth(1).unwrap().get(&ArcValue::from(parse(":name")));
+    assert_eq!(name, Some(&ArcValue::String("b".into())));
+    assert_eq!(Value::from(&clone), parse("[{:name \"a\"} {:name \"b\"}]"));
+    assert_eq!(clone.nth(2), None);
+    assert_eq!(document.nth(0), None);
+}
@@ -0,0 +1,65 @@
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::stats::{analyze, Stats};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_report() {
+    let documents: Vec<Value> = [
+        r#"{:id 1 :name "a" :tags #{:x}}"#,
+        r#"{:id 2 :name nil :at #inst "2020-01-01T00:00:00Z"}"#,
+        r#"[{:id 3 :child {:id 4}} #uuid "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"]"#,
+    ]
+    .iter()
+    .map(|str| parse(str))
+    .collect();
+    assert_eq!(
+        analyze(&documents),
+        parse(
+            r#"{:documents 3
+                :keys {:id {:count 4 :types {:integer 4}}
+                       :name {:count 2 :types {:nil 1 :string 1}}
+                       :tags {:count 1 :types {:set 1}}
+                       :at {:count 1 :types {:tagged 1}}
+                       :child {:count 1 :types {:map 1}}}
+                :tags {"inst" 1 "uuid" 1}
+                :sizes {:min 31 :p50 52 :p90 70 :p99 70 :max 70}}"#
+        )
+    );
+}
+
+#[test]
+fn test_empty() {
+    let stats = Stats::new();
+    assert_eq!(stats.documents(), 0);
+    assert_eq!(
+        stats.report(),
+        parse("{:documents 0 :keys {} :tags {} :sizes {:min nil :p50 nil :p90 nil :p99 nil :max nil}}")
+    );
+}
+
+#[test]
+fn test_value_stats() {
+    let value = parse("{:a [1 2 {:b :a}] :c #my/tag \"text\"}");
+    assert_eq!(value.deep_count(), 11);
+    let stats = value.stats();
+    assert_eq!(stats.nodes, 11);
+    assert_eq!(stats.max_depth, 4);
+    assert_eq!((stats.keywords, stats.distinct_keywords), (4, 3));
+    assert_eq!(stats.types["map"], 2);
+    assert_eq!(stats.types["integer"], 2);
+    assert_eq!(stats.types["tagged"], 1);
+    assert_eq!(stats.estimated_size, value.estimated_size());
+
+    assert_eq!(Value::Integer(1).deep_count(), 1);
+    assert_eq!(Value::Integer(1).estimated_size(), 0);
+    assert_eq!(Value::String("abcd".into()).estimated_size(), 4);
+    let vector = parse("[1 2 3]");
+    assert_eq!(vector.estimated_size(), 3 * std::mem::size_of::<Value>());
+    assert!(parse("[\"abcd\" 2 3]").estimated_size() > vector.estimated_size());
+}
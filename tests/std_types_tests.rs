@@ -0,0 +1,96 @@
+extern crate edn;
+
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use edn::parser::Parser;
+use edn::std_types::{self, Form};
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_duration() {
+    for &(duration, str) in &[
+        (Duration::new(0, 0), "PT0S"),
+        (Duration::from_millis(1500), "PT1.5S"),
+        (Duration::new(0, 1), "PT0.000000001S"),
+        (Duration::from_secs(60), "PT1M"),
+        (Duration::from_secs(3600 * 25 + 61), "PT25H1M1S"),
+    ] {
+        let value = Value::from(duration);
+        assert_eq!(
+            edn::to_string(&value),
+            format!("#time/duration \"{}\"", str)
+        );
+        assert_eq!(value.as_duration(), Some(duration));
+        assert_eq!(
+            std_types::duration(duration, Form::String),
+            Value::from(str)
+        );
+        assert_eq!(Value::from(str).as_duration(), Some(duration));
+    }
+
+    for &(str, secs) in &[("P2D", 172_800), ("P1DT1H", 90_000), ("pt90m", 5400)] {
+        assert_eq!(
+            Value::from(str).as_duration(),
+            Some(Duration::from_secs(secs))
+        );
+    }
+    for str in &[
+        "",
+        "P",
+        "PT",
+        "1S",
+        "PT1",
+        "PT-1S",
+        "P1Y",
+        "PT1.5M",
+        "PT0.1234567891S",
+    ] {
+        assert_eq!(Value::from(*str).as_duration(), None, "{}", str);
+    }
+    assert_eq!(parse("#inst \"PT1S\"").as_duration(), None);
+}
+
+#[test]
+fn test_addresses() {
+    let ip = IpAddr::from(Ipv6Addr::LOCALHOST);
+    assert_eq!(edn::to_string(&Value::from(ip)), "#net/ip \"::1\"");
+    assert_eq!(
+        parse("#net/ip \"10.0.0.1\"").as_ip_addr(),
+        "10.0.0.1".parse().ok()
+    );
+    assert_eq!(Value::from("::1").as_ip_addr(), Some(ip));
+    assert_eq!(Value::from("localhost").as_ip_addr(), None);
+
+    let socket = SocketAddr::new(ip, 8080);
+    assert_eq!(
+        edn::to_string(&Value::from(socket)),
+        "#net/socket \"[::1]:8080\""
+    );
+    assert_eq!(Value::from(socket).as_socket_addr(), Some(socket));
+    assert_eq!(
+        std_types::socket_addr(socket, Form::String),
+        Value::from("[::1]:8080")
+    );
+    assert_eq!(parse("#net/ip \"[::1]:8080\"").as_socket_addr(), None);
+}
+
+#[test]
+fn test_path() {
+    let path = PathBuf::from("/etc/app/config.edn");
+    assert_eq!(
+        edn::to_string(&Value::from(path.clone())),
+        "#fs/path \"/etc/app/config.edn\""
+    );
+    assert_eq!(Value::from(path.as_path()).as_path(), Some(path.clone()));
+    assert_eq!(
+        std_types::path(Path::new("a b"), Form::String).as_path(),
+        Some(PathBuf::from("a b"))
+    );
+    assert_eq!(Value::Integer(1).as_path(), None);
+}
@@ -0,0 +1,113 @@
+#[macro_use]
+extern crate edn;
+
+use edn::keys::FromValue;
+use edn::parser::Parser;
+use edn::tagged::TaggedValue;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_tagged_value() {
+    let value = parse("#my/id 42");
+    let id = TaggedValue::<i64>::from_value(&value).unwrap();
+    assert_eq!(id, TaggedValue::new("my/id", 42));
+    assert_eq!(Value::from(id), value);
+
+    let err = TaggedValue::<i64>::from_value(&parse("#my/id \"x\"")).unwrap_err();
+    assert_eq!(err.to_string(), "expected an integer, found a string");
+    let err = TaggedValue::<i64>::from_value(&parse("42")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected a tagged element, found an integer"
+    );
+
+    let points = TaggedValue::<Vec<Option<f64>>>::from_value(&parse("#geo/path [1.5 nil 2]"));
+    assert_eq!(points.unwrap().value, vec![Some(1.5), None, Some(2.0)]);
+}
+
+#[test]
+fn test_with_tag() {
+    let value = parse("#my/id 42");
+    assert_eq!(
+        TaggedValue::<i64>::with_tag(&value, "my/id").unwrap().value,
+        42
+    );
+    for &(tag, input, message) in &[
+        ("other", "#my/id 42", "expected #other, found #my/id"),
+        ("my/id", "42", "expected #my/id, found an integer"),
+        (
+            "my/id",
+            "#my/id :a",
+            "#my/id: expected an integer, found a keyword",
+        ),
+    ] {
+        let err = TaggedValue::<i64>::with_tag(&parse(input), tag).unwrap_err();
+        assert_eq!(err.message, message);
+    }
+}
+
+#[test]
+fn test_keys() {
+    let value = parse("{:id #my/id 42 :at #inst \"2020-01-01T00:00:00Z\"}");
+    let (id, at): (TaggedValue<i64>, Option<TaggedValue<String>>) =
+        edn_keys!(value, :id, :at).unwrap();
+    assert_eq!((id.value, at.unwrap().tag), (42, "inst".to_string()));
+}
+
+#[test]
+fn test_tagged_serialize() {
+    use edn::ser::Serializer;
+    use edn::tagged::{self, TaggedSerialize};
+
+    struct Point(f64, f64);
+
+    impl TaggedSerialize for Point {
+        const TAG: &'static str = "geo/point";
+
+        fn to_untagged(&self) -> Value {
+            Value::vector(vec![self.0, self.1])
+        }
+    }
+
+    let value = tagged::to_value(&Point(1.5, 2.5));
+    assert_eq!(value, parse("#geo/point [1.5 2.5]"));
+    let point = TaggedValue::<Vec<f64>>::with_tag(&value, Point::TAG).unwrap();
+    assert_eq!(point.value, vec![1.5, 2.5]);
+
+    let mut serializer = Serializer::new(vec![]);
+    serializer.serialize_tagged(&Point(0.0, -1.0)).unwrap();
+    assert_eq!(serializer.into_inner(), b"#geo/point [0.0 -1.0]");
+}
+
+#[test]
+fn test_installed_readers() {
+    use edn::parser::{Error, ParserBuilder};
+    use edn::readers::{self, ReaderRegistry};
+
+    readers::install("test.installed/double", |value| match value {
+        Value::Integer(integer) => Ok(Value::Integer(integer * 2)),
+        _ => Err(Error::data("expected an integer")),
+    });
+    assert_eq!(parse("[#test.installed/double 21]"), parse("[42]"));
+    let err = edn::parser::from_str("#test.installed/double nil").unwrap_err();
+    assert_eq!((err.lo, err.message.as_str()), (0, "expected an integer"));
+
+    let registry = ReaderRegistry::new();
+    let builder = ParserBuilder::new().readers(&registry);
+    assert_eq!(
+        builder.from_str("#test.installed/double 1").unwrap(),
+        Value::Tagged("test.installed/double".into(), Box::new(Value::Integer(1)))
+    );
+    let defaults = readers::default_readers();
+    let builder = ParserBuilder::new().readers(&defaults);
+    assert_eq!(builder.from_str("#test.installed/double 1").unwrap(), Value::Integer(2));
+
+    let value = std::thread::spawn(|| parse("#test.installed/double 5"))
+        .join()
+        .unwrap();
+    assert_eq!(value, Value::Integer(10));
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "json")]
+
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::transit;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_to_string() {
+    let cases = [
+        (":a", r#"["~#'","~:a"]"#),
+        ("[:a :abc :abc sym sym]", r#"["~:a","~:abc","^0","~$sym","^1"]"#),
+        (
+            r#"[{"long" 1 :name "x"} {"long" 9007199254740993 :name "~y"}]"#,
+            r#"[["^ ","long",1,"~:name","x"],["^ ","^0","~i9007199254740993","^1","~~y"]]"#,
+        ),
+        ("#{1}", r#"["~#set",[1]]"#),
+        ("(1 \\a nil)", r#"["~#list",[1,"~ca",null]]"#),
+        ("{[1] 2}", r#"["~#cmap",[[1],2]]"#),
+        ("{1 2}", r#"["^ ","~i1",2]"#),
+        (r#"#uuid "abc""#, r#"["~#'","~uabc"]"#),
+        ("[#point [1 2] #point [3 4]]", r#"[["~#point",[1,2]],["^0",[3,4]]]"#),
+    ];
+    for &(edn, transit) in &cases {
+        assert_eq!(transit::to_string(&parse(edn)), transit, "{}", edn);
+        assert_eq!(transit::from_str(transit), Ok(parse(edn)), "{}", transit);
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let value = parse(
+        r#"[{:user/id 1 :user/tags #{:admin :staff} :user/friends [{:user/id 2} {:user/id 3}]}
+            {[1 2] (sym "^str") 1.5 -2.0 true \newline }]"#,
+    );
+    assert_eq!(transit::from_str(&transit::to_string(&value)), Ok(value));
+    assert_eq!(
+        transit::from_str(&transit::to_string(&Value::from(f64::INFINITY))),
+        Ok(Value::from(f64::INFINITY))
+    );
+    #[cfg(feature = "ratio")]
+    {
+        let ratio = Value::Vector(vec![Value::Ratio(-22, 7), Value::Ratio(1, 2)]);
+        assert_eq!(transit::from_str(&transit::to_string(&ratio)), Ok(ratio));
+    }
+}
+
+#[test]
+fn test_from_str() {
+    assert_eq!(
+        transit::from_str(r#"["~m0","~m1700000000000","~t2020-01-01"]"#),
+        Ok(parse(
+            r#"[#inst "1970-01-01T00:00:00.000-00:00" #inst "2023-11-14T22:13:20.000-00:00" #inst "2020-01-01"]"#
+        ))
+    );
+    assert_eq!(
+        transit::from_str(r#"{"~:a": 1, "b": ["~#set", [1]]}"#),
+        Ok(parse("{:a 1 \"b\" #{1}}"))
+    );
+    assert!(transit::from_str(r#"["^0"]"#).is_err());
+    assert!(transit::from_str(r#"["~#set", 1]"#).is_err());
+    assert!(transit::from_str("[").is_err());
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "uuid")]
+
+extern crate edn;
+extern crate uuid;
+
+use edn::parser::{Category, Error, Parser};
+use edn::readers::{self, ReaderRegistry};
+use edn::Value;
+use uuid::Uuid;
+
+const UUID: &str = "f81d4fae-7dec-11d0-a765-00a0c91e6bf6";
+
+#[test]
+fn test_from_uuid() {
+    let uuid = Uuid::parse_str(UUID).unwrap();
+    let value = Value::from(uuid);
+    assert_eq!(
+        value,
+        Value::Tagged("uuid".into(), Box::new(Value::String(UUID.into())))
+    );
+    assert_eq!(value.as_uuid(), Some(uuid));
+}
+
+#[test]
+fn test_uuid_reader() {
+    let mut registry = ReaderRegistry::new();
+    registry.register("uuid", readers::uuid);
+
+    let mut parser = Parser::with_readers(
+        r#"#uuid "F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6" #uuid "nope""#,
+        &registry,
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Ok(Value::from(Uuid::parse_str(UUID).unwrap())))
+    );
+    assert_eq!(
+        parser.read(),
+        Some(Err(Error {
+            lo: 45,
+            hi: 57,
+            line: 1,
+            column: 46,
+            category: Category::Data,
+            message: "expected a UUID string for `#uuid`".into(),
+        }))
+    );
+}
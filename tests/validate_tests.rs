@@ -0,0 +1,45 @@
+extern crate edn;
+
+use std::fs;
+use std::path::PathBuf;
+
+use edn::parser::{self, Category};
+use edn::validate;
+
+#[test]
+fn test_check_str() {
+    assert_eq!(validate::check_str("{:a 1} [2] ; done"), None);
+    let err = validate::check_str("{:a 1}\n[2 3)").unwrap();
+    assert_eq!((err.line, err.column), (2, 5));
+}
+
+#[test]
+fn test_check_dir() {
+    let dir = std::env::temp_dir().join(format!("edn-validate-{}", std::process::id()));
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("a.edn"), "{:ok true}").unwrap();
+    fs::write(dir.join("b.edn"), "[1 2").unwrap();
+    fs::write(dir.join("nested/c.edn"), "#{1 1} :x").unwrap();
+    fs::write(dir.join("notes.txt"), "(").unwrap();
+
+    let report = validate::check_dir(&dir).unwrap();
+    assert_eq!(report.files, 3);
+    assert!(!report.is_ok());
+    assert_eq!(report.diagnostics.len(), 1);
+    assert_eq!(report.diagnostics[0].path, dir.join("b.edn"));
+    assert_eq!(report.diagnostics[0].error.category, Category::Eof);
+
+    let missing = validate::check_files(&[PathBuf::from("/nonexistent/x.edn")]);
+    assert_eq!(missing.diagnostics[0].error.category, Category::Io);
+
+    let value = report.to_value();
+    let error = &value.get_kw("errors").unwrap()[0];
+    assert_eq!(value.get_kw("files"), Some(&parser::from_str("3").unwrap()));
+    assert_eq!(
+        error.get_kw("category"),
+        Some(&parser::from_str(":eof").unwrap())
+    );
+    assert_eq!(error.get_kw("lo"), Some(&parser::from_str("0").unwrap()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
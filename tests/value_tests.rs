@@ -0,0 +1,619 @@
+#![cfg(not(feature = "immutable"))]
+
+extern crate edn;
+
+use edn::parser::Parser;
+use edn::Value;
+
+fn parse(str: &str) -> Value {
+    Parser::new(str).read().unwrap().unwrap()
+}
+
+#[test]
+fn test_into_collections() {
+    assert_eq!(
+        parse("(1 2)").into_vec(),
+        Ok(vec![Value::Integer(1), Value::Integer(2)])
+    );
+    assert_eq!(
+        parse("[1 2]").into_vec(),
+        Ok(vec![Value::Integer(1), Value::Integer(2)])
+    );
+    assert_eq!(parse("#{1}").into_vec(), Err(parse("#{1}")));
+
+    let map = parse("{:a 1}").into_map().unwrap();
+    assert_eq!(
+        map.get(&Value::Keyword("a".into())),
+        Some(&Value::Integer(1))
+    );
+    assert_eq!(parse("[]").into_map(), Err(parse("[]")));
+
+    let set = parse("#{1 2}").into_set().unwrap();
+    assert!(set.contains(&Value::Integer(2)));
+    assert_eq!(Value::Nil.into_set(), Err(Value::Nil));
+}
+
+#[test]
+fn test_into_iter() {
+    assert_eq!(
+        parse("[1 2 3]").into_iter().collect::<Vec<_>>(),
+        vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)]
+    );
+    assert_eq!(
+        parse("{:a 1 :b 2}").into_iter().collect::<Vec<_>>(),
+        vec![parse("[:a 1]"), parse("[:b 2]")]
+    );
+    assert_eq!(parse("#{1}").into_iter().count(), 1);
+    assert_eq!(Value::Nil.into_iter().count(), 0);
+    assert_eq!(Value::Integer(1).into_iter().count(), 0);
+}
+
+#[test]
+fn test_iter() {
+    use edn::iter::{ValueIter, ValueIterMut};
+
+    let value = parse("(1 2 3)");
+    match value.iter() {
+        ValueIter::Items(items) => assert_eq!(
+            items.collect::<Vec<_>>(),
+            vec![&Value::Integer(1), &Value::Integer(2), &Value::Integer(3)]
+        ),
+        ValueIter::Entries(_) => panic!(),
+    }
+    let value = parse("{:a 1}");
+    match value.iter() {
+        ValueIter::Entries(entries) => {
+            assert_eq!(
+                entries.collect::<Vec<_>>(),
+                vec![(&parse(":a"), &Value::Integer(1))]
+            )
+        }
+        ValueIter::Items(_) => panic!(),
+    }
+    match parse("#{1}").iter() {
+        ValueIter::Items(items) => assert_eq!(items.count(), 1),
+        ValueIter::Entries(_) => panic!(),
+    }
+    match Value::Nil.iter() {
+        ValueIter::Items(items) => assert_eq!(items.count(), 0),
+        ValueIter::Entries(_) => panic!(),
+    }
+
+    let mut value = parse("[1 2]");
+    if let ValueIterMut::Items(items) = value.iter_mut() {
+        for item in items {
+            *item = Value::Integer(item.as_i64().unwrap() * 10);
+        }
+    }
+    assert_eq!(value, parse("[10 20]"));
+    let mut value = parse("{:a 1}");
+    if let ValueIterMut::Entries(entries) = value.iter_mut() {
+        for (_, value) in entries {
+            *value = Value::Nil;
+        }
+    }
+    assert_eq!(value, parse("{:a nil}"));
+    match parse("#{1}").iter_mut() {
+        ValueIterMut::Items(items) => assert_eq!(items.count(), 0),
+        ValueIterMut::Entries(_) => panic!(),
+    }
+}
+
+#[test]
+fn test_keywords_and_symbols() {
+    use edn::{Keyword, Symbol};
+
+    match parse(":person/first-name") {
+        Value::Keyword(keyword) => {
+            assert_eq!(keyword.namespace(), Some("person"));
+            assert_eq!(keyword.name(), "first-name");
+            assert_eq!(keyword, Keyword::namespaced("person", "first-name"));
+            assert_eq!(keyword.to_string(), ":person/first-name");
+        }
+        _ => panic!(),
+    }
+    match parse("clojure.core//") {
+        Value::Symbol(symbol) => {
+            assert_eq!(symbol.namespace(), Some("clojure.core"));
+            assert_eq!(symbol.name(), "/");
+            assert_eq!(symbol.to_string(), "clojure.core//");
+        }
+        _ => panic!(),
+    }
+    assert_eq!(parse("/"), Value::Symbol(Symbol::new("/")));
+    assert_eq!(parse("foo"), Value::from(Symbol::new("foo")));
+
+    assert_eq!(":a/b".parse(), Ok(Keyword::namespaced("a", "b")));
+    assert_eq!("a/b".parse(), Ok(Keyword::namespaced("a", "b")));
+    assert_eq!(":".parse::<Keyword>(), Err("empty keyword".into()));
+    assert_eq!("a/b".parse(), Ok(Symbol::namespaced("a", "b")));
+
+    // Keywords without a namespace sort first, then by namespace and name.
+    let mut keywords = vec![
+        Keyword::from("b/a"),
+        Keyword::from("z"),
+        Keyword::from("a/b"),
+        Keyword::from("a/a"),
+    ];
+    keywords.sort();
+    assert_eq!(
+        keywords,
+        vec![
+            Keyword::from("z"),
+            Keyword::from("a/a"),
+            Keyword::from("a/b"),
+            Keyword::from("b/a"),
+        ]
+    );
+}
+
+#[test]
+fn test_set_operations() {
+    use edn::TypeError;
+
+    let a = parse("#{:read :write :admin}");
+    let b = parse("#{:read :deploy}");
+    assert_eq!(a.union(&b), Ok(parse("#{:read :write :admin :deploy}")));
+    assert_eq!(a.intersection(&b), Ok(parse("#{:read}")));
+    assert_eq!(a.difference(&b), Ok(parse("#{:write :admin}")));
+    assert_eq!(a.is_subset(&b), Ok(false));
+    assert_eq!(parse("#{:read}").is_subset(&a), Ok(true));
+    assert_eq!(parse("#{}").is_subset(&b), Ok(true));
+
+    let error = TypeError {
+        expected: "a set",
+        found: "a vector",
+    };
+    assert_eq!(a.union(&parse("[:read]")), Err(error.clone()));
+    assert_eq!(parse("[:read]").is_subset(&a), Err(error.clone()));
+    assert_eq!(error.to_string(), "expected a set, found a vector");
+}
+
+#[test]
+fn test_sequence_operations() {
+    use edn::TypeError;
+
+    assert_eq!(parse("(1 2)").conj(Value::Integer(0)), Ok(parse("(0 1 2)")));
+    assert_eq!(parse("[1 2]").conj(Value::Integer(3)), Ok(parse("[1 2 3]")));
+    assert_eq!(parse("#{1}").conj(Value::Integer(1)), Ok(parse("#{1}")));
+    assert_eq!(
+        parse("{:a 1}").conj(parse("[:b 2]")),
+        Ok(parse("{:a 1 :b 2}"))
+    );
+    assert_eq!(
+        parse("{:a 1}").conj(parse(":b")),
+        Err(TypeError {
+            expected: "a [key value] vector",
+            found: "a keyword",
+        })
+    );
+
+    assert_eq!(parse("(1 2)").concat(&parse("[3]")), Ok(parse("(1 2 3)")));
+    assert_eq!(parse("[1 2]").concat(&parse("(3)")), Ok(parse("[1 2 3]")));
+    assert_eq!(
+        parse("[1]").concat(&parse("#{2}")),
+        Err(TypeError {
+            expected: "a list or vector",
+            found: "a set",
+        })
+    );
+
+    assert_eq!(parse("[0 1 2 3]").slice(1..3), Ok(parse("[1 2]")));
+    assert_eq!(parse("(0 1 2 3)").slice(2..10), Ok(parse("(2 3)")));
+    assert_eq!(parse("[0 1]").slice(5..6), Ok(parse("[]")));
+
+    assert_eq!(parse("[3 1 3 2 1]").distinct(), Ok(parse("[3 1 2]")));
+    assert_eq!(parse("(:a :a)").distinct(), Ok(parse("(:a)")));
+
+    assert_eq!(
+        parse("[:a :b :a \"a\"]").frequencies(),
+        Ok(parse("{:a 2 :b 1 \"a\" 1}"))
+    );
+    assert_eq!(
+        parse("{}").frequencies(),
+        Err(TypeError {
+            expected: "a list, vector or set",
+            found: "a map",
+        })
+    );
+}
+
+#[test]
+fn test_index() {
+    let mut value = parse("{:name \"edn\" \"version\" [0 3] :user/id 7 1 :one}");
+    assert_eq!(value["name"], Value::String("edn".into()));
+    assert_eq!(value[":name"], Value::String("edn".into()));
+    assert_eq!(value["version"][1], Value::Integer(3));
+    assert_eq!(value[":user/id"], Value::Integer(7));
+    assert_eq!(value[Value::Integer(1)], Value::Keyword("one".into()));
+    assert_eq!(value["missing"][0], Value::Nil);
+    assert_eq!(value.get("version").and_then(|v| v.get(2)), None);
+    assert_eq!(value.get(0), None);
+
+    value["version"][1] = Value::Integer(4);
+    *value.get_mut(":name").unwrap() = Value::Nil;
+    assert_eq!(
+        value,
+        parse("{:name nil \"version\" [0 4] :user/id 7 1 :one}")
+    );
+    assert_eq!(value.get_mut("nope"), None);
+}
+
+#[test]
+fn test_numeric_eq() {
+    use edn::NumericEq;
+    use std::collections::HashSet;
+
+    assert!(parse("1") != parse("1.0"));
+    assert!(parse("1").numeric_eq(&parse("1.0")));
+    assert!(parse("[1 {2 #{3}}]").numeric_eq(&parse("[1.0 {2.0 #{3.0}}]")));
+    assert!(parse("#{1 2.0}").numeric_eq(&parse("#{2 1.0}")));
+    assert!(!parse("1").numeric_eq(&parse("1.5")));
+    assert!(!parse("(1)").numeric_eq(&parse("[1]")));
+
+    let set: HashSet<NumericEq> = vec![parse("1"), parse("{:a 2.0 :b 3}")]
+        .into_iter()
+        .map(NumericEq)
+        .collect();
+    assert!(set.contains(&NumericEq(parse("1.0"))));
+    assert!(set.contains(&NumericEq(parse("{:a 2 :b 3.0}"))));
+    assert!(!set.contains(&NumericEq(parse("2"))));
+}
+
+#[test]
+fn test_accessors() {
+    use edn::{Keyword, Symbol};
+
+    let mut value = parse("[nil true \"s\" \\c sym :kw -1 2 2.5 (1) #{} {} #tag 1]");
+    {
+        let items = value.as_vector().unwrap();
+        assert!(items[0].is_nil());
+        assert_eq!(items[1].as_bool(), Some(true));
+        assert_eq!(items[2].as_str(), Some("s"));
+        assert_eq!(items[3].as_char(), Some('c'));
+        assert_eq!(items[4].as_symbol(), Some(&Symbol::new("sym")));
+        assert_eq!(items[5].as_keyword(), Some(&Keyword::new("kw")));
+        assert_eq!(items[6].as_i64(), Some(-1));
+        assert_eq!(items[6].as_u64(), None);
+        assert_eq!(items[7].as_u64(), Some(2));
+        assert_eq!(items[7].as_f64(), Some(2.0));
+        assert!(!items[7].is_f64());
+        assert_eq!(items[8].as_f64(), Some(2.5));
+        assert_eq!(items[8].as_i64(), None);
+        assert!(items[6].is_number() && items[8].is_number() && !items[2].is_number());
+        assert_eq!(items[9].as_list().map(|items| items.len()), Some(1));
+        assert!(items[9].as_vector().is_none());
+        assert!(items[10].is_set() && items[11].is_map() && !items[10].is_map());
+        assert_eq!(items[12].as_tagged(), Some(("tag", &Value::Integer(1))));
+        assert!(items.iter().filter(|item| item.is_string()).count() == 1);
+    }
+
+    value.as_vector_mut().unwrap()[2].as_str_mut().unwrap().push('!');
+    assert_eq!(value[2].as_str(), Some("s!"));
+    *value[12].as_tagged_mut().unwrap().1 = Value::Nil;
+    assert_eq!(value[12], parse("#tag nil"));
+    assert_eq!(value.as_map_mut(), None);
+}
+
+#[test]
+fn test_keyword_lookup() {
+    use edn::Keyword;
+
+    let user = parse("{:name \"Ada\" :user/id 1 \"email\" \"ada@example.com\"}");
+    assert_eq!(user.get_kw("name"), Some(&Value::String("Ada".into())));
+    assert_eq!(user.get_kw(":user/id"), Some(&Value::Integer(1)));
+    assert_eq!(user.get_kw("email"), None);
+
+    let id = Keyword::namespaced("user", "id");
+    assert_eq!(id.lookup(&user), Some(&Value::Integer(1)));
+    assert_eq!(Keyword::new("age").lookup_or(&user, &Value::Integer(0)), &Value::Integer(0));
+    assert_eq!(
+        Keyword::new("admin").lookup(&parse("#{:admin :dev}")),
+        Some(&parse(":admin"))
+    );
+    assert_eq!(id.lookup(&parse("[:user/id]")), None);
+}
+
+#[test]
+fn test_collection_adapters() {
+    use edn::TypeError;
+
+    let inc = |value: &Value| match *value {
+        Value::Integer(i) => Value::Integer(i + 1),
+        ref other => other.clone(),
+    };
+    assert_eq!(parse("(1 2)").map_items(inc), Ok(parse("(2 3)")));
+    assert_eq!(parse("[1 2]").map_items(inc), Ok(parse("[2 3]")));
+    assert_eq!(parse("#{1 2}").map_items(inc), Ok(parse("#{2 3}")));
+    assert_eq!(
+        parse("{:a 1}").map_items(inc),
+        Err(TypeError {
+            expected: "a list, vector or set",
+            found: "a map",
+        })
+    );
+
+    let map = parse("{:a 1 :b 2 :secret 3}");
+    assert_eq!(map.map_values(inc), Ok(parse("{:a 2 :b 3 :secret 4}")));
+    assert_eq!(
+        map.map_keys(|key| Value::String(key.as_keyword().unwrap().name().into())),
+        Ok(parse("{\"a\" 1 \"b\" 2 \"secret\" 3}"))
+    );
+    assert_eq!(
+        map.filter_entries(|key, _| key != &parse(":secret")),
+        Ok(parse("{:a 1 :b 2}"))
+    );
+    assert_eq!(
+        parse("[]").map_values(inc),
+        Err(TypeError {
+            expected: "a map",
+            found: "a vector",
+        })
+    );
+}
+
+#[test]
+fn test_get_in() {
+    let value = parse("{:user/friends [{:user/name \"Ada\"} {:user/name \"Bob\"}] \"n\" (1 2)}");
+    let friends = parse(":user/friends");
+    let name = parse(":user/name");
+    assert_eq!(
+        value.get_in(&[&friends, &Value::Integer(1), &name]),
+        Some(&Value::String("Bob".into()))
+    );
+    assert_eq!(
+        value.get_in(&[&Value::String("n".into()), &Value::Integer(0)]),
+        Some(&Value::Integer(1))
+    );
+    assert_eq!(value.get_in(&[&friends, &Value::Integer(-1)]), None);
+    assert_eq!(value.get_in(&[&friends, &name]), None);
+    assert_eq!(value.get_in(&[]), Some(&value));
+
+    assert_eq!(
+        value.get_path(&[&"user/friends", &0, &":user/name"]),
+        Some(&Value::String("Ada".into()))
+    );
+    assert_eq!(value.get_path(&[&"n", &5]), None);
+}
+
+#[test]
+fn test_paths() {
+    use edn::iter::PathSegment::*;
+
+    let value = parse("{:a [1 {:b 2}] :c #{3} :d #tag 4 :e []}");
+    let paths: Vec<_> = value.paths().collect();
+    let (a, b, c, d, e) = (
+        parse(":a"),
+        parse(":b"),
+        parse(":c"),
+        parse(":d"),
+        parse(":e"),
+    );
+    let three = Value::Integer(3);
+    assert_eq!(
+        paths,
+        vec![
+            (vec![Key(&a), Index(0)], &Value::Integer(1)),
+            (vec![Key(&a), Index(1), Key(&b)], &Value::Integer(2)),
+            (vec![Key(&c), Element(&three)], &three),
+            (vec![Key(&d), Tag("tag")], &Value::Integer(4)),
+            (vec![Key(&e)], &parse("[]")),
+        ]
+    );
+    assert_eq!(Value::Nil.paths().collect::<Vec<_>>(), vec![(vec![], &Value::Nil)]);
+}
+
+#[test]
+fn test_partial_eq_primitives() {
+    assert_eq!(parse("\"foo\""), "foo");
+    assert_eq!("foo", parse("\"foo\""));
+    assert_eq!(parse("\"foo\""), String::from("foo"));
+    assert_ne!(parse(":foo"), "foo");
+
+    assert_eq!(parse("42"), 42);
+    assert_eq!(42u8, parse("42"));
+    assert_eq!(parse("-1"), -1i32);
+    assert_ne!(parse("-1"), u64::MAX);
+    assert_ne!(parse("42.0"), 42);
+
+    assert_eq!(parse("1.5"), 1.5);
+    assert_eq!(1.5f32, parse("1.5"));
+    assert_ne!(parse("1"), 1.0);
+
+    assert_eq!(parse("true"), true);
+    assert_eq!(false, parse("false"));
+    assert_eq!(parse("\\a"), 'a');
+
+    let value = parse("[1 2]");
+    assert_eq!(value[0], 1);
+    assert_eq!(&value[1], 2);
+}
+
+#[test]
+fn test_walk() {
+    let value = parse("{:a [1 #tag 2] :b #{3}}");
+    let mut integers = vec![];
+    let mut count = 0;
+    value.walk(|value| {
+        count += 1;
+        if let Value::Integer(integer) = *value {
+            integers.push(integer);
+        }
+    });
+    integers.sort();
+    assert_eq!(integers, vec![1, 2, 3]);
+    assert_eq!(count, 9);
+
+    let untagged = value.clone().transform(|value| match value {
+        Value::Tagged(_, value) => *value,
+        value => value,
+    });
+    assert_eq!(untagged, parse("{:a [1 2] :b #{3}}"));
+
+    let strings = value.transform(|value| match value {
+        Value::Keyword(keyword) => Value::from(keyword.name()),
+        value => value,
+    });
+    assert_eq!(strings, parse("{\"a\" [1 #tag 2] \"b\" #{3}}"));
+
+    let redacted =
+        parse("{:user {:password \"hunter2\"} :password [1]}").transform_pre(|value| match value {
+            Value::Map(mut map) => {
+                if let Some(password) = map.get_kw_mut("password") {
+                    *password = Value::from("***");
+                }
+                Value::Map(map)
+            }
+            value => value,
+        });
+    assert_eq!(
+        redacted,
+        parse("{:user {:password \"***\"} :password \"***\"}")
+    );
+
+    // The result of `f` is walked, not `f` applied again to it.
+    let unwrapped = parse("[[[1]]]").transform_pre(|value| match value {
+        Value::Vector(items) if items.len() == 1 => items[0].clone(),
+        value => value,
+    });
+    assert_eq!(unwrapped, parse("[1]"));
+}
+
+#[test]
+fn test_merge() {
+    use edn::Conflict;
+
+    let defaults = parse("{:db {:host \"localhost\" :port 5432} :tags [:a] :debug false}");
+    let overrides = parse("{:db {:host \"db.prod\"} :tags [:b] :workers 4}");
+    assert_eq!(
+        defaults.merge(&overrides),
+        parse("{:db {:host \"db.prod\" :port 5432} :tags [:b] :debug false :workers 4}")
+    );
+    assert_eq!(
+        defaults.merge_with(&overrides, Conflict::Keep),
+        parse("{:db {:host \"localhost\" :port 5432} :tags [:a] :debug false :workers 4}")
+    );
+    assert_eq!(
+        parse("{:tags [:a] :ids #{1} :l (1) :n 1}").merge_with(
+            &parse("{:tags [:b] :ids #{2} :l (2) :n 2}"),
+            Conflict::Combine
+        ),
+        parse("{:tags [:a :b] :ids #{1 2} :l (1 2) :n 2}")
+    );
+    assert_eq!(defaults.merge(&Value::Nil), Value::Nil);
+    assert_eq!(Value::Nil.merge(&overrides), overrides);
+}
+
+#[test]
+fn test_update_in() {
+    let value = parse("{:users [{:name \"Ada\" :logins 1}]}");
+    let users = Value::Keyword("users".into());
+    let logins = Value::Keyword("logins".into());
+    let inc = |value: Value| Value::Integer(value.as_i64().unwrap_or(0) + 1);
+
+    assert_eq!(
+        value.update_in(&[&users, &Value::Integer(0), &logins], inc),
+        Some(parse("{:users [{:name \"Ada\" :logins 2}]}"))
+    );
+    assert_eq!(
+        value.update_in(&[&users, &Value::Integer(1), &logins], inc),
+        Some(parse("{:users [{:name \"Ada\" :logins 1} {:logins 1}]}"))
+    );
+    assert_eq!(
+        value.update_in(&[&Value::Keyword("stats".into()), &logins], inc),
+        Some(parse(
+            "{:users [{:name \"Ada\" :logins 1}] :stats {:logins 1}}"
+        ))
+    );
+    assert_eq!(value.update_in(&[], |_| Value::Nil), Some(Value::Nil));
+    assert_eq!(value.update_in(&[&users, &Value::Integer(2)], inc), None);
+    assert_eq!(
+        value.update_in(&[&users, &Value::Integer(0), &logins, &logins], inc),
+        None
+    );
+}
+
+#[test]
+fn test_checked_numbers() {
+    use edn::NumberError;
+    use std::cmp::Ordering;
+    use std::convert::TryFrom;
+
+    assert_eq!(u8::try_from(&parse("255")), Ok(255));
+    assert_eq!(
+        u8::try_from(&parse("256")).map_err(|err| err.to_string()),
+        Err("256 is out of range for u8".into())
+    );
+    assert!(matches!(
+        u64::try_from(&parse("-1")),
+        Err(NumberError::OutOfRange { target: "u64", .. })
+    ));
+    assert_eq!(
+        i64::try_from(&parse("1.0")).map_err(|err| err.to_string()),
+        Err("expected an integer, found a float".into())
+    );
+    assert_eq!(
+        f64::try_from(&parse("9007199254740992")),
+        Ok(9007199254740992.0)
+    );
+    assert!(f64::try_from(&parse("9007199254740993")).is_err());
+    assert_eq!(f32::try_from(&parse("0.1")), Ok(0.1));
+    assert!(f32::try_from(&Value::from(1e39)).is_err());
+    assert_eq!(f32::try_from(&parse("16777216")), Ok(16777216.0));
+    assert!(f32::try_from(&parse("16777217")).is_err());
+    assert!(f64::try_from(&parse("\"1\"")).is_err());
+
+    assert_eq!(Value::from(0.5f32), parse("0.5"));
+    assert_eq!(Value::from(u32::MAX), parse("4294967295"));
+    assert_eq!(Value::from(-3i8), Value::Integer(-3));
+
+    // `Ord` puts integers before floats; `numeric_cmp` compares values.
+    assert!(parse("2") < parse("1.5"));
+    assert_eq!(
+        parse("2").numeric_cmp(&parse("1.5")),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(parse("1").numeric_cmp(&parse("1.0")), Some(Ordering::Equal));
+    assert_eq!(
+        parse("-1.5").numeric_cmp(&parse("-1")),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        parse("9223372036854775807").numeric_cmp(&parse("9223372036854775807.0")),
+        Some(Ordering::Less)
+    );
+    assert_eq!(parse("1").numeric_cmp(&parse("##NaN")), None);
+    assert_eq!(parse("1").numeric_cmp(&parse(":a")), None);
+}
+
+#[test]
+fn test_normalize() {
+    use edn::Normalize;
+
+    let mut value = parse("{:b #{3 1 2} :a (-0.0 [0.5 -0.0]) ^{:z 1 :y 2} c {:d 1 :c 2}}");
+    let mut sorted = value.clone();
+    sorted.sort_maps();
+    assert_eq!(sorted, value);
+    if cfg!(feature = "ordered") {
+        assert_ne!(edn::to_string(&sorted), edn::to_string(&value));
+    }
+    assert_eq!(
+        edn::to_string(&sorted),
+        "{c {:c 2, :d 1}, :a (-0.0 [0.5 -0.0]), :b #{1 2 3}}"
+    );
+
+    value.normalize();
+    assert_eq!(
+        edn::to_string(&value),
+        "{c {:c 2, :d 1}, :a (0.0 [0.5 0.0]), :b #{1 2 3}}"
+    );
+    value.normalize_with(&Normalize::new().lists_as_vectors(true));
+    assert_eq!(
+        value,
+        parse("{:a [0.0 [0.5 0.0]] :b #{1 2 3} c {:c 2 :d 1}}")
+    );
+
+    let mut value = parse("[1 2]");
+    assert_eq!(value.take(), parse("[1 2]"));
+    assert_eq!(value, Value::Nil);
+}